@@ -565,6 +565,42 @@ pub extern "C" fn casper_get_balance(
     })
 }
 
+/// Retrieves an item from a dictionary stored on the network.
+///
+/// See [super::get_dictionary_item](super::get_dictionary_item) for more details.
+#[no_mangle]
+pub extern "C" fn casper_get_dictionary_item(
+    maybe_rpc_id: *const c_char,
+    node_address: *const c_char,
+    verbosity_level: u64,
+    state_root_hash: *const c_char,
+    dictionary_seed_uref: *const c_char,
+    dictionary_item_key: *const c_char,
+    response_buf: *mut c_uchar,
+    response_buf_len: usize,
+) -> casper_error_t {
+    let mut runtime = RUNTIME.lock().expect("should lock");
+    let runtime = try_unwrap_option!(&mut *runtime, or_else => Error::FFISetupNotCalled);
+    let maybe_rpc_id = try_unsafe_arg!(maybe_rpc_id);
+    let node_address = try_unsafe_arg!(node_address);
+    let state_root_hash = try_unsafe_arg!(state_root_hash);
+    let dictionary_seed_uref = try_unsafe_arg!(dictionary_seed_uref);
+    let dictionary_item_key = try_unsafe_arg!(dictionary_item_key);
+    runtime.block_on(async move {
+        let result = super::get_dictionary_item(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            state_root_hash,
+            dictionary_seed_uref,
+            dictionary_item_key,
+        );
+        let response = try_unwrap_rpc!(result);
+        copy_str_to_buf(&response, response_buf, response_buf_len);
+        casper_error_t::CASPER_SUCCESS
+    })
+}
+
 /// Retrieves era information from the network.
 ///
 /// See [super::get_era_info_by_switch_block](super::get_era_info_by_switch_block) for more details.
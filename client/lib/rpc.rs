@@ -11,14 +11,17 @@ use casper_execution_engine::core::engine_state::ExecutableDeployItem;
 use casper_node::{
     crypto::hash::Digest,
     rpcs::{
-        account::{PutDeploy, PutDeployParams},
+        account::{PutDeploy, PutDeployParams, SpeculativeExecute, SpeculativeExecuteParams},
         chain::{
             BlockIdentifier, GetBlock, GetBlockParams, GetBlockTransfers, GetBlockTransfersParams,
             GetEraInfoBySwitchBlock, GetEraInfoParams, GetStateRootHash, GetStateRootHashParams,
         },
         docs::ListRpcs,
         info::{GetDeploy, GetDeployParams},
-        state::{GetAuctionInfo, GetBalance, GetBalanceParams, GetItem, GetItemParams},
+        state::{
+            GetAuctionInfo, GetBalance, GetBalanceParams, GetDictionaryItem,
+            GetDictionaryItemParams, GetItem, GetItemParams,
+        },
         RpcWithOptionalParams, RpcWithParams, RpcWithoutParams, RPC_API_PATH,
     },
     types::{BlockHash, Deploy, DeployHash},
@@ -149,6 +152,31 @@ impl RpcCall {
         Ok(response)
     }
 
+    pub(crate) fn get_dictionary_item(
+        self,
+        state_root_hash: &str,
+        dictionary_seed_uref: &str,
+        dictionary_item_key: &str,
+    ) -> Result<JsonRpc> {
+        let state_root_hash =
+            Digest::from_hex(state_root_hash).map_err(|error| Error::CryptoError {
+                context: "state_root_hash",
+                error,
+            })?;
+        let seed_uref = URef::from_formatted_str(dictionary_seed_uref)
+            .map_err(|error| Error::FailedToParseURef("dictionary_seed_uref", error))?;
+        let key = Key::dictionary(seed_uref, dictionary_item_key.as_bytes());
+
+        let params = GetDictionaryItemParams {
+            state_root_hash,
+            dictionary_seed_uref: dictionary_seed_uref.to_string(),
+            dictionary_item_key: dictionary_item_key.to_string(),
+        };
+        let response = GetDictionaryItem::request_with_map_params(self, params)?;
+        validation::validate_query_response(&response, &state_root_hash, &key, &[])?;
+        Ok(response)
+    }
+
     pub(crate) fn get_era_info_by_switch_block(
         self,
         maybe_block_identifier: &str,
@@ -221,6 +249,28 @@ impl RpcCall {
         PutDeploy::request_with_map_params(self, params)
     }
 
+    pub(crate) fn speculative_execute(
+        self,
+        maybe_state_root_hash: &str,
+        deploy: Deploy,
+    ) -> Result<JsonRpc> {
+        let state_root_hash = if maybe_state_root_hash.is_empty() {
+            None
+        } else {
+            let hash =
+                Digest::from_hex(maybe_state_root_hash).map_err(|error| Error::CryptoError {
+                    context: "state_root_hash",
+                    error,
+                })?;
+            Some(hash)
+        };
+        let params = SpeculativeExecuteParams {
+            state_root_hash,
+            deploy,
+        };
+        SpeculativeExecute::request_with_map_params(self, params)
+    }
+
     pub(crate) fn get_block(self, maybe_block_identifier: &str) -> Result<JsonRpc> {
         let maybe_block_identifier = Self::block_identifier(maybe_block_identifier)?;
         let response = match maybe_block_identifier {
@@ -358,6 +408,10 @@ impl RpcClient for GetItem {
     const RPC_METHOD: &'static str = <Self as RpcWithParams>::METHOD;
 }
 
+impl RpcClient for GetDictionaryItem {
+    const RPC_METHOD: &'static str = Self::METHOD;
+}
+
 impl RpcClient for GetEraInfoBySwitchBlock {
     const RPC_METHOD: &'static str = Self::METHOD;
 }
@@ -370,6 +424,10 @@ impl RpcClient for ListRpcs {
     const RPC_METHOD: &'static str = Self::METHOD;
 }
 
+impl RpcClient for SpeculativeExecute {
+    const RPC_METHOD: &'static str = Self::METHOD;
+}
+
 pub(crate) trait IntoJsonMap: Serialize {
     fn into_json_map(self) -> Map<String, Value>
     where
@@ -389,5 +447,7 @@ impl IntoJsonMap for GetStateRootHashParams {}
 impl IntoJsonMap for GetDeployParams {}
 impl IntoJsonMap for GetBalanceParams {}
 impl IntoJsonMap for GetItemParams {}
+impl IntoJsonMap for GetDictionaryItemParams {}
 impl IntoJsonMap for GetEraInfoParams {}
 impl IntoJsonMap for ListRpcs {}
+impl IntoJsonMap for SpeculativeExecuteParams {}
@@ -3,16 +3,15 @@ use std::convert::TryFrom;
 use jsonrpc_lite::JsonRpc;
 use thiserror::Error;
 
-use casper_execution_engine::{
-    core, core::ValidationError, shared::stored_value::StoredValue,
-    storage::trie::merkle_proof::TrieMerkleProof,
-};
 use casper_node::{
     crypto::hash::Digest,
     rpcs::chain::{BlockIdentifier, EraSummary, GetEraInfoResult},
     types::{json_compatibility, Block, BlockValidationError, JsonBlock},
 };
-use casper_types::{bytesrepr, Key, U512};
+use casper_types::{
+    bytesrepr, validate_balance_proof, validate_query_proof, Key, ProofValidationError,
+    StoredValue, TrieMerkleProof, U512,
+};
 
 const GET_ITEM_RESULT_BALANCE_VALUE: &str = "balance_value";
 const GET_ITEM_RESULT_STORED_VALUE: &str = "stored_value";
@@ -35,7 +34,7 @@ pub enum ValidateResponseError {
 
     /// Failed to validate Merkle proofs.
     #[error(transparent)]
-    ValidationError(#[from] ValidationError),
+    ValidationError(#[from] ProofValidationError),
 
     /// Failed to validate a block.
     #[error("Block validation error {0}")]
@@ -101,8 +100,8 @@ pub(crate) fn validate_get_era_info_response(
                 _ => return Err(ValidateResponseError::ValidateResponseFailedToParse),
             };
 
-            core::validate_query_proof(
-                &state_root_hash.to_owned().into(),
+            validate_query_proof(
+                &state_root_hash.to_array(),
                 &proofs,
                 &key,
                 path,
@@ -164,14 +163,8 @@ pub(crate) fn validate_query_response(
         }
     }
 
-    core::validate_query_proof(
-        &state_root_hash.to_owned().into(),
-        &proofs,
-        key,
-        path,
-        proof_value,
-    )
-    .map_err(Into::into)
+    validate_query_proof(&state_root_hash.to_array(), &proofs, key, path, proof_value)
+        .map_err(Into::into)
 }
 
 pub(crate) fn validate_get_balance_response(
@@ -210,13 +203,8 @@ pub(crate) fn validate_get_balance_response(
             .map_err(|_| ValidateResponseError::ValidateResponseFailedToParse)?
     };
 
-    core::validate_balance_proof(
-        &state_root_hash.to_owned().into(),
-        &balance_proof,
-        *key,
-        &balance,
-    )
-    .map_err(Into::into)
+    validate_balance_proof(&state_root_hash.to_array(), &balance_proof, *key, &balance)
+        .map_err(Into::into)
 }
 
 pub(crate) fn validate_get_block_response(
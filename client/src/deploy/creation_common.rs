@@ -15,6 +15,7 @@ pub(super) enum DisplayOrder {
     Verbose,
     NodeAddress,
     RpcId,
+    StateRootHash,
     SecretKey,
     Input,
     Output,
@@ -262,16 +263,21 @@ pub(super) mod gas_price {
 pub(super) mod dependencies {
     use super::*;
 
-    const ARG_NAME: &str = "dependency";
+    const ARG_NAME: &str = "dependencies";
+    const ARG_ALIAS: &str = "dependency";
     const ARG_VALUE_NAME: &str = common::ARG_HEX_STRING;
     const ARG_HELP: &str =
-        "A hex-encoded deploy hash of a deploy which must be executed before this deploy";
+        "Hex-encoded deploy hashes of deploys which must be executed before this deploy, separated \
+        by commas or given by repeating the argument. The node will reject the deploy if any of \
+        these are unknown to it or have expired";
 
     pub(in crate::deploy) fn arg() -> Arg<'static, 'static> {
         Arg::with_name(ARG_NAME)
             .long(ARG_NAME)
+            .alias(ARG_ALIAS)
             .required(false)
             .multiple(true)
+            .use_delimiter(true)
             .value_name(ARG_VALUE_NAME)
             .takes_value(true)
             .help(ARG_HELP)
@@ -7,6 +7,7 @@ mod docs;
 mod generate_completion;
 mod get_auction_info;
 mod get_balance;
+mod get_dictionary_item;
 mod get_era_info_by_switch_block;
 mod get_state_hash;
 mod keygen;
@@ -18,11 +19,11 @@ use clap::{crate_description, crate_version, App};
 
 use casper_client::Error;
 use casper_node::rpcs::{
-    account::PutDeploy,
+    account::{PutDeploy, SpeculativeExecute},
     chain::{GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetStateRootHash},
     docs::ListRpcs,
     info::GetDeploy,
-    state::{GetAuctionInfo, GetBalance, GetItem as QueryState},
+    state::{GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem as QueryState},
 };
 
 use deploy::{ListDeploys, MakeDeploy, SendDeploy, SignDeploy};
@@ -38,6 +39,7 @@ const APP_NAME: &str = "Casper client";
 /// This struct defines the order in which the subcommands are shown in the app's help message.
 enum DisplayOrder {
     PutDeploy,
+    EstimateGas,
     MakeDeploy,
     SignDeploy,
     SendDeploy,
@@ -49,6 +51,7 @@ enum DisplayOrder {
     GetStateRootHash,
     QueryState,
     GetBalance,
+    GetDictionaryItem,
     GetEraInfo,
     GetAuctionInfo,
     Keygen,
@@ -62,6 +65,9 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
         .version(crate_version!())
         .about(crate_description!())
         .subcommand(PutDeploy::build(DisplayOrder::PutDeploy as usize))
+        .subcommand(SpeculativeExecute::build(
+            DisplayOrder::EstimateGas as usize,
+        ))
         .subcommand(MakeDeploy::build(DisplayOrder::MakeDeploy as usize))
         .subcommand(SignDeploy::build(DisplayOrder::SignDeploy as usize))
         .subcommand(SendDeploy::build(DisplayOrder::SendDeploy as usize))
@@ -77,6 +83,9 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
             DisplayOrder::GetStateRootHash as usize,
         ))
         .subcommand(QueryState::build(DisplayOrder::QueryState as usize))
+        .subcommand(GetDictionaryItem::build(
+            DisplayOrder::GetDictionaryItem as usize,
+        ))
         .subcommand(GetEraInfoBySwitchBlock::build(
             DisplayOrder::GetEraInfo as usize,
         ))
@@ -94,6 +103,7 @@ async fn main() {
     let arg_matches = cli().get_matches();
     let (result, matches) = match arg_matches.subcommand() {
         (PutDeploy::NAME, Some(matches)) => (PutDeploy::run(matches), matches),
+        (SpeculativeExecute::NAME, Some(matches)) => (SpeculativeExecute::run(matches), matches),
         (MakeDeploy::NAME, Some(matches)) => (MakeDeploy::run(matches), matches),
         (SignDeploy::NAME, Some(matches)) => (SignDeploy::run(matches), matches),
         (SendDeploy::NAME, Some(matches)) => (SendDeploy::run(matches), matches),
@@ -105,6 +115,7 @@ async fn main() {
         (GetBalance::NAME, Some(matches)) => (GetBalance::run(matches), matches),
         (GetStateRootHash::NAME, Some(matches)) => (GetStateRootHash::run(matches), matches),
         (QueryState::NAME, Some(matches)) => (QueryState::run(matches), matches),
+        (GetDictionaryItem::NAME, Some(matches)) => (GetDictionaryItem::run(matches), matches),
         (GetEraInfoBySwitchBlock::NAME, Some(matches)) => {
             (GetEraInfoBySwitchBlock::run(matches), matches)
         }
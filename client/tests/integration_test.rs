@@ -14,7 +14,7 @@ use hex::FromHexError;
 
 use casper_client::{DeployStrParams, Error, PaymentStrParams, SessionStrParams};
 use casper_node::rpcs::{
-    account::{PutDeploy, PutDeployParams},
+    account::{PutDeploy, PutDeployParams, SpeculativeExecute, SpeculativeExecuteParams},
     chain::{GetStateRootHash, GetStateRootHashParams},
     info::{GetDeploy, GetDeployParams},
     state::{GetBalance, GetBalanceParams},
@@ -188,6 +188,26 @@ impl MockServerHandle {
         .map_err(ErrWrapper)
     }
 
+    fn estimate_gas(
+        &self,
+        maybe_state_root_hash: &str,
+        deploy_params: DeployStrParams,
+        session_params: SessionStrParams,
+        payment_params: PaymentStrParams,
+    ) -> Result<(), ErrWrapper> {
+        casper_client::estimate_gas(
+            "1",
+            &self.url(),
+            0,
+            maybe_state_root_hash,
+            deploy_params,
+            session_params,
+            payment_params,
+        )
+        .map(|_| ())
+        .map_err(ErrWrapper)
+    }
+
     fn send_deploy_file(&self, input_path: &str) -> Result<(), ErrWrapper> {
         casper_client::send_deploy_file("1", &self.url(), 0, input_path)
             .map(|_| ())
@@ -796,6 +816,59 @@ mod put_deploy {
     }
 }
 
+mod estimate_gas {
+    use super::*;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn should_succeed_with_valid_state_root_hash() {
+        let server_handle =
+            MockServerHandle::spawn::<SpeculativeExecuteParams>(SpeculativeExecute::METHOD);
+        assert_eq!(
+            server_handle.estimate_gas(
+                VALID_STATE_ROOT_HASH,
+                deploy_params::test_data_valid(),
+                session_params::test_data_with_package_hash(),
+                payment_params::test_data_with_name()
+            ),
+            Ok(())
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn should_succeed_with_empty_state_root_hash() {
+        let server_handle =
+            MockServerHandle::spawn::<SpeculativeExecuteParams>(SpeculativeExecute::METHOD);
+        assert_eq!(
+            server_handle.estimate_gas(
+                "",
+                deploy_params::test_data_valid(),
+                session_params::test_data_with_package_hash(),
+                payment_params::test_data_with_name()
+            ),
+            Ok(())
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn should_fail_with_bad_state_root_hash() {
+        let server_handle =
+            MockServerHandle::spawn::<SpeculativeExecuteParams>(SpeculativeExecute::METHOD);
+        assert_eq!(
+            server_handle.estimate_gas(
+                "deadbeef",
+                deploy_params::test_data_valid(),
+                session_params::test_data_with_package_hash(),
+                payment_params::test_data_with_name()
+            ),
+            Err(Error::CryptoError {
+                context: "state_root_hash",
+                error: CryptoError::FromHex(FromHexError::InvalidStringLength)
+            }
+            .into())
+        );
+    }
+}
+
 mod rate_limit {
     use super::*;
     use casper_node::types::Timestamp;
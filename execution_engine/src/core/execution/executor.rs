@@ -191,6 +191,7 @@ impl Executor {
                     return ExecutionResult::Success {
                         effect: runtime.context().effect(),
                         transfers: runtime.context().transfers().to_owned(),
+                        events: runtime.context().events().to_owned(),
                         cost: runtime.context().gas_counter(),
                     };
                 }
@@ -215,6 +216,7 @@ impl Executor {
                     return ExecutionResult::Success {
                         effect: runtime.context().effect(),
                         transfers: runtime.context().transfers().to_owned(),
+                        events: runtime.context().events().to_owned(),
                         cost: runtime.context().gas_counter(),
                     };
                 }
@@ -239,6 +241,7 @@ impl Executor {
                     return ExecutionResult::Success {
                         effect: runtime.context().effect(),
                         transfers: runtime.context().transfers().to_owned(),
+                        events: runtime.context().events().to_owned(),
                         cost: runtime.context().gas_counter(),
                     }
                 }
@@ -262,6 +265,7 @@ impl Executor {
         ExecutionResult::Success {
             effect: runtime.context().effect(),
             transfers: runtime.context().transfers().to_owned(),
+            events: runtime.context().events().to_owned(),
             cost: runtime.context().gas_counter(),
         }
     }
@@ -341,6 +345,7 @@ impl Executor {
             Ok(()) => ExecutionResult::Success {
                 effect: runtime.context().effect(),
                 transfers: runtime.context().transfers().to_owned(),
+                events: runtime.context().events().to_owned(),
                 cost: runtime.context().gas_counter(),
             },
             Err(error) => ExecutionResult::Failure {
@@ -732,6 +737,7 @@ impl DirectSystemContractCall {
                 Ok(ret) => ExecutionResult::Success {
                     effect: runtime.context().effect(),
                     transfers: runtime.context().transfers().to_owned(),
+                    events: runtime.context().events().to_owned(),
                     cost: runtime.context().gas_counter(),
                 }
                 .take_with_ret(ret),
@@ -50,6 +50,11 @@ pub enum FunctionIndex {
     Blake2b,
     RecordTransfer,
     RecordEraInfo,
+    EmitEvent,
+    VerifySignature,
+    NewDictionary,
+    DictionaryGet,
+    DictionaryPut,
 }
 
 impl Into<usize> for FunctionIndex {
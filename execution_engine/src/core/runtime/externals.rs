@@ -10,6 +10,7 @@ use casper_types::{
     contracts::{ContractPackageStatus, EntryPoints, NamedKeys},
     system::auction::{EraId, EraInfo},
     ContractHash, ContractPackageHash, ContractVersion, Group, Key, URef, U512,
+    UREF_SERIALIZED_LENGTH,
 };
 
 use super::{args::Args, scoped_instrumenter::ScopedInstrumenter, Error, Runtime};
@@ -968,6 +969,143 @@ where
                 self.record_era_info(era_id, era_info)?;
                 Ok(Some(RuntimeValue::I32(0)))
             }
+
+            FunctionIndex::EmitEvent => {
+                // args(0) = pointer to serialized topic
+                // args(1) = size of serialized topic
+                // args(2) = pointer to serialized payload `CLValue`
+                // args(3) = size of serialized payload `CLValue`
+                let (topic_ptr, topic_size, payload_ptr, payload_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.emit_event,
+                    [topic_ptr, topic_size, payload_ptr, payload_size],
+                )?;
+                scoped_instrumenter.add_property("topic_size", topic_size.to_string());
+                scoped_instrumenter.add_property("payload_size", payload_size.to_string());
+                let topic = self.string_from_mem(topic_ptr, topic_size)?;
+                let payload = self.cl_value_from_mem(payload_ptr, payload_size)?;
+                let ret = self.emit_event(topic, payload);
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
+
+            FunctionIndex::VerifySignature => {
+                // args(0) = pointer to serialized public key
+                // args(1) = size of serialized public key
+                // args(2) = pointer to message bytes
+                // args(3) = size of message bytes
+                // args(4) = pointer to serialized signature
+                // args(5) = size of serialized signature
+                let (
+                    public_key_ptr,
+                    public_key_size,
+                    message_ptr,
+                    message_size,
+                    signature_ptr,
+                    signature_size,
+                ) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.verify_signature,
+                    [
+                        public_key_ptr,
+                        public_key_size,
+                        message_ptr,
+                        message_size,
+                        signature_ptr,
+                        signature_size,
+                    ],
+                )?;
+                scoped_instrumenter.add_property("message_size", message_size.to_string());
+                let verified = self.verify_signature(
+                    public_key_ptr,
+                    public_key_size,
+                    message_ptr,
+                    message_size,
+                    signature_ptr,
+                    signature_size,
+                )?;
+                Ok(Some(RuntimeValue::I32(i32::from(verified))))
+            }
+
+            FunctionIndex::NewDictionary => {
+                // args(0) = pointer to array for return value
+                // args(1) = length of array for return value
+                let (dest_ptr, dest_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.new_dictionary,
+                    [dest_ptr, dest_size],
+                )?;
+                if (dest_size as usize) < UREF_SERIALIZED_LENGTH {
+                    return Err(Error::Revert(api_error::ApiError::BufferTooSmall).into());
+                }
+                let seed_uref = self.new_dictionary()?;
+                let seed_uref_bytes = seed_uref.into_bytes().map_err(Error::BytesRepr)?;
+                self.memory
+                    .set(dest_ptr, &seed_uref_bytes)
+                    .map_err(|e| Error::Interpreter(e.into()))?;
+                Ok(Some(RuntimeValue::I32(0)))
+            }
+
+            FunctionIndex::DictionaryGet => {
+                // args(0) = pointer to seed uref in Wasm memory
+                // args(1) = size of seed uref in Wasm memory
+                // args(2) = pointer to dictionary item key bytes in Wasm memory
+                // args(3) = size of dictionary item key bytes in Wasm memory
+                // args(4) = pointer to output size (output param)
+                let (uref_ptr, uref_size, key_bytes_ptr, key_bytes_size, output_size_ptr) =
+                    Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.dictionary_get,
+                    [
+                        uref_ptr,
+                        uref_size,
+                        key_bytes_ptr,
+                        key_bytes_size,
+                        output_size_ptr,
+                    ],
+                )?;
+                scoped_instrumenter.add_property("key_bytes_size", key_bytes_size);
+                let ret = self.dictionary_get(
+                    uref_ptr,
+                    uref_size,
+                    key_bytes_ptr,
+                    key_bytes_size,
+                    output_size_ptr,
+                )?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
+
+            FunctionIndex::DictionaryPut => {
+                // args(0) = pointer to seed uref in Wasm memory
+                // args(1) = size of seed uref in Wasm memory
+                // args(2) = pointer to dictionary item key bytes in Wasm memory
+                // args(3) = size of dictionary item key bytes in Wasm memory
+                // args(4) = pointer to value
+                // args(5) = size of value
+                let (uref_ptr, uref_size, key_bytes_ptr, key_bytes_size, value_ptr, value_size) =
+                    Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.dictionary_put,
+                    [
+                        uref_ptr,
+                        uref_size,
+                        key_bytes_ptr,
+                        key_bytes_size,
+                        value_ptr,
+                        value_size,
+                    ],
+                )?;
+                scoped_instrumenter.add_property("key_bytes_size", key_bytes_size);
+                scoped_instrumenter.add_property("value_size", value_size);
+                let ret = self.dictionary_put(
+                    uref_ptr,
+                    uref_size,
+                    key_bytes_ptr,
+                    key_bytes_size,
+                    value_ptr,
+                    value_size,
+                )?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
         }
     }
 }
@@ -32,9 +32,10 @@ use casper_types::{
         standard_payment::{self, StandardPayment},
         SystemContractType,
     },
-    AccessRights, ApiError, CLType, CLTyped, CLValue, ContractHash, ContractPackageHash,
-    ContractVersionKey, ContractWasm, DeployHash, EntryPointType, Key, Phase, ProtocolVersion,
-    PublicKey, RuntimeArgs, Transfer, TransferResult, TransferredTo, URef, U128, U256, U512,
+    AccessRights, ApiError, CLType, CLTyped, CLValue, ContractEvent, ContractHash,
+    ContractPackageHash, ContractVersionKey, ContractWasm, DeployHash, EntryPointType, Key, Phase,
+    ProtocolVersion, PublicKey, RuntimeArgs, Signature, Transfer, TransferResult, TransferredTo,
+    URef, DICTIONARY_ITEM_KEY_MAX_LENGTH, U128, U256, U512,
 };
 
 use crate::{
@@ -56,6 +57,9 @@ use crate::{
     storage::{global_state::StateReader, protocol_data::ProtocolData},
 };
 
+/// The maximum length in bytes of the topic of an event emitted by a contract.
+const MAX_EVENT_TOPIC_LENGTH: usize = 64;
+
 pub struct Runtime<'a, R> {
     system_contract_cache: SystemContractCache,
     config: EngineConfig,
@@ -98,6 +102,7 @@ pub fn key_to_tuple(key: Key) -> Option<([u8; 32], AccessRights)> {
         Key::Balance(_) => None,
         Key::Bid(_) => None,
         Key::Withdraw(_) => None,
+        Key::Dictionary(_) => None,
     }
 }
 
@@ -1084,6 +1089,24 @@ where
         Ok(self.context.validate_uref(&uref).is_ok())
     }
 
+    /// Verifies the signature (defined as `signature_ptr` and `signature_size` tuple) of the raw
+    /// message bytes (defined as `message_ptr` and `message_size` tuple) against the public key
+    /// (defined as `public_key_ptr` and `public_key_size` tuple).
+    fn verify_signature(
+        &mut self,
+        public_key_ptr: u32,
+        public_key_size: u32,
+        message_ptr: u32,
+        message_size: u32,
+        signature_ptr: u32,
+        signature_size: u32,
+    ) -> Result<bool, Trap> {
+        let public_key: PublicKey = self.t_from_mem(public_key_ptr, public_key_size)?;
+        let message = self.bytes_from_mem(message_ptr, message_size as usize)?;
+        let signature: Signature = self.t_from_mem(signature_ptr, signature_size)?;
+        Ok(casper_types::verify(message, &signature, &public_key).is_ok())
+    }
+
     /// Load the uref known by the given name into the Wasm memory
     fn load_key(
         &mut self,
@@ -2074,6 +2097,11 @@ where
             *transfers = runtime.context.transfers().to_owned();
         }
 
+        // The sub-call's context starts with no events, so append any it emitted to ours.
+        self.context
+            .events_mut()
+            .extend(runtime.context.events().iter().cloned());
+
         let error = match result {
             Err(error) => error,
             // If `Ok` and the `host_buffer` is `None`, the contract's execution succeeded but did
@@ -2529,6 +2557,17 @@ where
         Ok(())
     }
 
+    /// Records an event emitted by the currently executing contract.
+    fn emit_event(&mut self, topic: String, payload: CLValue) -> Result<(), ApiError> {
+        if topic.is_empty() || topic.len() > MAX_EVENT_TOPIC_LENGTH {
+            return Err(ApiError::InvalidArgument);
+        }
+        self.context
+            .events_mut()
+            .push(ContractEvent { topic, payload });
+        Ok(())
+    }
+
     /// Records given auction info at a given era id
     fn record_era_info(&mut self, era_id: EraId, era_info: EraInfo) -> Result<(), Error> {
         if self.context.base_key() != Key::from(self.protocol_data().auction()) {
@@ -2594,6 +2633,78 @@ where
         Ok(Ok(()))
     }
 
+    /// Creates a new dictionary, returning the seed [`URef`] which identifies it.
+    fn new_dictionary(&mut self) -> Result<URef, Error> {
+        self.context.new_unit_uref()
+    }
+
+    /// Reads the item stored under the item key specified by `key_bytes_ptr` and
+    /// `key_bytes_size` in the dictionary identified by the seed `URef` specified by `uref_ptr` and
+    /// `uref_size`.  The value is written to the host buffer.
+    fn dictionary_get(
+        &mut self,
+        uref_ptr: u32,
+        uref_size: u32,
+        key_bytes_ptr: u32,
+        key_bytes_size: u32,
+        output_size_ptr: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        if !self.can_write_to_host_buffer() {
+            // Exit early if the host buffer is already occupied
+            return Ok(Err(ApiError::HostBufferFull));
+        }
+
+        if key_bytes_size as usize > DICTIONARY_ITEM_KEY_MAX_LENGTH {
+            return Ok(Err(ApiError::InvalidArgument));
+        }
+
+        let seed_uref: URef = self.t_from_mem(uref_ptr, uref_size)?;
+        let dictionary_item_key = self.bytes_from_mem(key_bytes_ptr, key_bytes_size as usize)?;
+        let cl_value = match self
+            .context
+            .dictionary_get(seed_uref, &dictionary_item_key)?
+        {
+            Some(stored_value) => CLValue::try_from(stored_value).map_err(Error::TypeMismatch)?,
+            None => return Ok(Err(ApiError::ValueNotFound)),
+        };
+
+        let value_size = cl_value.inner_bytes().len() as u32;
+        if let Err(error) = self.write_host_buffer(cl_value) {
+            return Ok(Err(error));
+        }
+
+        let value_bytes = value_size.to_le_bytes(); // Wasm is little-endian
+        if let Err(error) = self.memory.set(output_size_ptr, &value_bytes) {
+            return Err(Error::Interpreter(error.into()).into());
+        }
+
+        Ok(Ok(()))
+    }
+
+    /// Writes the value specified by `value_ptr` and `value_size` under the item key specified by
+    /// `key_bytes_ptr` and `key_bytes_size` in the dictionary identified by the seed `URef`
+    /// specified by `uref_ptr` and `uref_size`.
+    fn dictionary_put(
+        &mut self,
+        uref_ptr: u32,
+        uref_size: u32,
+        key_bytes_ptr: u32,
+        key_bytes_size: u32,
+        value_ptr: u32,
+        value_size: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        if key_bytes_size as usize > DICTIONARY_ITEM_KEY_MAX_LENGTH {
+            return Ok(Err(ApiError::InvalidArgument));
+        }
+
+        let seed_uref: URef = self.t_from_mem(uref_ptr, uref_size)?;
+        let dictionary_item_key = self.bytes_from_mem(key_bytes_ptr, key_bytes_size as usize)?;
+        let cl_value = self.cl_value_from_mem(value_ptr, value_size)?;
+        self.context
+            .dictionary_put(seed_uref, &dictionary_item_key, cl_value)?;
+        Ok(Ok(()))
+    }
+
     /// Reverts contract execution with a status specified.
     fn revert(&mut self, status: u32) -> Trap {
         Error::Revert(status.into()).into()
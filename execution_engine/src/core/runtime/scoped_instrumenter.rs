@@ -143,6 +143,11 @@ impl Drop for ScopedInstrumenter {
             FunctionIndex::Blake2b => "host_blake2b",
             FunctionIndex::RecordTransfer => "host_record_transfer",
             FunctionIndex::RecordEraInfo => "host_record_era_info",
+            FunctionIndex::EmitEvent => "host_emit_event",
+            FunctionIndex::VerifySignature => "host_verify_signature",
+            FunctionIndex::NewDictionary => "host_new_dictionary",
+            FunctionIndex::DictionaryGet => "host_dictionary_get",
+            FunctionIndex::DictionaryPut => "host_dictionary_put",
         };
 
         let mut properties = mem::take(&mut self.properties);
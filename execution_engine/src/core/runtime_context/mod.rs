@@ -15,9 +15,9 @@ use casper_types::{
     bytesrepr::ToBytes,
     contracts::NamedKeys,
     system::auction::EraInfo,
-    AccessRights, BlockTime, CLType, CLValue, Contract, ContractPackage, ContractPackageHash,
-    DeployHash, DeployInfo, EntryPointAccess, EntryPointType, Key, KeyTag, Phase, ProtocolVersion,
-    PublicKey, RuntimeArgs, Transfer, TransferAddr, URef, KEY_HASH_LENGTH,
+    AccessRights, BlockTime, CLType, CLValue, Contract, ContractEvent, ContractPackage,
+    ContractPackageHash, DeployHash, DeployInfo, EntryPointAccess, EntryPointType, Key, KeyTag,
+    Phase, ProtocolVersion, PublicKey, RuntimeArgs, Transfer, TransferAddr, URef, KEY_HASH_LENGTH,
 };
 
 use crate::{
@@ -105,6 +105,7 @@ pub struct RuntimeContext<'a, R> {
     protocol_data: ProtocolData,
     entry_point_type: EntryPointType,
     transfers: Vec<TransferAddr>,
+    events: Vec<ContractEvent>,
 }
 
 impl<'a, R> RuntimeContext<'a, R>
@@ -156,6 +157,7 @@ where
             phase,
             protocol_data,
             transfers,
+            events: Vec::new(),
         }
     }
 
@@ -260,6 +262,10 @@ where
                 self.named_keys.remove(name);
                 Ok(())
             }
+            Key::Dictionary(_) => {
+                self.named_keys.remove(name);
+                Ok(())
+            }
         }
     }
 
@@ -480,6 +486,42 @@ where
         }
     }
 
+    /// Reads the item stored under `dictionary_item_key` in the dictionary identified by
+    /// `seed_uref`.
+    pub fn dictionary_get(
+        &mut self,
+        seed_uref: URef,
+        dictionary_item_key: &[u8],
+    ) -> Result<Option<StoredValue>, Error> {
+        self.validate_uref(&seed_uref)?;
+        if !seed_uref.is_readable() {
+            return Err(Error::InvalidAccess {
+                required: AccessRights::READ,
+            });
+        }
+        let dictionary_key = Key::dictionary(seed_uref, dictionary_item_key);
+        self.read_gs_direct(&dictionary_key)
+    }
+
+    /// Writes `cl_value` under `dictionary_item_key` in the dictionary identified by `seed_uref`.
+    pub fn dictionary_put(
+        &mut self,
+        seed_uref: URef,
+        dictionary_item_key: &[u8],
+        cl_value: CLValue,
+    ) -> Result<(), Error> {
+        self.validate_uref(&seed_uref)?;
+        if !seed_uref.is_writeable() {
+            return Err(Error::InvalidAccess {
+                required: AccessRights::WRITE,
+            });
+        }
+        let stored_value = StoredValue::CLValue(cl_value);
+        self.validate_value(&stored_value)?;
+        let dictionary_key = Key::dictionary(seed_uref, dictionary_item_key);
+        self.metered_write_gs_unsafe(dictionary_key, stored_value)
+    }
+
     pub fn store_function(
         &mut self,
         contract: StoredValue,
@@ -526,6 +568,15 @@ where
         &mut self.transfers
     }
 
+    /// Returns the events emitted so far within this context, in order of emission.
+    pub fn events(&self) -> &Vec<ContractEvent> {
+        &self.events
+    }
+
+    pub fn events_mut(&mut self) -> &mut Vec<ContractEvent> {
+        &mut self.events
+    }
+
     /// Validates whether keys used in the `value` are not forged.
     fn validate_value(&self, value: &StoredValue) -> Result<(), Error> {
         match value {
@@ -673,6 +724,8 @@ where
             Key::Balance(_) => false,
             Key::Bid(_) => true,
             Key::Withdraw(_) => true,
+            // Dictionary items are only readable via their seed `URef`.
+            Key::Dictionary(_) => false,
         }
     }
 
@@ -687,6 +740,7 @@ where
             Key::Balance(_) => false,
             Key::Bid(_) => false,
             Key::Withdraw(_) => false,
+            Key::Dictionary(_) => false,
         }
     }
 
@@ -701,6 +755,7 @@ where
             Key::Balance(_) => false,
             Key::Bid(_) => false,
             Key::Withdraw(_) => false,
+            Key::Dictionary(_) => false,
         }
     }
 
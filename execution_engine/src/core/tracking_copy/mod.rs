@@ -6,14 +6,13 @@ mod tests;
 
 use std::{
     collections::{BTreeSet, HashMap, HashSet, VecDeque},
-    convert::{From, TryInto},
+    convert::From,
     iter,
 };
 
 use linked_hash_map::LinkedHashMap;
-use thiserror::Error;
 
-use casper_types::{bytesrepr, CLType, CLValue, CLValueError, Key, KeyTag, Tagged, U512};
+use casper_types::{bytesrepr, CLType, CLValueError, Key, KeyTag, Tagged, U512};
 
 pub use self::ext::TrackingCopyExt;
 use self::meter::{heap_meter::HeapSize, Meter};
@@ -536,48 +535,11 @@ impl<R: StateReader<Key, StoredValue>> StateReader<Key, StoredValue> for &Tracki
     }
 }
 
-#[derive(Error, Debug, PartialEq, Eq)]
-pub enum ValidationError {
-    #[error("The path should not have a different length than the proof less one.")]
-    PathLengthDifferentThanProofLessOne,
-
-    #[error("The provided key does not match the key in the proof.")]
-    UnexpectedKey,
-
-    #[error("The provided value does not match the value in the proof.")]
-    UnexpectedValue,
-
-    #[error("The proof hash is invalid.")]
-    InvalidProofHash,
-
-    #[error("The path went cold.")]
-    PathCold,
-
-    #[error("Serialization error: {0}")]
-    BytesRepr(bytesrepr::Error),
-
-    #[error("Key is not a URef")]
-    KeyIsNotAURef(Key),
-
-    #[error("Failed to convert stored value to key")]
-    ValueToCLValueConversion,
-
-    #[error("{0}")]
-    CLValueError(CLValueError),
-}
-
-impl From<CLValueError> for ValidationError {
-    fn from(err: CLValueError) -> Self {
-        ValidationError::CLValueError(err)
-    }
-}
-
-impl From<bytesrepr::Error> for ValidationError {
-    fn from(error: bytesrepr::Error) -> Self {
-        Self::BytesRepr(error)
-    }
-}
+pub use casper_types::ProofValidationError as ValidationError;
 
+/// Validates the proofs of a query of global state against the state root hash `hash`.
+///
+/// See [`casper_types::validate_query_proof`].
 pub fn validate_query_proof(
     hash: &Blake2bHash,
     proofs: &[TrieMerkleProof<Key, StoredValue>],
@@ -585,85 +547,28 @@ pub fn validate_query_proof(
     path: &[String],
     expected_value: &StoredValue,
 ) -> Result<(), ValidationError> {
-    if proofs.len() != path.len() + 1 {
-        return Err(ValidationError::PathLengthDifferentThanProofLessOne);
-    }
-
-    let mut proofs_iter = proofs.iter();
-
-    // length check above means we are safe to unwrap here
-    let first_proof = proofs_iter.next().unwrap();
-
-    if first_proof.key() != &expected_first_key.normalize() {
-        return Err(ValidationError::UnexpectedKey);
-    }
-
-    if hash != &first_proof.compute_state_hash()? {
-        return Err(ValidationError::InvalidProofHash);
-    }
-
-    let mut proof_value = first_proof.value();
-
-    for (proof, path_component) in proofs_iter.zip(path.iter()) {
-        let named_keys = match proof_value {
-            StoredValue::Account(account) => account.named_keys(),
-            StoredValue::Contract(contract) => contract.named_keys(),
-            _ => return Err(ValidationError::PathCold),
-        };
-
-        let key = match named_keys.get(path_component) {
-            Some(key) => key,
-            None => return Err(ValidationError::PathCold),
-        };
-
-        if proof.key() != &key.normalize() {
-            return Err(ValidationError::UnexpectedKey);
-        }
-
-        if hash != &proof.compute_state_hash()? {
-            return Err(ValidationError::InvalidProofHash);
-        }
-
-        proof_value = proof.value();
-    }
-
-    if proof_value != expected_value {
-        return Err(ValidationError::UnexpectedValue);
-    }
-
-    Ok(())
+    casper_types::validate_query_proof(
+        &hash.value(),
+        proofs,
+        expected_first_key,
+        path,
+        expected_value,
+    )
 }
 
+/// Validates a proof of the balance of a purse against the state root hash `hash`.
+///
+/// See [`casper_types::validate_balance_proof`].
 pub fn validate_balance_proof(
     hash: &Blake2bHash,
     balance_proof: &TrieMerkleProof<Key, StoredValue>,
     expected_purse_key: Key,
     expected_motes: &U512,
 ) -> Result<(), ValidationError> {
-    let expected_balance_key = expected_purse_key
-        .into_uref()
-        .map(|uref| Key::Balance(uref.addr()))
-        .ok_or_else(|| ValidationError::KeyIsNotAURef(expected_purse_key.to_owned()))?;
-
-    if balance_proof.key() != &expected_balance_key.normalize() {
-        return Err(ValidationError::UnexpectedKey);
-    }
-
-    if hash != &balance_proof.compute_state_hash()? {
-        return Err(ValidationError::InvalidProofHash);
-    }
-
-    let balance_proof_stored_value = balance_proof.value().to_owned();
-
-    let balance_proof_clvalue: CLValue = balance_proof_stored_value
-        .try_into()
-        .map_err(|_| ValidationError::ValueToCLValueConversion)?;
-
-    let balance_motes: U512 = balance_proof_clvalue.into_t()?;
-
-    if expected_motes != &balance_motes {
-        return Err(ValidationError::UnexpectedValue);
-    }
-
-    Ok(())
+    casper_types::validate_balance_proof(
+        &hash.value(),
+        balance_proof,
+        expected_purse_key,
+        expected_motes,
+    )
 }
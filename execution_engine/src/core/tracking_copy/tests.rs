@@ -18,7 +18,7 @@ use crate::{
     shared::{
         account::{Account, AssociatedKeys},
         newtypes::{Blake2bHash, CorrelationId},
-        stored_value::{gens::stored_value_arb, StoredValue},
+        stored_value::StoredValue,
         transform::Transform,
     },
     storage::{
@@ -15,10 +15,9 @@ pub mod stored_value;
 pub mod system_config;
 pub mod test_utils;
 pub mod transform;
-mod type_mismatch;
 pub mod utils;
 pub mod wasm;
 pub mod wasm_config;
 pub mod wasm_prep;
 
-pub use type_mismatch::TypeMismatch;
+pub use casper_types::StoredValueTypeMismatch as TypeMismatch;
@@ -24,6 +24,18 @@ const DEFAULT_CALL_CONTRACT_COST: u32 = 4_500;
 const DEFAULT_CALL_CONTRACT_ARGS_SIZE_WEIGHT: u32 = 420;
 
 const DEFAULT_CREATE_PURSE_COST: u32 = 170_000;
+
+const DEFAULT_DICTIONARY_GET_COST: u32 = 6_000;
+const DEFAULT_DICTIONARY_GET_KEY_SIZE_WEIGHT: u32 = 590;
+
+const DEFAULT_DICTIONARY_PUT_COST: u32 = 14_000;
+const DEFAULT_DICTIONARY_PUT_KEY_BYTES_SIZE_WEIGHT: u32 = 1_800;
+const DEFAULT_DICTIONARY_PUT_VALUE_SIZE_WEIGHT: u32 = 520;
+
+const DEFAULT_EMIT_EVENT_COST: u32 = 22_000;
+const DEFAULT_EMIT_EVENT_TOPIC_SIZE_WEIGHT: u32 = 1_100;
+const DEFAULT_EMIT_EVENT_PAYLOAD_SIZE_WEIGHT: u32 = 980;
+
 const DEFAULT_GET_BALANCE_COST: u32 = 3_800;
 const DEFAULT_GET_BLOCKTIME_COST: u32 = 330;
 const DEFAULT_GET_CALLER_COST: u32 = 380;
@@ -39,6 +51,8 @@ const DEFAULT_LOAD_NAMED_KEYS_COST: u32 = 42_000;
 const DEFAULT_NEW_UREF_COST: u32 = 17_000;
 const DEFAULT_NEW_UREF_VALUE_SIZE_WEIGHT: u32 = 590;
 
+const DEFAULT_NEW_DICTIONARY_COST: u32 = DEFAULT_NEW_UREF_COST;
+
 const DEFAULT_PRINT_COST: u32 = 20_000;
 const DEFAULT_PRINT_TEXT_SIZE_WEIGHT: u32 = 4_600;
 
@@ -67,6 +81,9 @@ const DEFAULT_TRANSFER_FROM_PURSE_TO_PURSE_COST: u32 = 82_000;
 const DEFAULT_TRANSFER_TO_ACCOUNT_COST: u32 = 24_000;
 const DEFAULT_UPDATE_ASSOCIATED_KEY_COST: u32 = 4_200;
 
+const DEFAULT_VERIFY_SIGNATURE_COST: u32 = 300_000;
+const DEFAULT_VERIFY_SIGNATURE_MESSAGE_SIZE_WEIGHT: u32 = 120;
+
 const DEFAULT_WRITE_COST: u32 = 14_000;
 const DEFAULT_WRITE_VALUE_SIZE_WEIGHT: u32 = 980;
 
@@ -227,6 +244,11 @@ pub struct HostFunctionCosts {
     pub remove_contract_user_group_urefs: HostFunction<[Cost; 6]>,
     pub print: HostFunction<[Cost; 2]>,
     pub blake2b: HostFunction<[Cost; 4]>,
+    pub emit_event: HostFunction<[Cost; 4]>,
+    pub verify_signature: HostFunction<[Cost; 6]>,
+    pub new_dictionary: HostFunction<[Cost; 2]>,
+    pub dictionary_get: HostFunction<[Cost; 5]>,
+    pub dictionary_put: HostFunction<[Cost; 6]>,
 }
 
 impl Default for HostFunctionCosts {
@@ -344,6 +366,48 @@ impl Default for HostFunctionCosts {
                 [NOT_USED, DEFAULT_PRINT_TEXT_SIZE_WEIGHT],
             ),
             blake2b: HostFunction::default(),
+            emit_event: HostFunction::new(
+                DEFAULT_EMIT_EVENT_COST,
+                [
+                    NOT_USED,
+                    DEFAULT_EMIT_EVENT_TOPIC_SIZE_WEIGHT,
+                    NOT_USED,
+                    DEFAULT_EMIT_EVENT_PAYLOAD_SIZE_WEIGHT,
+                ],
+            ),
+            verify_signature: HostFunction::new(
+                DEFAULT_VERIFY_SIGNATURE_COST,
+                [
+                    NOT_USED,
+                    NOT_USED,
+                    NOT_USED,
+                    DEFAULT_VERIFY_SIGNATURE_MESSAGE_SIZE_WEIGHT,
+                    NOT_USED,
+                    NOT_USED,
+                ],
+            ),
+            new_dictionary: HostFunction::fixed(DEFAULT_NEW_DICTIONARY_COST),
+            dictionary_get: HostFunction::new(
+                DEFAULT_DICTIONARY_GET_COST,
+                [
+                    NOT_USED,
+                    NOT_USED,
+                    NOT_USED,
+                    DEFAULT_DICTIONARY_GET_KEY_SIZE_WEIGHT,
+                    NOT_USED,
+                ],
+            ),
+            dictionary_put: HostFunction::new(
+                DEFAULT_DICTIONARY_PUT_COST,
+                [
+                    NOT_USED,
+                    NOT_USED,
+                    NOT_USED,
+                    DEFAULT_DICTIONARY_PUT_KEY_BYTES_SIZE_WEIGHT,
+                    NOT_USED,
+                    DEFAULT_DICTIONARY_PUT_VALUE_SIZE_WEIGHT,
+                ],
+            ),
         }
     }
 }
@@ -393,6 +457,11 @@ impl ToBytes for HostFunctionCosts {
         ret.append(&mut self.remove_contract_user_group_urefs.to_bytes()?);
         ret.append(&mut self.print.to_bytes()?);
         ret.append(&mut self.blake2b.to_bytes()?);
+        ret.append(&mut self.emit_event.to_bytes()?);
+        ret.append(&mut self.verify_signature.to_bytes()?);
+        ret.append(&mut self.new_dictionary.to_bytes()?);
+        ret.append(&mut self.dictionary_get.to_bytes()?);
+        ret.append(&mut self.dictionary_put.to_bytes()?);
         Ok(ret)
     }
 
@@ -439,11 +508,26 @@ impl ToBytes for HostFunctionCosts {
             + self.remove_contract_user_group_urefs.serialized_length()
             + self.print.serialized_length()
             + self.blake2b.serialized_length()
+            + self.emit_event.serialized_length()
+            + self.verify_signature.serialized_length()
+            + self.new_dictionary.serialized_length()
+            + self.dictionary_get.serialized_length()
+            + self.dictionary_put.serialized_length()
     }
 }
 
-impl FromBytes for HostFunctionCosts {
-    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+impl HostFunctionCosts {
+    /// Deserializes costs in the layout used before the host functions following `blake2b` were
+    /// added, as found in protocol data written by earlier versions.  The costs of the added host
+    /// functions are set to their defaults.
+    pub(crate) fn from_legacy_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        Self::from_bytes_with_layout(bytes, true)
+    }
+
+    fn from_bytes_with_layout(
+        bytes: &[u8],
+        is_legacy: bool,
+    ) -> Result<(Self, &[u8]), bytesrepr::Error> {
         let (read_value, rem) = FromBytes::from_bytes(bytes)?;
         let (read_value_local, rem) = FromBytes::from_bytes(rem)?;
         let (write, rem) = FromBytes::from_bytes(rem)?;
@@ -486,6 +570,13 @@ impl FromBytes for HostFunctionCosts {
         let (remove_contract_user_group_urefs, rem) = FromBytes::from_bytes(rem)?;
         let (print, rem) = FromBytes::from_bytes(rem)?;
         let (blake2b, rem) = FromBytes::from_bytes(rem)?;
+        let defaults = HostFunctionCosts::default();
+        let (emit_event, rem) = added_cost_from_bytes(rem, is_legacy, defaults.emit_event)?;
+        let (verify_signature, rem) =
+            added_cost_from_bytes(rem, is_legacy, defaults.verify_signature)?;
+        let (new_dictionary, rem) = added_cost_from_bytes(rem, is_legacy, defaults.new_dictionary)?;
+        let (dictionary_get, rem) = added_cost_from_bytes(rem, is_legacy, defaults.dictionary_get)?;
+        let (dictionary_put, rem) = added_cost_from_bytes(rem, is_legacy, defaults.dictionary_put)?;
         Ok((
             HostFunctionCosts {
                 read_value,
@@ -530,12 +621,37 @@ impl FromBytes for HostFunctionCosts {
                 remove_contract_user_group_urefs,
                 print,
                 blake2b,
+                emit_event,
+                verify_signature,
+                new_dictionary,
+                dictionary_get,
+                dictionary_put,
             },
             rem,
         ))
     }
 }
 
+/// Deserializes the cost of a host function added after the legacy layout, or returns `default`
+/// if the bytes are in the legacy layout.
+fn added_cost_from_bytes<T: FromBytes>(
+    bytes: &[u8],
+    is_legacy: bool,
+    default: T,
+) -> Result<(T, &[u8]), bytesrepr::Error> {
+    if is_legacy {
+        Ok((default, bytes))
+    } else {
+        T::from_bytes(bytes)
+    }
+}
+
+impl FromBytes for HostFunctionCosts {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        Self::from_bytes_with_layout(bytes, false)
+    }
+}
+
 impl Distribution<HostFunctionCosts> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> HostFunctionCosts {
         HostFunctionCosts {
@@ -581,6 +697,11 @@ impl Distribution<HostFunctionCosts> for Standard {
             remove_contract_user_group_urefs: rng.gen(),
             print: rng.gen(),
             blake2b: rng.gen(),
+            emit_event: rng.gen(),
+            verify_signature: rng.gen(),
+            new_dictionary: rng.gen(),
+            dictionary_get: rng.gen(),
+            dictionary_put: rng.gen(),
         }
     }
 }
@@ -639,6 +760,11 @@ pub mod gens {
             remove_contract_user_group_urefs in host_function_cost_arb(),
             print in host_function_cost_arb(),
             blake2b in host_function_cost_arb(),
+            emit_event in host_function_cost_arb(),
+            verify_signature in host_function_cost_arb(),
+            new_dictionary in host_function_cost_arb(),
+            dictionary_get in host_function_cost_arb(),
+            dictionary_put in host_function_cost_arb(),
         ) -> HostFunctionCosts {
             HostFunctionCosts {
                 read_value,
@@ -683,6 +809,11 @@ pub mod gens {
                 remove_contract_user_group_urefs,
                 print,
                 blake2b,
+                emit_event,
+                verify_signature,
+                new_dictionary,
+                dictionary_get,
+                dictionary_put,
             }
         }
     }
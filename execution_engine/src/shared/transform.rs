@@ -368,8 +368,9 @@ impl From<&Transform> for casper_types::Transform {
 pub mod gens {
     use proptest::{collection::vec, prelude::*};
 
+    use casper_types::gens::stored_value_arb;
+
     use super::Transform;
-    use crate::shared::stored_value::gens::stored_value_arb;
 
     pub fn transform_arb() -> impl Strategy<Value = Transform> {
         prop_oneof![
@@ -89,13 +89,26 @@ impl ToBytes for WasmConfig {
     }
 }
 
-impl FromBytes for WasmConfig {
-    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+impl WasmConfig {
+    /// Deserializes a config whose host function costs are in the legacy layout.
+    ///
+    /// See [`HostFunctionCosts::from_legacy_bytes`].
+    pub(crate) fn from_legacy_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        Self::from_bytes_with(bytes, HostFunctionCosts::from_legacy_bytes)
+    }
+
+    fn from_bytes_with(
+        bytes: &[u8],
+        host_function_costs_from_bytes: fn(
+            &[u8],
+        )
+            -> Result<(HostFunctionCosts, &[u8]), bytesrepr::Error>,
+    ) -> Result<(Self, &[u8]), bytesrepr::Error> {
         let (max_memory, rem) = FromBytes::from_bytes(bytes)?;
         let (max_stack_height, rem) = FromBytes::from_bytes(rem)?;
         let (opcode_costs, rem) = FromBytes::from_bytes(rem)?;
         let (storage_costs, rem) = FromBytes::from_bytes(rem)?;
-        let (host_function_costs, rem) = FromBytes::from_bytes(rem)?;
+        let (host_function_costs, rem) = host_function_costs_from_bytes(rem)?;
 
         Ok((
             WasmConfig {
@@ -110,6 +123,12 @@ impl FromBytes for WasmConfig {
     }
 }
 
+impl FromBytes for WasmConfig {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        Self::from_bytes_with(bytes, HostFunctionCosts::from_bytes)
+    }
+}
+
 impl Distribution<WasmConfig> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> WasmConfig {
         WasmConfig {
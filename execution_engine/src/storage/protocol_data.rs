@@ -171,9 +171,12 @@ impl ToBytes for ProtocolData {
     }
 }
 
-impl FromBytes for ProtocolData {
-    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
-        let (wasm_config, rem) = WasmConfig::from_bytes(bytes)?;
+impl ProtocolData {
+    fn from_bytes_with(
+        bytes: &[u8],
+        wasm_config_from_bytes: fn(&[u8]) -> Result<(WasmConfig, &[u8]), bytesrepr::Error>,
+    ) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (wasm_config, rem) = wasm_config_from_bytes(bytes)?;
         let (system_config, rem) = FromBytes::from_bytes(rem)?;
         let (mint, rem) = HashAddr::from_bytes(rem)?;
         let (handle_payment, rem) = HashAddr::from_bytes(rem)?;
@@ -194,6 +197,17 @@ impl FromBytes for ProtocolData {
     }
 }
 
+impl FromBytes for ProtocolData {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        // Protocol data written by earlier versions lacks the costs of host functions added since,
+        // and is too short to be read in the current layout.  Fall back to the legacy layout,
+        // which charges the default costs for those host functions.
+        Self::from_bytes_with(bytes, WasmConfig::from_bytes).or_else(|error| {
+            Self::from_bytes_with(bytes, WasmConfig::from_legacy_bytes).map_err(|_| error)
+        })
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod gens {
     use proptest::prop_compose;
@@ -230,8 +244,14 @@ pub(crate) mod gens {
 mod tests {
     use proptest::proptest;
 
-    use crate::shared::{system_config::SystemConfig, wasm_config::WasmConfig};
-    use casper_types::{bytesrepr, ContractHash};
+    use crate::shared::{
+        host_function_costs::HostFunctionCosts, system_config::SystemConfig,
+        wasm_config::WasmConfig,
+    };
+    use casper_types::{
+        bytesrepr::{self, ToBytes},
+        ContractHash,
+    };
 
     use super::{gens, ProtocolData};
 
@@ -301,6 +321,34 @@ mod tests {
         assert_eq!(actual[2], auction_reference);
     }
 
+    #[test]
+    fn should_deserialize_legacy_protocol_data() {
+        let protocol_data = ProtocolData::new(
+            WasmConfig::default(),
+            SystemConfig::default(),
+            [1u8; 32].into(),
+            [2u8; 32].into(),
+            [3u8; 32].into(),
+            [4u8; 32].into(),
+        );
+        let bytes = protocol_data.to_bytes().unwrap();
+
+        // The host function costs are the last field of the Wasm config, and the legacy layout
+        // lacks the costs of the host functions added after `blake2b`.
+        let costs = HostFunctionCosts::default();
+        let added_costs_length = costs.emit_event.serialized_length()
+            + costs.verify_signature.serialized_length()
+            + costs.new_dictionary.serialized_length()
+            + costs.dictionary_get.serialized_length()
+            + costs.dictionary_put.serialized_length();
+        let wasm_config_length = protocol_data.wasm_config().serialized_length();
+        let mut legacy_bytes = bytes[..wasm_config_length - added_costs_length].to_vec();
+        legacy_bytes.extend_from_slice(&bytes[wasm_config_length..]);
+
+        let decoded: ProtocolData = bytesrepr::deserialize(legacy_bytes).unwrap();
+        assert_eq!(decoded, protocol_data);
+    }
+
     proptest! {
         #[test]
         fn should_serialize_and_deserialize_with_arbitrary_values(
@@ -107,6 +107,13 @@ impl<'a> Writable for InMemoryReadWriteTransaction<'a> {
         sub_view.insert(Bytes::from(key), Bytes::from(value));
         Ok(())
     }
+
+    fn delete(&mut self, handle: Self::Handle, key: &[u8]) -> Result<(), Self::Error> {
+        if let Some(sub_view) = self.view.get_mut(&handle) {
+            sub_view.remove(&Bytes::from(key));
+        }
+        Ok(())
+    }
 }
 
 /// An environment for the in-memory trie store.
@@ -13,7 +13,10 @@ use serde::{
 };
 
 use crate::shared::newtypes::Blake2bHash;
-use casper_types::bytesrepr::{self, Bytes, FromBytes, ToBytes, U8_SERIALIZED_LENGTH};
+use casper_types::{
+    bytesrepr::{self, Bytes, FromBytes, ToBytes, U8_SERIALIZED_LENGTH},
+    TriePointer,
+};
 
 #[cfg(test)]
 pub mod gens;
@@ -96,6 +99,15 @@ impl FromBytes for Pointer {
     }
 }
 
+impl From<Pointer> for TriePointer {
+    fn from(pointer: Pointer) -> Self {
+        match pointer {
+            Pointer::LeafPointer(hash) => TriePointer::LeafPointer(hash.value()),
+            Pointer::NodePointer(hash) => TriePointer::NodePointer(hash.value()),
+        }
+    }
+}
+
 pub type PointerBlockValue = Option<Pointer>;
 pub type PointerBlockArray = [PointerBlockValue; RADIX];
 
@@ -141,6 +141,11 @@ impl LmdbTrieStore {
         Ok(LmdbTrieStore { db })
     }
 
+    /// Returns the underlying database.
+    pub fn get_db(&self) -> Database {
+        self.db
+    }
+
     fn name(maybe_name: Option<&str>) -> String {
         maybe_name
             .map(|name| format!("{}-{}", trie_store::NAME, name))
@@ -185,6 +185,7 @@ where
                 let indexed_pointers_with_hole = pointer_block
                     .to_indexed_pointers()
                     .filter(|(index, _)| *index as usize != hole_index)
+                    .map(|(index, pointer)| (index, pointer.into()))
                     .collect();
                 let next = match store.get(txn, pointer.hash())? {
                     Some(next) => next,
@@ -294,6 +295,62 @@ where
     Ok(missing_descendants)
 }
 
+/// Visits up to `max_visits` trie keys from `trie_keys_to_visit`, adding each one to `reachable`
+/// and queueing the keys of its descendants.
+///
+/// Keys which are already in `reachable` are not descended into again, since their descendants
+/// were queued when they were first marked.  Keys which are referenced but not present in the
+/// store are still marked, but have no descendants to queue.
+///
+/// Returns the number of newly marked keys.  The traversal is complete once `trie_keys_to_visit`
+/// is empty.
+pub fn mark_reachable_trie_keys<K, V, T, S, E>(
+    _correlation_id: CorrelationId,
+    txn: &T,
+    store: &S,
+    trie_keys_to_visit: &mut Vec<Blake2bHash>,
+    reachable: &mut HashSet<Blake2bHash>,
+    max_visits: usize,
+) -> Result<usize, E>
+where
+    K: ToBytes + FromBytes + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + std::fmt::Debug,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<bytesrepr::Error>,
+{
+    let mut marked = 0;
+    while marked < max_visits {
+        let trie_key = match trie_keys_to_visit.pop() {
+            Some(trie_key) => trie_key,
+            None => break,
+        };
+        if !reachable.insert(trie_key) {
+            continue;
+        }
+        marked += 1;
+        match store.get(txn, &trie_key)? {
+            None | Some(Trie::Leaf { .. }) => (),
+            Some(Trie::Node { pointer_block }) => {
+                for (_, pointer) in pointer_block.to_indexed_pointers() {
+                    let descendant_trie_key = pointer.into_hash();
+                    if !reachable.contains(&descendant_trie_key) {
+                        trie_keys_to_visit.push(descendant_trie_key);
+                    }
+                }
+            }
+            Some(Trie::Extension { pointer, .. }) => {
+                let descendant_trie_key = pointer.into_hash();
+                if !reachable.contains(&descendant_trie_key) {
+                    trie_keys_to_visit.push(descendant_trie_key);
+                }
+            }
+        }
+    }
+    Ok(marked)
+}
+
 struct TrieScan<K, V> {
     tip: Trie<K, V>,
     parents: Parents<K, V>,
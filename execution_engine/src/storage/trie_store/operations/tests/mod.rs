@@ -680,7 +680,7 @@ where
                 read_with_proof::<_, _, _, _, E>(correlation_id, txn, store, root, key)?;
             match maybe_proof {
                 ReadResult::Found(proof) => {
-                    let hash = proof.compute_state_hash()?;
+                    let hash = Blake2bHash::from(proof.compute_state_hash()?);
                     ret.push(hash == *root && proof.value() == value);
                 }
                 ReadResult::NotFound => {
@@ -847,7 +847,7 @@ where
                 read_with_proof::<_, _, _, _, E>(correlation_id, &txn, store, root_hash, key)?;
             match maybe_proof {
                 ReadResult::Found(proof) => {
-                    let hash = proof.compute_state_hash()?;
+                    let hash = Blake2bHash::from(proof.compute_state_hash()?);
                     if hash != *root_hash || proof.value() != value {
                         return Ok(false);
                     }
@@ -0,0 +1,377 @@
+//! Incremental mark-and-sweep pruning of an LMDB-backed trie store.
+//!
+//! A pruning cycle starts from a set of retained state roots.  During the mark phase every `Trie`
+//! reachable from those roots is recorded, a batch at a time.  During the sweep phase the store is
+//! scanned in key order, again a batch at a time, and every `Trie` which was not marked is
+//! deleted.
+//!
+//! Commits may happen between batches.  Any root produced while a cycle is running must be passed
+//! to [`LmdbTriePruner::protect`], which ensures nothing reachable from it is swept.  Each sweep
+//! batch runs in a single read-write transaction, so it cannot interleave with a commit.
+use std::{collections::HashSet, convert::TryFrom, mem, sync::Arc};
+
+use lmdb::Cursor;
+
+use casper_types::{bytesrepr, Key};
+
+use crate::{
+    shared::{
+        newtypes::{Blake2bHash, CorrelationId},
+        stored_value::StoredValue,
+    },
+    storage::{
+        error,
+        store::Store,
+        transaction_source::{lmdb::LmdbEnvironment, Transaction, TransactionSource},
+        trie::Trie,
+        trie_store::{lmdb::LmdbTrieStore, operations::mark_reachable_trie_keys},
+    },
+};
+
+/// The outcome of a single pruning step.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruningProgress {
+    /// The number of `Trie` values marked as reachable during the step.
+    pub marked: usize,
+    /// The number of unreachable `Trie` values deleted during the step.
+    pub deleted: usize,
+    /// Whether the step completed the current pruning cycle.
+    pub finished: bool,
+}
+
+enum Phase {
+    Idle,
+    Marking {
+        trie_keys_to_visit: Vec<Blake2bHash>,
+        reachable: HashSet<Blake2bHash>,
+    },
+    Sweeping {
+        protected_roots: Vec<Blake2bHash>,
+        reachable: HashSet<Blake2bHash>,
+        last_swept: Option<Blake2bHash>,
+    },
+}
+
+/// Removes `Trie` values which are not reachable from a set of retained state roots.
+pub struct LmdbTriePruner {
+    environment: Arc<LmdbEnvironment>,
+    trie_store: Arc<LmdbTrieStore>,
+    batch_size: usize,
+    phase: Phase,
+}
+
+impl LmdbTriePruner {
+    /// Creates a pruner which visits at most `batch_size` trie keys per step.
+    pub fn new(
+        environment: Arc<LmdbEnvironment>,
+        trie_store: Arc<LmdbTrieStore>,
+        batch_size: usize,
+    ) -> Self {
+        LmdbTriePruner {
+            environment,
+            trie_store,
+            batch_size: batch_size.max(1),
+            phase: Phase::Idle,
+        }
+    }
+
+    /// Returns `true` if no pruning cycle is in progress.
+    pub fn is_idle(&self) -> bool {
+        matches!(self.phase, Phase::Idle)
+    }
+
+    /// Starts a new pruning cycle retaining everything reachable from `retained_roots`.
+    ///
+    /// Returns `false` and does nothing if a cycle is already in progress.
+    pub fn start(&mut self, retained_roots: Vec<Blake2bHash>) -> bool {
+        if !self.is_idle() {
+            return false;
+        }
+        self.phase = Phase::Marking {
+            trie_keys_to_visit: retained_roots,
+            reachable: HashSet::new(),
+        };
+        true
+    }
+
+    /// Ensures that nothing reachable from `trie_key` is removed by the cycle in progress.
+    ///
+    /// Must be called for every root committed, and every trie put, while a cycle is running.
+    pub fn protect(&mut self, trie_key: Blake2bHash) {
+        match &mut self.phase {
+            Phase::Idle => (),
+            Phase::Marking {
+                trie_keys_to_visit, ..
+            } => trie_keys_to_visit.push(trie_key),
+            Phase::Sweeping {
+                protected_roots, ..
+            } => protected_roots.push(trie_key),
+        }
+    }
+
+    /// Performs one batch of work of the cycle in progress.
+    pub fn step(&mut self, correlation_id: CorrelationId) -> Result<PruningProgress, error::Error> {
+        match mem::replace(&mut self.phase, Phase::Idle) {
+            Phase::Idle => Ok(PruningProgress {
+                finished: true,
+                ..PruningProgress::default()
+            }),
+            Phase::Marking {
+                mut trie_keys_to_visit,
+                mut reachable,
+            } => {
+                let txn = self.environment.create_read_txn()?;
+                let marked = mark_reachable_trie_keys::<Key, StoredValue, _, _, error::Error>(
+                    correlation_id,
+                    &txn,
+                    self.trie_store.as_ref(),
+                    &mut trie_keys_to_visit,
+                    &mut reachable,
+                    self.batch_size,
+                )?;
+                txn.commit()?;
+                self.phase = if trie_keys_to_visit.is_empty() {
+                    Phase::Sweeping {
+                        protected_roots: Vec::new(),
+                        reachable,
+                        last_swept: None,
+                    }
+                } else {
+                    Phase::Marking {
+                        trie_keys_to_visit,
+                        reachable,
+                    }
+                };
+                Ok(PruningProgress {
+                    marked,
+                    ..PruningProgress::default()
+                })
+            }
+            Phase::Sweeping {
+                mut protected_roots,
+                mut reachable,
+                last_swept,
+            } => {
+                let mut txn = self.environment.create_read_write_txn()?;
+
+                // Roots committed since the mark phase finished share most of their tries with
+                // the retained roots, so marking them to completion here is cheap.
+                let marked = mark_reachable_trie_keys::<Key, StoredValue, _, _, error::Error>(
+                    correlation_id,
+                    &txn,
+                    self.trie_store.as_ref(),
+                    &mut protected_roots,
+                    &mut reachable,
+                    usize::MAX,
+                )?;
+
+                let trie_keys = {
+                    let mut cursor =
+                        lmdb::Transaction::open_ro_cursor(&txn, self.trie_store.get_db())?;
+                    let iter = match &last_swept {
+                        Some(last_swept) => cursor.iter_from(last_swept),
+                        None => cursor.iter_start(),
+                    };
+                    let last_swept_bytes: Option<&[u8]> = last_swept.as_ref().map(AsRef::as_ref);
+                    iter.map(|(raw_key, _)| raw_key)
+                        .filter(|raw_key| Some(*raw_key) != last_swept_bytes)
+                        .take(self.batch_size)
+                        .map(|raw_key| {
+                            Blake2bHash::try_from(raw_key).map_err(|_| bytesrepr::Error::Formatting)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?
+                };
+
+                let mut deleted = 0;
+                for trie_key in &trie_keys {
+                    if !reachable.contains(trie_key) {
+                        Store::<Blake2bHash, Trie<Key, StoredValue>>::delete(
+                            self.trie_store.as_ref(),
+                            &mut txn,
+                            trie_key,
+                        )?;
+                        deleted += 1;
+                    }
+                }
+                txn.commit()?;
+
+                let finished = trie_keys.len() < self.batch_size;
+                if !finished {
+                    self.phase = Phase::Sweeping {
+                        protected_roots,
+                        reachable,
+                        last_swept: trie_keys.last().copied(),
+                    };
+                }
+                Ok(PruningProgress {
+                    marked,
+                    deleted,
+                    finished,
+                })
+            }
+        }
+    }
+
+    /// Runs a complete pruning cycle, retaining everything reachable from `retained_roots`.
+    ///
+    /// If a cycle is already in progress, that cycle is run to completion instead.  Returns the
+    /// total number of deleted `Trie` values.
+    pub fn prune(
+        &mut self,
+        correlation_id: CorrelationId,
+        retained_roots: Vec<Blake2bHash>,
+    ) -> Result<usize, error::Error> {
+        self.start(retained_roots);
+        let mut deleted = 0;
+        loop {
+            let progress = self.step(correlation_id)?;
+            deleted += progress.deleted;
+            if progress.finished {
+                return Ok(deleted);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lmdb::DatabaseFlags;
+    use tempfile::tempdir;
+
+    use casper_types::{account::AccountHash, CLValue};
+
+    use super::*;
+    use crate::{
+        shared::{additive_map::AdditiveMap, transform::Transform},
+        storage::{
+            global_state::{lmdb::LmdbGlobalState, CommitResult, StateProvider, StateReader},
+            protocol_data_store::lmdb::LmdbProtocolDataStore,
+            DEFAULT_TEST_MAX_DB_SIZE, DEFAULT_TEST_MAX_READERS,
+        },
+    };
+
+    const BATCH_SIZE: usize = 2;
+
+    fn account_key(seed: u8) -> Key {
+        Key::Account(AccountHash::new([seed; 32]))
+    }
+
+    fn value(n: i32) -> StoredValue {
+        StoredValue::CLValue(CLValue::from_t(n).unwrap())
+    }
+
+    fn commit(state: &LmdbGlobalState, root: Blake2bHash, writes: &[(Key, i32)]) -> Blake2bHash {
+        let mut effects = AdditiveMap::new();
+        for (key, n) in writes {
+            effects.insert(*key, Transform::Write(value(*n)));
+        }
+        match state.commit(CorrelationId::new(), root, effects).unwrap() {
+            CommitResult::Success { state_root } => state_root,
+            _ => panic!("commit failed"),
+        }
+    }
+
+    fn trie_count(state: &LmdbGlobalState) -> usize {
+        let txn = state.environment.create_read_txn().unwrap();
+        let mut cursor =
+            lmdb::Transaction::open_ro_cursor(&txn, state.trie_store.get_db()).unwrap();
+        cursor.iter_start().count()
+    }
+
+    fn create_state() -> (tempfile::TempDir, LmdbGlobalState) {
+        let temp_dir = tempdir().unwrap();
+        let environment = Arc::new(
+            LmdbEnvironment::new(
+                &temp_dir.path().to_path_buf(),
+                DEFAULT_TEST_MAX_DB_SIZE,
+                DEFAULT_TEST_MAX_READERS,
+            )
+            .unwrap(),
+        );
+        let trie_store =
+            Arc::new(LmdbTrieStore::new(&environment, None, DatabaseFlags::empty()).unwrap());
+        let protocol_data_store = Arc::new(
+            LmdbProtocolDataStore::new(&environment, None, DatabaseFlags::empty()).unwrap(),
+        );
+        let state = LmdbGlobalState::empty(environment, trie_store, protocol_data_store).unwrap();
+        (temp_dir, state)
+    }
+
+    fn pruner(state: &LmdbGlobalState) -> LmdbTriePruner {
+        LmdbTriePruner::new(
+            Arc::clone(&state.environment),
+            Arc::clone(&state.trie_store),
+            BATCH_SIZE,
+        )
+    }
+
+    #[test]
+    fn should_prune_tries_unreachable_from_retained_roots() {
+        let correlation_id = CorrelationId::new();
+        let (_temp_dir, state) = create_state();
+
+        let root_1 = commit(&state, state.empty_root_hash, &[(account_key(1), 1)]);
+        let root_2 = commit(&state, root_1, &[(account_key(2), 2)]);
+        let root_3 = commit(&state, root_2, &[(account_key(1), 3)]);
+        let count_before = trie_count(&state);
+
+        let deleted = pruner(&state).prune(correlation_id, vec![root_3]).unwrap();
+        assert!(deleted > 0);
+        assert_eq!(trie_count(&state), count_before - deleted);
+
+        let checkout = state.checkout(root_3).unwrap().unwrap();
+        assert_eq!(
+            checkout.read(correlation_id, &account_key(1)).unwrap(),
+            Some(value(3))
+        );
+        assert_eq!(
+            checkout.read(correlation_id, &account_key(2)).unwrap(),
+            Some(value(2))
+        );
+        assert!(state.checkout(root_1).unwrap().is_none());
+        assert!(state.checkout(root_2).unwrap().is_none());
+    }
+
+    #[test]
+    fn should_not_prune_anything_reachable_from_any_retained_root() {
+        let correlation_id = CorrelationId::new();
+        let (_temp_dir, state) = create_state();
+
+        let root_1 = commit(&state, state.empty_root_hash, &[(account_key(1), 1)]);
+        let root_2 = commit(&state, root_1, &[(account_key(2), 2)]);
+
+        pruner(&state)
+            .prune(correlation_id, vec![state.empty_root_hash, root_1, root_2])
+            .unwrap();
+
+        let missing = state
+            .missing_trie_keys(correlation_id, vec![state.empty_root_hash, root_1, root_2])
+            .unwrap();
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn should_not_prune_roots_protected_during_cycle() {
+        let correlation_id = CorrelationId::new();
+        let (_temp_dir, state) = create_state();
+
+        let root_1 = commit(&state, state.empty_root_hash, &[(account_key(1), 1)]);
+        let mut pruner = pruner(&state);
+        assert!(pruner.start(vec![root_1]));
+
+        // Finish the mark phase, then commit a new root before sweeping.
+        while let Phase::Marking { .. } = pruner.phase {
+            pruner.step(correlation_id).unwrap();
+        }
+        let root_2 = commit(&state, root_1, &[(account_key(2), 2), (account_key(3), 3)]);
+        pruner.protect(root_2);
+
+        while !pruner.step(correlation_id).unwrap().finished {}
+        assert!(pruner.is_idle());
+
+        let missing = state
+            .missing_trie_keys(correlation_id, vec![root_1, root_2])
+            .unwrap();
+        assert!(missing.is_empty());
+    }
+}
@@ -404,6 +404,18 @@ where
         Err(format!("{:?}", query_result))
     }
 
+    /// Queries the item stored under `dictionary_item_key` in the dictionary identified by
+    /// `seed_uref`.
+    pub fn query_dictionary_item(
+        &self,
+        maybe_post_state: Option<Blake2bHash>,
+        seed_uref: URef,
+        dictionary_item_key: &str,
+    ) -> Result<StoredValue, String> {
+        let dictionary_key = Key::dictionary(seed_uref, dictionary_item_key.as_bytes());
+        self.query(maybe_post_state, dictionary_key, &[])
+    }
+
     pub fn query_with_proof(
         &self,
         maybe_post_state: Option<Blake2bHash>,
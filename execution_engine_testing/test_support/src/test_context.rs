@@ -120,6 +120,19 @@ impl TestContext {
             .map_err(Error::from)
     }
 
+    /// Queries for the item stored under `dictionary_item_key` in the dictionary identified by
+    /// `seed_uref`.
+    pub fn query_dictionary_item(
+        &self,
+        seed_uref: URef,
+        dictionary_item_key: &str,
+    ) -> Result<Value> {
+        self.inner
+            .query_dictionary_item(None, seed_uref, dictionary_item_key)
+            .map(Value::new)
+            .map_err(Error::from)
+    }
+
     /// Gets the balance of the purse under the given [`URefAddr`].
     ///
     /// Note that this requires performing an earlier query to retrieve `purse_addr`.
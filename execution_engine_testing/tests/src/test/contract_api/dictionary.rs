@@ -0,0 +1,88 @@
+use assert_matches::assert_matches;
+
+use casper_engine_test_support::{
+    internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::{
+    core::{engine_state::Error, execution},
+    shared::stored_value::StoredValue,
+};
+use casper_types::{
+    runtime_args, ApiError, CLValue, RuntimeArgs, DICTIONARY_ITEM_KEY_MAX_LENGTH,
+};
+
+const DICTIONARY_WASM: &str = "dictionary.wasm";
+const ARG_DICTIONARY_ITEM_KEY: &str = "dictionary_item_key";
+const ARG_VALUE: &str = "value";
+const DICTIONARY_SEED_UREF: &str = "dictionary_seed_uref";
+const DICTIONARY_ITEM_KEY: &str = "alice";
+const VALUE: &str = "hello";
+
+#[ignore]
+#[test]
+fn should_put_and_query_dictionary_item() {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        DICTIONARY_WASM,
+        runtime_args! { ARG_DICTIONARY_ITEM_KEY => DICTIONARY_ITEM_KEY, ARG_VALUE => VALUE },
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .expect_success()
+        .commit();
+
+    let account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have account");
+    let seed_uref = *account
+        .named_keys()
+        .get(DICTIONARY_SEED_UREF)
+        .expect("should have seed uref")
+        .as_uref()
+        .expect("should be uref");
+
+    let stored_value = builder
+        .query_dictionary_item(None, seed_uref, DICTIONARY_ITEM_KEY)
+        .expect("should have dictionary item");
+    let expected_value = CLValue::from_t(VALUE.to_string()).expect("should create CLValue");
+    assert_eq!(stored_value, StoredValue::CLValue(expected_value));
+
+    assert!(builder
+        .query_dictionary_item(None, seed_uref, "bob")
+        .is_err());
+}
+
+#[ignore]
+#[test]
+fn should_fail_to_put_dictionary_item_with_too_long_key() {
+    let dictionary_item_key = "a".repeat(DICTIONARY_ITEM_KEY_MAX_LENGTH + 1);
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        DICTIONARY_WASM,
+        runtime_args! { ARG_DICTIONARY_ITEM_KEY => dictionary_item_key, ARG_VALUE => VALUE },
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .commit();
+
+    let exec_result = builder
+        .get_exec_result(0)
+        .expect("should have exec response")
+        .first()
+        .cloned()
+        .expect("should have exec result");
+
+    assert_matches!(
+        exec_result.as_error(),
+        Some(Error::Exec(execution::Error::Revert(ApiError::InvalidArgument)))
+    );
+}
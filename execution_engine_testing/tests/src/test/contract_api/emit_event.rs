@@ -0,0 +1,75 @@
+use assert_matches::assert_matches;
+
+use casper_engine_test_support::{
+    internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_execution_engine::core::{engine_state::Error, execution};
+use casper_types::{runtime_args, ApiError, CLValue, ContractEvent, RuntimeArgs};
+
+const EMIT_EVENT_WASM: &str = "emit_event.wasm";
+const ARG_TOPIC: &str = "topic";
+const ARG_PAYLOAD: &str = "payload";
+const TOPIC: &str = "token_minted";
+const PAYLOAD: u64 = 1_000;
+
+#[ignore]
+#[test]
+fn should_record_emitted_event_in_execution_result() {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        EMIT_EVENT_WASM,
+        runtime_args! { ARG_TOPIC => TOPIC, ARG_PAYLOAD => PAYLOAD },
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .expect_success()
+        .commit();
+
+    let exec_result = builder
+        .get_exec_result(0)
+        .expect("should have exec response")
+        .first()
+        .cloned()
+        .expect("should have exec result");
+
+    let expected_event = ContractEvent {
+        topic: TOPIC.to_string(),
+        payload: CLValue::from_t(PAYLOAD).expect("should create CLValue"),
+    };
+    assert_eq!(exec_result.events(), &[expected_event]);
+}
+
+#[ignore]
+#[test]
+fn should_fail_to_emit_event_with_empty_topic() {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        EMIT_EVENT_WASM,
+        runtime_args! { ARG_TOPIC => "", ARG_PAYLOAD => PAYLOAD },
+    )
+    .build();
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request)
+        .commit();
+
+    let exec_result = builder
+        .get_exec_result(0)
+        .expect("should have exec response")
+        .first()
+        .cloned()
+        .expect("should have exec result");
+
+    assert!(exec_result.events().is_empty());
+    assert_matches!(
+        exec_result.as_error(),
+        Some(Error::Exec(execution::Error::Revert(ApiError::InvalidArgument)))
+    );
+}
@@ -1,6 +1,8 @@
 mod account;
 mod blake2b;
 mod create_purse;
+mod dictionary;
+mod emit_event;
 mod get_arg;
 mod get_blocktime;
 mod get_caller;
@@ -15,3 +17,4 @@ mod transfer_purse_to_account;
 mod transfer_purse_to_purse;
 mod transfer_stored;
 mod transfer_u512_stored;
+mod verify_signature;
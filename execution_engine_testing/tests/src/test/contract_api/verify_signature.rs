@@ -0,0 +1,90 @@
+use ed25519_dalek::ExpandedSecretKey;
+
+use casper_engine_test_support::{
+    internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    runtime_args, PublicKey, RuntimeArgs, Signature,
+};
+
+const VERIFY_SIGNATURE_WASM: &str = "verify_signature.wasm";
+const ARG_PUBLIC_KEY: &str = "public_key";
+const ARG_MESSAGE: &str = "message";
+const ARG_SIGNATURE: &str = "signature";
+const VERIFICATION_RESULT: &str = "verification_result";
+const MESSAGE: &[u8] = b"casper";
+
+fn ed25519_keypair(seed: u8) -> (ed25519_dalek::SecretKey, PublicKey) {
+    let secret_key = ed25519_dalek::SecretKey::from_bytes(&[seed; 32]).expect("should create key");
+    let public_key = PublicKey::Ed25519((&secret_key).into());
+    (secret_key, public_key)
+}
+
+fn sign(message: &[u8], secret_key: &ed25519_dalek::SecretKey) -> Signature {
+    let public_key = secret_key.into();
+    let signature = ExpandedSecretKey::from(secret_key).sign(message, &public_key);
+    Signature::Ed25519(signature.to_bytes())
+}
+
+fn verify_signature(public_key: PublicKey, message: &[u8], signature: Signature) -> bool {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        VERIFY_SIGNATURE_WASM,
+        runtime_args! {
+            ARG_PUBLIC_KEY => public_key,
+            ARG_MESSAGE => Bytes::from(message.to_vec()),
+            ARG_SIGNATURE => Bytes::from(signature.to_bytes().expect("should serialize")),
+        },
+    )
+    .build();
+
+    builder.exec(exec_request).expect_success().commit();
+
+    let account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have account");
+
+    let uref = account
+        .named_keys()
+        .get(VERIFICATION_RESULT)
+        .expect("should have value");
+
+    builder
+        .query(None, *uref, &[])
+        .expect("should query")
+        .as_cl_value()
+        .cloned()
+        .expect("should be CLValue")
+        .into_t()
+        .expect("should convert")
+}
+
+#[ignore]
+#[test]
+fn should_verify_valid_signature() {
+    let (secret_key, public_key) = ed25519_keypair(1);
+    let signature = sign(MESSAGE, &secret_key);
+    assert!(verify_signature(public_key, MESSAGE, signature));
+}
+
+#[ignore]
+#[test]
+fn should_not_verify_signature_of_different_message() {
+    let (secret_key, public_key) = ed25519_keypair(1);
+    let signature = sign(MESSAGE, &secret_key);
+    assert!(!verify_signature(public_key, b"casper labs", signature));
+}
+
+#[ignore]
+#[test]
+fn should_not_verify_signature_against_different_public_key() {
+    let (secret_key, _) = ed25519_keypair(1);
+    let (_, other_public_key) = ed25519_keypair(2);
+    let signature = sign(MESSAGE, &secret_key);
+    assert!(!verify_signature(other_public_key, MESSAGE, signature));
+}
@@ -18,12 +18,15 @@ use tracing::{error, info, trace};
 
 use crate::config;
 use casper_node::{
+    crypto::{hash::Digest, AsymmetricKeyExt},
     logging,
     reactor::{initializer, joiner, validator, ReactorExit, Runner},
     setup_signal_hooks,
     types::ExitCode,
     utils::WithDir,
+    SignerAddress, SigningServer,
 };
+use casper_types::SecretKey;
 use prometheus::Registry;
 
 // We override the standard allocator to gather metrics and tune the allocator via th MALLOC_CONF
@@ -73,6 +76,69 @@ pub enum Cli {
         #[structopt(long)]
         new_config: PathBuf,
     },
+    /// Export a range of blocks with their deploys, finality signatures and execution results, and
+    /// optionally the global state at a given state root, to an archive file.
+    ExportChain {
+        /// Path to configuration file of the node.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Height of the first block to export.
+        #[structopt(long, default_value = "0")]
+        from_height: u64,
+        /// Height of the last block to export.  Defaults to the highest block in storage.
+        #[structopt(long)]
+        to_height: Option<u64>,
+        /// Hex-encoded state root hash of the global state to export.  If not given, no global
+        /// state is exported.
+        #[structopt(long, parse(try_from_str = Digest::from_hex))]
+        state_root_hash: Option<Digest>,
+        /// Path of the archive file to create.
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Import the contents of an archive file created by `export-chain`.
+    ImportChain {
+        /// Path to configuration file of the node.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Path of the archive file to import.
+        #[structopt(long)]
+        input: PathBuf,
+    },
+    /// Check the integrity of the stored blocks, deploys, finality signatures and global state,
+    /// writing a JSON report of anything missing or corrupted.
+    ///
+    /// Exits with code 2 if any problems were found, or with code 1 if the check failed to run.
+    CheckDb {
+        /// Path to configuration file of the node.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Height of the first block to check.
+        #[structopt(long, default_value = "0")]
+        from_height: u64,
+        /// Height of the last block to check.  Defaults to the highest block in storage.
+        #[structopt(long)]
+        to_height: Option<u64>,
+        /// Path of the file to write the report to.  Defaults to standard output, which the
+        /// node's logs may also be written to.
+        #[structopt(long)]
+        output: Option<PathBuf>,
+    },
+    /// Run a remote signer holding the validator's secret key.
+    ///
+    /// Signs consensus messages for a node configured with `consensus.remote_signer_address`, and
+    /// refuses to sign two different units with the same sequence number in the same era.
+    RemoteSigner {
+        /// Path to the validator's secret key file.
+        #[structopt(long)]
+        secret_key: PathBuf,
+        /// Address to listen on, either 'unix:<path to socket>' or 'tcp:<loopback IP>:<port>'.
+        #[structopt(long)]
+        listen: SignerAddress,
+        /// Path of the file recording the last unit signed in each era.
+        #[structopt(long)]
+        state: PathBuf,
+    },
 }
 
 #[derive(Debug)]
@@ -242,6 +308,69 @@ impl Cli {
                 casper_node::migrate_data(WithDir::new(old_root, old_config), new_config)?;
                 Ok(ExitCode::Success as i32)
             }
+            Cli::ExportChain {
+                config,
+                from_height,
+                to_height,
+                state_root_hash,
+                output,
+            } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(version = %env!("CARGO_PKG_VERSION"), "exporting chain");
+                casper_node::export_chain(
+                    config,
+                    from_height,
+                    to_height,
+                    state_root_hash,
+                    &output,
+                )?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ImportChain { config, input } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(version = %env!("CARGO_PKG_VERSION"), "importing chain");
+                casper_node::import_chain(config, &input)?;
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::CheckDb {
+                config,
+                from_height,
+                to_height,
+                output,
+            } => {
+                let config = Self::init(&config, vec![])?;
+
+                info!(version = %env!("CARGO_PKG_VERSION"), "checking storage");
+                let report = casper_node::check_db(config, from_height, to_height)?;
+                let encoded_report = serde_json::to_string_pretty(&report)?;
+                match output {
+                    Some(path) => fs::write(&path, encoded_report)
+                        .context("could not write report")
+                        .with_context(|| path.display().to_string())?,
+                    None => println!("{}", encoded_report),
+                }
+                if report.is_ok() {
+                    Ok(ExitCode::Success as i32)
+                } else {
+                    Ok(ExitCode::CheckDbProblemsFound as i32)
+                }
+            }
+            Cli::RemoteSigner {
+                secret_key: secret_key_path,
+                listen,
+                state,
+            } => {
+                logging::init()?;
+                let secret_key = SecretKey::from_file(&secret_key_path)
+                    .context("could not load secret key")
+                    .with_context(|| secret_key_path.display().to_string())?;
+
+                info!(version = %env!("CARGO_PKG_VERSION"), "starting remote signer");
+                SigningServer::new(secret_key, state)?.run(&listen)?;
+                Ok(ExitCode::Success as i32)
+            }
         }
     }
 
@@ -0,0 +1,77 @@
+//! # Highway network simulator
+//!
+//! Runs the Highway consensus protocol on a simulated network described by a scenario file, and
+//! prints finality latency, fault tolerance and throughput statistics for each run. The scenario
+//! format is documented in `components/consensus/highway_core/highway_testing/simulator.rs`.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use anyhow::{bail, Context};
+use structopt::StructOpt;
+
+use casper_node::{SimulationReport, SimulationScenario};
+
+/// The format of the simulation reports.
+#[derive(Debug)]
+enum Format {
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        match value {
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => bail!("unknown format '{}', expected 'json' or 'csv'", value),
+        }
+    }
+}
+
+// Note: The docstring on `Cli` is the help shown when calling the binary with `--help`.
+#[derive(Debug, StructOpt)]
+/// Simulates a Highway era on a virtual network.
+struct Cli {
+    /// Path to the scenario file.
+    scenario: PathBuf,
+
+    /// The output format: 'json' or 'csv'.
+    #[structopt(short, long, default_value = "json")]
+    format: Format,
+
+    /// Path to the output file. The reports are written to stdout if not given.
+    #[structopt(short, long)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::from_args();
+
+    let scenario = SimulationScenario::from_file(&cli.scenario)?;
+    let reports = scenario.run()?;
+
+    let mut writer: Box<dyn Write> = match &cli.output {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).with_context(|| {
+                format!("failed to create output file {}", path.display())
+            })?))
+        }
+        None => Box::new(io::stdout()),
+    };
+    match cli.format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut writer, &reports)?;
+            writeln!(writer)?;
+        }
+        Format::Csv => SimulationReport::write_csv(&reports, &mut writer)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
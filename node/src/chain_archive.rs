@@ -0,0 +1,1233 @@
+//! Export and import of the linear chain to and from a portable archive file.
+//!
+//! An archive starts with an 8-byte magic value and a little-endian `u32` format version, followed
+//! by a sequence of records.  Each record is framed as a little-endian `u32` length, the
+//! `bytesrepr` encoding of the record, and the 32-byte hash of that encoding.  The first record is
+//! always a header describing the archive's contents and the last is an end marker carrying the
+//! total number of records, so that truncated archives are detected.
+//!
+//! Within an archive, each block is preceded by its deploys and followed by its finality
+//! signatures and the execution results of its deploys.  If a state root hash was given on export,
+//! the tries of the global state under that root follow the last block.
+//!
+//! The node must not be running while a chain is exported or imported.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use lmdb::DatabaseFlags;
+use num::rational::Ratio;
+use prometheus::Registry;
+use thiserror::Error;
+use tracing::{debug, info};
+
+use casper_execution_engine::{
+    shared::newtypes::Blake2bHash,
+    storage::{
+        error::lmdb::Error as GlobalStateError,
+        store::Store,
+        transaction_source::{lmdb::LmdbEnvironment, Transaction, TransactionSource},
+        trie::Trie,
+        trie_store::lmdb::LmdbTrieStore,
+    },
+};
+use casper_types::{
+    bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH},
+    ExecutionResult, Key, PublicKey, StoredValue, U512,
+};
+
+use crate::{
+    components::{
+        consensus::EraId,
+        storage::{Error as StorageError, Storage},
+    },
+    crypto::hash::{self, Digest},
+    reactor::validator::Config,
+    types::{chainspec, Block, BlockHash, BlockSignatures, Chainspec, Deploy, DeployHash},
+    utils::{Loadable, WithDir},
+};
+
+/// The magic value identifying a chain archive.
+const MAGIC: [u8; 8] = *b"CSPRCHAN";
+/// The version of the archive format written by this node.
+const FORMAT_VERSION: u32 = 1;
+/// The maximum size of a single serialized record: 64 MiB.
+const MAX_RECORD_SIZE: u32 = 64 * 1024 * 1024;
+/// The number of tries written to global state per transaction on import.
+const TRIE_BATCH_SIZE: usize = 1_000;
+
+const HEADER_TAG: u8 = 0;
+const DEPLOY_TAG: u8 = 1;
+const BLOCK_TAG: u8 = 2;
+const BLOCK_SIGNATURES_TAG: u8 = 3;
+const EXECUTION_RESULTS_TAG: u8 = 4;
+const TRIE_TAG: u8 = 5;
+const END_TAG: u8 = 6;
+
+/// Error returned as a result of exporting or importing a chain.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Error loading the chainspec.
+    #[error("error loading chainspec: {0}")]
+    LoadChainspec(chainspec::Error),
+
+    /// Error creating the archive file.
+    #[error("error creating archive {path}: {error}")]
+    CreateArchive {
+        /// The file path.
+        path: String,
+        /// The IO error.
+        error: io::Error,
+    },
+
+    /// Error opening the archive file.
+    #[error("error opening archive {path}: {error}")]
+    OpenArchive {
+        /// The file path.
+        path: String,
+        /// The IO error.
+        error: io::Error,
+    },
+
+    /// Error reading from or writing to the archive.
+    #[error("archive io error: {0}")]
+    Io(#[from] io::Error),
+
+    /// Error serializing or deserializing a record.
+    #[error("archive serialization error: {0}")]
+    BytesRepr(#[from] bytesrepr::Error),
+
+    /// Error in the linear chain storage.
+    #[error("storage error: {0}")]
+    Storage(#[from] StorageError),
+
+    /// Error in the global state storage.
+    #[error("global state error: {0}")]
+    GlobalState(#[from] GlobalStateError),
+
+    /// There are no blocks in storage to export.
+    #[error("no blocks in storage")]
+    NoBlocks,
+
+    /// The requested range of block heights is empty.
+    #[error("invalid block height range: {from_height} to {to_height}")]
+    InvalidHeightRange {
+        /// The first height of the range.
+        from_height: u64,
+        /// The last height of the range.
+        to_height: u64,
+    },
+
+    /// A block in the requested range is missing from storage.
+    #[error("no block at height {0} in storage")]
+    MissingBlock(u64),
+
+    /// A deploy of an exported block is missing from storage.
+    #[error("deploy {deploy_hash} of block at height {height} missing from storage")]
+    MissingDeploy {
+        /// The hash of the deploy.
+        deploy_hash: DeployHash,
+        /// The height of the block.
+        height: u64,
+    },
+
+    /// A trie of the global state is missing.
+    #[error("trie {0} missing from global state")]
+    MissingTrie(Blake2bHash),
+
+    /// The file is not a chain archive.
+    #[error("not a chain archive")]
+    NotAnArchive,
+
+    /// The archive was written in an unsupported format version.
+    #[error("unsupported archive format version {0}, expected {}", FORMAT_VERSION)]
+    UnsupportedFormatVersion(u32),
+
+    /// A record exceeds the maximum record size.
+    #[error("archive record of {0} bytes exceeds maximum record size")]
+    RecordTooLarge(u32),
+
+    /// The archive ended before its end marker.
+    #[error("archive is truncated")]
+    Truncated,
+
+    /// The checksum of a record does not match its contents.
+    #[error("checksum mismatch in archive record {0}")]
+    ChecksumMismatch(u64),
+
+    /// The record count in the end marker does not match the number of records read.
+    #[error("archive holds {actual} records, but its end marker records {expected}")]
+    RecordCountMismatch {
+        /// The record count in the end marker.
+        expected: u64,
+        /// The number of records read.
+        actual: u64,
+    },
+
+    /// A record appeared where it was not expected.
+    #[error("unexpected {0} record in archive")]
+    UnexpectedRecord(&'static str),
+
+    /// The archive was exported from a different chain.
+    #[error("archive is of chain {archive}, but node is configured for chain {node}")]
+    ChainNameMismatch {
+        /// The chain name in the archive header.
+        archive: String,
+        /// The chain name in the node's chainspec.
+        node: String,
+    },
+
+    /// A block failed validation.
+    #[error("invalid block at height {height}: {error}")]
+    InvalidBlock {
+        /// The height of the block.
+        height: u64,
+        /// The validation error.
+        error: String,
+    },
+
+    /// A block does not follow on from the previous block in the archive.
+    #[error("block at height {0} does not follow on from the previous block")]
+    BlockNotContiguous(u64),
+
+    /// The parent of the first block in the archive is not in storage.
+    #[error("parent of the first archived block, at height {0}, missing from storage")]
+    MissingParentBlock(u64),
+
+    /// A deploy failed validation.
+    #[error("invalid deploy {deploy_hash}: {error}")]
+    InvalidDeploy {
+        /// The hash of the deploy.
+        deploy_hash: DeployHash,
+        /// The validation error.
+        error: String,
+    },
+
+    /// A deploy of a block was not found in the archive before the block.
+    #[error("deploy {deploy_hash} of block at height {height} missing from archive")]
+    MissingArchivedDeploy {
+        /// The hash of the deploy.
+        deploy_hash: DeployHash,
+        /// The height of the block.
+        height: u64,
+    },
+
+    /// The finality signatures of a block are invalid.
+    #[error("invalid finality signatures for block at height {height}: {error}")]
+    InvalidFinalitySignatures {
+        /// The height of the block.
+        height: u64,
+        /// The validation error.
+        error: String,
+    },
+
+    /// The validators of an era are unknown, as the switch block of the era before is not in
+    /// storage.
+    #[error("validators of era {0} unknown")]
+    UnknownEraValidators(EraId),
+
+    /// An execution result does not belong to a deploy of the preceding block.
+    #[error("execution result of deploy {deploy_hash} does not belong to block {block_hash}")]
+    UnexpectedExecutionResult {
+        /// The hash of the deploy.
+        deploy_hash: DeployHash,
+        /// The hash of the block.
+        block_hash: BlockHash,
+    },
+}
+
+/// The header of a chain archive, describing its contents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ArchiveHeader {
+    chain_name: String,
+    protocol_version: String,
+    from_height: u64,
+    to_height: u64,
+    state_root_hash: Option<Digest>,
+}
+
+impl ToBytes for ArchiveHeader {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.chain_name.to_bytes()?);
+        buffer.extend(self.protocol_version.to_bytes()?);
+        buffer.extend(self.from_height.to_bytes()?);
+        buffer.extend(self.to_height.to_bytes()?);
+        buffer.extend(self.state_root_hash.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.chain_name.serialized_length()
+            + self.protocol_version.serialized_length()
+            + self.from_height.serialized_length()
+            + self.to_height.serialized_length()
+            + self.state_root_hash.serialized_length()
+    }
+}
+
+impl FromBytes for ArchiveHeader {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (chain_name, remainder) = String::from_bytes(bytes)?;
+        let (protocol_version, remainder) = String::from_bytes(remainder)?;
+        let (from_height, remainder) = u64::from_bytes(remainder)?;
+        let (to_height, remainder) = u64::from_bytes(remainder)?;
+        let (state_root_hash, remainder) = Option::<Digest>::from_bytes(remainder)?;
+        let header = ArchiveHeader {
+            chain_name,
+            protocol_version,
+            from_height,
+            to_height,
+            state_root_hash,
+        };
+        Ok((header, remainder))
+    }
+}
+
+/// A single record of a chain archive.
+#[derive(Clone, Debug, PartialEq)]
+enum Record {
+    Header(ArchiveHeader),
+    Deploy(Box<Deploy>),
+    Block(Box<Block>),
+    BlockSignatures(Box<BlockSignatures>),
+    ExecutionResults {
+        block_hash: BlockHash,
+        execution_results: Vec<(DeployHash, ExecutionResult)>,
+    },
+    Trie(Box<Trie<Key, StoredValue>>),
+    End {
+        record_count: u64,
+    },
+}
+
+impl Record {
+    fn kind(&self) -> &'static str {
+        match self {
+            Record::Header(_) => "header",
+            Record::Deploy(_) => "deploy",
+            Record::Block(_) => "block",
+            Record::BlockSignatures(_) => "block signatures",
+            Record::ExecutionResults { .. } => "execution results",
+            Record::Trie(_) => "trie",
+            Record::End { .. } => "end",
+        }
+    }
+}
+
+impl ToBytes for Record {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        match self {
+            Record::Header(header) => {
+                buffer.push(HEADER_TAG);
+                buffer.extend(header.to_bytes()?);
+            }
+            Record::Deploy(deploy) => {
+                buffer.push(DEPLOY_TAG);
+                buffer.extend(deploy.to_bytes()?);
+            }
+            Record::Block(block) => {
+                buffer.push(BLOCK_TAG);
+                buffer.extend(block.to_bytes()?);
+            }
+            Record::BlockSignatures(signatures) => {
+                buffer.push(BLOCK_SIGNATURES_TAG);
+                buffer.extend(signatures.to_bytes()?);
+            }
+            Record::ExecutionResults {
+                block_hash,
+                execution_results,
+            } => {
+                buffer.push(EXECUTION_RESULTS_TAG);
+                buffer.extend(block_hash.to_bytes()?);
+                buffer.extend(execution_results.to_bytes()?);
+            }
+            Record::Trie(trie) => {
+                buffer.push(TRIE_TAG);
+                buffer.extend(trie.to_bytes()?);
+            }
+            Record::End { record_count } => {
+                buffer.push(END_TAG);
+                buffer.extend(record_count.to_bytes()?);
+            }
+        }
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH
+            + match self {
+                Record::Header(header) => header.serialized_length(),
+                Record::Deploy(deploy) => deploy.serialized_length(),
+                Record::Block(block) => block.serialized_length(),
+                Record::BlockSignatures(signatures) => signatures.serialized_length(),
+                Record::ExecutionResults {
+                    block_hash,
+                    execution_results,
+                } => block_hash.serialized_length() + execution_results.serialized_length(),
+                Record::Trie(trie) => trie.serialized_length(),
+                Record::End { record_count } => record_count.serialized_length(),
+            }
+    }
+}
+
+impl FromBytes for Record {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        match tag {
+            HEADER_TAG => {
+                let (header, remainder) = ArchiveHeader::from_bytes(remainder)?;
+                Ok((Record::Header(header), remainder))
+            }
+            DEPLOY_TAG => {
+                let (deploy, remainder) = Deploy::from_bytes(remainder)?;
+                Ok((Record::Deploy(Box::new(deploy)), remainder))
+            }
+            BLOCK_TAG => {
+                let (block, remainder) = Block::from_bytes(remainder)?;
+                Ok((Record::Block(Box::new(block)), remainder))
+            }
+            BLOCK_SIGNATURES_TAG => {
+                let (signatures, remainder) = BlockSignatures::from_bytes(remainder)?;
+                Ok((Record::BlockSignatures(Box::new(signatures)), remainder))
+            }
+            EXECUTION_RESULTS_TAG => {
+                let (block_hash, remainder) = BlockHash::from_bytes(remainder)?;
+                let (execution_results, remainder) =
+                    Vec::<(DeployHash, ExecutionResult)>::from_bytes(remainder)?;
+                let record = Record::ExecutionResults {
+                    block_hash,
+                    execution_results,
+                };
+                Ok((record, remainder))
+            }
+            TRIE_TAG => {
+                let (trie, remainder) = Trie::<Key, StoredValue>::from_bytes(remainder)?;
+                Ok((Record::Trie(Box::new(trie)), remainder))
+            }
+            END_TAG => {
+                let (record_count, remainder) = u64::from_bytes(remainder)?;
+                Ok((Record::End { record_count }, remainder))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+/// Writes records to a chain archive.
+struct ArchiveWriter<W: Write> {
+    writer: W,
+    record_count: u64,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    /// Creates a new archive writer, writing the magic value and format version.
+    fn new(mut writer: W) -> Result<Self, Error> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        Ok(ArchiveWriter {
+            writer,
+            record_count: 0,
+        })
+    }
+
+    /// Writes a single record along with its length and checksum.
+    fn write(&mut self, record: &Record) -> Result<(), Error> {
+        let bytes = record.to_bytes()?;
+        if bytes.len() > MAX_RECORD_SIZE as usize {
+            return Err(Error::RecordTooLarge(bytes.len() as u32));
+        }
+        self.writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&bytes)?;
+        self.writer.write_all(&hash::hash(&bytes).to_array())?;
+        self.record_count += 1;
+        Ok(())
+    }
+
+    /// Writes the end marker and flushes the archive, returning the underlying writer.
+    fn finish(mut self) -> Result<W, Error> {
+        let record_count = self.record_count + 1;
+        self.write(&Record::End { record_count })?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads records from a chain archive, verifying their checksums.
+struct ArchiveReader<R: Read> {
+    reader: R,
+    record_count: u64,
+    finished: bool,
+}
+
+impl<R: Read> ArchiveReader<R> {
+    /// Creates a new archive reader, checking the magic value and format version.
+    fn new(mut reader: R) -> Result<Self, Error> {
+        let mut magic = [0; MAGIC.len()];
+        read_exact(&mut reader, &mut magic).map_err(|error| match error {
+            Error::Truncated => Error::NotAnArchive,
+            error => error,
+        })?;
+        if magic != MAGIC {
+            return Err(Error::NotAnArchive);
+        }
+        let mut version = [0; 4];
+        read_exact(&mut reader, &mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != FORMAT_VERSION {
+            return Err(Error::UnsupportedFormatVersion(version));
+        }
+        Ok(ArchiveReader {
+            reader,
+            record_count: 0,
+            finished: false,
+        })
+    }
+
+    /// Reads the next record, returning `None` once the end marker has been read.
+    fn next_record(&mut self) -> Result<Option<Record>, Error> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let mut length = [0; 4];
+        read_exact(&mut self.reader, &mut length)?;
+        let length = u32::from_le_bytes(length);
+        if length > MAX_RECORD_SIZE {
+            return Err(Error::RecordTooLarge(length));
+        }
+
+        // Read through `take` so that a corrupt length cannot cause a huge allocation up front.
+        let mut bytes = Vec::new();
+        (&mut self.reader)
+            .take(u64::from(length))
+            .read_to_end(&mut bytes)?;
+        if bytes.len() != length as usize {
+            return Err(Error::Truncated);
+        }
+
+        let mut checksum = [0; Digest::LENGTH];
+        read_exact(&mut self.reader, &mut checksum)?;
+        self.record_count += 1;
+        if hash::hash(&bytes) != Digest::from(checksum) {
+            return Err(Error::ChecksumMismatch(self.record_count));
+        }
+
+        match bytesrepr::deserialize(bytes)? {
+            Record::End { record_count } => {
+                if record_count != self.record_count {
+                    return Err(Error::RecordCountMismatch {
+                        expected: record_count,
+                        actual: self.record_count,
+                    });
+                }
+                self.finished = true;
+                Ok(None)
+            }
+            record => Ok(Some(record)),
+        }
+    }
+}
+
+/// Fills the buffer from the reader, mapping an early end of the input to `Error::Truncated`.
+fn read_exact<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<(), Error> {
+    reader.read_exact(buffer).map_err(|error| {
+        if error.kind() == io::ErrorKind::UnexpectedEof {
+            Error::Truncated
+        } else {
+            Error::Io(error)
+        }
+    })
+}
+
+/// Direct access to the trie store of the global state.
+pub(crate) struct GlobalState {
+    environment: LmdbEnvironment,
+    trie_store: LmdbTrieStore,
+}
+
+impl GlobalState {
+    pub(crate) fn open(root: &Path, config: &Config) -> Result<Self, GlobalStateError> {
+        let storage_config = WithDir::new(root, config.storage.clone());
+        let path = storage_config.with_dir(storage_config.value().path.clone());
+        let environment = LmdbEnvironment::new(
+            path.as_path(),
+            config.contract_runtime.max_global_state_size(),
+            config.contract_runtime.max_readers(),
+        )?;
+        let trie_store = LmdbTrieStore::new(&environment, None, DatabaseFlags::empty())?;
+        Ok(GlobalState {
+            environment,
+            trie_store,
+        })
+    }
+
+    /// Writes the given tries in a single transaction.
+    fn write(&self, tries: &[(Blake2bHash, Trie<Key, StoredValue>)]) -> Result<(), Error> {
+        let mut txn = self.environment.create_read_write_txn()?;
+        for (trie_key, trie) in tries {
+            self.trie_store.put(&mut txn, trie_key, trie)?;
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Calls `visit` on every trie reachable from the given root, each exactly once.
+    ///
+    /// Returns the number of tries visited.
+    fn visit_tries<F>(&self, root: Blake2bHash, mut visit: F) -> Result<u64, Error>
+    where
+        F: FnMut(Blake2bHash, Trie<Key, StoredValue>) -> Result<(), Error>,
+    {
+        let txn = self.environment.create_read_txn()?;
+        let mut visited = HashSet::new();
+        let mut to_visit = vec![root];
+        while let Some(trie_key) = to_visit.pop() {
+            if !visited.insert(trie_key) {
+                continue;
+            }
+            let trie: Trie<Key, StoredValue> = self
+                .trie_store
+                .get(&txn, &trie_key)?
+                .ok_or(Error::MissingTrie(trie_key))?;
+            match &trie {
+                Trie::Leaf { .. } => {}
+                Trie::Node { pointer_block } => to_visit.extend(
+                    pointer_block
+                        .to_indexed_pointers()
+                        .map(|(_, pointer)| pointer.into_hash()),
+                ),
+                Trie::Extension { pointer, .. } => to_visit.push(*pointer.hash()),
+            }
+            visit(trie_key, trie)?;
+        }
+        txn.commit()?;
+        Ok(visited.len() as u64)
+    }
+
+    /// Returns the keys of the tries reachable from the given root which are missing.
+    ///
+    /// Tries in `present` are skipped, and any further tries found are added to it, so that tries
+    /// shared between several roots are only read once.  A missing trie is therefore only reported
+    /// for the first root it is found under.
+    pub(crate) fn find_missing_tries(
+        &self,
+        root: Blake2bHash,
+        present: &mut HashSet<Blake2bHash>,
+    ) -> Result<Vec<Blake2bHash>, GlobalStateError> {
+        let txn = self.environment.create_read_txn()?;
+        let mut missing = Vec::new();
+        let mut to_visit = vec![root];
+        while let Some(trie_key) = to_visit.pop() {
+            if present.contains(&trie_key) || missing.contains(&trie_key) {
+                continue;
+            }
+            let trie: Trie<Key, StoredValue> = match self.trie_store.get(&txn, &trie_key)? {
+                Some(trie) => trie,
+                None => {
+                    missing.push(trie_key);
+                    continue;
+                }
+            };
+            present.insert(trie_key);
+            match trie {
+                Trie::Leaf { .. } => {}
+                Trie::Node { pointer_block } => to_visit.extend(
+                    pointer_block
+                        .to_indexed_pointers()
+                        .map(|(_, pointer)| pointer.into_hash()),
+                ),
+                Trie::Extension { pointer, .. } => to_visit.push(*pointer.hash()),
+            }
+        }
+        txn.commit()?;
+        Ok(missing)
+    }
+}
+
+impl From<lmdb::Error> for Error {
+    fn from(error: lmdb::Error) -> Self {
+        Error::GlobalState(GlobalStateError::from(error))
+    }
+}
+
+fn open_storage(root: &Path, config: &Config, chainspec: &Chainspec) -> Result<Storage, Error> {
+    let storage_config = WithDir::new(root, config.storage.clone());
+    let storage = Storage::new(
+        &storage_config,
+        None,
+        chainspec.protocol_config.version.clone(),
+        &Registry::new(),
+    )?;
+    Ok(storage)
+}
+
+/// Exports the blocks in the given range of heights, along with their deploys, finality
+/// signatures and execution results, to a new archive file.
+///
+/// If `maybe_to_height` is `None`, all blocks up to the highest one in storage are exported.  If
+/// `maybe_state_root_hash` is given, the global state under that root is exported too.
+pub fn export_chain(
+    config: WithDir<Config>,
+    from_height: u64,
+    maybe_to_height: Option<u64>,
+    maybe_state_root_hash: Option<Digest>,
+    output: &Path,
+) -> Result<(), Error> {
+    let (root, config) = config.into_parts();
+    let chainspec = Chainspec::from_path(&root).map_err(Error::LoadChainspec)?;
+    let storage = open_storage(&root, &config, &chainspec)?;
+
+    let to_height = match maybe_to_height {
+        Some(to_height) => to_height,
+        None => storage.read_highest_block_height().ok_or(Error::NoBlocks)?,
+    };
+    if from_height > to_height {
+        return Err(Error::InvalidHeightRange {
+            from_height,
+            to_height,
+        });
+    }
+
+    let file = File::create(output).map_err(|error| Error::CreateArchive {
+        path: output.display().to_string(),
+        error,
+    })?;
+    let mut writer = ArchiveWriter::new(BufWriter::new(file))?;
+    writer.write(&Record::Header(ArchiveHeader {
+        chain_name: chainspec.network_config.name.clone(),
+        protocol_version: chainspec.protocol_config.version.to_string(),
+        from_height,
+        to_height,
+        state_root_hash: maybe_state_root_hash,
+    }))?;
+
+    for height in from_height..=to_height {
+        let (block, signatures) = storage
+            .read_block_and_signatures_by_height(height)?
+            .ok_or(Error::MissingBlock(height))?;
+
+        let mut execution_results = Vec::new();
+        for deploy_hash in block.deploy_hashes().iter().chain(block.transfer_hashes()) {
+            let (deploy, metadata) =
+                storage
+                    .read_deploy_and_metadata(deploy_hash)?
+                    .ok_or(Error::MissingDeploy {
+                        deploy_hash: *deploy_hash,
+                        height,
+                    })?;
+            if let Some(execution_result) = metadata.execution_results.get(block.hash()) {
+                execution_results.push((*deploy_hash, execution_result.clone()));
+            }
+            writer.write(&Record::Deploy(Box::new(deploy)))?;
+        }
+
+        let block_hash = *block.hash();
+        writer.write(&Record::Block(Box::new(block)))?;
+        writer.write(&Record::BlockSignatures(Box::new(signatures)))?;
+        writer.write(&Record::ExecutionResults {
+            block_hash,
+            execution_results,
+        })?;
+    }
+    info!(from_height, to_height, "exported blocks");
+
+    if let Some(state_root_hash) = maybe_state_root_hash {
+        let global_state = GlobalState::open(&root, &config)?;
+        let trie_count = global_state
+            .visit_tries(Blake2bHash::from(state_root_hash), |_, trie| {
+                writer.write(&Record::Trie(Box::new(trie)))
+            })?;
+        info!(%state_root_hash, trie_count, "exported global state");
+    }
+
+    writer.finish()?;
+    info!(path = %output.display(), "chain export complete");
+    Ok(())
+}
+
+/// The state of an import between records.
+struct Importer {
+    /// The validators of the genesis era, from the chainspec.
+    genesis_validators: BTreeMap<PublicKey, U512>,
+    /// The fraction of validator weight which finality signatures must exceed.
+    finality_threshold: Ratio<u64>,
+    /// The validators of the eras of the blocks imported so far.
+    era_validators: HashMap<EraId, BTreeMap<PublicKey, U512>>,
+    /// Deploys read since the last block was written, awaiting their block.
+    deploys: HashMap<DeployHash, Deploy>,
+    /// A verified block awaiting its finality signatures.
+    pending_block: Option<Block>,
+    /// The last block written, along with the hashes of its deploys.
+    last_block: Option<(Block, HashSet<DeployHash>)>,
+    /// Verified tries awaiting a write to global state.
+    tries: Vec<(Blake2bHash, Trie<Key, StoredValue>)>,
+}
+
+impl Importer {
+    fn new(genesis_validators: BTreeMap<PublicKey, U512>, finality_threshold: Ratio<u64>) -> Self {
+        Importer {
+            genesis_validators,
+            finality_threshold,
+            era_validators: HashMap::new(),
+            deploys: HashMap::new(),
+            pending_block: None,
+            last_block: None,
+            tries: Vec::new(),
+        }
+    }
+
+    fn import_deploy(&mut self, mut deploy: Deploy) -> Result<(), Error> {
+        let deploy_hash = *deploy.id();
+        deploy.is_valid().map_err(|error| Error::InvalidDeploy {
+            deploy_hash,
+            error: error.to_string(),
+        })?;
+        self.deploys.insert(deploy_hash, deploy);
+        Ok(())
+    }
+
+    fn import_block(
+        &mut self,
+        storage: &Storage,
+        header: &ArchiveHeader,
+        block: Block,
+    ) -> Result<(), Error> {
+        let height = block.height();
+        if self.pending_block.is_some() {
+            return Err(Error::UnexpectedRecord("block"));
+        }
+        block.verify().map_err(|error| Error::InvalidBlock {
+            height,
+            error: error.to_string(),
+        })?;
+
+        let is_contiguous = match &self.last_block {
+            None if height == 0 => header.from_height == 0,
+            None => {
+                // The first block must follow on from a block already in storage.
+                let parent = storage
+                    .read_stored_block_by_height(height - 1)?
+                    .ok_or(Error::MissingParentBlock(height - 1))?;
+                height == header.from_height && block.parent() == Some(&parent.block_hash)
+            }
+            Some((previous, _)) => {
+                height == previous.height() + 1 && block.parent() == Some(previous.hash())
+            }
+        };
+        if !is_contiguous || height > header.to_height {
+            return Err(Error::BlockNotContiguous(height));
+        }
+
+        for deploy_hash in block.deploy_hashes().iter().chain(block.transfer_hashes()) {
+            if !self.deploys.contains_key(deploy_hash) {
+                return Err(Error::MissingArchivedDeploy {
+                    deploy_hash: *deploy_hash,
+                    height,
+                });
+            }
+        }
+        self.pending_block = Some(block);
+        Ok(())
+    }
+
+    fn import_block_signatures(
+        &mut self,
+        storage: &mut Storage,
+        signatures: BlockSignatures,
+    ) -> Result<(), Error> {
+        let block = self
+            .pending_block
+            .take()
+            .ok_or(Error::UnexpectedRecord("block signatures"))?;
+        let height = block.height();
+        if signatures.block_hash != *block.hash() || signatures.era_id != block.header().era_id() {
+            return Err(Error::InvalidFinalitySignatures {
+                height,
+                error: "signatures are not for this block".to_string(),
+            });
+        }
+        let finality_threshold = self.finality_threshold;
+        let validators = self.validators(storage, signatures.era_id)?;
+        signatures
+            .verify_quorum(validators, finality_threshold)
+            .map_err(|error| Error::InvalidFinalitySignatures {
+                height,
+                error: error.to_string(),
+            })?;
+
+        // Only now that the block and its signatures have been verified is anything written.
+        let mut deploy_hashes = HashSet::new();
+        for deploy_hash in block.deploy_hashes().iter().chain(block.transfer_hashes()) {
+            if let Some(deploy) = self.deploys.remove(deploy_hash) {
+                storage.write_deploy(&deploy)?;
+            }
+            deploy_hashes.insert(*deploy_hash);
+        }
+        self.deploys.clear();
+        storage.write_block(&block)?;
+        storage.write_block_signatures(signatures)?;
+        debug!(height, block_hash = %block.hash(), "imported block");
+        self.last_block = Some((block, deploy_hashes));
+        Ok(())
+    }
+
+    /// Returns the validators of the given era, read from the switch block of the era before.
+    fn validators(
+        &mut self,
+        storage: &Storage,
+        era_id: EraId,
+    ) -> Result<&BTreeMap<PublicKey, U512>, Error> {
+        if !self.era_validators.contains_key(&era_id) {
+            let validators = if era_id.0 == 0 {
+                self.genesis_validators.clone()
+            } else {
+                storage
+                    .read_switch_block_header_by_era_id(EraId(era_id.0 - 1))?
+                    .and_then(|header| header.next_era_validator_weights().cloned())
+                    .ok_or(Error::UnknownEraValidators(era_id))?
+            };
+            self.era_validators.insert(era_id, validators);
+        }
+        Ok(&self.era_validators[&era_id])
+    }
+
+    fn import_execution_results(
+        &mut self,
+        storage: &Storage,
+        block_hash: BlockHash,
+        execution_results: Vec<(DeployHash, ExecutionResult)>,
+    ) -> Result<(), Error> {
+        let deploy_hashes = match &self.last_block {
+            Some((block, deploy_hashes))
+                if self.pending_block.is_none() && *block.hash() == block_hash =>
+            {
+                deploy_hashes
+            }
+            _ => return Err(Error::UnexpectedRecord("execution results")),
+        };
+        for (deploy_hash, _) in &execution_results {
+            if !deploy_hashes.contains(deploy_hash) {
+                return Err(Error::UnexpectedExecutionResult {
+                    deploy_hash: *deploy_hash,
+                    block_hash,
+                });
+            }
+        }
+        storage.write_execution_results(&block_hash, execution_results.into_iter().collect())?;
+        Ok(())
+    }
+
+    fn import_trie(
+        &mut self,
+        global_state: &GlobalState,
+        trie: Trie<Key, StoredValue>,
+    ) -> Result<(), Error> {
+        let trie_key = Blake2bHash::new(&trie.to_bytes()?);
+        self.tries.push((trie_key, trie));
+        if self.tries.len() >= TRIE_BATCH_SIZE {
+            global_state.write(&self.tries)?;
+            self.tries.clear();
+        }
+        Ok(())
+    }
+}
+
+/// Imports the blocks, deploys, finality signatures, execution results and global state held in
+/// an archive file into storage.
+///
+/// Every block is verified against its hash and must follow on from the previous block in the
+/// archive, or for the first block from the block already in storage at the height below.  Before
+/// a block is written, its finality signatures must be valid signatures of validators of its era
+/// holding more than the chainspec's finality threshold of their total weight.  If the archive
+/// holds global state, the complete trie under its state root must be present once imported.
+pub fn import_chain(config: WithDir<Config>, input: &Path) -> Result<(), Error> {
+    let (root, config) = config.into_parts();
+    let chainspec = Chainspec::from_path(&root).map_err(Error::LoadChainspec)?;
+
+    let file = File::open(input).map_err(|error| Error::OpenArchive {
+        path: input.display().to_string(),
+        error,
+    })?;
+    let mut reader = ArchiveReader::new(BufReader::new(file))?;
+
+    let header = match reader.next_record()? {
+        Some(Record::Header(header)) => header,
+        Some(record) => return Err(Error::UnexpectedRecord(record.kind())),
+        None => return Err(Error::Truncated),
+    };
+    if header.chain_name != chainspec.network_config.name {
+        return Err(Error::ChainNameMismatch {
+            archive: header.chain_name,
+            node: chainspec.network_config.name.clone(),
+        });
+    }
+    info!(
+        from_height = header.from_height,
+        to_height = header.to_height,
+        protocol_version = %header.protocol_version,
+        "importing chain archive"
+    );
+
+    let mut storage = open_storage(&root, &config, &chainspec)?;
+    let global_state = match header.state_root_hash {
+        Some(_) => Some(GlobalState::open(&root, &config)?),
+        None => None,
+    };
+
+    let genesis_validators = chainspec
+        .network_config
+        .chainspec_validator_stakes()
+        .into_iter()
+        .map(|(public_key, motes)| (public_key, motes.value()))
+        .collect();
+    let mut importer = Importer::new(
+        genesis_validators,
+        chainspec.highway_config.finality_threshold_fraction,
+    );
+    while let Some(record) = reader.next_record()? {
+        match record {
+            Record::Deploy(deploy) => importer.import_deploy(*deploy)?,
+            Record::Block(block) => importer.import_block(&storage, &header, *block)?,
+            Record::BlockSignatures(signatures) => {
+                importer.import_block_signatures(&mut storage, *signatures)?
+            }
+            Record::ExecutionResults {
+                block_hash,
+                execution_results,
+            } => importer.import_execution_results(&storage, block_hash, execution_results)?,
+            Record::Trie(trie) => match &global_state {
+                Some(global_state) => importer.import_trie(global_state, *trie)?,
+                None => return Err(Error::UnexpectedRecord("trie")),
+            },
+            record @ Record::Header(_) | record @ Record::End { .. } => {
+                return Err(Error::UnexpectedRecord(record.kind()))
+            }
+        }
+    }
+
+    if importer.pending_block.is_some() {
+        return Err(Error::Truncated);
+    }
+    match &importer.last_block {
+        Some((block, _)) if block.height() == header.to_height => {}
+        Some((block, _)) => return Err(Error::MissingBlock(block.height() + 1)),
+        None => return Err(Error::MissingBlock(header.from_height)),
+    }
+    info!(
+        from_height = header.from_height,
+        to_height = header.to_height,
+        "imported blocks"
+    );
+
+    if let (Some(global_state), Some(state_root_hash)) = (&global_state, header.state_root_hash) {
+        global_state.write(&importer.tries)?;
+        let trie_count =
+            global_state.visit_tries(Blake2bHash::from(state_root_hash), |_, _| Ok(()))?;
+        info!(%state_root_hash, trie_count, "imported global state");
+    }
+
+    info!(path = %input.display(), "chain import complete");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use rand::Rng;
+    use tempfile::TempDir;
+
+    use casper_types::SecretKey;
+
+    use super::*;
+    use crate::{
+        components::storage::Config as StorageConfig, crypto::AsymmetricKeyExt, testing::TestRng,
+        types::FinalitySignature,
+    };
+
+    fn write_archive(records: &[Record]) -> Vec<u8> {
+        let mut writer = ArchiveWriter::new(Vec::new()).unwrap();
+        for record in records {
+            writer.write(record).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    fn read_archive(bytes: Vec<u8>) -> Result<Vec<Record>, Error> {
+        let mut reader = ArchiveReader::new(Cursor::new(bytes))?;
+        let mut records = Vec::new();
+        while let Some(record) = reader.next_record()? {
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    fn test_records(rng: &mut TestRng) -> Vec<Record> {
+        let block = Block::random(rng);
+        let execution_results = vec![(DeployHash::random(rng), rng.gen())];
+        vec![
+            Record::Header(ArchiveHeader {
+                chain_name: "casper-example".to_string(),
+                protocol_version: "1.0.0".to_string(),
+                from_height: block.height(),
+                to_height: block.height(),
+                state_root_hash: Some(*block.header().state_root_hash()),
+            }),
+            Record::Deploy(Box::new(Deploy::random(rng))),
+            Record::BlockSignatures(Box::new(BlockSignatures::new(
+                *block.hash(),
+                block.header().era_id(),
+            ))),
+            Record::ExecutionResults {
+                block_hash: *block.hash(),
+                execution_results,
+            },
+            Record::Block(Box::new(block)),
+        ]
+    }
+
+    fn new_storage() -> (Storage, TempDir) {
+        let (storage_config, tempdir) = StorageConfig::default_for_tests();
+        let storage = Storage::new(
+            &WithDir::new(tempdir.path(), storage_config),
+            None,
+            semver::Version::new(1, 0, 0),
+            &Registry::new(),
+        )
+        .unwrap();
+        (storage, tempdir)
+    }
+
+    fn header(height: u64) -> ArchiveHeader {
+        ArchiveHeader {
+            chain_name: "casper-example".to_string(),
+            protocol_version: "1.0.0".to_string(),
+            from_height: height,
+            to_height: height,
+            state_root_hash: None,
+        }
+    }
+
+    fn sign(block: &Block, secret_keys: &[&SecretKey]) -> BlockSignatures {
+        let era_id = block.header().era_id();
+        let mut signatures = BlockSignatures::new(*block.hash(), era_id);
+        for secret_key in secret_keys {
+            let public_key = PublicKey::from(*secret_key);
+            let signature = FinalitySignature::new(*block.hash(), era_id, secret_key, public_key);
+            signatures.insert_proof(public_key, signature.signature);
+        }
+        signatures
+    }
+    #[test]
+    fn archive_roundtrip() {
+        let mut rng = crate::new_rng();
+        let records = test_records(&mut rng);
+        let bytes = write_archive(&records);
+        assert_eq!(read_archive(bytes).unwrap(), records);
+    }
+
+    #[test]
+    fn should_detect_corrupt_archive() {
+        let mut rng = crate::new_rng();
+        let bytes = write_archive(&test_records(&mut rng));
+
+        // Flip a byte in the body of the first record.
+        let mut corrupt = bytes.clone();
+        corrupt[MAGIC.len() + 4 + 4] ^= 0xff;
+        assert!(matches!(
+            read_archive(corrupt),
+            Err(Error::ChecksumMismatch(1))
+        ));
+
+        // Drop the end marker.
+        let truncated = bytes[..bytes.len() - 1].to_vec();
+        assert!(matches!(read_archive(truncated), Err(Error::Truncated)));
+
+        // Change the magic value.
+        let mut not_an_archive = bytes;
+        not_an_archive[0] = b'X';
+        assert!(matches!(
+            read_archive(not_an_archive),
+            Err(Error::NotAnArchive)
+        ));
+    }
+
+    #[test]
+    fn should_verify_finality_signatures_on_import() {
+        let mut rng = crate::new_rng();
+        let (mut storage, _tempdir) = new_storage();
+
+        let secret_keys: Vec<_> = (0..3).map(|_| SecretKey::random(&mut rng)).collect();
+        let genesis_validators: BTreeMap<_, _> = secret_keys
+            .iter()
+            .map(|secret_key| (PublicKey::from(secret_key), U512::from(100)))
+            .collect();
+        let outsider = SecretKey::random(&mut rng);
+        let mut importer = Importer::new(genesis_validators, Ratio::new(1, 3));
+
+        let block = Block::random_with_specifics(&mut rng, EraId(0), 0, false);
+        let rejected = vec![
+            // No signatures at all.
+            sign(&block, &[]),
+            // A signature by a key which is not a validator.
+            sign(&block, &[&secret_keys[0], &secret_keys[1], &outsider]),
+            // Signatures by validators holding exactly the threshold weight.
+            sign(&block, &[&secret_keys[0]]),
+        ];
+        for signatures in rejected {
+            importer.pending_block = Some(block.clone());
+            assert!(matches!(
+                importer.import_block_signatures(&mut storage, signatures),
+                Err(Error::InvalidFinalitySignatures { height: 0, .. })
+            ));
+        }
+        assert_eq!(storage.read_highest_block_height(), None);
+
+        importer.pending_block = Some(block.clone());
+        let signatures = sign(&block, &[&secret_keys[0], &secret_keys[1]]);
+        importer
+            .import_block_signatures(&mut storage, signatures.clone())
+            .unwrap();
+        let (stored_block, stored_signatures) = storage
+            .read_block_and_signatures_by_height(0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored_block, block);
+        assert_eq!(stored_signatures, signatures);
+
+        // The validators of a later era are unknown without the switch block of the era before.
+        let block = Block::random_with_specifics(&mut rng, EraId(2), 1, false);
+        importer.pending_block = Some(block.clone());
+        assert!(matches!(
+            importer.import_block_signatures(&mut storage, sign(&block, &[&secret_keys[0]])),
+            Err(Error::UnknownEraValidators(EraId(2)))
+        ));
+    }
+
+    #[test]
+    fn should_require_parent_of_first_block_in_storage() {
+        let mut rng = crate::new_rng();
+        let (mut storage, _tempdir) = new_storage();
+        let mut importer = Importer::new(BTreeMap::new(), Ratio::new(1, 3));
+
+        let block = Block::random_with_specifics(&mut rng, EraId(0), 5, false);
+        assert!(matches!(
+            importer.import_block(&storage, &header(5), block.clone()),
+            Err(Error::MissingParentBlock(4))
+        ));
+
+        // A block at the height below which is not the parent.
+        let other = Block::random_with_specifics(&mut rng, EraId(0), 4, false);
+        storage.write_block(&other).unwrap();
+        assert!(matches!(
+            importer.import_block(&storage, &header(5), block),
+            Err(Error::BlockNotContiguous(5))
+        ));
+    }
+}
@@ -3,6 +3,7 @@
 //! The block proposer stores deploy hashes in memory, tracking their suitability for inclusion into
 //! a new block. Upon request, it returns a list of candidates that can be included.
 
+mod config;
 mod deploy_sets;
 mod event;
 mod metrics;
@@ -12,7 +13,6 @@ mod tests;
 
 use std::{
     collections::{HashMap, HashSet},
-    convert::Infallible,
     time::Duration,
 };
 
@@ -20,9 +20,12 @@ use datasize::DataSize;
 use prometheus::{self, Registry};
 use tracing::{debug, error, info, trace, warn};
 
+use casper_types::PublicKey;
+
 use crate::{
     components::Component,
     effect::{
+        announcements::BlockProposerAnnouncement,
         requests::{BlockProposerRequest, ProtoBlockRequest, StateStoreRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
     },
@@ -30,7 +33,8 @@ use crate::{
     NodeRng,
 };
 use casper_execution_engine::shared::gas::Gas;
-pub(crate) use deploy_sets::BlockProposerDeploySets;
+pub use config::Config;
+pub(crate) use deploy_sets::{BlockProposerDeploySets, PendingDeployLimitError};
 pub(crate) use event::{DeployType, Event};
 use metrics::BlockProposerMetrics;
 use num_traits::Zero;
@@ -66,6 +70,17 @@ type FinalizationQueue = HashMap<BlockHeight, Vec<DeployHash>>;
 /// fulfill the corresponding requests.
 type RequestQueue = HashMap<BlockHeight, Vec<ProtoBlockRequest>>;
 
+/// The deploys held by the block proposer which haven't been executed yet.
+#[derive(Clone, DataSize, Debug, Default)]
+pub(crate) struct PendingDeploys {
+    /// Deploys waiting to be included in a block, in the order they would be proposed.
+    pub(crate) pending: Vec<DeployHash>,
+    /// The total serialized size of the deploys in `pending`, in bytes.
+    pub(crate) pending_bytes: u64,
+    /// Deploys which have been included in a finalized block, but haven't been executed yet.
+    pub(crate) finalized_unexecuted: Vec<DeployHash>,
+}
+
 /// Current operational state of a block proposer.
 #[derive(DataSize, Debug)]
 #[allow(clippy::large_enum_variant)]
@@ -78,6 +93,8 @@ enum BlockProposerState {
         state_key: Vec<u8>,
         /// The deploy config from the current chainspec.
         deploy_config: DeployConfig,
+        /// The block proposer's own configuration.
+        config: Config,
     },
     /// Normal operation.
     Ready(BlockProposerReady),
@@ -90,9 +107,15 @@ impl BlockProposer {
         effect_builder: EffectBuilder<REv>,
         next_finalized_block: BlockHeight,
         chainspec: &Chainspec,
+        config: Config,
     ) -> Result<(Self, Effects<Event>), prometheus::Error>
     where
-        REv: From<Event> + From<StorageRequest> + From<StateStoreRequest> + Send + 'static,
+        REv: From<Event>
+            + From<StorageRequest>
+            + From<StateStoreRequest>
+            + From<BlockProposerAnnouncement>
+            + Send
+            + 'static,
     {
         debug!(%next_finalized_block, "creating block proposer");
         // load the state from storage or use a fresh instance if loading fails.
@@ -109,6 +132,7 @@ impl BlockProposer {
                 pending: Vec::new(),
                 state_key,
                 deploy_config: chainspec.deploy_config,
+                config,
             },
             metrics: BlockProposerMetrics::new(registry)?,
         };
@@ -119,10 +143,15 @@ impl BlockProposer {
 
 impl<REv> Component<REv> for BlockProposer
 where
-    REv: From<Event> + From<StorageRequest> + From<StateStoreRequest> + Send + 'static,
+    REv: From<Event>
+        + From<StorageRequest>
+        + From<StateStoreRequest>
+        + From<BlockProposerAnnouncement>
+        + Send
+        + 'static,
 {
     type Event = Event;
-    type ConstructionError = Infallible;
+    type ConstructionError = prometheus::Error;
 
     fn handle_event(
         &mut self,
@@ -141,6 +170,7 @@ where
                     ref mut pending,
                     state_key,
                     deploy_config,
+                    config,
                 },
                 Event::Loaded {
                     finalized_deploys,
@@ -153,7 +183,9 @@ where
                         next_finalized_block,
                     ),
                     unhandled_finalized: Default::default(),
+                    unexecuted_finalized: Default::default(),
                     deploy_config: *deploy_config,
+                    config: *config,
                     state_key: state_key.clone(),
                     request_queue: Default::default(),
                 };
@@ -205,8 +237,12 @@ struct BlockProposerReady {
     /// seen but were reported as reported to `finalized_deploys()`. They are used to
     /// filter deploys for proposal, similar to `self.sets.finalized_deploys`.
     unhandled_finalized: HashSet<DeployHash>,
+    /// Hashes of deploys contained in finalized blocks which haven't been executed yet.
+    unexecuted_finalized: HashSet<DeployHash>,
     /// We don't need the whole Chainspec here, just the deploy config.
     deploy_config: DeployConfig,
+    /// The block proposer's own configuration, holding the limits on pending deploys.
+    config: Config,
     /// Key for storing the block proposer state.
     state_key: Vec<u8>,
     /// The queue of requests awaiting being handled.
@@ -220,7 +256,7 @@ impl BlockProposerReady {
         event: Event,
     ) -> Effects<Event>
     where
-        REv: Send + From<StateStoreRequest>,
+        REv: Send + From<StateStoreRequest> + From<BlockProposerAnnouncement>,
     {
         match event {
             Event::Request(BlockProposerRequest::RequestProtoBlock(request)) => {
@@ -248,8 +284,37 @@ impl BlockProposerReady {
                         .ignore()
                 }
             }
+            Event::Request(BlockProposerRequest::CheckPendingDeployLimits {
+                hash,
+                deploy_type,
+                responder,
+            }) => {
+                let result = self
+                    .sets
+                    .evictions_for(&self.config, &hash, &deploy_type)
+                    .map(|_| ());
+                responder.respond(result).ignore()
+            }
+            Event::Request(BlockProposerRequest::GetPendingDeploys { account, responder }) => {
+                responder
+                    .respond(self.pending_deploys(account.as_ref()))
+                    .ignore()
+            }
             Event::BufferDeploy { hash, deploy_type } => {
-                self.add_deploy_or_transfer(Timestamp::now(), hash, *deploy_type);
+                let account = *deploy_type.header().account();
+                self.add_deploy_or_transfer(Timestamp::now(), hash, *deploy_type)
+                    .into_iter()
+                    .flat_map(|superseded_hash| {
+                        effect_builder
+                            .announce_deploy_superseded(superseded_hash, hash, account)
+                            .ignore()
+                    })
+                    .collect()
+            }
+            Event::ExecutedDeploys { deploys } => {
+                for deploy_hash in deploys.iter() {
+                    self.unexecuted_finalized.remove(deploy_hash);
+                }
                 Effects::new()
             }
             Event::Prune => {
@@ -300,16 +365,18 @@ impl BlockProposerReady {
 
     /// Adds a deploy to the block proposer.
     ///
-    /// Returns `false` if the deploy has been rejected.
+    /// A deploy replaces any pending deploys from the same account with the same timestamp and a
+    /// strictly lower gas price, and is rejected if such a pending deploy has a strictly higher gas
+    /// price. Returns the hashes of the replaced deploys.
     fn add_deploy_or_transfer(
         &mut self,
         current_instant: Timestamp,
         hash: DeployHash,
         deploy_or_transfer: DeployType,
-    ) {
+    ) -> Vec<DeployHash> {
         if deploy_or_transfer.header().expired(current_instant) {
             trace!(%hash, "expired deploy rejected from the buffer");
-            return;
+            return Vec::new();
         }
         if self.unhandled_finalized.remove(&hash) {
             info!(%hash,
@@ -318,14 +385,44 @@ impl BlockProposerReady {
             self.sets
                 .finalized_deploys
                 .insert(hash, deploy_or_transfer.take_header());
-            return;
+            return Vec::new();
         }
         // only add the deploy if it isn't contained in a finalized block
         if self.sets.finalized_deploys.contains_key(&hash) {
             info!(%hash, "deploy rejected from the buffer");
-        } else {
-            self.sets.pending.insert(hash, deploy_or_transfer);
-            info!(%hash, "added deploy to the buffer");
+            return Vec::new();
+        }
+        if self.sets.pending.contains_key(&hash) {
+            trace!(%hash, "deploy already in the buffer");
+            return Vec::new();
+        }
+        if let Some(superseding_hash) = self.sets.superseding(&deploy_or_transfer) {
+            info!(%hash, %superseding_hash, "superseded deploy rejected from the buffer");
+            return Vec::new();
+        }
+        // make room for the deploy, evicting lower-priority ones if we're at a limit
+        match self
+            .sets
+            .evictions_for(&self.config, &hash, &deploy_or_transfer)
+        {
+            Ok(evicted) => {
+                let replaced = self.sets.replaced_by(&deploy_or_transfer);
+                for replaced_hash in replaced.iter() {
+                    self.sets.pending.remove(replaced_hash);
+                    info!(%replaced_hash, superseded_by = %hash, "replaced deploy in the buffer");
+                }
+                for evicted_hash in evicted {
+                    self.sets.pending.remove(&evicted_hash);
+                    info!(%evicted_hash, "evicted deploy from the buffer");
+                }
+                self.sets.pending.insert(hash, deploy_or_transfer);
+                info!(%hash, "added deploy to the buffer");
+                replaced
+            }
+            Err(error) => {
+                info!(%hash, %error, "deploy rejected from the buffer");
+                Vec::new()
+            }
         }
     }
 
@@ -335,6 +432,7 @@ impl BlockProposerReady {
         I: IntoIterator<Item = DeployHash>,
     {
         for deploy_hash in deploys.into_iter() {
+            self.unexecuted_finalized.insert(deploy_hash);
             match self.sets.pending.remove(&deploy_hash) {
                 Some(deploy_type) => {
                     self.sets
@@ -401,6 +499,9 @@ impl BlockProposerReady {
     }
 
     /// Returns a list of candidates for inclusion into a block.
+    ///
+    /// Pending deploys are considered in the order given by
+    /// `BlockProposerDeploySets::pending_in_priority_order`.
     fn propose_proto_block(
         &mut self,
         deploy_config: DeployConfig,
@@ -418,7 +519,7 @@ impl BlockProposerReady {
         let mut block_gas_running_total = Gas::zero();
         let mut block_size_running_total = 0usize;
 
-        for (hash, deploy_type) in self.sets.pending.iter() {
+        for (hash, deploy_type) in self.sets.pending_in_priority_order() {
             let at_max_transfers = transfers.len() == max_transfers;
             let at_max_deploys = wasm_deploys.len() == max_deploys
                 || (deploy_type.is_wasm()
@@ -477,9 +578,44 @@ impl BlockProposerReady {
         ProtoBlock::new(wasm_deploys, transfers, block_timestamp, random_bit)
     }
 
+    /// Returns the deploys which haven't been executed yet, optionally only those created by the
+    /// given account.
+    fn pending_deploys(&self, account: Option<&PublicKey>) -> PendingDeploys {
+        let is_included =
+            |header: &DeployHeader| account.map_or(true, |account| header.account() == account);
+
+        let mut result = PendingDeploys::default();
+        for (hash, deploy_type) in self.sets.pending_in_priority_order() {
+            if is_included(deploy_type.header()) {
+                result.pending.push(*hash);
+                result.pending_bytes += deploy_type.size() as u64;
+            }
+        }
+
+        // Deploys finalized before we saw them have no known header, so can't be matched against
+        // an account.
+        result.finalized_unexecuted = self
+            .unexecuted_finalized
+            .iter()
+            .filter(|hash| match self.sets.finalized_deploys.get(*hash) {
+                Some(header) => is_included(header),
+                None => account.is_none(),
+            })
+            .copied()
+            .collect();
+        result.finalized_unexecuted.sort();
+        result
+    }
+
     /// Prunes expired deploy information from the BlockProposer, returns the total deploys pruned.
     fn prune(&mut self, current_instant: Timestamp) -> usize {
-        self.sets.prune(current_instant)
+        let pruned = self.sets.prune(current_instant);
+        let sets = &self.sets;
+        let unhandled_finalized = &self.unhandled_finalized;
+        self.unexecuted_finalized.retain(|hash| {
+            sets.finalized_deploys.contains_key(hash) || unhandled_finalized.contains(hash)
+        });
+        pruned
     }
 
     fn contains_finalized(&self, dep: &DeployHash) -> bool {
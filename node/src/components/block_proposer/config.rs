@@ -0,0 +1,36 @@
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+/// Default maximum number of pending deploys per account.
+const DEFAULT_MAX_PENDING_DEPLOYS_PER_ACCOUNT: u32 = 100;
+
+/// Default maximum number of pending deploys in total.
+const DEFAULT_MAX_PENDING_DEPLOYS: u32 = 10_000;
+
+/// Default maximum total size of pending deploys: 256 MiB.
+const DEFAULT_MAX_PENDING_BYTES: u64 = 268_435_456;
+
+/// Block proposer configuration.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Maximum number of pending deploys held for a single account.
+    pub max_pending_deploys_per_account: u32,
+
+    /// Maximum number of pending deploys held in total.
+    pub max_pending_deploys: u32,
+
+    /// Maximum total serialized size in bytes of all pending deploys.
+    pub max_pending_bytes: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            max_pending_deploys_per_account: DEFAULT_MAX_PENDING_DEPLOYS_PER_ACCOUNT,
+            max_pending_deploys: DEFAULT_MAX_PENDING_DEPLOYS,
+            max_pending_bytes: DEFAULT_MAX_PENDING_BYTES,
+        }
+    }
+}
@@ -1,14 +1,41 @@
 use std::{
-    collections::HashMap,
+    cmp::{Ordering, Reverse},
+    collections::{BTreeMap, BinaryHeap, HashMap},
     fmt::{self, Display, Formatter},
 };
 
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use super::{event::DeployType, BlockHeight, FinalizationQueue};
+use casper_types::PublicKey;
+
+use super::{event::DeployType, BlockHeight, Config, FinalizationQueue};
 use crate::types::{Chainspec, DeployHash, DeployHeader, Timestamp};
 
+/// The reason why a deploy cannot be admitted to the set of pending deploys.
+#[derive(Clone, Copy, DataSize, Debug, Error, PartialEq, Eq, Serialize)]
+pub enum PendingDeployLimitError {
+    /// The account already has the maximum number of pending deploys.
+    #[error("account already has the maximum of {max} pending deploys")]
+    AccountLimit {
+        /// The per-account limit.
+        max: u32,
+    },
+    /// The node already holds the maximum number of pending deploys.
+    #[error("node already holds the maximum of {max} pending deploys")]
+    TotalCountLimit {
+        /// The total count limit.
+        max: u32,
+    },
+    /// The node already holds the maximum total size of pending deploys.
+    #[error("node already holds the maximum of {max} bytes of pending deploys")]
+    TotalSizeLimit {
+        /// The total size limit in bytes.
+        max: u64,
+    },
+}
+
 /// Stores the internal state of the BlockProposer.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct BlockProposerDeploySets {
@@ -56,6 +83,116 @@ impl BlockProposerDeploySets {
     }
 }
 
+impl BlockProposerDeploySets {
+    /// Returns the pending deploys in the order in which they should be considered for inclusion
+    /// in a proposed block.
+    ///
+    /// Deploys from a single account are always ordered by their timestamp, so an account cannot
+    /// jump its own queue by raising the gas price of a later deploy. Across accounts, the next
+    /// deploy of every account competes on gas price (highest first), then on timestamp (earliest
+    /// first), with the deploy hash as a final tie-breaker to keep the order deterministic.
+    pub(super) fn pending_in_priority_order(&self) -> Vec<(&DeployHash, &DeployType)> {
+        let mut per_account: BTreeMap<&PublicKey, Vec<(&DeployHash, &DeployType)>> =
+            BTreeMap::new();
+        for (hash, deploy_type) in self.pending.iter() {
+            per_account
+                .entry(deploy_type.header().account())
+                .or_default()
+                .push((hash, deploy_type));
+        }
+
+        // Each account's queue is sorted in reverse, so the earliest deploy can be popped off the
+        // end.
+        let mut queues: Vec<_> = per_account
+            .into_iter()
+            .map(|(_, mut deploys)| {
+                deploys.sort_by(|(hash1, deploy1), (hash2, deploy2)| {
+                    (deploy2.header().timestamp(), *hash2)
+                        .cmp(&(deploy1.header().timestamp(), *hash1))
+                });
+                deploys
+            })
+            .collect();
+
+        let mut heap = BinaryHeap::with_capacity(queues.len());
+        for (queue_index, queue) in queues.iter_mut().enumerate() {
+            if let Some((hash, deploy_type)) = queue.pop() {
+                heap.push(PrioritizedDeploy::new(queue_index, hash, deploy_type));
+            }
+        }
+
+        let mut ordered = Vec::with_capacity(self.pending.len());
+        while let Some(PrioritizedDeploy {
+            queue_index,
+            hash,
+            deploy_type,
+        }) = heap.pop()
+        {
+            ordered.push((hash, deploy_type));
+            if let Some((hash, deploy_type)) = queues[queue_index].pop() {
+                heap.push(PrioritizedDeploy::new(queue_index, hash, deploy_type));
+            }
+        }
+        ordered
+    }
+}
+
+/// The head of a single account's queue of pending deploys, ordered by priority for proposal.
+struct PrioritizedDeploy<'a> {
+    /// Index of the account queue the deploy was taken from.
+    queue_index: usize,
+    hash: &'a DeployHash,
+    deploy_type: &'a DeployType,
+}
+
+impl<'a> PrioritizedDeploy<'a> {
+    fn new(queue_index: usize, hash: &'a DeployHash, deploy_type: &'a DeployType) -> Self {
+        PrioritizedDeploy {
+            queue_index,
+            hash,
+            deploy_type,
+        }
+    }
+
+    fn priority(&self) -> Priority<'a> {
+        priority(self.hash, self.deploy_type)
+    }
+}
+
+/// The priority of a pending deploy: greater values are proposed first and evicted last.
+type Priority<'a> = (u64, Reverse<Timestamp>, Reverse<&'a DeployHash>);
+
+/// Returns the priority of a pending deploy, based on its gas price, then its timestamp, then its
+/// hash.
+fn priority<'a>(hash: &'a DeployHash, deploy_type: &DeployType) -> Priority<'a> {
+    let header = deploy_type.header();
+    (
+        header.gas_price(),
+        Reverse(header.timestamp()),
+        Reverse(hash),
+    )
+}
+
+impl<'a> PartialEq for PrioritizedDeploy<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority() == other.priority()
+    }
+}
+
+impl<'a> Eq for PrioritizedDeploy<'a> {}
+
+impl<'a> PartialOrd for PrioritizedDeploy<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Ord for PrioritizedDeploy<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority().cmp(&other.priority())
+    }
+}
+
 impl Display for BlockProposerDeploySets {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
@@ -67,6 +204,35 @@ impl Display for BlockProposerDeploySets {
     }
 }
 
+/// The resources taken up by pending deploys, as counted against the limits in `Config`.
+struct PendingUsage {
+    /// The number of pending deploys from the account of the deploy being admitted.
+    account_count: usize,
+    /// The total number of pending deploys.
+    total_count: usize,
+    /// The total serialized size of pending deploys.
+    total_bytes: u64,
+}
+
+impl PendingUsage {
+    /// Returns whether the given deploy can be added without exceeding any of the limits.
+    fn admits(&self, config: &Config, deploy_type: &DeployType) -> bool {
+        self.account_count < config.max_pending_deploys_per_account as usize
+            && self.total_count < config.max_pending_deploys as usize
+            && self.total_bytes.saturating_add(deploy_type.size() as u64)
+                <= config.max_pending_bytes
+    }
+
+    /// Accounts for the removal of a pending deploy.
+    fn remove(&mut self, deploy_type: &DeployType, same_account: bool) {
+        if same_account {
+            self.account_count -= 1;
+        }
+        self.total_count -= 1;
+        self.total_bytes -= deploy_type.size() as u64;
+    }
+}
+
 /// Create a state storage key for block proposer deploy sets based on a chainspec.
 ///
 /// We namespace based on a chainspec to prevent validators from loading data for a different chain
@@ -80,6 +246,121 @@ pub fn create_storage_key(chainspec: &Chainspec) -> Vec<u8> {
 }
 
 impl BlockProposerDeploySets {
+    /// Returns the hashes of the pending deploys which are replaced by the given deploy: those from
+    /// the same account with the same timestamp, but a strictly lower gas price.
+    pub(super) fn replaced_by(&self, deploy_type: &DeployType) -> Vec<DeployHash> {
+        let header = deploy_type.header();
+        self.pending
+            .iter()
+            .filter(|(_, pending)| {
+                let pending_header = pending.header();
+                pending_header.account() == header.account()
+                    && pending_header.timestamp() == header.timestamp()
+                    && pending_header.gas_price() < header.gas_price()
+            })
+            .map(|(pending_hash, _)| *pending_hash)
+            .collect()
+    }
+
+    /// Returns the hash of a pending deploy which supersedes the given deploy, i.e. one from the
+    /// same account with the same timestamp, but a strictly higher gas price.
+    pub(super) fn superseding(&self, deploy_type: &DeployType) -> Option<&DeployHash> {
+        let header = deploy_type.header();
+        self.pending
+            .iter()
+            .find(|(_, pending)| {
+                let pending_header = pending.header();
+                pending_header.account() == header.account()
+                    && pending_header.timestamp() == header.timestamp()
+                    && pending_header.gas_price() > header.gas_price()
+            })
+            .map(|(pending_hash, _)| pending_hash)
+    }
+
+    /// Returns the hashes of the pending deploys which have to be evicted to admit the given deploy
+    /// without exceeding the limits set in `config`.
+    ///
+    /// Deploys replaced by the new one (see `replaced_by`) don't count against the limits and are
+    /// not part of the result. Apart from those, only deploys with a lower priority than the new
+    /// one are ever evicted, lowest priority first. If that doesn't free up enough room, the new
+    /// deploy is not admitted.
+    pub(super) fn evictions_for(
+        &self,
+        config: &Config,
+        hash: &DeployHash,
+        deploy_type: &DeployType,
+    ) -> Result<Vec<DeployHash>, PendingDeployLimitError> {
+        let replaced = self.replaced_by(deploy_type);
+        let remaining_pending = || {
+            self.pending
+                .iter()
+                .filter(|(pending_hash, _)| !replaced.contains(pending_hash))
+        };
+
+        let account = deploy_type.header().account();
+        let mut usage = PendingUsage {
+            account_count: remaining_pending()
+                .filter(|(_, pending)| pending.header().account() == account)
+                .count(),
+            total_count: remaining_pending().count(),
+            total_bytes: remaining_pending()
+                .map(|(_, pending)| pending.size() as u64)
+                .sum(),
+        };
+        if usage.admits(config, deploy_type) {
+            return Ok(Vec::new());
+        }
+
+        // All pending deploys with a lower priority than the new one, lowest priority first.
+        let new_priority = priority(hash, deploy_type);
+        let mut candidates: Vec<_> = remaining_pending()
+            .filter(|(pending_hash, pending)| priority(pending_hash, pending) < new_priority)
+            .collect();
+        candidates.sort_by(|(hash1, deploy1), (hash2, deploy2)| {
+            priority(hash1, deploy1).cmp(&priority(hash2, deploy2))
+        });
+
+        let max_per_account = config.max_pending_deploys_per_account as usize;
+        let mut evicted = Vec::new();
+        let mut remaining = Vec::with_capacity(candidates.len());
+        for (pending_hash, pending) in candidates {
+            let same_account = pending.header().account() == account;
+            if same_account && usage.account_count >= max_per_account {
+                usage.remove(pending, same_account);
+                evicted.push(*pending_hash);
+            } else {
+                remaining.push((pending_hash, pending));
+            }
+        }
+        if usage.account_count >= max_per_account {
+            return Err(PendingDeployLimitError::AccountLimit {
+                max: config.max_pending_deploys_per_account,
+            });
+        }
+
+        let mut remaining = remaining.into_iter();
+        while !usage.admits(config, deploy_type) {
+            match remaining.next() {
+                Some((pending_hash, pending)) => {
+                    usage.remove(pending, pending.header().account() == account);
+                    evicted.push(*pending_hash);
+                }
+                None if usage.total_count >= config.max_pending_deploys as usize => {
+                    return Err(PendingDeployLimitError::TotalCountLimit {
+                        max: config.max_pending_deploys,
+                    });
+                }
+                None => {
+                    return Err(PendingDeployLimitError::TotalSizeLimit {
+                        max: config.max_pending_bytes,
+                    });
+                }
+            }
+        }
+
+        Ok(evicted)
+    }
+
     /// Prunes expired deploy information from the BlockProposerState, returns the total deploys
     /// pruned
     pub(crate) fn prune(&mut self, current_instant: Timestamp) -> usize {
@@ -91,6 +91,8 @@ pub enum Event {
         hash: DeployHash,
         deploy_type: Box<DeployType>,
     },
+    /// The deploys of a block have been executed.
+    ExecutedDeploys { deploys: Vec<DeployHash> },
     /// The block proposer has been asked to prune stale deploys
     Prune,
     /// A proto block has been finalized. We should never propose its deploys again.
@@ -113,6 +115,9 @@ impl Display for Event {
                 next_finalized_block
             ),
             Event::BufferDeploy { hash, .. } => write!(f, "block-proposer add {}", hash),
+            Event::ExecutedDeploys { deploys } => {
+                write!(f, "block-proposer executed {} deploys", deploys.len())
+            }
             Event::Prune => write!(f, "block-proposer prune"),
             Event::FinalizedProtoBlock { block, height } => {
                 write!(
@@ -63,6 +63,24 @@ fn generate_deploy(
     gas_price: u64,
 ) -> Deploy {
     let secret_key = SecretKey::random(rng);
+    generate_deploy_for_account(
+        &secret_key,
+        timestamp,
+        ttl,
+        dependencies,
+        payment_amount,
+        gas_price,
+    )
+}
+
+fn generate_deploy_for_account(
+    secret_key: &SecretKey,
+    timestamp: Timestamp,
+    ttl: TimeDiff,
+    dependencies: Vec<DeployHash>,
+    payment_amount: Gas,
+    gas_price: u64,
+) -> Deploy {
     let chain_name = "chain".to_string();
     let args = runtime_args! {
         ARG_AMOUNT => payment_amount.value()
@@ -84,7 +102,7 @@ fn generate_deploy(
         chain_name,
         payment,
         session,
-        &secret_key,
+        secret_key,
     )
 }
 
@@ -92,9 +110,11 @@ fn create_test_proposer() -> BlockProposerReady {
     BlockProposerReady {
         sets: Default::default(),
         deploy_config: Default::default(),
+        config: Default::default(),
         state_key: b"block-proposer-test".to_vec(),
         request_queue: Default::default(),
         unhandled_finalized: Default::default(),
+        unexecuted_finalized: Default::default(),
     }
 }
 
@@ -668,3 +688,597 @@ fn should_return_deploy_dependencies() {
     assert_eq!(deploys2.len(), 1);
     assert!(deploys2.contains(deploy2.id()));
 }
+
+/// Creates a deploy config which allows at most `max_deploy_count` wasm deploys per block.
+fn deploy_config_with_max_deploys(max_deploy_count: u32) -> DeployConfig {
+    DeployConfig {
+        block_max_deploy_count: max_deploy_count,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn should_propose_deploys_with_highest_gas_price_first() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let mut rng = crate::new_rng();
+    let cheap = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        1,
+    );
+    let expensive = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        3,
+    );
+    let medium = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        2,
+    );
+
+    let mut proposer = create_test_proposer();
+    for deploy in &[&cheap, &expensive, &medium] {
+        proposer.add_deploy_or_transfer(creation_time, *deploy.id(), deploy.deploy_type().unwrap());
+    }
+
+    // only two deploys fit: the cheapest one should be left behind
+    let block = proposer.propose_proto_block(
+        deploy_config_with_max_deploys(2),
+        block_time,
+        HashSet::new(),
+        true,
+    );
+    assert_eq!(block.wasm_deploys(), &vec![*expensive.id(), *medium.id()]);
+}
+
+#[test]
+fn should_break_gas_price_ties_by_timestamp() {
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let mut rng = crate::new_rng();
+    let later = generate_deploy(
+        &mut rng,
+        Timestamp::from(110),
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    let earlier = generate_deploy(
+        &mut rng,
+        Timestamp::from(100),
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+
+    let mut proposer = create_test_proposer();
+    proposer.add_deploy_or_transfer(block_time, *later.id(), later.deploy_type().unwrap());
+    proposer.add_deploy_or_transfer(block_time, *earlier.id(), earlier.deploy_type().unwrap());
+
+    let block = proposer.propose_proto_block(
+        deploy_config_with_max_deploys(1),
+        block_time,
+        HashSet::new(),
+        true,
+    );
+    assert_eq!(block.wasm_deploys(), &vec![*earlier.id()]);
+}
+
+#[test]
+fn should_propose_deploys_of_one_account_in_timestamp_order() {
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let mut rng = crate::new_rng();
+    let secret_key = SecretKey::random(&mut rng);
+    // the account's later deploy pays more, but must not overtake its earlier one
+    let first = generate_deploy_for_account(
+        &secret_key,
+        Timestamp::from(100),
+        ttl,
+        vec![],
+        default_gas_payment(),
+        1,
+    );
+    let second = generate_deploy_for_account(
+        &secret_key,
+        Timestamp::from(110),
+        ttl,
+        vec![],
+        default_gas_payment(),
+        5,
+    );
+    let other_account = generate_deploy(
+        &mut rng,
+        Timestamp::from(100),
+        ttl,
+        vec![],
+        default_gas_payment(),
+        2,
+    );
+
+    let mut proposer = create_test_proposer();
+    for deploy in &[&second, &other_account, &first] {
+        proposer.add_deploy_or_transfer(block_time, *deploy.id(), deploy.deploy_type().unwrap());
+    }
+
+    let block = proposer.propose_proto_block(
+        deploy_config_with_max_deploys(3),
+        block_time,
+        HashSet::new(),
+        true,
+    );
+    assert_eq!(
+        block.wasm_deploys(),
+        &vec![*other_account.id(), *first.id(), *second.id()]
+    );
+}
+
+#[test]
+fn should_propose_deterministically_regardless_of_arrival_order() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+    let block_time = Timestamp::from(120);
+
+    let mut rng = crate::new_rng();
+    let deploys: Vec<_> = (0..20)
+        .map(|index| {
+            generate_deploy(
+                &mut rng,
+                creation_time + TimeDiff::from(index % 3),
+                ttl,
+                vec![],
+                default_gas_payment(),
+                index % 4 + 1,
+            )
+        })
+        .collect();
+
+    let mut proposer1 = create_test_proposer();
+    for deploy in deploys.iter() {
+        proposer1.add_deploy_or_transfer(
+            creation_time,
+            *deploy.id(),
+            deploy.deploy_type().unwrap(),
+        );
+    }
+    let mut proposer2 = create_test_proposer();
+    for deploy in deploys.iter().rev() {
+        proposer2.add_deploy_or_transfer(
+            creation_time,
+            *deploy.id(),
+            deploy.deploy_type().unwrap(),
+        );
+    }
+
+    let config = deploy_config_with_max_deploys(10);
+    let block1 = proposer1.propose_proto_block(config, block_time, HashSet::new(), true);
+    let block2 = proposer2.propose_proto_block(config, block_time, HashSet::new(), true);
+    assert_eq!(block1.wasm_deploys().len(), 10);
+    assert_eq!(block1.wasm_deploys(), block2.wasm_deploys());
+}
+
+fn create_test_proposer_with_config(config: Config) -> BlockProposerReady {
+    BlockProposerReady {
+        config,
+        ..create_test_proposer()
+    }
+}
+
+#[test]
+fn should_evict_lowest_priority_deploy_when_at_total_count_limit() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+
+    let mut rng = crate::new_rng();
+    let mut proposer = create_test_proposer_with_config(Config {
+        max_pending_deploys: 2,
+        ..Default::default()
+    });
+    let deploys: Vec<_> = (1..=3)
+        .map(|gas_price| {
+            generate_deploy(
+                &mut rng,
+                creation_time,
+                ttl,
+                vec![],
+                default_gas_payment(),
+                gas_price,
+            )
+        })
+        .collect();
+
+    // the cheapest deploy is evicted to make room for the most expensive one
+    for deploy in deploys.iter() {
+        proposer.add_deploy_or_transfer(creation_time, *deploy.id(), deploy.deploy_type().unwrap());
+    }
+    assert_eq!(proposer.sets.pending.len(), 2);
+    assert!(!proposer.sets.pending.contains_key(deploys[0].id()));
+
+    // a new deploy with the lowest priority is rejected
+    let cheap = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        1,
+    );
+    assert_eq!(
+        proposer
+            .sets
+            .evictions_for(&proposer.config, cheap.id(), &cheap.deploy_type().unwrap()),
+        Err(PendingDeployLimitError::TotalCountLimit { max: 2 })
+    );
+    proposer.add_deploy_or_transfer(creation_time, *cheap.id(), cheap.deploy_type().unwrap());
+    assert_eq!(proposer.sets.pending.len(), 2);
+    assert!(!proposer.sets.pending.contains_key(cheap.id()));
+}
+
+#[test]
+fn should_respect_per_account_pending_limit() {
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+
+    let mut rng = crate::new_rng();
+    let secret_key = SecretKey::random(&mut rng);
+    let mut proposer = create_test_proposer_with_config(Config {
+        max_pending_deploys_per_account: 2,
+        ..Default::default()
+    });
+    let deploys: Vec<_> = (0..3)
+        .map(|index| {
+            generate_deploy_for_account(
+                &secret_key,
+                Timestamp::from(100 + index),
+                ttl,
+                vec![],
+                default_gas_payment(),
+                DEFAULT_TEST_GAS_PRICE,
+            )
+        })
+        .collect();
+    for deploy in deploys.iter() {
+        proposer.add_deploy_or_transfer(
+            Timestamp::from(110),
+            *deploy.id(),
+            deploy.deploy_type().unwrap(),
+        );
+    }
+
+    // the account's latest deploy has the lowest priority, so it is rejected
+    assert_eq!(proposer.sets.pending.len(), 2);
+    assert!(!proposer.sets.pending.contains_key(deploys[2].id()));
+    assert_eq!(
+        proposer.sets.evictions_for(
+            &proposer.config,
+            deploys[2].id(),
+            &deploys[2].deploy_type().unwrap()
+        ),
+        Err(PendingDeployLimitError::AccountLimit { max: 2 })
+    );
+
+    // other accounts are unaffected
+    let other = generate_deploy(
+        &mut rng,
+        Timestamp::from(100),
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    proposer.add_deploy_or_transfer(
+        Timestamp::from(110),
+        *other.id(),
+        other.deploy_type().unwrap(),
+    );
+    assert_eq!(proposer.sets.pending.len(), 3);
+
+    // a deploy with a higher gas price evicts the account's lowest-priority deploy
+    let expensive = generate_deploy_for_account(
+        &secret_key,
+        Timestamp::from(105),
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE + 1,
+    );
+    proposer.add_deploy_or_transfer(
+        Timestamp::from(110),
+        *expensive.id(),
+        expensive.deploy_type().unwrap(),
+    );
+    assert_eq!(proposer.sets.pending.len(), 3);
+    assert!(proposer.sets.pending.contains_key(deploys[0].id()));
+    assert!(!proposer.sets.pending.contains_key(deploys[1].id()));
+    assert!(proposer.sets.pending.contains_key(expensive.id()));
+}
+
+#[test]
+fn should_evict_deploys_to_respect_pending_bytes_limit() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+
+    let mut rng = crate::new_rng();
+    let cheap = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        1,
+    );
+    let expensive = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        2,
+    );
+    let cheap_type = cheap.deploy_type().unwrap();
+    let expensive_type = expensive.deploy_type().unwrap();
+
+    // only room for one of the two deploys
+    let max_pending_bytes = cheap_type.size().max(expensive_type.size()) as u64;
+    let mut proposer = create_test_proposer_with_config(Config {
+        max_pending_bytes,
+        ..Default::default()
+    });
+
+    proposer.add_deploy_or_transfer(creation_time, *cheap.id(), cheap_type);
+    proposer.add_deploy_or_transfer(creation_time, *expensive.id(), expensive_type);
+    assert_eq!(proposer.sets.pending.len(), 1);
+    assert!(proposer.sets.pending.contains_key(expensive.id()));
+
+    assert_eq!(
+        proposer
+            .sets
+            .evictions_for(&proposer.config, cheap.id(), &cheap.deploy_type().unwrap()),
+        Err(PendingDeployLimitError::TotalSizeLimit {
+            max: max_pending_bytes
+        })
+    );
+}
+
+#[test]
+fn should_replace_pending_deploy_with_higher_gas_price_and_same_timestamp() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+
+    let mut rng = crate::new_rng();
+    let secret_key = SecretKey::random(&mut rng);
+    let mut proposer = create_test_proposer();
+    let original = generate_deploy_for_account(
+        &secret_key,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        1,
+    );
+    let replacement = generate_deploy_for_account(
+        &secret_key,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        2,
+    );
+
+    let replaced = proposer.add_deploy_or_transfer(
+        creation_time,
+        *original.id(),
+        original.deploy_type().unwrap(),
+    );
+    assert!(replaced.is_empty());
+
+    let replaced = proposer.add_deploy_or_transfer(
+        creation_time,
+        *replacement.id(),
+        replacement.deploy_type().unwrap(),
+    );
+    assert_eq!(replaced, vec![*original.id()]);
+    assert_eq!(proposer.sets.pending.len(), 1);
+    assert!(proposer.sets.pending.contains_key(replacement.id()));
+
+    // the superseded deploy is not re-added if it is received again, e.g. via gossip
+    let replaced = proposer.add_deploy_or_transfer(
+        creation_time,
+        *original.id(),
+        original.deploy_type().unwrap(),
+    );
+    assert!(replaced.is_empty());
+    assert_eq!(proposer.sets.pending.len(), 1);
+    assert!(!proposer.sets.pending.contains_key(original.id()));
+}
+
+#[test]
+fn should_not_replace_pending_deploy_without_strictly_higher_gas_price() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+
+    let mut rng = crate::new_rng();
+    let secret_key = SecretKey::random(&mut rng);
+    let mut proposer = create_test_proposer();
+    let original = generate_deploy_for_account(
+        &secret_key,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    // a different payment amount gives the same-priced deploy a different hash
+    let same_price = generate_deploy_for_account(
+        &secret_key,
+        creation_time,
+        ttl,
+        vec![],
+        Gas::from(2u32),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    let other_timestamp = generate_deploy_for_account(
+        &secret_key,
+        Timestamp::from(101),
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE + 1,
+    );
+
+    for deploy in &[&original, &same_price, &other_timestamp] {
+        let replaced = proposer.add_deploy_or_transfer(
+            creation_time,
+            *deploy.id(),
+            deploy.deploy_type().unwrap(),
+        );
+        assert!(replaced.is_empty());
+    }
+    assert_eq!(proposer.sets.pending.len(), 3);
+
+    // deploys from other accounts never replace each other
+    let other_account = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE + 1,
+    );
+    let replaced = proposer.add_deploy_or_transfer(
+        creation_time,
+        *other_account.id(),
+        other_account.deploy_type().unwrap(),
+    );
+    assert!(replaced.is_empty());
+    assert_eq!(proposer.sets.pending.len(), 4);
+}
+
+#[test]
+fn should_not_count_replaced_deploy_against_pending_limits() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+
+    let mut rng = crate::new_rng();
+    let secret_key = SecretKey::random(&mut rng);
+    let mut proposer = create_test_proposer_with_config(Config {
+        max_pending_deploys_per_account: 1,
+        ..Default::default()
+    });
+    let original = generate_deploy_for_account(
+        &secret_key,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        1,
+    );
+    let replacement = generate_deploy_for_account(
+        &secret_key,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        2,
+    );
+
+    proposer.add_deploy_or_transfer(
+        creation_time,
+        *original.id(),
+        original.deploy_type().unwrap(),
+    );
+    assert_eq!(
+        proposer.sets.evictions_for(
+            &proposer.config,
+            replacement.id(),
+            &replacement.deploy_type().unwrap()
+        ),
+        Ok(vec![])
+    );
+    let replaced = proposer.add_deploy_or_transfer(
+        creation_time,
+        *replacement.id(),
+        replacement.deploy_type().unwrap(),
+    );
+    assert_eq!(replaced, vec![*original.id()]);
+    assert_eq!(proposer.sets.pending.len(), 1);
+}
+
+#[test]
+fn should_list_pending_and_finalized_unexecuted_deploys() {
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(100));
+
+    let mut rng = crate::new_rng();
+    let secret_key = SecretKey::random(&mut rng);
+    let account = PublicKey::from(&secret_key);
+    let mut proposer = create_test_proposer();
+    let cheap = generate_deploy_for_account(
+        &secret_key,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        1,
+    );
+    let expensive = generate_deploy_for_account(
+        &secret_key,
+        Timestamp::from(101),
+        ttl,
+        vec![],
+        default_gas_payment(),
+        2,
+    );
+    let other = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        3,
+    );
+    for deploy in &[&cheap, &expensive, &other] {
+        proposer.add_deploy_or_transfer(creation_time, *deploy.id(), deploy.deploy_type().unwrap());
+    }
+
+    let all = proposer.pending_deploys(None);
+    assert_eq!(all.pending, vec![*other.id(), *cheap.id(), *expensive.id()]);
+    let expected_bytes: usize = [&cheap, &expensive, &other]
+        .iter()
+        .map(|deploy| deploy.deploy_type().unwrap().size())
+        .sum();
+    assert_eq!(all.pending_bytes, expected_bytes as u64);
+    assert!(all.finalized_unexecuted.is_empty());
+
+    // once finalized, a deploy is listed as unexecuted rather than pending
+    proposer.finalized_deploys(vec![*cheap.id()]);
+    let of_account = proposer.pending_deploys(Some(&account));
+    assert_eq!(of_account.pending, vec![*expensive.id()]);
+    assert_eq!(
+        of_account.pending_bytes,
+        expensive.deploy_type().unwrap().size() as u64
+    );
+    assert_eq!(of_account.finalized_unexecuted, vec![*cheap.id()]);
+
+    // expired finalized deploys are no longer tracked
+    proposer.prune(Timestamp::from(300));
+    let all = proposer.pending_deploys(None);
+    assert!(all.pending.is_empty());
+    assert!(all.finalized_unexecuted.is_empty());
+}
@@ -9,7 +9,9 @@ mod era_supervisor;
 mod highway_core;
 mod metrics;
 mod protocols;
-#[cfg(test)]
+mod signer;
+#[cfg(any(test, feature = "consensus-sim"))]
+#[cfg_attr(not(test), allow(dead_code))]
 mod tests;
 mod traits;
 
@@ -27,7 +29,7 @@ use hex_fmt::HexFmt;
 use serde::{Deserialize, Serialize};
 use tracing::error;
 
-use casper_types::{PublicKey, U512};
+use casper_types::{PublicKey, Signature, U512};
 
 use crate::{
     components::Component,
@@ -51,8 +53,14 @@ use crate::{
 use crate::effect::EffectExt;
 pub use config::Config;
 pub(crate) use consensus_protocol::{BlockContext, EraReport};
-pub(crate) use era_supervisor::{EraId, EraSupervisor};
+pub use consensus_protocol::{EquivocationStatus, ParticipationStatus, RoundSuccess};
+pub(crate) use era_supervisor::{EraId, EraSupervisor, EraValidatorStatus};
+#[cfg(feature = "consensus-sim")]
+pub use highway_core::highway_testing::simulator::{
+    Error as SimulationError, RunReport as SimulationReport, Scenario as SimulationScenario,
+};
 pub(crate) use protocols::highway::HighwayProtocol;
+pub use signer::{SignerAddress, SigningServer};
 use traits::NodeIdT;
 
 #[cfg(test)]
@@ -92,6 +100,13 @@ pub enum Event<I> {
     },
     /// A queued action to be handled by a specific era.
     Action { era_id: EraId, action_id: ActionId },
+    /// The signature of one of our own vertices, requested by the given era, or `None` if signing
+    /// failed.
+    VertexSigned {
+        era_id: EraId,
+        hash: Digest,
+        maybe_signature: Option<Signature>,
+    },
     /// We are receiving the data we require to propose a new block.
     NewProtoBlock {
         era_id: EraId,
@@ -187,6 +202,21 @@ impl<I: Debug> Display for Event<I> {
             Event::Action { era_id, action_id } => {
                 write!(f, "action (ID {}) for era {}", action_id.0, era_id.0)
             }
+            Event::VertexSigned {
+                era_id,
+                hash,
+                maybe_signature,
+            } => write!(
+                f,
+                "{} own vertex {} for era {}",
+                if maybe_signature.is_some() {
+                    "signed"
+                } else {
+                    "failed to sign"
+                },
+                hash,
+                era_id.0
+            ),
             Event::NewProtoBlock {
                 era_id,
                 proto_block,
@@ -300,6 +330,11 @@ where
                 timer_id,
             } => handling_es.handle_timer(era_id, timestamp, timer_id),
             Event::Action { era_id, action_id } => handling_es.handle_action(era_id, action_id),
+            Event::VertexSigned {
+                era_id,
+                hash,
+                maybe_signature,
+            } => handling_es.handle_vertex_signed(era_id, hash, maybe_signature),
             Event::MessageReceived { sender, msg } => handling_es.handle_message(sender, msg),
             Event::NewPeer(peer_id) => handling_es.handle_new_peer(peer_id),
             Event::NewProtoBlock {
@@ -383,6 +418,9 @@ where
             Event::ConsensusRequest(ConsensusRequest::Status(responder)) => {
                 handling_es.status(responder)
             }
+            Event::ConsensusRequest(ConsensusRequest::ValidatorStatus(responder)) => {
+                handling_es.validator_status(responder)
+            }
         }
     }
 }
@@ -1,13 +1,14 @@
 use std::sync::Arc;
 
 use datasize::DataSize;
-use tracing::info;
+use tracing::{error, info};
 
-use casper_types::{PublicKey, SecretKey, Signature};
+use casper_types::{PublicKey, Signature};
 
 use crate::{
     components::consensus::{
         candidate_block::CandidateBlock,
+        signer::Signer,
         traits::{Context, ValidatorSecret},
     },
     crypto::{
@@ -16,25 +17,20 @@ use crate::{
     },
 };
 
+/// The validator's signer, used to sign the units, pings and endorsements.
+///
+/// Only a signer holding the key in the node process signs directly. Other signers may block, so
+/// their signatures are requested via the era supervisor, which creates them off the reactor
+/// thread.
 #[derive(DataSize)]
 pub(crate) struct Keypair {
-    secret_key: Arc<SecretKey>,
-    public_key: PublicKey,
+    #[data_size(skip)]
+    signer: Arc<dyn Signer>,
 }
 
 impl Keypair {
-    pub(crate) fn new(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
-        Self {
-            secret_key,
-            public_key,
-        }
-    }
-}
-
-impl From<Arc<SecretKey>> for Keypair {
-    fn from(secret_key: Arc<SecretKey>) -> Self {
-        let public_key: PublicKey = secret_key.as_ref().into();
-        Self::new(secret_key, public_key)
+    pub(crate) fn new(signer: Arc<dyn Signer>) -> Self {
+        Self { signer }
     }
 }
 
@@ -42,8 +38,18 @@ impl ValidatorSecret for Keypair {
     type Hash = Digest;
     type Signature = Signature;
 
-    fn sign(&self, hash: &Digest) -> Signature {
-        crypto::sign(hash, self.secret_key.as_ref(), &self.public_key)
+    fn sign(&self, hash: &Digest) -> Option<Signature> {
+        match self.signer.local() {
+            Some(local_signer) => Some(local_signer.sign_hash(hash)),
+            None => {
+                error!(%hash, "remote signer can't sign on the reactor thread");
+                None
+            }
+        }
+    }
+
+    fn is_blocking(&self) -> bool {
+        self.signer.local().is_none()
     }
 }
 
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use casper_types::SecretKey;
 
 use crate::{
-    components::consensus::EraId,
+    components::consensus::{signer::SignerAddress, EraId},
     crypto::hash::Digest,
     types::{chainspec::HighwayConfig, Chainspec, TimeDiff, Timestamp},
     utils::External,
@@ -20,8 +20,16 @@ use crate::{
 pub struct Config {
     /// Path to secret key file.
     pub secret_key_path: External<SecretKey>,
+    /// Address of a remote signer holding the validator's secret key. If set, consensus messages
+    /// are signed by the remote signer, and `secret_key_path` is not used. Only Unix sockets and
+    /// loopback TCP addresses are supported.
+    pub remote_signer_address: Option<SignerAddress>,
+    /// Timeout for connecting to the remote signer and for each signing request.
+    pub remote_signer_timeout: TimeDiff,
     /// Path to the folder where unit hash files will be stored.
     pub unit_hashes_folder: PathBuf,
+    /// Path to the folder where the per-era write-ahead logs of consensus vertices will be stored.
+    pub write_ahead_log_folder: PathBuf,
     /// The duration for which incoming vertices with missing dependencies are kept in a queue.
     pub pending_vertex_timeout: TimeDiff,
     /// The maximum number of blocks by which execution is allowed to lag behind finalization.
@@ -33,7 +41,10 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             secret_key_path: External::Missing,
+            remote_signer_address: None,
+            remote_signer_timeout: "5sec".parse().unwrap(),
             unit_hashes_folder: Default::default(),
+            write_ahead_log_folder: Default::default(),
             pending_vertex_timeout: "10sec".parse().unwrap(),
             max_execution_delay: 3,
         }
@@ -51,6 +62,8 @@ pub(crate) struct ProtocolConfig {
     /// auction_delay + 1
     pub(crate) auction_delay: u64,
     pub(crate) unbonding_delay: u64,
+    /// Whether validators proven to have equivocated are slashed in the era's switch block.
+    pub(crate) slash_equivocators: bool,
     /// The network protocol version.
     #[data_size(skip)]
     pub(crate) protocol_version: Version,
@@ -72,6 +85,7 @@ impl From<&Chainspec> for ProtocolConfig {
             minimum_era_height: chainspec.core_config.minimum_era_height,
             auction_delay: chainspec.core_config.auction_delay,
             unbonding_delay: chainspec.core_config.unbonding_delay,
+            slash_equivocators: chainspec.core_config.slash_equivocators,
             protocol_version: chainspec.protocol_config.version.clone(),
             last_activation_point: chainspec.protocol_config.activation_point.era_id(),
             name: chainspec.network_config.name.clone(),
@@ -2,10 +2,13 @@ use std::{any::Any, collections::BTreeMap, fmt::Debug, path::PathBuf};
 
 use anyhow::Error;
 use datasize::DataSize;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    components::consensus::{traits::Context, ActionId, TimerId},
+    components::consensus::{
+        highway_core::active_validator::UnsignedVertex, traits::Context, ActionId, TimerId,
+    },
     types::{TimeDiff, Timestamp},
 };
 
@@ -79,6 +82,55 @@ pub(crate) struct FinalizedBlock<C: Context> {
     pub(crate) proposer: C::ValidatorId,
 }
 
+/// Whether a validator is taking part in a consensus instance, as seen by this node.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ParticipationStatus {
+    /// The validator has been seen within the maximum round length.
+    Active,
+    /// The validator has created units, but hasn't been seen for longer than the maximum round
+    /// length.
+    Offline,
+    /// The validator hasn't created any units.
+    Inactive,
+}
+
+/// Whether a validator is known to have equivocated.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EquivocationStatus {
+    /// There is no evidence of an equivocation by the validator.
+    Honest,
+    /// The validator equivocated in this consensus instance.
+    Equivocated,
+    /// The validator equivocated in an earlier consensus instance.
+    EquivocatedInOtherEra,
+}
+
+/// A validator's participation in a consensus instance, as seen by this node.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct ValidatorParticipation<VID> {
+    pub(crate) validator_id: VID,
+    /// The timestamp of the validator's latest unit, unless it has none or is faulty.
+    pub(crate) last_unit_timestamp: Option<Timestamp>,
+    pub(crate) participation: ParticipationStatus,
+    pub(crate) equivocation: EquivocationStatus,
+    /// The round exponent of the validator's latest unit, unless it has none or is faulty.
+    pub(crate) round_exp: Option<u8>,
+}
+
+/// The success of our own validator's recent rounds in a consensus instance.
+#[derive(Clone, Copy, DataSize, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RoundSuccess {
+    /// The round exponent currently used by our validator.
+    pub round_exponent: u8,
+    /// The number of recent rounds in which a proposal was finalized.
+    pub successful_rounds: u64,
+    /// The number of recent rounds taken into account.
+    pub total_rounds: u64,
+}
+
 pub(crate) type ProtocolOutcomes<I, C> = Vec<ProtocolOutcome<I, C>>;
 
 // TODO: get rid of anyhow::Error; use variant and derive Clone and PartialEq. This is for testing.
@@ -89,6 +141,9 @@ pub(crate) enum ProtocolOutcome<I, C: Context> {
     InvalidIncomingMessage(Vec<u8>, I, Error),
     ScheduleTimer(Timestamp, TimerId),
     QueueAction(ActionId),
+    /// Sign one of our own vertices. Signing may block, so this must not happen on the reactor
+    /// thread. The signature needs to be passed to `ConsensusProtocol::handle_signature`.
+    SignVertex(Box<UnsignedVertex<C>>),
     /// Request deploys for a new block, providing the necessary context.
     CreateNewBlock {
         block_context: BlockContext,
@@ -142,6 +197,14 @@ pub(crate) trait ConsensusProtocol<I, C: Context>: Send {
     /// Triggers a queued action.
     fn handle_action(&mut self, action_id: ActionId) -> ProtocolOutcomes<I, C>;
 
+    /// Handles the signature of one of our own vertices, requested with
+    /// `ProtocolOutcome::SignVertex`. `None` means signing failed.
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        maybe_signature: Option<C::Signature>,
+    ) -> ProtocolOutcomes<I, C>;
+
     /// Proposes a new value for consensus.
     fn propose(
         &mut self,
@@ -187,6 +250,12 @@ pub(crate) trait ConsensusProtocol<I, C: Context>: Send {
     /// Returns the list of all validators that were observed as faulty in this consensus instance.
     fn validators_with_evidence(&self) -> Vec<&C::ValidatorId>;
 
+    /// Returns the serialized evidence against validator `vid`, if this instance has any.
+    ///
+    /// Evidence from a different instance that caused the validator to be marked as faulty is not
+    /// included.
+    fn serialized_evidence(&self, vid: &C::ValidatorId) -> Option<Vec<u8>>;
+
     /// Returns true if the protocol has received some messages since initialization.
     fn has_received_messages(&self) -> bool;
 
@@ -202,4 +271,10 @@ pub(crate) trait ConsensusProtocol<I, C: Context>: Send {
 
     // TODO: Make this lees Highway-specific.
     fn next_round_length(&self) -> Option<TimeDiff>;
+
+    /// Returns the participation of each validator in this instance, as seen by this node.
+    fn validator_participation(&self) -> Vec<ValidatorParticipation<C::ValidatorId>>;
+
+    /// Returns the success of our recent rounds, if this instance is an active validator.
+    fn round_success(&self) -> Option<RoundSuccess>;
 }
@@ -9,10 +9,12 @@ mod era;
 mod era_id;
 
 use std::{
+    cmp::Reverse,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     convert::TryInto,
     fmt::{self, Debug, Formatter},
-    path::PathBuf,
+    fs, io,
+    path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
@@ -27,9 +29,10 @@ use futures::FutureExt;
 use itertools::Itertools;
 use prometheus::Registry;
 use rand::Rng;
+use tokio::task;
 use tracing::{debug, error, info, trace, warn};
 
-use casper_types::{AsymmetricType, ProtocolVersion, PublicKey, SecretKey, U512};
+use casper_types::{AsymmetricType, ProtocolVersion, PublicKey, Signature, U512};
 
 use crate::{
     components::{
@@ -39,9 +42,10 @@ use crate::{
             config::ProtocolConfig,
             consensus_protocol::{
                 BlockContext, ConsensusProtocol, EraReport, FinalizedBlock as CpFinalizedBlock,
-                ProtocolOutcome, ProtocolOutcomes,
+                ProtocolOutcome, ProtocolOutcomes, RoundSuccess, ValidatorParticipation,
             },
             metrics::ConsensusMetrics,
+            signer::{LocalSigner, RemoteSigner, Signer, SigningRequest},
             traits::{ConsensusValueT, NodeIdT},
             ActionId, Config, ConsensusMessage, Event, ReactorEventT, TimerId,
         },
@@ -63,6 +67,9 @@ use crate::{
 
 pub use self::{era::Era, era_id::EraId};
 
+/// The prefix of the file names of the eras' write-ahead logs.
+const WRITE_AHEAD_LOG_PREFIX: &str = "highway_wal_";
+
 /// The delay in milliseconds before we shutdown after the number of faulty validators exceeded the
 /// fault tolerance threshold.
 const FTT_EXCEEDED_SHUTDOWN_DELAY_MILLIS: u64 = 60 * 1000;
@@ -77,6 +84,7 @@ type ConsensusConstructor<I> = dyn Fn(
     Timestamp,                                    // start time for this era
     u64,                                          // random seed
     Timestamp,                                    // now timestamp
+    Option<PathBuf>,                              // path to the era's write-ahead log
 ) -> (
     Box<dyn ConsensusProtocol<I, ClContext>>,
     Vec<ProtocolOutcome<I, ClContext>>,
@@ -90,7 +98,8 @@ pub struct EraSupervisor<I> {
     /// This map always contains exactly `2 * bonded_eras + 1` entries, with the last one being the
     /// current one.
     active_eras: HashMap<EraId, Era<I>>,
-    secret_signing_key: Arc<SecretKey>,
+    #[data_size(skip)]
+    signer: Arc<dyn Signer>,
     pub(super) public_signing_key: PublicKey,
     current_era: EraId,
     protocol_config: ProtocolConfig,
@@ -113,6 +122,8 @@ pub struct EraSupervisor<I> {
     finished_joining: bool,
     /// The path to the folder where unit hash files will be stored.
     unit_hashes_folder: PathBuf,
+    /// The path to the folder where the write-ahead logs of the eras' vertices will be stored.
+    write_ahead_log_folder: PathBuf,
     /// The next upgrade activation point. When the era immediately before the activation point is
     /// deactivated, the era supervisor indicates that the node should stop running to allow an
     /// upgrade.
@@ -127,6 +138,17 @@ pub struct EraSupervisor<I> {
     pub(crate) enqueued_requests: VecDeque<ConsensusRequest>,
 }
 
+/// The validators of a bonded era and their participation, as seen by this node.
+#[derive(Clone, DataSize, Debug)]
+pub(crate) struct EraValidatorStatus {
+    pub(crate) era_id: EraId,
+    /// The validators' weights and participation.
+    #[data_size(skip)]
+    pub(crate) validators: Vec<(U512, ValidatorParticipation<PublicKey>)>,
+    /// The success of our recent rounds, if we are an active validator in this era.
+    pub(crate) our_round_success: Option<RoundSuccess>,
+}
+
 impl<I> Debug for EraSupervisor<I> {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         let ae: Vec<_> = self.active_eras.keys().collect();
@@ -159,9 +181,16 @@ where
             );
         }
         let unit_hashes_folder = config.with_dir(config.value().unit_hashes_folder.clone());
+        let write_ahead_log_folder = config.with_dir(config.value().write_ahead_log_folder.clone());
         let (root, config) = config.into_parts();
-        let secret_signing_key = Arc::new(config.secret_key_path.clone().load(root)?);
-        let public_signing_key = PublicKey::from(secret_signing_key.as_ref());
+        let signer: Arc<dyn Signer> = match &config.remote_signer_address {
+            Some(address) => Arc::new(RemoteSigner::connect(
+                address.clone(),
+                config.remote_signer_timeout.into(),
+            )?),
+            None => Arc::new(LocalSigner::new(config.secret_key_path.clone().load(root)?)),
+        };
+        let public_signing_key = signer.public_key();
         info!(our_id = %public_signing_key, "EraSupervisor pubkey",);
         let metrics = ConsensusMetrics::new(registry)
             .expect("failure to setup and register ConsensusMetrics");
@@ -175,7 +204,7 @@ where
 
         let era_supervisor = Self {
             active_eras: Default::default(),
-            secret_signing_key,
+            signer,
             public_signing_key,
             current_era,
             protocol_config,
@@ -186,6 +215,7 @@ where
             metrics,
             finished_joining: false,
             unit_hashes_folder,
+            write_ahead_log_folder,
             next_upgrade_activation_point,
             stop_for_upgrade: false,
             next_executed_height: 0,
@@ -377,10 +407,11 @@ where
             start_time,
             seed,
             timestamp,
+            Some(self.write_ahead_log_file(&instance_id)),
         );
 
         if should_activate {
-            let secret = Keypair::new(self.secret_signing_key.clone(), our_id);
+            let secret = Keypair::new(Arc::clone(&self.signer));
             let unit_hash_file = self.unit_hashes_folder.join(format!(
                 "unit_hash_{:?}_{}.dat",
                 instance_id,
@@ -418,7 +449,11 @@ where
         let oldest_evidence_era_id = oldest_bonded_era(&self.protocol_config, oldest_bonded_era_id);
         if let Some(obsolete_era_id) = oldest_evidence_era_id.checked_sub(1) {
             trace!(era = obsolete_era_id.0, "removing obsolete era");
-            self.active_eras.remove(&obsolete_era_id);
+            if let Some(era) = self.active_eras.remove(&obsolete_era_id) {
+                let wal_file = self.write_ahead_log_file(era.consensus.instance_id());
+                drop(era);
+                remove_write_ahead_log(&wal_file);
+            }
         }
 
         outcomes
@@ -444,7 +479,7 @@ where
     /// To be called when we transition from the joiner to the validator reactor.
     pub(crate) fn finished_joining(&mut self, now: Timestamp) -> ProtocolOutcomes<I, ClContext> {
         self.finished_joining = true;
-        let secret = Keypair::new(self.secret_signing_key.clone(), self.public_signing_key);
+        let secret = Keypair::new(Arc::clone(&self.signer));
         let public_key = self.public_signing_key;
         let unit_hashes_folder = self.unit_hashes_folder.clone();
         self.active_eras
@@ -585,9 +620,50 @@ where
 
         self.is_initialized = true;
         self.next_block_height = self.active_eras[&self.current_era].start_height;
+        self.remove_obsolete_write_ahead_logs();
         result_map
     }
 
+    /// Returns the path of the write-ahead log of the era with the given instance ID.
+    fn write_ahead_log_file(&self, instance_id: &Digest) -> PathBuf {
+        self.write_ahead_log_folder
+            .join(format!("{}{:?}.dat", WRITE_AHEAD_LOG_PREFIX, instance_id))
+    }
+
+    /// Removes the write-ahead logs of all eras that are not active anymore, e.g. because the node
+    /// was shut down for a long time.
+    fn remove_obsolete_write_ahead_logs(&self) {
+        let active_wal_files: HashSet<PathBuf> = self
+            .active_eras
+            .values()
+            .map(|era| self.write_ahead_log_file(era.consensus.instance_id()))
+            .collect();
+        let entries = match fs::read_dir(&self.write_ahead_log_folder) {
+            Ok(entries) => entries,
+            Err(error) => {
+                if error.kind() != io::ErrorKind::NotFound {
+                    warn!(
+                        %error,
+                        folder = %self.write_ahead_log_folder.display(),
+                        "failed to list write-ahead logs"
+                    );
+                }
+                return;
+            }
+        };
+        for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+            let is_wal_file = path
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .map_or(false, |file_name| {
+                    file_name.starts_with(WRITE_AHEAD_LOG_PREFIX)
+                });
+            if is_wal_file && !active_wal_files.contains(&path) {
+                remove_write_ahead_log(&path);
+            }
+        }
+    }
+
     /// The number of past eras whose validators are still bonded. After this many eras, a former
     /// validator is allowed to withdraw their stake, so their signature can't be trusted anymore.
     ///
@@ -737,6 +813,17 @@ where
         self.delegate_to_era(era_id, move |consensus| consensus.handle_action(action_id))
     }
 
+    pub(super) fn handle_vertex_signed(
+        &mut self,
+        era_id: EraId,
+        hash: Digest,
+        maybe_signature: Option<Signature>,
+    ) -> Effects<Event<I>> {
+        self.delegate_to_era(era_id, move |consensus| {
+            consensus.handle_signature(hash, maybe_signature)
+        })
+    }
+
     pub(super) fn handle_message(&mut self, sender: I, msg: ConsensusMessage) -> Effects<Event<I>> {
         match msg {
             ConsensusMessage::Protocol { era_id, payload } => {
@@ -812,16 +899,35 @@ where
             return Effects::new();
         }
         let our_pk = self.era_supervisor.public_signing_key;
-        let our_sk = self.era_supervisor.secret_signing_key.clone();
         let era_id = block.header().era_id();
         self.era_supervisor.executed_block(block.header());
-        let maybe_fin_sig = if self.era_supervisor.is_validator_in(&our_pk, era_id) {
-            let block_hash = block.hash();
-            Some(FinalitySignature::new(*block_hash, era_id, &our_sk, our_pk))
+        let mut effects = if self.era_supervisor.is_validator_in(&our_pk, era_id) {
+            // Signing may block, so it is done on a separate thread.
+            let signer = Arc::clone(&self.era_supervisor.signer);
+            let block_hash = *block.hash();
+            async move {
+                let request = SigningRequest::FinalitySignature { era_id, block_hash };
+                let result = task::spawn_blocking(move || signer.sign(&request))
+                    .await
+                    .expect("should run");
+                let maybe_fin_sig = match result {
+                    Ok(signature) => Some(FinalitySignature {
+                        block_hash,
+                        era_id,
+                        signature,
+                        public_key: our_pk,
+                    }),
+                    Err(error) => {
+                        error!(%error, %block_hash, "failed to create finality signature");
+                        None
+                    }
+                };
+                responder.respond(maybe_fin_sig).await
+            }
+            .ignore()
         } else {
-            None
+            responder.respond(None).ignore()
         };
-        let mut effects = responder.respond(maybe_fin_sig).ignore();
         if era_id < self.era_supervisor.current_era {
             trace!(era = era_id.0, "executed block in old era");
             return effects;
@@ -1061,6 +1167,31 @@ where
                         timer_id,
                     })
             }
+            ProtocolOutcome::SignVertex(vertex) => {
+                // Signing may block, e.g. on a remote signer, so it is done on a separate thread.
+                let signer = Arc::clone(&self.era_supervisor.signer);
+                let hash = vertex.hash();
+                let request = SigningRequest::for_vertex(era_id, &vertex);
+                async move {
+                    task::spawn_blocking(move || signer.sign(&request))
+                        .await
+                        .expect("should run")
+                }
+                .event(move |result| {
+                    let maybe_signature = match result {
+                        Ok(signature) => Some(signature),
+                        Err(error) => {
+                            error!(%error, %hash, "failed to sign consensus message");
+                            None
+                        }
+                    };
+                    Event::VertexSigned {
+                        era_id,
+                        hash,
+                        maybe_signature,
+                    }
+                })
+            }
             ProtocolOutcome::QueueAction(action_id) => self
                 .effect_builder
                 .immediately()
@@ -1102,18 +1233,29 @@ where
                 era.add_accusations(&equivocators);
                 era.add_accusations(value.accusations());
                 // If this is the era's last block, it contains rewards. Everyone who is accused in
-                // the block or seen as equivocating via the consensus protocol gets slashed.
-                let era_end = terminal_block_data.map(|tbd| EraReport {
-                    rewards: tbd.rewards,
-                    // TODO: In the first 90 days we don't slash, and we just report all
-                    // equivocators as "inactive" instead. Change this back 90 days after launch,
-                    // and put era.accusations() into equivocators instead of inactive_validators.
-                    equivocators: vec![],
-                    inactive_validators: tbd
-                        .inactive_validators
-                        .into_iter()
-                        .chain(era.accusations())
-                        .collect(),
+                // the block or seen as equivocating via the consensus protocol gets slashed, if
+                // slashing is enabled in the chainspec. Otherwise they are only evicted.
+                // Accusations are verified: blocks are only considered valid once
+                // we have evidence against every validator accused in them.
+                let slash_equivocators = self.era_supervisor.protocol_config.slash_equivocators;
+                let era_end = terminal_block_data.map(|tbd| {
+                    if slash_equivocators {
+                        EraReport {
+                            rewards: tbd.rewards,
+                            equivocators: era.accusations(),
+                            inactive_validators: tbd.inactive_validators,
+                        }
+                    } else {
+                        EraReport {
+                            rewards: tbd.rewards,
+                            equivocators: vec![],
+                            inactive_validators: tbd
+                                .inactive_validators
+                                .into_iter()
+                                .chain(era.accusations())
+                                .collect(),
+                        }
+                    }
                 });
                 let finalized_block = FinalizedBlock::new(
                     value.into(),
@@ -1223,6 +1365,19 @@ where
                     .effect_builder
                     .announce_fault_event(era_id, pub_key, Timestamp::now())
                     .ignore();
+                // Persist the evidence, so that the validator's slashing can be audited later.
+                if let Some(evidence) = self
+                    .era_supervisor
+                    .active_eras
+                    .get(&era_id)
+                    .and_then(|era| era.consensus.serialized_evidence(&pub_key))
+                {
+                    effects.extend(
+                        self.effect_builder
+                            .put_evidence_to_storage(era_id, pub_key, evidence)
+                            .ignore(),
+                    );
+                }
                 for e_id in (era_id.0..=(era_id.0 + self.era_supervisor.bonded_eras())).map(EraId) {
                     let candidate_blocks =
                         if let Some(era) = self.era_supervisor.active_eras.get_mut(&e_id) {
@@ -1321,6 +1476,41 @@ where
         responder.respond((public_key, round_length)).ignore()
     }
 
+    pub(super) fn validator_status(
+        &self,
+        responder: Responder<(PublicKey, Vec<EraValidatorStatus>)>,
+    ) -> Effects<Event<I>> {
+        let public_key = self.era_supervisor.public_signing_key;
+        let eras = self
+            .era_supervisor
+            .active_eras
+            .iter()
+            .filter(|(era_id, _)| self.era_supervisor.is_bonded(**era_id))
+            .sorted_by_key(|(era_id, _)| Reverse(**era_id))
+            .map(|(era_id, era)| {
+                let validators = era
+                    .consensus
+                    .validator_participation()
+                    .into_iter()
+                    .map(|participation| {
+                        let weight = era
+                            .validators()
+                            .get(&participation.validator_id)
+                            .copied()
+                            .unwrap_or_default();
+                        (weight, participation)
+                    })
+                    .collect();
+                EraValidatorStatus {
+                    era_id: *era_id,
+                    validators,
+                    our_round_success: era.consensus.round_success(),
+                }
+            })
+            .collect();
+        responder.respond((public_key, eras)).ignore()
+    }
+
     fn disconnect(&self, sender: I) -> Effects<Event<I>> {
         self.effect_builder
             .announce_disconnect_from_peer(sender)
@@ -1349,6 +1539,15 @@ fn instance_id(protocol_config: &ProtocolConfig, era_id: EraId) -> Digest {
     result.into()
 }
 
+/// Removes the write-ahead log at the given path, if it exists.
+fn remove_write_ahead_log(path: &Path) {
+    match fs::remove_file(path) {
+        Ok(()) => debug!(path = %path.display(), "removed write-ahead log"),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => (),
+        Err(error) => warn!(%error, path = %path.display(), "failed to remove write-ahead log"),
+    }
+}
+
 /// The number of past eras whose validators are still bonded. After this many eras, a former
 /// validator is allowed to withdraw their stake, so their signature can't be trusted anymore.
 ///
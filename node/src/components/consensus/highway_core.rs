@@ -40,7 +40,8 @@ pub(crate) mod validators;
 
 mod endorsement;
 mod evidence;
-#[cfg(test)]
+#[cfg(any(test, feature = "consensus-sim"))]
+#[cfg_attr(not(test), allow(dead_code))]
 pub(crate) mod highway_testing;
 
 pub(crate) use state::{round_id, State, Weight};
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     fmt::{self, Debug},
     fs::{self, File},
     io::{self, Read, Write},
@@ -11,7 +12,7 @@ use tracing::{error, info, trace, warn};
 use super::{
     endorsement::{Endorsement, SignedEndorsement},
     evidence::Evidence,
-    highway::{Endorsements, Ping, ValidVertex, Vertex, WireUnit},
+    highway::{Endorsements, HashedWireUnit, Ping, ValidVertex, Vertex, WireUnit},
     state::{self, Panorama, State, Unit, Weight},
     validators::ValidatorIndex,
 };
@@ -42,6 +43,59 @@ pub(crate) enum Effect<C: Context> {
     ///
     /// When this is returned, the validator automatically deactivates.
     WeAreFaulty(Fault<C>),
+    /// A new vertex of ours needs to be signed, which may block. The signature needs to be passed
+    /// to `on_vertex_signed`, along with the vertex's hash.
+    RequestSignature(UnsignedVertex<C>),
+}
+
+/// One of our own vertices, awaiting its signature.
+#[derive(Clone, DataSize, Eq, PartialEq, Debug)]
+pub(crate) enum UnsignedVertex<C: Context> {
+    /// A new unit.
+    Unit(HashedWireUnit<C>),
+    /// A ping, to show that we are online.
+    Ping {
+        creator: ValidatorIndex,
+        timestamp: Timestamp,
+    },
+    /// An endorsement of another validator's unit.
+    Endorsement {
+        unit: C::Hash,
+        creator: ValidatorIndex,
+    },
+}
+
+impl<C: Context> UnsignedVertex<C> {
+    /// Returns the hash that needs to be signed.
+    pub(crate) fn hash(&self) -> C::Hash {
+        match self {
+            UnsignedVertex::Unit(hwunit) => hwunit.hash(),
+            UnsignedVertex::Ping { creator, timestamp } => Ping::<C>::hash(*creator, *timestamp),
+            UnsignedVertex::Endorsement { unit, creator } => {
+                Endorsement::<C>::new(*unit, *creator).hash()
+            }
+        }
+    }
+
+    /// Returns the vertex with the given signature.
+    fn into_vertex(self, signature: C::Signature) -> Vertex<C> {
+        match self {
+            UnsignedVertex::Unit(hashed_wire_unit) => Vertex::Unit(SignedWireUnit {
+                hashed_wire_unit,
+                signature,
+            }),
+            UnsignedVertex::Ping { creator, timestamp } => {
+                Vertex::Ping(Ping::with_signature(creator, timestamp, signature))
+            }
+            UnsignedVertex::Endorsement { unit, creator } => {
+                let endorsement = Endorsement::new(unit, creator);
+                Vertex::Endorsements(Endorsements::new(vec![SignedEndorsement::new(
+                    endorsement,
+                    signature,
+                )]))
+            }
+        }
+    }
 }
 
 /// A validator that actively participates in consensus by creating new vertices.
@@ -82,6 +136,8 @@ where
     target_ftt: Weight,
     /// If this flag is set we don't create new units and just send pings instead.
     paused: bool,
+    /// Our own vertices awaiting their signatures, by hash.
+    unsigned_vertices: HashMap<C::Hash, UnsignedVertex<C>>,
 }
 
 impl<C: Context> Debug for ActiveValidator<C> {
@@ -128,6 +184,7 @@ impl<C: Context> ActiveValidator<C> {
             own_last_unit,
             target_ftt,
             paused: false,
+            unsigned_vertices: HashMap::new(),
         };
         let effects = av.schedule_timer(start_time, state);
         (av, effects)
@@ -205,7 +262,7 @@ impl<C: Context> ActiveValidator<C> {
             } else if timestamp == r_id + self.witness_offset(r_len) {
                 let panorama = self.panorama_at(state, timestamp);
                 if panorama.has_correct() {
-                    if let Some(witness_unit) =
+                    if let Some(witness_effect) =
                         self.new_unit(panorama, timestamp, None, state, instance_id)
                     {
                         if self
@@ -214,7 +271,7 @@ impl<C: Context> ActiveValidator<C> {
                         {
                             info!(round_id = %r_id, "sending witness in round with no proposal");
                         }
-                        effects.push(Effect::NewVertex(ValidVertex(Vertex::Unit(witness_unit))));
+                        effects.push(witness_effect);
                         return effects;
                     }
                 }
@@ -223,8 +280,11 @@ impl<C: Context> ActiveValidator<C> {
         // We are not creating a new unit. Send a ping if necessary, to show that we're online.
         if !state.has_ping(self.vidx, timestamp) {
             warn!(%timestamp, "too many validators offline, sending ping");
-            let ping = Ping::new(self.vidx, timestamp, &self.secret);
-            effects.push(Effect::NewVertex(ValidVertex(Vertex::Ping(ping))));
+            let ping = UnsignedVertex::Ping {
+                creator: self.vidx,
+                timestamp,
+            };
+            effects.extend(self.sign(ping));
         }
         effects
     }
@@ -259,17 +319,11 @@ impl<C: Context> ActiveValidator<C> {
         if self.should_send_confirmation(uhash, now, state) {
             let panorama = state.confirmation_panorama(self.vidx, uhash);
             if panorama.has_correct() {
-                if let Some(confirmation_unit) =
-                    self.new_unit(panorama, now, None, state, instance_id)
-                {
-                    let vv = ValidVertex(Vertex::Unit(confirmation_unit));
-                    effects.push(Effect::NewVertex(vv));
-                }
+                effects.extend(self.new_unit(panorama, now, None, state, instance_id));
             }
         };
         if self.should_endorse(uhash, state) {
-            let endorsement = self.endorse(uhash);
-            effects.extend(vec![Effect::NewVertex(ValidVertex(endorsement))]);
+            effects.extend(self.endorse(uhash));
         }
         effects
     }
@@ -289,8 +343,7 @@ impl<C: Context> ActiveValidator<C> {
                 let unit = state.unit(v);
                 unit.new_hash_obs(state, vidx)
             })
-            .map(|v| self.endorse(v))
-            .map(|endorsement| Effect::NewVertex(ValidVertex(endorsement)))
+            .filter_map(|v| self.endorse(v))
             .collect()
     }
 
@@ -314,9 +367,7 @@ impl<C: Context> ActiveValidator<C> {
         let panorama = self.panorama_at(state, timestamp);
         let maybe_parent_hash = state.fork_choice(&panorama);
         if maybe_parent_hash.map_or(false, |hash| state.is_terminal_block(hash)) {
-            return self
-                .new_unit(panorama, timestamp, None, state, instance_id)
-                .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))));
+            return self.new_unit(panorama, timestamp, None, state, instance_id);
         }
         let maybe_parent = maybe_parent_hash.map(|bh| state.block(bh));
         let height = maybe_parent.map_or(0, |block| block.height);
@@ -359,7 +410,6 @@ impl<C: Context> ActiveValidator<C> {
             return vec![];
         }
         self.new_unit(panorama, timestamp, Some(value), state, instance_id)
-            .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))))
             .into_iter()
             .collect()
     }
@@ -408,7 +458,8 @@ impl<C: Context> ActiveValidator<C> {
         true
     }
 
-    /// Returns a new unit with the given data, and the correct sequence number.
+    /// Returns the effect adding a new unit with the given data and the correct sequence number,
+    /// or requesting its signature.
     ///
     /// Returns `None` if it's not possible to create a valid unit with the given panorama.
     fn new_unit(
@@ -418,11 +469,15 @@ impl<C: Context> ActiveValidator<C> {
         value: Option<C::ConsensusValue>,
         state: &State<C>,
         instance_id: C::InstanceId,
-    ) -> Option<SignedWireUnit<C>> {
+    ) -> Option<Effect<C>> {
         if !self.can_vote(state) {
             info!(?self.own_last_unit, "not voting - last own unit unknown");
             return None;
         }
+        if self.is_awaiting_unit_signature() {
+            info!("not voting - last own unit not signed yet");
+            return None;
+        }
         if let Some((prop_time, _)) = self.next_proposal.take() {
             warn!(
                 ?timestamp,
@@ -458,13 +513,93 @@ impl<C: Context> ActiveValidator<C> {
             endorsed,
         }
         .into_hashed();
-        self.write_last_unit(hwunit.hash()).unwrap_or_else(|err| {
+        if self.secret.is_blocking() {
+            return Some(self.request_signature(UnsignedVertex::Unit(hwunit)));
+        }
+        let swunit = SignedWireUnit::new(hwunit, &self.secret)?;
+        Some(self.own_unit_signed(swunit))
+    }
+
+    /// Records the hash of our new unit and returns the effect adding it to the state.
+    fn own_unit_signed(&mut self, swunit: SignedWireUnit<C>) -> Effect<C> {
+        self.write_last_unit(swunit.hash()).unwrap_or_else(|err| {
             panic!(
                 "should successfully write unit's hash to {:?}, got {:?}",
                 self.unit_hash_file, err
             )
         });
-        Some(SignedWireUnit::new(hwunit, &self.secret))
+        Effect::NewVertex(ValidVertex(Vertex::Unit(swunit)))
+    }
+
+    /// Returns the effect adding the signed ping or endorsement, or requesting its signature if
+    /// signing may block. Returns `None` if the signer refused.
+    fn sign(&mut self, vertex: UnsignedVertex<C>) -> Option<Effect<C>> {
+        if self.secret.is_blocking() {
+            return Some(self.request_signature(vertex));
+        }
+        let signature = self.secret.sign(&vertex.hash())?;
+        Some(Effect::NewVertex(ValidVertex(
+            vertex.into_vertex(signature),
+        )))
+    }
+
+    /// Returns the effect requesting the vertex's signature, and keeps the vertex until the
+    /// signature arrives.
+    fn request_signature(&mut self, vertex: UnsignedVertex<C>) -> Effect<C> {
+        self.unsigned_vertices.insert(vertex.hash(), vertex.clone());
+        Effect::RequestSignature(vertex)
+    }
+
+    /// Returns whether we are waiting for the signature of a unit. Until it arrives, we don't
+    /// create any new units, since they would need the same sequence number.
+    fn is_awaiting_unit_signature(&self) -> bool {
+        self.unsigned_vertices
+            .values()
+            .any(|vertex| matches!(vertex, UnsignedVertex::Unit(_)))
+    }
+
+    /// Returns actions a validator needs to take upon receiving the signature of one of its own
+    /// vertices, requested with `Effect::RequestSignature`. `None` means the signer failed or
+    /// refused to sign.
+    pub(crate) fn on_vertex_signed(
+        &mut self,
+        hash: &C::Hash,
+        maybe_signature: Option<C::Signature>,
+        state: &State<C>,
+    ) -> Vec<Effect<C>> {
+        let vertex = match self.unsigned_vertices.remove(hash) {
+            Some(vertex) => vertex,
+            None => {
+                warn!(?hash, "received signature for unknown vertex");
+                return vec![];
+            }
+        };
+        let signature = match maybe_signature {
+            Some(signature) => signature,
+            None => return vec![],
+        };
+        match vertex {
+            UnsignedVertex::Unit(hashed_wire_unit) => {
+                if self.is_faulty(state)
+                    || hashed_wire_unit.wire_unit().panorama[self.vidx]
+                        != state.panorama()[self.vidx]
+                {
+                    error!(
+                        ?hash,
+                        "our latest unit changed while the new one was being signed; dropping it"
+                    );
+                    return vec![];
+                }
+                let swunit = SignedWireUnit {
+                    hashed_wire_unit,
+                    signature,
+                };
+                vec![self.own_unit_signed(swunit)]
+            }
+            vertex => vec![Effect::NewVertex(ValidVertex(
+                vertex.into_vertex(signature),
+            ))],
+        }
     }
 
     /// Returns a `ScheduleTimer` effect for the next time we need to be called.
@@ -552,14 +687,12 @@ impl<C: Context> ActiveValidator<C> {
                 .any(|(vidx, _)| state.is_faulty(vidx) && unit.new_hash_obs(state, vidx))
     }
 
-    /// Creates endorsement of the `vhash`.
-    fn endorse(&self, vhash: &C::Hash) -> Vertex<C> {
-        let endorsement = Endorsement::new(*vhash, self.vidx);
-        let signature = self.secret.sign(&endorsement.hash());
-        Vertex::Endorsements(Endorsements::new(vec![SignedEndorsement::new(
-            endorsement,
-            signature,
-        )]))
+    /// Creates endorsement of the `vhash`, or returns `None` if the signer refused.
+    fn endorse(&mut self, vhash: &C::Hash) -> Option<Effect<C>> {
+        self.sign(UnsignedVertex::Endorsement {
+            unit: *vhash,
+            creator: self.vidx,
+        })
     }
 
     /// Returns a panorama that is valid to use in our own unit at the given timestamp.
@@ -621,6 +754,7 @@ mod tests {
     use super::{
         super::{
             finality_detector::FinalityDetector,
+            highway_testing::TEST_INSTANCE_ID,
             state::{tests::*, Weight},
         },
         Vertex, *,
@@ -890,4 +1024,137 @@ mod tests {
         // Payment finalized! "One Pumpkin Spice Mochaccino for Corbyn!"
         assert_eq!(Some(&new_unit.hash()), test.next_finalized());
     }
+
+    /// A context whose validators' signers may block, so that signatures are requested with
+    /// `Effect::RequestSignature`.
+    #[derive(Clone, DataSize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    struct BlockingContext;
+
+    #[derive(Clone, DataSize, Debug, Eq, PartialEq)]
+    struct BlockingSecret(TestSecret);
+
+    impl ValidatorSecret for BlockingSecret {
+        type Hash = u64;
+        type Signature = u64;
+
+        fn sign(&self, hash: &Self::Hash) -> Option<Self::Signature> {
+            self.0.sign(hash)
+        }
+
+        fn is_blocking(&self) -> bool {
+            true
+        }
+    }
+
+    impl Context for BlockingContext {
+        type ConsensusValue = u32;
+        type ValidatorId = u32;
+        type ValidatorSecret = BlockingSecret;
+        type Signature = u64;
+        type Hash = u64;
+        type InstanceId = u64;
+
+        fn hash(data: &[u8]) -> Self::Hash {
+            TestContext::hash(data)
+        }
+
+        fn verify_signature(
+            hash: &Self::Hash,
+            public_key: &Self::ValidatorId,
+            signature: &<Self::ValidatorSecret as ValidatorSecret>::Signature,
+        ) -> bool {
+            TestContext::verify_signature(hash, public_key, signature)
+        }
+    }
+
+    /// Returns a state with two validators, and Alice's active validator with a blocking signer.
+    fn blocking_alice() -> (State<BlockingContext>, ActiveValidator<BlockingContext>) {
+        let state = State::new(&[Weight(3), Weight(4)], test_params(0), vec![]);
+        let secret = BlockingSecret(ALICE_SEC);
+        let (alice, _) = ActiveValidator::new(ALICE, secret, 410.into(), &state, None, Weight(2));
+        (state, alice)
+    }
+
+    /// Makes Alice create a new unit, and returns it unsigned.
+    fn request_unit(
+        alice: &mut ActiveValidator<BlockingContext>,
+        state: &State<BlockingContext>,
+        timestamp: Timestamp,
+    ) -> HashedWireUnit<BlockingContext> {
+        let panorama = state.panorama().clone();
+        match alice.new_unit(panorama, timestamp, None, state, TEST_INSTANCE_ID) {
+            Some(Effect::RequestSignature(UnsignedVertex::Unit(hwunit))) => hwunit,
+            effect => panic!("unexpected effect {:?}", effect),
+        }
+    }
+
+    #[test]
+    fn should_not_create_unit_while_awaiting_signature() {
+        let (state, mut alice) = blocking_alice();
+        let hwunit = request_unit(&mut alice, &state, 416.into());
+
+        // The second unit would need the same sequence number, so it isn't created.
+        let panorama = state.panorama().clone();
+        assert_eq!(
+            None,
+            alice.new_unit(panorama, 426.into(), None, &state, TEST_INSTANCE_ID)
+        );
+
+        // Once the signature arrives, the first unit is added.
+        let signature = ALICE_SEC.sign(&hwunit.hash());
+        match &*alice.on_vertex_signed(&hwunit.hash(), signature, &state) {
+            [Effect::NewVertex(ValidVertex(Vertex::Unit(swunit)))] => {
+                assert_eq!(hwunit, swunit.hashed_wire_unit);
+                assert_eq!(signature, Some(swunit.signature));
+            }
+            effects => panic!("unexpected effects {:?}", effects),
+        }
+    }
+
+    #[test]
+    fn should_drop_unit_with_stale_panorama() {
+        let (mut state, mut alice) = blocking_alice();
+        let hwunit = request_unit(&mut alice, &state, 416.into());
+
+        // While the unit is being signed, a different unit by Alice is added to the state, e.g.
+        // one created before a restart.
+        let other_hwunit = WireUnit {
+            panorama: state.panorama().clone(),
+            creator: ALICE,
+            instance_id: TEST_INSTANCE_ID,
+            value: None,
+            seq_number: 0,
+            timestamp: 420.into(),
+            round_exp: state.params().init_round_exp(),
+            endorsed: BTreeSet::new(),
+        }
+        .into_hashed();
+        let secret = BlockingSecret(ALICE_SEC);
+        state.add_valid_unit(SignedWireUnit::new(other_hwunit, &secret).unwrap());
+
+        // Adding the signed unit would be an equivocation, so it is dropped.
+        let signature = ALICE_SEC.sign(&hwunit.hash());
+        assert!(alice
+            .on_vertex_signed(&hwunit.hash(), signature, &state)
+            .is_empty());
+
+        // Alice is no longer waiting for a signature and creates her next unit.
+        let next_hwunit = request_unit(&mut alice, &state, 426.into());
+        assert_eq!(1, next_hwunit.wire_unit().seq_number);
+    }
+
+    #[test]
+    fn should_clear_unit_if_signing_failed() {
+        let (state, mut alice) = blocking_alice();
+        let hwunit = request_unit(&mut alice, &state, 416.into());
+
+        assert!(alice
+            .on_vertex_signed(&hwunit.hash(), None, &state)
+            .is_empty());
+
+        // Alice is no longer waiting for a signature and creates a new unit instead.
+        let next_hwunit = request_unit(&mut alice, &state, 426.into());
+        assert_ne!(hwunit, next_hwunit);
+        assert_eq!(0, next_hwunit.wire_unit().seq_number);
+    }
 }
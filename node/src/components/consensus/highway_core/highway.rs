@@ -71,7 +71,7 @@ impl<C: Context> PreValidatedVertex<C> {
         self.0.timestamp()
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "consensus-sim"))]
     pub(crate) fn into_vertex(self) -> Vertex<C> {
         self.0
     }
@@ -458,6 +458,21 @@ impl<C: Context> Highway<C> {
         .unwrap_or_default()
     }
 
+    /// Takes action on the signature of one of our own vertices, requested with
+    /// `Effect::RequestSignature`.
+    pub(crate) fn on_vertex_signed(
+        &mut self,
+        hash: &C::Hash,
+        maybe_signature: Option<C::Signature>,
+        timestamp: Timestamp,
+    ) -> Vec<Effect<C>> {
+        self.map_active_validator(
+            |av, state| av.on_vertex_signed(hash, maybe_signature, state),
+            timestamp,
+        )
+        .unwrap_or_default()
+    }
+
     /// Takes action on a new evidence.
     fn on_new_evidence(&mut self, evidence: Evidence<C>) -> Vec<Effect<C>> {
         let state = &self.state;
@@ -498,7 +513,9 @@ impl<C: Context> Highway<C> {
                     result.extend(self.add_valid_vertex(vv.clone(), timestamp))
                 }
                 Effect::WeAreFaulty(_) => self.deactivate_validator(),
-                Effect::ScheduleTimer(_) | Effect::RequestNewBlock { .. } => (),
+                Effect::ScheduleTimer(_)
+                | Effect::RequestNewBlock { .. }
+                | Effect::RequestSignature(_) => (),
             }
         }
         result.extend(effects);
@@ -700,7 +717,7 @@ pub(crate) mod tests {
         assert_eq!(Err(expected), highway.pre_validate_vertex(invalid_vertex));
 
         let hwunit = wunit.into_hashed();
-        let valid_signature = CAROL_SEC.sign(&hwunit.hash());
+        let valid_signature = CAROL_SEC.sign(&hwunit.hash()).unwrap();
         let correct_signature_unit = SignedWireUnit {
             hashed_wire_unit: hwunit,
             signature: valid_signature,
@@ -794,9 +811,9 @@ pub(crate) mod tests {
                         wunit1: &WireUnit<TestContext>,
                         signer1: &TestSecret| {
             let hwunit0 = wunit0.clone().into_hashed();
-            let swunit0 = SignedWireUnit::new(hwunit0, signer0);
+            let swunit0 = SignedWireUnit::new(hwunit0, signer0).unwrap();
             let hwunit1 = wunit1.clone().into_hashed();
-            let swunit1 = SignedWireUnit::new(hwunit1, signer1);
+            let swunit1 = SignedWireUnit::new(hwunit1, signer1).unwrap();
             let evidence = Evidence::Equivocation(swunit0, swunit1);
             let vertex = Vertex::Evidence(evidence);
             highway
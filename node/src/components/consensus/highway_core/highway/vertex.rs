@@ -142,15 +142,16 @@ where
 }
 
 impl<C: Context> SignedWireUnit<C> {
+    /// Signs the unit, or returns `None` if the signer refused.
     pub(crate) fn new(
         hashed_wire_unit: HashedWireUnit<C>,
         secret_key: &C::ValidatorSecret,
-    ) -> Self {
-        let signature = secret_key.sign(&hashed_wire_unit.hash);
-        SignedWireUnit {
+    ) -> Option<Self> {
+        let signature = secret_key.sign(&hashed_wire_unit.hash)?;
+        Some(SignedWireUnit {
             hashed_wire_unit,
             signature,
-        }
+        })
     }
 
     pub(crate) fn wire_unit(&self) -> &WireUnit<C> {
@@ -333,16 +334,29 @@ where
 }
 
 impl<C: Context> Ping<C> {
-    /// Creates a new signed ping.
+    /// Creates a new signed ping, or returns `None` if the signer refused.
     pub(crate) fn new(
         creator: ValidatorIndex,
         timestamp: Timestamp,
         sk: &C::ValidatorSecret,
+    ) -> Option<Self> {
+        Some(Ping {
+            creator,
+            timestamp,
+            signature: sk.sign(&Self::hash(creator, timestamp))?,
+        })
+    }
+
+    /// Creates a ping with the given signature of its hash.
+    pub(crate) fn with_signature(
+        creator: ValidatorIndex,
+        timestamp: Timestamp,
+        signature: C::Signature,
     ) -> Self {
         Ping {
             creator,
             timestamp,
-            signature: sk.sign(&Self::hash(creator, timestamp)),
+            signature,
         }
     }
 
@@ -370,7 +384,7 @@ impl<C: Context> Ping<C> {
     }
 
     /// Computes the hash of a ping, i.e. of the creator and timestamp.
-    fn hash(creator: ValidatorIndex, timestamp: Timestamp) -> C::Hash {
+    pub(crate) fn hash(creator: ValidatorIndex, timestamp: Timestamp) -> C::Hash {
         let bytes = bincode::serialize(&(creator, timestamp)).expect("serialize Ping");
         <C as Context>::hash(&bytes)
     }
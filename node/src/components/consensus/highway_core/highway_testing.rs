@@ -1,3 +1,6 @@
+#[cfg_attr(not(feature = "consensus-sim"), allow(dead_code))]
+pub(crate) mod simulator;
+
 use std::{
     collections::{hash_map::DefaultHasher, HashMap, VecDeque},
     fmt::{self, Debug, Display, Formatter},
@@ -8,6 +11,7 @@ use std::{
 use datasize::DataSize;
 use hex_fmt::HexFmt;
 use itertools::Itertools;
+use num::rational::Ratio;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use tracing::{trace, warn};
@@ -128,6 +132,9 @@ impl From<Effect<TestContext>> for HighwayMessage {
                 HighwayMessage::RequestBlock(block_context)
             }
             Effect::WeAreFaulty(fault) => HighwayMessage::WeAreFaulty(Box::new(fault)),
+            Effect::RequestSignature(_) => {
+                unreachable!("test secrets don't request signatures asynchronously")
+            }
         }
     }
 }
@@ -156,6 +163,9 @@ pub(crate) enum TestRunError {
     SenderMissingDependency(ValidatorId, Dependency<TestContext>),
     /// No more messages in the message queue.
     NoMessages,
+    /// The validator's finality detector saw more faulty weight than the fault tolerance
+    /// threshold.
+    FttExceeded(ValidatorId),
 }
 
 impl Display for TestRunError {
@@ -173,6 +183,9 @@ impl Display for TestRunError {
             TestRunError::MissingValidator(id) => {
                 write!(f, "Virtual net is missing validator {:?}.", id)
             }
+            TestRunError::FttExceeded(id) => {
+                write!(f, "{:?} saw the fault tolerance threshold exceeded.", id)
+            }
         }
     }
 }
@@ -192,19 +205,36 @@ impl Distribution {
 }
 
 trait DeliveryStrategy {
+    /// Returns when the `message` sent by `sender` at `base_delivery_timestamp` is delivered to
+    /// `recipient`, or whether it is dropped.
     fn gen_delay(
         &mut self,
         rng: &mut NodeRng,
         message: &HighwayMessage,
+        sender: ValidatorId,
+        recipient: ValidatorId,
         distributon: &Distribution,
         base_delivery_timestamp: Timestamp,
     ) -> DeliverySchedule;
 }
 
+/// A block finalized by a validator.
+#[derive(Clone, Copy, Debug)]
+struct Finalization {
+    /// The block's timestamp.
+    block_timestamp: Timestamp,
+    /// The time at which the validator detected the block's finality.
+    finalized_at: Timestamp,
+    /// Whether the block is the last block of the era.
+    terminal: bool,
+}
+
 struct HighwayValidator {
     highway: Highway<TestContext>,
     finality_detector: FinalityDetector<TestContext>,
     fault: Option<DesFault>,
+    /// The blocks finalized by this validator, in order.
+    finalizations: Vec<Finalization>,
 }
 
 impl HighwayValidator {
@@ -217,6 +247,7 @@ impl HighwayValidator {
             highway,
             finality_detector,
             fault,
+            finalizations: Vec::new(),
         }
     }
 
@@ -276,7 +307,7 @@ impl HighwayValidator {
                                 }
                                 let secret = TestSecret(wunit2.creator.0.into());
                                 let hwunit2 = wunit2.into_hashed();
-                                let swunit2 = SignedWireUnit::new(hwunit2, &secret);
+                                let swunit2 = SignedWireUnit::new(hwunit2, &secret).unwrap();
                                 let vertex2 = Box::new(Vertex::Unit(swunit2));
                                 vec![msg, HighwayMessage::NewVertex(vertex2)]
                             }
@@ -352,28 +383,42 @@ where
 
         let messages = self.process_message(rng, recipient, message, delivery_time)?;
 
-        let targeted_messages = messages
-            .into_iter()
-            .filter_map(|hwm| {
+        // The delivery of a message is scheduled separately for each of its recipients.
+        let sender = recipient;
+        let mut targeted_messages = Vec::new();
+        for hwm in messages {
+            let TargetedMessage { message, target } = hwm.into_targeted(sender);
+            for recipient in self.virtual_net.recipients(target) {
                 let delivery = self.delivery_time_strategy.gen_delay(
                     rng,
-                    &hwm,
+                    message.payload(),
+                    sender,
+                    recipient,
                     &self.delivery_time_distribution,
                     delivery_time,
                 );
                 match delivery {
                     DeliverySchedule::Drop => {
-                        trace!("{:?} message is dropped.", hwm);
-                        None
+                        trace!(
+                            "{:?} message to {} is dropped.",
+                            message.payload(),
+                            recipient
+                        );
                     }
                     DeliverySchedule::AtInstant(timestamp) => {
-                        trace!("{:?} scheduled for {:?}", hwm, timestamp);
-                        let targeted = hwm.into_targeted(recipient);
-                        Some((targeted, timestamp))
+                        trace!(
+                            "{:?} to {} scheduled for {:?}",
+                            message.payload(),
+                            recipient,
+                            timestamp
+                        );
+                        let target = Target::SingleValidator(recipient);
+                        let targeted = TargetedMessage::new(message.clone(), target);
+                        targeted_messages.push((targeted, timestamp));
                     }
                 }
-            })
-            .collect();
+            }
+        }
 
         self.virtual_net.dispatch_messages(targeted_messages);
         Ok(())
@@ -478,23 +523,26 @@ where
         let recipient = self.node_mut(&validator_id)?;
         recipient.push_messages_produced(messages.clone());
 
-        self.run_finality_detector(&validator_id)?;
+        self.run_finality_detector(&validator_id, delivery_time)?;
 
         Ok(messages)
     }
 
     /// Runs finality detector.
-    fn run_finality_detector(&mut self, validator_id: &ValidatorId) -> TestResult<()> {
+    fn run_finality_detector(
+        &mut self,
+        validator_id: &ValidatorId,
+        now: Timestamp,
+    ) -> TestResult<()> {
         let recipient = self.node_mut(validator_id)?;
 
         let finalized_values = recipient
             .validator_mut()
             .run_finality()
-            // TODO: https://casperlabs.atlassian.net/browse/HWY-119
-            .expect("FTT exceeded but not handled");
+            .map_err(|_| TestRunError::FttExceeded(*validator_id))?;
         for FinalizedBlock {
             value,
-            timestamp: _,
+            timestamp,
             height,
             terminal_block_data,
             equivocators: _,
@@ -511,9 +559,15 @@ where
                 value,
                 height
             );
+            let terminal = terminal_block_data.is_some();
             if let Some(t) = terminal_block_data {
                 warn!(?t.rewards, "rewards and inactive validators are not verified yet");
             }
+            recipient.validator_mut().finalizations.push(Finalization {
+                block_timestamp: timestamp,
+                finalized_at: now,
+                terminal,
+            });
             recipient.push_finalized(value);
         }
 
@@ -736,9 +790,12 @@ struct HighwayTestHarnessBuilder<DS: DeliveryStrategy> {
     /// Defaults to 0 (network is perfectly secure).
     faulty_percent: u64,
     fault_type: Option<DesFault>,
-    /// FTT value for the finality detector.
+    /// The weights and faults of the validators.
+    /// If not given, they are generated randomly within the limits set by the other fields.
+    validators: Option<Vec<(Weight, Option<DesFault>)>>,
+    /// FTT value for the finality detector, as a fraction of the total validators' weight.
     /// If not given, defaults to 1/3 of total validators' weight.
-    ftt: Option<u64>,
+    ftt: Option<Ratio<u64>>,
     /// Number of consensus values to be proposed by the nodes in the network.
     /// Those will be generated by the test framework.
     /// Defaults to 10.
@@ -766,6 +823,8 @@ impl DeliveryStrategy for InstantDeliveryNoDropping {
         &mut self,
         _rng: &mut NodeRng,
         message: &HighwayMessage,
+        _sender: ValidatorId,
+        _recipient: ValidatorId,
         _distributon: &Distribution,
         base_delivery_timestamp: Timestamp,
     ) -> DeliverySchedule {
@@ -788,6 +847,7 @@ impl HighwayTestHarnessBuilder<InstantDeliveryNoDropping> {
             max_faulty_validators: 10,
             faulty_percent: 0,
             fault_type: None,
+            validators: None,
             ftt: None,
             consensus_values_count: 10,
             delivery_distribution: Distribution::Uniform,
@@ -838,7 +898,40 @@ impl<DS: DeliveryStrategy> HighwayTestHarnessBuilder<DS> {
         self
     }
 
-    fn build(self, rng: &mut NodeRng) -> Result<HighwayTestHarness<DS>, BuilderError> {
+    /// Sets the weights and faults of the validators, instead of generating them randomly.
+    fn validators(mut self, validators: Vec<(Weight, Option<DesFault>)>) -> Self {
+        self.validators = Some(validators);
+        self
+    }
+
+    /// Sets the FTT as a fraction of the total validators' weight.
+    fn ftt(mut self, ftt: Ratio<u64>) -> Self {
+        self.ftt = Some(ftt);
+        self
+    }
+
+    /// Replaces the strategy of message delivery.
+    fn delivery_strategy<DS2: DeliveryStrategy>(
+        self,
+        delivery_strategy: DS2,
+    ) -> HighwayTestHarnessBuilder<DS2> {
+        HighwayTestHarnessBuilder {
+            max_faulty_validators: self.max_faulty_validators,
+            faulty_percent: self.faulty_percent,
+            fault_type: self.fault_type,
+            validators: self.validators,
+            ftt: self.ftt,
+            consensus_values_count: self.consensus_values_count,
+            delivery_distribution: self.delivery_distribution,
+            delivery_strategy,
+            weight_limits: self.weight_limits,
+            start_time: self.start_time,
+            weight_distribution: self.weight_distribution,
+            params: self.params,
+        }
+    }
+
+    fn build(mut self, rng: &mut NodeRng) -> Result<HighwayTestHarness<DS>, BuilderError> {
         let consensus_values = (0..self.consensus_values_count)
             .map(|el| vec![el])
             .collect::<VecDeque<ConsensusValue>>();
@@ -846,73 +939,20 @@ impl<DS: DeliveryStrategy> HighwayTestHarnessBuilder<DS> {
         let instance_id = 0;
         let start_time = self.start_time;
 
-        let (lower, upper) = {
-            let (l, u) = self.weight_limits;
-            if l >= u {
-                return Err(BuilderError::WeightLimits);
-            }
-            (l, u)
-        };
-
-        let (faulty_weights, honest_weights): (Vec<Weight>, Vec<Weight>) = {
-            if self.faulty_percent == 0 {
-                // All validators are honest.
-                let validators_num = rng.gen_range(2..self.max_faulty_validators + 1);
-                let honest_validators: Vec<Weight> = self
-                    .weight_distribution
-                    .gen_range_vec(rng, lower, upper, validators_num)
-                    .into_iter()
-                    .map(Weight)
-                    .collect();
-
-                (vec![], honest_validators)
-            } else {
-                // At least 2 validators total and at least one faulty.
-                let faulty_num = rng.gen_range(1..self.max_faulty_validators + 1);
-
-                // Randomly (but within chosed range) assign weights to faulty nodes.
-                let faulty_weights = self
-                    .weight_distribution
-                    .gen_range_vec(rng, lower, upper, faulty_num);
-
-                // Assign enough weights to honest nodes so that we reach expected
-                // `faulty_percentage` ratio.
-                let honest_weights = {
-                    let faulty_sum = faulty_weights.iter().sum::<u64>();
-                    let mut weights_to_distribute: u64 =
-                        (faulty_sum * 100 + self.faulty_percent - 1) / self.faulty_percent
-                            - faulty_sum;
-                    let mut weights = vec![];
-                    while weights_to_distribute > 0 {
-                        let weight = if weights_to_distribute < upper {
-                            weights_to_distribute
-                        } else {
-                            rng.gen_range(lower..upper)
-                        };
-                        weights.push(weight);
-                        weights_to_distribute -= weight
-                    }
-                    weights
-                };
-
-                (
-                    faulty_weights.into_iter().map(Weight).collect(),
-                    honest_weights.into_iter().map(Weight).collect(),
-                )
-            }
+        let weights_and_faults = match self.validators.take() {
+            Some(validators) => validators,
+            None => self.random_weights_and_faults(rng)?,
         };
-
-        let weights_sum = faulty_weights
+        let weights_sum = weights_and_faults
             .iter()
-            .chain(honest_weights.iter())
+            .map(|(weight, _)| *weight)
             .sum::<Weight>();
 
         let validators: Validators<ValidatorId> = Validators::from_iter(
-            faulty_weights
+            weights_and_faults
                 .iter()
-                .chain(honest_weights.iter())
                 .enumerate()
-                .map(|(i, weight)| (ValidatorId(i as u64), *weight)),
+                .map(|(i, (weight, _))| (ValidatorId(i as u64), *weight)),
         );
 
         trace!("Weights: {:?}", validators.iter().collect::<Vec<_>>());
@@ -924,7 +964,10 @@ impl<DS: DeliveryStrategy> HighwayTestHarnessBuilder<DS> {
 
         let ftt = self
             .ftt
-            .map(|p| p * weights_sum.0 / 100)
+            .map(|ftt| {
+                let total_weight = u128::from(weights_sum.0);
+                (total_weight * u128::from(*ftt.numer()) / u128::from(*ftt.denom())) as u64
+            })
             .unwrap_or_else(|| (weights_sum.0 - 1) / 3);
         let params = self.params;
 
@@ -945,21 +988,14 @@ impl<DS: DeliveryStrategy> HighwayTestHarnessBuilder<DS> {
                 )
             };
 
-        let faulty_num = faulty_weights.len();
-
         let (validators, init_messages) = {
             let mut validators_loc = vec![];
             let mut init_messages = vec![];
 
-            for validator in validators.iter() {
+            for (validator, (_, fault)) in validators.iter().zip(&weights_and_faults) {
                 let vid = *validator.id();
-                let fault = if vid.0 < faulty_num as u64 {
-                    self.fault_type
-                } else {
-                    None
-                };
                 let (highway, finality_detector, msgs) = highway_consensus((vid, &mut secrets));
-                let highway_consensus = HighwayValidator::new(highway, finality_detector, fault);
+                let highway_consensus = HighwayValidator::new(highway, finality_detector, *fault);
                 let validator = Node::new(vid, highway_consensus);
                 let qm: Vec<QueueEntry<HighwayMessage>> = msgs
                     .into_iter()
@@ -991,6 +1027,75 @@ impl<DS: DeliveryStrategy> HighwayTestHarnessBuilder<DS> {
 
         Ok(hwth)
     }
+
+    /// Generates random weights within the weight limits, the first of which belong to faulty
+    /// validators if a percentage of faulty weight was set.
+    fn random_weights_and_faults(
+        &self,
+        rng: &mut NodeRng,
+    ) -> Result<Vec<(Weight, Option<DesFault>)>, BuilderError> {
+        let (lower, upper) = {
+            let (l, u) = self.weight_limits;
+            if l >= u {
+                return Err(BuilderError::WeightLimits);
+            }
+            (l, u)
+        };
+
+        let (faulty_weights, honest_weights): (Vec<Weight>, Vec<Weight>) = {
+            if self.faulty_percent == 0 {
+                // All validators are honest.
+                let validators_num = rng.gen_range(2..self.max_faulty_validators + 1);
+                let honest_validators: Vec<Weight> = self
+                    .weight_distribution
+                    .gen_range_vec(rng, lower, upper, validators_num)
+                    .into_iter()
+                    .map(Weight)
+                    .collect();
+
+                (vec![], honest_validators)
+            } else {
+                // At least 2 validators total and at least one faulty.
+                let faulty_num = rng.gen_range(1..self.max_faulty_validators + 1);
+
+                // Randomly (but within chosed range) assign weights to faulty nodes.
+                let faulty_weights = self
+                    .weight_distribution
+                    .gen_range_vec(rng, lower, upper, faulty_num);
+
+                // Assign enough weights to honest nodes so that we reach expected
+                // `faulty_percentage` ratio.
+                let honest_weights = {
+                    let faulty_sum = faulty_weights.iter().sum::<u64>();
+                    let mut weights_to_distribute: u64 =
+                        (faulty_sum * 100 + self.faulty_percent - 1) / self.faulty_percent
+                            - faulty_sum;
+                    let mut weights = vec![];
+                    while weights_to_distribute > 0 {
+                        let weight = if weights_to_distribute < upper {
+                            weights_to_distribute
+                        } else {
+                            rng.gen_range(lower..upper)
+                        };
+                        weights.push(weight);
+                        weights_to_distribute -= weight
+                    }
+                    weights
+                };
+
+                (
+                    faulty_weights.into_iter().map(Weight).collect(),
+                    honest_weights.into_iter().map(Weight).collect(),
+                )
+            }
+        };
+
+        let faulty = faulty_weights
+            .into_iter()
+            .map(|weight| (weight, self.fault_type));
+        let honest = honest_weights.into_iter().map(|weight| (weight, None));
+        Ok(faulty.chain(honest).collect())
+    }
 }
 
 #[derive(Clone, DataSize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -1031,8 +1136,8 @@ impl ValidatorSecret for TestSecret {
     type Hash = HashWrapper;
     type Signature = SignatureWrapper;
 
-    fn sign(&self, data: &Self::Hash) -> Self::Signature {
-        SignatureWrapper(data.0 + self.0)
+    fn sign(&self, data: &Self::Hash) -> Option<Self::Signature> {
+        Some(SignatureWrapper(data.0 + self.0))
     }
 }
 
@@ -1060,6 +1165,7 @@ impl Context for TestContext {
     }
 }
 
+#[cfg(test)]
 mod test_harness {
     use std::{collections::HashSet, fmt::Debug};
 
@@ -873,7 +873,7 @@ impl<C: Context> State<C> {
     }
 
     /// Returns the number of units received.
-    #[cfg(test)]
+    #[cfg(any(test, feature = "consensus-sim"))]
     pub(crate) fn unit_count(&self) -> usize {
         self.units.len()
     }
@@ -3,6 +3,7 @@ mod round_success_meter;
 mod synchronizer;
 #[cfg(test)]
 mod tests;
+mod write_ahead_log;
 
 use std::{
     any::Any,
@@ -23,7 +24,10 @@ use casper_types::{system::auction::BLOCK_REWARD, U512};
 use crate::{
     components::consensus::{
         config::{Config, ProtocolConfig},
-        consensus_protocol::{BlockContext, ConsensusProtocol, ProtocolOutcome, ProtocolOutcomes},
+        consensus_protocol::{
+            BlockContext, ConsensusProtocol, ProtocolOutcome, ProtocolOutcomes, RoundSuccess,
+            ValidatorParticipation,
+        },
         highway_core::{
             active_validator::Effect as AvEffect,
             finality_detector::{FinalityDetector, FttExceeded},
@@ -37,11 +41,14 @@ use crate::{
     types::{TimeDiff, Timestamp},
 };
 
-use self::{round_success_meter::RoundSuccessMeter, synchronizer::Synchronizer};
+use self::{
+    round_success_meter::RoundSuccessMeter, synchronizer::Synchronizer,
+    write_ahead_log::WriteAheadLog,
+};
 
 /// Never allow more than this many units in a piece of evidence for conflicting endorsements,
 /// even if eras are longer than this.
-const MAX_ENDORSEMENT_EVIDENCE_LIMIT: u64 = 10000;
+pub(crate) const MAX_ENDORSEMENT_EVIDENCE_LIMIT: u64 = 10000;
 
 /// The timer for creating new units, as a validator actively participating in consensus.
 const TIMER_ID_ACTIVE_VALIDATOR: TimerId = TimerId(0);
@@ -71,6 +78,9 @@ where
     round_success_meter: RoundSuccessMeter<C>,
     synchronizer: Synchronizer<I, C>,
     evidence_only: bool,
+    /// The log to which every vertex is written before it is added to the state or gossiped.
+    #[data_size(skip)]
+    write_ahead_log: Option<WriteAheadLog<C>>,
 }
 
 impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
@@ -86,6 +96,7 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         era_start_time: Timestamp,
         seed: u64,
         now: Timestamp,
+        write_ahead_log_file: Option<PathBuf>,
     ) -> (Box<dyn ConsensusProtocol<I, C>>, ProtocolOutcomes<I, C>) {
         let validators_count = validator_stakes.len();
         let sum_stakes: U512 = validator_stakes.iter().map(|(_, stake)| *stake).sum();
@@ -185,7 +196,7 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             .unwrap_or_else(|| {
                 RoundSuccessMeter::new(round_exp, min_round_exp, max_round_exp, start_timestamp)
             });
-        let hw_proto = Box::new(HighwayProtocol {
+        let mut hw_proto = Box::new(HighwayProtocol {
             pending_values: HashMap::new(),
             finality_detector: FinalityDetector::new(ftt),
             highway: Highway::new(instance_id, validators, params),
@@ -196,10 +207,86 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
                 instance_id,
             ),
             evidence_only: false,
+            write_ahead_log: None,
         });
+        if let Some(path) = write_ahead_log_file {
+            hw_proto.open_write_ahead_log(path);
+        }
         (hw_proto, outcomes)
     }
 
+    /// Opens the write-ahead log at the given path and adds the vertices it contains to the
+    /// protocol state.
+    fn open_write_ahead_log(&mut self, path: PathBuf) {
+        let (write_ahead_log, vertices) = match WriteAheadLog::open(path.clone()) {
+            Ok(result) => result,
+            Err(error) => {
+                error!(%error, path = %path.display(), "failed to open write-ahead log");
+                return;
+            }
+        };
+        let now = Timestamp::now();
+        let mut replayed_count = 0;
+        for vertex in vertices {
+            if self.highway.has_vertex(&vertex) {
+                continue;
+            }
+            let pvv = match self.highway.pre_validate_vertex(vertex) {
+                Ok(pvv) => pvv,
+                Err((vertex, error)) => {
+                    warn!(?vertex, ?error, "invalid vertex in write-ahead log");
+                    continue;
+                }
+            };
+            if let Some(dependency) = self.highway.missing_dependency(&pvv) {
+                warn!(
+                    ?dependency,
+                    "missing dependency of vertex in write-ahead log"
+                );
+                continue;
+            }
+            match self.highway.validate_vertex(pvv) {
+                Ok(vv) => {
+                    // We are not an active validator yet, so this has no effects.
+                    self.highway.add_valid_vertex(vv, now);
+                    replayed_count += 1;
+                }
+                Err((pvv, error)) => {
+                    warn!(vertex = ?pvv.inner(), ?error, "invalid vertex in write-ahead log");
+                }
+            }
+        }
+        info!(
+            %replayed_count,
+            path = %path.display(),
+            "replayed write-ahead log"
+        );
+        self.write_ahead_log = Some(write_ahead_log);
+    }
+
+    /// Appends the vertex to the write-ahead log, if there is one.
+    ///
+    /// If `sync` is `true`, the log is synced to disk, so that the vertex is persisted before it is
+    /// gossiped. This is only needed for our own vertices.
+    fn persist_vertex(&mut self, vertex: &Vertex<C>, sync: bool) {
+        if let Some(write_ahead_log) = &mut self.write_ahead_log {
+            let result = write_ahead_log.append(vertex).and_then(|()| {
+                if sync {
+                    write_ahead_log.sync()
+                } else {
+                    Ok(())
+                }
+            });
+            if let Err(error) = result {
+                error!(
+                    %error,
+                    path = %write_ahead_log.path().display(),
+                    "failed to append vertex to write-ahead log"
+                );
+            }
+        }
+    }
+
     fn process_av_effects<E>(&mut self, av_effects: E) -> ProtocolOutcomes<I, C>
     where
         E: IntoIterator<Item = AvEffect<C>>,
@@ -213,6 +300,7 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
     fn process_av_effect(&mut self, effect: AvEffect<C>) -> ProtocolOutcomes<I, C> {
         match effect {
             AvEffect::NewVertex(vv) => {
+                self.persist_vertex(vv.inner(), true);
                 self.calculate_round_exponent(&vv);
                 self.process_new_vertex(vv.into())
             }
@@ -240,6 +328,9 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
                 error!("this validator is faulty: {:?}", fault);
                 vec![ProtocolOutcome::WeAreFaulty]
             }
+            AvEffect::RequestSignature(unsigned_vertex) => {
+                vec![ProtocolOutcome::SignVertex(Box::new(unsigned_vertex))]
+            }
         }
     }
 
@@ -390,6 +481,9 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             error!(vertex = ?vv.inner(), "unexpected vertex in evidence-only mode");
             return vec![];
         }
+        if !self.highway.has_vertex(vv.inner()) {
+            self.persist_vertex(vv.inner(), false);
+        }
         self.log_proposal(vv.inner(), "adding valid proposal to the protocol state");
         // Check whether we should change the round exponent.
         // It's important to do it before the vertex is added to the state - this way if the last
@@ -707,6 +801,17 @@ where
         }
     }
 
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        maybe_signature: Option<C::Signature>,
+    ) -> ProtocolOutcomes<I, C> {
+        let effects = self
+            .highway
+            .on_vertex_signed(&hash, maybe_signature, Timestamp::now());
+        self.process_av_effects(effects)
+    }
+
     fn propose(
         &mut self,
         value: C::ConsensusValue,
@@ -823,6 +928,12 @@ where
         self.highway.validators_with_evidence().collect()
     }
 
+    fn serialized_evidence(&self, vid: &C::ValidatorId) -> Option<Vec<u8>> {
+        let vidx = self.highway.validators().get_index(vid)?;
+        let evidence = self.highway.state().maybe_evidence(vidx)?;
+        Some(bincode::serialize(evidence).expect("should serialize evidence"))
+    }
+
     fn has_received_messages(&self) -> bool {
         !self.highway.state().is_empty()
             || !self.synchronizer.is_empty()
@@ -878,4 +989,16 @@ where
     fn next_round_length(&self) -> Option<TimeDiff> {
         self.highway.next_round_length()
     }
+
+    fn validator_participation(&self) -> Vec<ValidatorParticipation<C::ValidatorId>> {
+        participation::validator_participation(&self.highway, Timestamp::now())
+    }
+
+    fn round_success(&self) -> Option<RoundSuccess> {
+        if self.highway.is_active() {
+            Some(self.round_success_meter.round_success())
+        } else {
+            None
+        }
+    }
 }
@@ -2,6 +2,7 @@ use std::cmp::Reverse;
 
 use crate::{
     components::consensus::{
+        consensus_protocol::{EquivocationStatus, ParticipationStatus, ValidatorParticipation},
         highway_core::{
             highway::Highway,
             state::{Fault, State},
@@ -95,3 +96,42 @@ impl<C: Context> Participation<C> {
         }
     }
 }
+
+/// Returns the participation of each validator in the Highway instance, as seen at `now`.
+pub(crate) fn validator_participation<C: Context>(
+    highway: &Highway<C>,
+    now: Timestamp,
+) -> Vec<ValidatorParticipation<C::ValidatorId>> {
+    let state = highway.state();
+    highway
+        .validators()
+        .enumerate_ids()
+        .map(|(idx, v_id)| {
+            let maybe_fault = state.maybe_fault(idx);
+            let equivocation = match maybe_fault {
+                None => EquivocationStatus::Honest,
+                Some(Fault::Banned) | Some(Fault::Indirect) => {
+                    EquivocationStatus::EquivocatedInOtherEra
+                }
+                Some(Fault::Direct(_)) => EquivocationStatus::Equivocated,
+            };
+            // Units by banned validators are not accepted, so they can't have participated.
+            let is_banned = matches!(maybe_fault, Some(Fault::Banned));
+            let participation = if is_banned || state.panorama()[idx].is_none() {
+                ParticipationStatus::Inactive
+            } else if state.last_seen(idx) + state.params().max_round_length() < now {
+                ParticipationStatus::Offline
+            } else {
+                ParticipationStatus::Active
+            };
+            let latest_unit = state.panorama()[idx].correct().map(|hash| state.unit(hash));
+            ValidatorParticipation {
+                validator_id: v_id.clone(),
+                last_unit_timestamp: latest_unit.map(|unit| unit.timestamp),
+                participation,
+                equivocation,
+                round_exp: latest_unit.map(|unit| unit.round_exp),
+            }
+        })
+        .collect()
+}
@@ -5,6 +5,7 @@ use tracing::trace;
 
 use crate::{
     components::consensus::{
+        consensus_protocol::RoundSuccess,
         highway_core::{finality_detector::FinalityDetector, round_id, State, Weight},
         traits::Context,
     },
@@ -152,6 +153,16 @@ impl<C: Context> RoundSuccessMeter<C> {
         }
     }
 
+    /// Returns the current round exponent and the number of successful recent rounds.
+    pub fn round_success(&self) -> RoundSuccess {
+        let total_rounds = self.rounds.len();
+        RoundSuccess {
+            round_exponent: self.current_round_exp,
+            successful_rounds: (total_rounds - self.count_failures()) as u64,
+            total_rounds: total_rounds as u64,
+        }
+    }
+
     fn clean_old_rounds(&mut self) {
         while self.rounds.len() > NUM_ROUNDS_TO_CONSIDER {
             self.rounds.pop_back();
@@ -10,7 +10,9 @@ use crate::{
         candidate_block::CandidateBlock,
         cl_context::{ClContext, Keypair},
         config::Config,
-        consensus_protocol::{ConsensusProtocol, ProtocolOutcome},
+        consensus_protocol::{
+            ConsensusProtocol, EquivocationStatus, ParticipationStatus, ProtocolOutcome,
+        },
         highway_core::{
             highway::{SignedWireUnit, Vertex, WireUnit},
             highway_testing,
@@ -19,6 +21,7 @@ use crate::{
             State,
         },
         protocols::highway::{HighwayMessage, ACTION_ID_VERTEX},
+        signer::LocalSigner,
         tests::utils::{new_test_chainspec, ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY},
         traits::Context,
         HighwayProtocol,
@@ -69,7 +72,10 @@ where
     let chainspec = new_test_chainspec(weights.clone());
     let config = Config {
         secret_key_path: Default::default(),
+        remote_signer_address: None,
+        remote_signer_timeout: "5sec".parse().unwrap(),
         unit_hashes_folder: Default::default(),
+        write_ahead_log_folder: Default::default(),
         pending_vertex_timeout: "1min".parse().unwrap(),
         max_execution_delay: 3,
     };
@@ -85,6 +91,7 @@ where
         start_timestamp,
         0,
         start_timestamp,
+        None,
     );
     // We expect for messages:
     // * log participation timer,
@@ -142,9 +149,9 @@ fn send_a_wire_unit_with_too_small_a_round_exp() {
         round_exp: 0,
         endorsed: BTreeSet::new(),
     };
-    let alice_keypair: Keypair = Keypair::from(Arc::new(ALICE_SECRET_KEY.clone()));
+    let alice_keypair = Keypair::new(Arc::new(LocalSigner::new(ALICE_SECRET_KEY.clone())));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     let sender = NodeId(123);
@@ -197,9 +204,9 @@ fn send_a_valid_wire_unit() {
         round_exp: 14,
         endorsed: BTreeSet::new(),
     };
-    let alice_keypair: Keypair = Keypair::from(Arc::new(ALICE_SECRET_KEY.clone()));
+    let alice_keypair = Keypair::new(Arc::new(LocalSigner::new(ALICE_SECRET_KEY.clone())));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     let sender = NodeId(123);
@@ -239,9 +246,9 @@ fn detect_doppelganger() {
         round_exp,
         endorsed: BTreeSet::new(),
     };
-    let alice_keypair: Keypair = Keypair::from(Arc::new(ALICE_SECRET_KEY.clone()));
+    let alice_keypair = Keypair::new(Arc::new(LocalSigner::new(ALICE_SECRET_KEY.clone())));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     // Activate ALICE as validator.
@@ -269,3 +276,26 @@ fn detect_doppelganger() {
     }
     panic!("failed to return DoppelgangerDetected effect");
 }
+
+#[test]
+fn should_report_participation_of_inactive_and_banned_validators() {
+    let highway_protocol = new_test_highway_protocol(
+        vec![(*ALICE_PUBLIC_KEY, 100), (*BOB_PUBLIC_KEY, 10)],
+        vec![*BOB_PUBLIC_KEY],
+    );
+    let participation = highway_protocol.validator_participation();
+    assert_eq!(participation.len(), 2);
+    for validator in &participation {
+        assert_eq!(validator.participation, ParticipationStatus::Inactive);
+        assert_eq!(validator.last_unit_timestamp, None);
+        assert_eq!(validator.round_exp, None);
+        let expected_equivocation = if validator.validator_id == *BOB_PUBLIC_KEY {
+            EquivocationStatus::EquivocatedInOtherEra
+        } else {
+            EquivocationStatus::Honest
+        };
+        assert_eq!(validator.equivocation, expected_equivocation);
+    }
+    // We are not an active validator in this instance.
+    assert_eq!(highway_protocol.round_success(), None);
+}
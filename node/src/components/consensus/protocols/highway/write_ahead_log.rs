@@ -0,0 +1,236 @@
+//! A write-ahead log of the vertices added to the protocol state of a Highway instance.
+//!
+//! Each vertex is appended to the log before it is added to the state, or, if it was created by
+//! our own active validator, before it is gossiped. On startup, the vertices are replayed into a
+//! fresh `Highway` instance, so that a restarted node doesn't have to download the era's state
+//! from its peers again.
+//!
+//! Appending doesn't sync the log to disk: losing vertices received from peers only means
+//! downloading them again. The log is only synced before one of our own vertices is gossiped,
+//! which also persists all entries appended before it, so that a restarted validator can never
+//! equivocate by creating a conflicting vertex.
+//!
+//! Every entry consists of the length of the serialized vertex as a little-endian `u32`, followed
+//! by the vertex in `bincode` encoding. A corrupt or incomplete last entry, e.g. because the node
+//! crashed while writing it, is discarded when the log is opened. A corrupt entry anywhere else
+//! cannot be caused by a crash, so opening the log fails instead.
+
+use std::{
+    convert::TryInto,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    marker::PhantomData,
+    mem,
+    path::{Path, PathBuf},
+};
+
+use tracing::warn;
+
+use crate::components::consensus::{highway_core::highway::Vertex, traits::Context};
+
+/// The number of bytes of the length prefix of each entry.
+const LENGTH_PREFIX_SIZE: usize = mem::size_of::<u32>();
+
+/// An append-only log of the vertices of a Highway instance.
+#[derive(Debug)]
+pub(crate) struct WriteAheadLog<C> {
+    path: PathBuf,
+    file: File,
+    /// Whether entries have been appended since the log was last synced to disk.
+    is_dirty: bool,
+    _phantom: PhantomData<C>,
+}
+
+impl<C: Context> WriteAheadLog<C> {
+    /// Opens the log at the given path, creating it if it doesn't exist, and returns it along with
+    /// all vertices it contains, in the order in which they were appended.
+    ///
+    /// Returns an error of kind `InvalidData` if any entry but the last one is corrupt.
+    pub(crate) fn open(path: PathBuf) -> io::Result<(Self, Vec<Vertex<C>>)> {
+        if let Some(parent_directory) = path.parent() {
+            fs::create_dir_all(parent_directory)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        let mut vertices = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let remaining = &bytes[offset..];
+            if let Some((vertex, entry_length)) = Self::read_entry(remaining) {
+                vertices.push(vertex);
+                offset += entry_length;
+            } else if Self::is_last_entry(remaining) {
+                warn!(
+                    path = %path.display(),
+                    discarded_bytes = remaining.len(),
+                    "discarding corrupt or incomplete entry at the end of the write-ahead log"
+                );
+                file.set_len(offset as u64)?;
+                file.sync_data()?;
+                break;
+            } else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "corrupt entry at offset {} of write-ahead log {}",
+                        offset,
+                        path.display()
+                    ),
+                ));
+            }
+        }
+
+        let wal = WriteAheadLog {
+            path,
+            file,
+            is_dirty: false,
+            _phantom: PhantomData,
+        };
+        Ok((wal, vertices))
+    }
+
+    /// Appends the vertex to the log, without syncing it to disk.
+    pub(crate) fn append(&mut self, vertex: &Vertex<C>) -> io::Result<()> {
+        let serialized = bincode::serialize(vertex)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let length: u32 = serialized
+            .len()
+            .try_into()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "vertex too large"))?;
+        let mut entry = Vec::with_capacity(LENGTH_PREFIX_SIZE + serialized.len());
+        entry.extend_from_slice(&length.to_le_bytes());
+        entry.extend(serialized);
+        self.file.write_all(&entry)?;
+        self.is_dirty = true;
+        Ok(())
+    }
+
+    /// Syncs all appended entries to disk, if there are any that haven't been synced yet.
+    pub(crate) fn sync(&mut self) -> io::Result<()> {
+        if self.is_dirty {
+            self.file.sync_data()?;
+            self.is_dirty = false;
+        }
+        Ok(())
+    }
+
+    /// Returns the path of the log file.
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Parses the entry at the start of `bytes`, returning the vertex and the length of the entry,
+    /// or `None` if there is no complete and valid entry.
+    fn read_entry(bytes: &[u8]) -> Option<(Vertex<C>, usize)> {
+        let length_prefix = bytes.get(..LENGTH_PREFIX_SIZE)?;
+        let length = u32::from_le_bytes(length_prefix.try_into().ok()?) as usize;
+        let entry_length = LENGTH_PREFIX_SIZE.checked_add(length)?;
+        let serialized = bytes.get(LENGTH_PREFIX_SIZE..entry_length)?;
+        let vertex = bincode::deserialize(serialized).ok()?;
+        Some((vertex, entry_length))
+    }
+
+    /// Returns whether the entry at the start of `bytes` extends to the end of the log, judging
+    /// by its length prefix, or is too short to have a complete length prefix.
+    fn is_last_entry(bytes: &[u8]) -> bool {
+        match bytes.get(..LENGTH_PREFIX_SIZE) {
+            Some(length_prefix) => {
+                let length = u32::from_le_bytes(length_prefix.try_into().unwrap_or_default());
+                LENGTH_PREFIX_SIZE.saturating_add(length as usize) >= bytes.len()
+            }
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::consensus::highway_core::{
+        highway::Ping,
+        state::tests::{TestContext, TestSecret, ALICE_SEC, BOB_SEC},
+        validators::ValidatorIndex,
+    };
+
+    fn ping(creator: u32, secret: &TestSecret) -> Vertex<TestContext> {
+        Vertex::Ping(Ping::new(ValidatorIndex(creator), 1000.into(), secret).unwrap())
+    }
+
+    #[test]
+    fn should_replay_vertices_and_discard_incomplete_entry() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("wal.dat");
+        let vertices = vec![ping(0, &ALICE_SEC), ping(1, &BOB_SEC)];
+
+        let (mut wal, replayed) = WriteAheadLog::<TestContext>::open(path.clone()).unwrap();
+        assert!(replayed.is_empty());
+        for vertex in &vertices {
+            wal.append(vertex).unwrap();
+        }
+        wal.sync().unwrap();
+        drop(wal);
+
+        // Simulate a crash while writing a third entry.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&100u32.to_le_bytes()).unwrap();
+        file.write_all(&[1, 2, 3]).unwrap();
+        drop(file);
+
+        let (mut wal, replayed) = WriteAheadLog::<TestContext>::open(path.clone()).unwrap();
+        assert_eq!(replayed, vertices);
+
+        // New entries are appended after the last complete one.
+        wal.append(&vertices[0]).unwrap();
+        drop(wal);
+        let (_, replayed) = WriteAheadLog::<TestContext>::open(path).unwrap();
+        assert_eq!(replayed.len(), 3);
+        assert_eq!(replayed[2], vertices[0]);
+    }
+
+    #[test]
+    fn should_discard_corrupt_last_entry() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("wal.dat");
+        let vertex = ping(0, &ALICE_SEC);
+
+        let (mut wal, _) = WriteAheadLog::<TestContext>::open(path.clone()).unwrap();
+        wal.append(&vertex).unwrap();
+        drop(wal);
+
+        // Simulate a crash after the length of a second entry, but not its contents, was written.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&3u32.to_le_bytes()).unwrap();
+        file.write_all(&[0, 0, 0]).unwrap();
+        drop(file);
+
+        let (_, replayed) = WriteAheadLog::<TestContext>::open(path).unwrap();
+        assert_eq!(replayed, vec![vertex]);
+    }
+
+    #[test]
+    fn should_fail_to_open_with_corrupt_entry_before_the_last() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("wal.dat");
+
+        let (mut wal, _) = WriteAheadLog::<TestContext>::open(path.clone()).unwrap();
+        wal.append(&ping(0, &ALICE_SEC)).unwrap();
+        wal.append(&ping(1, &BOB_SEC)).unwrap();
+        drop(wal);
+
+        // Corrupt the first entry, followed by a valid one.
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[LENGTH_PREFIX_SIZE] ^= 0xff;
+        fs::write(&path, &bytes).unwrap();
+
+        let error = WriteAheadLog::<TestContext>::open(path.clone()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        // The log is left untouched.
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+    }
+}
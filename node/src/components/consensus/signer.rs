@@ -0,0 +1,625 @@
+//! Signers for the validator's consensus messages.
+//!
+//! The validator's units, pings, endorsements and finality signatures are signed by a [`Signer`].
+//! The [`LocalSigner`] holds the secret key in the node process. The [`RemoteSigner`] forwards
+//! every request to a separate signing daemon, e.g. the [`SigningServer`], over a Unix socket or a
+//! TCP connection to a loopback address, so that the key doesn't have to be readable by the node.
+//! Connections are not authenticated, so a daemon on another host must be reached through an
+//! authenticated tunnel, e.g. SSH or TLS with client certificates, forwarding to such a socket.
+//!
+//! The protocol between the node and the daemon is a sequence of requests and responses, each
+//! encoded as a single line of JSON: see [`Request`] and [`Response`]. Requests contain the whole
+//! unit, ping or endorsement, and the daemon computes the signed hash itself, so that it can't be
+//! made to sign e.g. a unit disguised as a ping. A signing daemon is expected to refuse to sign two
+//! different units with the same sequence number in the same era, and to return the same signature
+//! again if the node repeats a request, e.g. after a reconnect.
+//!
+//! Requests to a remote signer block, so they are never sent from the reactor thread.
+
+mod server;
+
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpStream},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+use datasize::DataSize;
+use hex_buffer_serde::{Hex, HexForm};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use casper_types::{light_client, PublicKey, SecretKey, Signature};
+
+use crate::{
+    components::consensus::{
+        cl_context::ClContext,
+        highway_core::{
+            active_validator::UnsignedVertex, highway::WireUnit, validators::ValidatorIndex,
+        },
+        EraId,
+    },
+    crypto::{self, hash::Digest},
+    types::{BlockHash, Timestamp},
+};
+
+pub use server::SigningServer;
+
+/// An error when requesting a signature.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Communication with the remote signer failed.
+    #[error("failed to communicate with remote signer at {address}: {error}")]
+    Io {
+        /// The address of the remote signer.
+        address: SignerAddress,
+        /// The underlying error.
+        error: io::Error,
+    },
+    /// The remote signer sent a response that could not be parsed.
+    #[error("invalid response from remote signer: {0}")]
+    InvalidResponse(serde_json::Error),
+    /// The remote signer sent a response of the wrong kind.
+    #[error("unexpected response from remote signer: {0:?}")]
+    UnexpectedResponse(Response),
+    /// The remote signer refused to sign.
+    #[error("remote signer refused to sign: {0}")]
+    Refused(String),
+    /// The request contains a unit that can't be decoded.
+    #[error("invalid unit in signing request: {0}")]
+    InvalidUnit(String),
+}
+
+/// A message the validator needs to have signed.
+///
+/// Units, pings and endorsements are not given as a hash, but with their contents, from which the
+/// hash that is signed is computed.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SigningRequest {
+    /// A new unit created by the validator.
+    Unit {
+        /// The era the unit belongs to.
+        era_id: EraId,
+        /// The unit, serialized with `bincode`.
+        #[serde(with = "HexForm::<Vec<u8>>")]
+        unit: Vec<u8>,
+    },
+    /// A ping created by the validator.
+    Ping {
+        /// The era the ping belongs to.
+        era_id: EraId,
+        /// The validator's index in the era.
+        creator: u32,
+        /// The ping's timestamp.
+        timestamp: Timestamp,
+    },
+    /// An endorsement of a unit, created by the validator.
+    Endorsement {
+        /// The era the endorsement belongs to.
+        era_id: EraId,
+        /// The hash of the endorsed unit.
+        unit: Digest,
+        /// The validator's index in the era.
+        creator: u32,
+    },
+    /// A finality signature for an executed block.
+    FinalitySignature {
+        /// The era the block belongs to.
+        era_id: EraId,
+        /// The block's hash.
+        block_hash: BlockHash,
+    },
+}
+
+impl SigningRequest {
+    /// Creates a request to sign one of the validator's own vertices.
+    pub(crate) fn for_vertex(era_id: EraId, vertex: &UnsignedVertex<ClContext>) -> Self {
+        match vertex {
+            UnsignedVertex::Unit(hwunit) => SigningRequest::Unit {
+                era_id,
+                unit: bincode::serialize(hwunit.wire_unit()).expect("serialize WireUnit"),
+            },
+            UnsignedVertex::Ping { creator, timestamp } => SigningRequest::Ping {
+                era_id,
+                creator: creator.0,
+                timestamp: *timestamp,
+            },
+            UnsignedVertex::Endorsement { unit, creator } => SigningRequest::Endorsement {
+                era_id,
+                unit: *unit,
+                creator: creator.0,
+            },
+        }
+    }
+
+    /// Returns the bytes that need to be signed.
+    ///
+    /// For units, pings and endorsements, this is their hash, computed the same way as when their
+    /// signatures are verified.
+    pub fn message(&self) -> Result<Vec<u8>, Error> {
+        let vertex = match self {
+            SigningRequest::Unit { unit, .. } => {
+                UnsignedVertex::Unit(decode_unit(unit)?.into_hashed())
+            }
+            SigningRequest::Ping {
+                creator, timestamp, ..
+            } => UnsignedVertex::Ping {
+                creator: ValidatorIndex(*creator),
+                timestamp: *timestamp,
+            },
+            SigningRequest::Endorsement { unit, creator, .. } => UnsignedVertex::Endorsement {
+                unit: *unit,
+                creator: ValidatorIndex(*creator),
+            },
+            SigningRequest::FinalitySignature { era_id, block_hash } => {
+                return Ok(light_client::finality_signature_message(
+                    &block_hash.inner().to_array(),
+                    era_id.0,
+                ));
+            }
+        };
+        Ok(vertex.hash().as_ref().to_vec())
+    }
+}
+
+/// Decodes a unit, returning an error unless the bytes are exactly its serialization.
+fn decode_unit(bytes: &[u8]) -> Result<WireUnit<ClContext>, Error> {
+    let unit: WireUnit<ClContext> =
+        bincode::deserialize(bytes).map_err(|error| Error::InvalidUnit(error.to_string()))?;
+    let reserialized =
+        bincode::serialize(&unit).map_err(|error| Error::InvalidUnit(error.to_string()))?;
+    if reserialized != bytes {
+        return Err(Error::InvalidUnit("unit has trailing bytes".to_string()));
+    }
+    Ok(unit)
+}
+
+/// A request sent to a remote signer.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    /// Requests the public key of the validator.
+    PublicKey,
+    /// Requests a signature.
+    Sign {
+        /// The message to sign.
+        request: SigningRequest,
+    },
+}
+
+/// A response from a remote signer.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    /// The public key of the validator.
+    PublicKey {
+        /// The public key.
+        public_key: PublicKey,
+    },
+    /// The requested signature.
+    Signature {
+        /// The signature.
+        signature: Signature,
+    },
+    /// The signer refused to sign.
+    Refused {
+        /// The reason for refusing.
+        reason: String,
+    },
+}
+
+/// Signs consensus messages on behalf of the validator.
+pub(crate) trait Signer: Send + Sync {
+    /// Returns the validator's public key.
+    fn public_key(&self) -> PublicKey;
+
+    /// Returns this signer if it holds the secret key in the node process. Other signers may
+    /// block, and must not be used on the reactor thread.
+    fn local(&self) -> Option<&LocalSigner> {
+        None
+    }
+
+    /// Signs the requested message.
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, Error>;
+}
+
+/// A signer using a secret key held in the node process.
+pub(crate) struct LocalSigner {
+    secret_key: SecretKey,
+    public_key: PublicKey,
+}
+
+impl LocalSigner {
+    /// Creates a new signer using the given secret key.
+    pub(crate) fn new(secret_key: SecretKey) -> Self {
+        let public_key = PublicKey::from(&secret_key);
+        LocalSigner {
+            secret_key,
+            public_key,
+        }
+    }
+
+    /// Signs the given hash of a unit, ping or endorsement.
+    pub(crate) fn sign_hash(&self, hash: &Digest) -> Signature {
+        crypto::sign(hash, &self.secret_key, &self.public_key)
+    }
+}
+
+impl Signer for LocalSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    fn local(&self) -> Option<&LocalSigner> {
+        Some(self)
+    }
+
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        Ok(crypto::sign(
+            request.message()?,
+            &self.secret_key,
+            &self.public_key,
+        ))
+    }
+}
+
+/// The address of a remote signer.
+#[derive(Clone, DataSize, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum SignerAddress {
+    /// The path of a Unix domain socket, given as `unix:<path>`.
+    Unix(PathBuf),
+    /// A loopback TCP address, given as `tcp:<IP address>:<port>`.
+    Tcp(SocketAddr),
+}
+
+impl SignerAddress {
+    /// Connects to the signer at this address, with the given read and write timeout.
+    fn connect(&self, timeout: Duration) -> io::Result<Stream> {
+        match self {
+            SignerAddress::Unix(path) => {
+                let stream = UnixStream::connect(path)?;
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))?;
+                Ok(Stream::Unix(stream))
+            }
+            SignerAddress::Tcp(address) => {
+                let stream = TcpStream::connect_timeout(address, timeout)?;
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))?;
+                stream.set_nodelay(true)?;
+                Ok(Stream::Tcp(stream))
+            }
+        }
+    }
+}
+
+impl Display for SignerAddress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SignerAddress::Unix(path) => write!(f, "unix:{}", path.display()),
+            SignerAddress::Tcp(address) => write!(f, "tcp:{}", address),
+        }
+    }
+}
+
+impl FromStr for SignerAddress {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = input.strip_prefix("unix:") {
+            Ok(SignerAddress::Unix(PathBuf::from(path)))
+        } else if let Some(address) = input.strip_prefix("tcp:") {
+            let address: SocketAddr = address.parse().map_err(|_| {
+                format!(
+                    "invalid signer address {:?}: expected 'tcp:<IP address>:<port>'",
+                    input
+                )
+            })?;
+            // Connections are not authenticated, so they must not leave the host.
+            if !address.ip().is_loopback() {
+                return Err(format!(
+                    "invalid signer address {:?}: only loopback addresses are allowed for TCP",
+                    input
+                ));
+            }
+            Ok(SignerAddress::Tcp(address))
+        } else {
+            Err(format!(
+                "invalid signer address {:?}: expected 'unix:<path>' or 'tcp:<IP address>:<port>'",
+                input
+            ))
+        }
+    }
+}
+
+impl TryFrom<String> for SignerAddress {
+    type Error = String;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        input.parse()
+    }
+}
+
+impl From<SignerAddress> for String {
+    fn from(address: SignerAddress) -> Self {
+        address.to_string()
+    }
+}
+
+/// A connection to a signer or from a node.
+enum Stream {
+    Unix(UnixStream),
+    Tcp(TcpStream),
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Unix(stream) => stream.read(buf),
+            Stream::Tcp(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Unix(stream) => stream.write(buf),
+            Stream::Tcp(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Unix(stream) => stream.flush(),
+            Stream::Tcp(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Writes the message as a single line of JSON.
+fn write_message<T: Serialize, W: Write>(writer: &mut W, message: &T) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()
+}
+
+/// Reads a single line, or returns `None` if the connection was closed.
+fn read_line<R: BufRead>(reader: &mut R) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+/// A connection to a remote signer, re-established on demand.
+struct Connection {
+    address: SignerAddress,
+    timeout: Duration,
+    stream: Mutex<Option<BufReader<Stream>>>,
+}
+
+impl Connection {
+    /// Sends the request and returns the response.
+    ///
+    /// If the existing connection fails, reconnects and sends the request once more.
+    fn request(&self, request: &Request) -> Result<Response, Error> {
+        let mut stream = self.stream.lock();
+        let line = match self.request_on(&mut stream, request) {
+            Ok(line) => line,
+            Err(error) => {
+                warn!(address = %self.address, %error, "remote signer request failed; reconnecting");
+                *stream = None;
+                self.request_on(&mut stream, request)
+                    .map_err(|error| Error::Io {
+                        address: self.address.clone(),
+                        error,
+                    })?
+            }
+        };
+        serde_json::from_str(&line).map_err(Error::InvalidResponse)
+    }
+
+    /// Sends the request on the given stream, connecting first if necessary, and returns the
+    /// response line.
+    fn request_on(
+        &self,
+        stream: &mut Option<BufReader<Stream>>,
+        request: &Request,
+    ) -> io::Result<String> {
+        if stream.is_none() {
+            *stream = Some(BufReader::new(self.address.connect(self.timeout)?));
+        }
+        let reader = stream.as_mut().expect("stream should exist");
+        write_message(reader.get_mut(), request)?;
+        read_line(reader)?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "remote signer closed the connection",
+            )
+        })
+    }
+}
+
+/// A signer that forwards all requests to a signing daemon.
+///
+/// Requests are blocking: the caller waits for each response, up to the configured timeout, and
+/// possibly twice that if the connection needs to be re-established. They must not be sent from
+/// the reactor thread.
+pub(crate) struct RemoteSigner {
+    connection: Connection,
+    public_key: PublicKey,
+}
+
+impl RemoteSigner {
+    /// Connects to the signer at the given address and requests the validator's public key.
+    pub(crate) fn connect(address: SignerAddress, timeout: Duration) -> Result<Self, Error> {
+        let connection = Connection {
+            address,
+            timeout,
+            stream: Mutex::new(None),
+        };
+        let public_key = match connection.request(&Request::PublicKey)? {
+            Response::PublicKey { public_key } => public_key,
+            Response::Refused { reason } => return Err(Error::Refused(reason)),
+            response => return Err(Error::UnexpectedResponse(response)),
+        };
+        info!(address = %connection.address, %public_key, "connected to remote signer");
+        Ok(RemoteSigner {
+            connection,
+            public_key,
+        })
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, Error> {
+        let request = Request::Sign {
+            request: request.clone(),
+        };
+        match self.connection.request(&request)? {
+            Response::Signature { signature } => Ok(signature),
+            Response::Refused { reason } => Err(Error::Refused(reason)),
+            response => Err(Error::UnexpectedResponse(response)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, thread};
+
+    use super::{server::Listener, *};
+    use crate::{
+        components::consensus::highway_core::state::Panorama, crypto::AsymmetricKeyExt,
+        testing::TestRng,
+    };
+
+    /// Returns a request to sign a unit with the given sequence number and timestamp.
+    pub(super) fn unit_request(
+        instance_id: Digest,
+        seq_number: u64,
+        timestamp: u64,
+    ) -> SigningRequest {
+        let wire_unit = WireUnit::<ClContext> {
+            panorama: Panorama::new(1),
+            creator: ValidatorIndex(0),
+            instance_id,
+            value: None,
+            seq_number,
+            timestamp: timestamp.into(),
+            round_exp: 14,
+            endorsed: BTreeSet::new(),
+        };
+        SigningRequest::Unit {
+            era_id: EraId(1),
+            unit: bincode::serialize(&wire_unit).unwrap(),
+        }
+    }
+
+    #[test]
+    fn remote_signer_should_refuse_conflicting_units() {
+        let mut rng = TestRng::new();
+        let secret_key = SecretKey::random(&mut rng);
+        let public_key = PublicKey::from(&secret_key);
+        let tempdir = tempfile::tempdir().unwrap();
+        let address = SignerAddress::Unix(tempdir.path().join("signer.sock"));
+        let server =
+            SigningServer::new(secret_key, tempdir.path().join("signer_state.json")).unwrap();
+        let listener = Listener::bind(&address).unwrap();
+        thread::spawn(move || server.serve(listener));
+
+        let signer = RemoteSigner::connect(address, Duration::from_secs(5)).unwrap();
+        assert_eq!(signer.public_key(), public_key);
+
+        let instance_id = Digest::random(&mut rng);
+        let unit = |seq_number, timestamp| unit_request(instance_id, seq_number, timestamp);
+
+        let signature = signer.sign(&unit(0, 1000)).unwrap();
+        crypto::verify(unit(0, 1000).message().unwrap(), &signature, &public_key).unwrap();
+        // Repeating a request is allowed, but signing a different unit with the same or an
+        // earlier sequence number isn't.
+        assert_eq!(signer.sign(&unit(0, 1000)).unwrap(), signature);
+        assert!(matches!(
+            signer.sign(&unit(0, 2000)),
+            Err(Error::Refused(_))
+        ));
+        signer.sign(&unit(1, 2000)).unwrap();
+        assert!(matches!(
+            signer.sign(&unit(0, 1000)),
+            Err(Error::Refused(_))
+        ));
+
+        // Bytes that aren't a unit, e.g. a hash, are refused.
+        let request = SigningRequest::Unit {
+            era_id: EraId(1),
+            unit: Digest::random(&mut rng).to_vec(),
+        };
+        assert!(matches!(signer.sign(&request), Err(Error::Refused(_))));
+
+        let block_hash = BlockHash::new(Digest::random(&mut rng));
+        let request = SigningRequest::FinalitySignature {
+            era_id: EraId(1),
+            block_hash,
+        };
+        let signature = signer.sign(&request).unwrap();
+        crypto::verify(request.message().unwrap(), &signature, &public_key).unwrap();
+    }
+
+    #[test]
+    fn should_sign_hash_of_vertex_contents() {
+        let mut rng = TestRng::new();
+        let signer = LocalSigner::new(SecretKey::random(&mut rng));
+
+        let ping = UnsignedVertex::<ClContext>::Ping {
+            creator: ValidatorIndex(3),
+            timestamp: 1000.into(),
+        };
+        let request = SigningRequest::for_vertex(EraId(1), &ping);
+        assert_eq!(request.message().unwrap(), ping.hash().to_vec());
+        assert_eq!(
+            signer.sign(&request).unwrap(),
+            signer.sign_hash(&ping.hash())
+        );
+
+        let endorsement = UnsignedVertex::<ClContext>::Endorsement {
+            unit: Digest::random(&mut rng),
+            creator: ValidatorIndex(3),
+        };
+        let request = SigningRequest::for_vertex(EraId(1), &endorsement);
+        assert_eq!(request.message().unwrap(), endorsement.hash().to_vec());
+
+        // Appending bytes to a unit makes it invalid, even though it still decodes.
+        let mut request = unit_request(Digest::random(&mut rng), 0, 1000);
+        if let SigningRequest::Unit { unit, .. } = &mut request {
+            unit.push(0);
+        }
+        assert!(matches!(request.message(), Err(Error::InvalidUnit(_))));
+    }
+
+    #[test]
+    fn should_only_allow_loopback_tcp_addresses() {
+        assert!("tcp:127.0.0.1:7777".parse::<SignerAddress>().is_ok());
+        assert!("tcp:[::1]:7777".parse::<SignerAddress>().is_ok());
+        assert!("tcp:10.0.0.1:7777".parse::<SignerAddress>().is_err());
+        assert!("tcp:0.0.0.0:7777".parse::<SignerAddress>().is_err());
+        assert!("tcp:signer.example.com:7777"
+            .parse::<SignerAddress>()
+            .is_err());
+    }
+}
@@ -0,0 +1,230 @@
+//! A signing daemon serving the remote signer protocol.
+//!
+//! The server holds the validator's secret key and records, per consensus instance, i.e. per era,
+//! the sequence number and hash of the last unit it signed. It refuses to sign a unit with a lower
+//! sequence number, or a different unit with the same sequence number, so that e.g. two nodes
+//! accidentally running with the same signer can't make the validator equivocate. The sequence
+//! number and instance are read from the unit itself, not taken from the node. The record is
+//! written to a state file before the signature of a new unit is returned, so that it survives
+//! restarts of the server.
+//!
+//! The server only listens on Unix sockets and loopback TCP addresses, since it doesn't
+//! authenticate its clients.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, BufReader},
+    net::TcpListener,
+    os::unix::net::UnixListener,
+    path::PathBuf,
+    sync::Arc,
+    thread,
+};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use casper_types::SecretKey;
+
+use super::{
+    decode_unit, read_line, write_message, LocalSigner, Request, Response, Signer, SignerAddress,
+    SigningRequest, Stream,
+};
+use crate::crypto::hash::Digest;
+
+/// The last unit signed in a consensus instance.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct SignedUnit {
+    seq_number: u64,
+    hash: Digest,
+}
+
+/// A listening socket accepting connections from nodes.
+pub(super) enum Listener {
+    Unix(UnixListener),
+    Tcp(TcpListener),
+}
+
+impl Listener {
+    /// Starts listening on the given address. A stale Unix socket file is removed first.
+    pub(super) fn bind(address: &SignerAddress) -> io::Result<Self> {
+        let listener = match address {
+            SignerAddress::Unix(path) => {
+                if path.exists() {
+                    fs::remove_file(path)?;
+                }
+                Listener::Unix(UnixListener::bind(path)?)
+            }
+            SignerAddress::Tcp(address) => Listener::Tcp(TcpListener::bind(address)?),
+        };
+        info!(%address, "remote signer listening");
+        Ok(listener)
+    }
+
+    fn accept(&self) -> io::Result<Stream> {
+        match self {
+            Listener::Unix(listener) => Ok(Stream::Unix(listener.accept()?.0)),
+            Listener::Tcp(listener) => {
+                let stream = listener.accept()?.0;
+                stream.set_nodelay(true)?;
+                Ok(Stream::Tcp(stream))
+            }
+        }
+    }
+}
+
+/// A signing daemon with double-sign protection.
+pub struct SigningServer {
+    signer: LocalSigner,
+    state_path: PathBuf,
+    last_signed_units: BTreeMap<Digest, SignedUnit>,
+}
+
+impl SigningServer {
+    /// Creates a server signing with the given key, loading the record of the last signed units
+    /// from the state file, if it exists.
+    pub fn new(secret_key: SecretKey, state_path: PathBuf) -> io::Result<Self> {
+        let last_signed_units = if state_path.exists() {
+            serde_json::from_slice(&fs::read(&state_path)?)?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(SigningServer {
+            signer: LocalSigner::new(secret_key),
+            state_path,
+            last_signed_units,
+        })
+    }
+
+    /// Listens on the given address and serves requests until an error occurs.
+    pub fn run(self, address: &SignerAddress) -> io::Result<()> {
+        let listener = Listener::bind(address)?;
+        self.serve(listener)
+    }
+
+    /// Serves requests from connections accepted by the listener, each on its own thread.
+    pub(super) fn serve(self, listener: Listener) -> io::Result<()> {
+        let server = Arc::new(Mutex::new(self));
+        loop {
+            let stream = listener.accept()?;
+            let server = Arc::clone(&server);
+            thread::spawn(move || {
+                if let Err(error) = handle_connection(&server, stream) {
+                    warn!(%error, "connection to node failed");
+                }
+            });
+        }
+    }
+
+    /// Returns the response to the request.
+    fn handle_request(&mut self, request: Request) -> Response {
+        let request = match request {
+            Request::PublicKey => {
+                return Response::PublicKey {
+                    public_key: self.signer.public_key(),
+                }
+            }
+            Request::Sign { request } => request,
+        };
+        if let SigningRequest::Unit { unit, .. } = &request {
+            if let Err(reason) = self.record_unit(unit) {
+                warn!(%reason, "refusing to sign unit");
+                return Response::Refused { reason };
+            }
+        }
+        match self.signer.sign(&request) {
+            Ok(signature) => Response::Signature { signature },
+            Err(error) => Response::Refused {
+                reason: error.to_string(),
+            },
+        }
+    }
+
+    /// Records the serialized unit as the last one signed in its consensus instance, or returns an
+    /// error if signing it could be an equivocation.
+    fn record_unit(&mut self, bytes: &[u8]) -> Result<(), String> {
+        let unit = decode_unit(bytes).map_err(|error| error.to_string())?;
+        let instance_id = unit.instance_id;
+        let seq_number = unit.seq_number;
+        let hash = unit.into_hashed().hash();
+        match self.last_signed_units.get(&instance_id) {
+            Some(last) if last.seq_number == seq_number && last.hash == hash => return Ok(()),
+            Some(last) if last.seq_number >= seq_number => {
+                return Err(format!(
+                    "already signed unit {} with sequence number {} in instance {}",
+                    last.hash, last.seq_number, instance_id
+                ));
+            }
+            _ => (),
+        }
+        let _ = self
+            .last_signed_units
+            .insert(instance_id, SignedUnit { seq_number, hash });
+        self.save_state()
+            .map_err(|error| format!("failed to save signer state: {}", error))
+    }
+
+    /// Atomically replaces the state file with the current record of signed units.
+    fn save_state(&self) -> io::Result<()> {
+        let temp_path = self.state_path.with_extension("tmp");
+        fs::write(&temp_path, serde_json::to_vec(&self.last_signed_units)?)?;
+        fs::rename(&temp_path, &self.state_path)
+    }
+}
+
+/// Answers the requests received on the stream until it is closed.
+fn handle_connection(server: &Mutex<SigningServer>, stream: Stream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    while let Some(line) = read_line(&mut reader)? {
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => server.lock().handle_request(request),
+            Err(error) => Response::Refused {
+                reason: format!("invalid request: {}", error),
+            },
+        };
+        write_message(reader.get_mut(), &response)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::tests::unit_request, *};
+    use crate::{crypto::AsymmetricKeyExt, testing::TestRng};
+
+    #[test]
+    fn should_remember_signed_units_after_restart() {
+        let mut rng = TestRng::new();
+        let secret_key = SecretKey::random(&mut rng);
+        let tempdir = tempfile::tempdir().unwrap();
+        let state_path = tempdir.path().join("signer_state.json");
+        let instance_id = Digest::random(&mut rng);
+        let sign_unit = |server: &mut SigningServer, seq_number, timestamp| {
+            let request = unit_request(instance_id, seq_number, timestamp);
+            server.handle_request(Request::Sign { request })
+        };
+
+        let mut server = SigningServer::new(secret_key.duplicate(), state_path.clone()).unwrap();
+        assert!(matches!(
+            sign_unit(&mut server, 5, 1000),
+            Response::Signature { .. }
+        ));
+        drop(server);
+
+        let mut server = SigningServer::new(secret_key, state_path).unwrap();
+        assert!(matches!(
+            sign_unit(&mut server, 5, 2000),
+            Response::Refused { .. }
+        ));
+        assert!(matches!(
+            sign_unit(&mut server, 4, 2000),
+            Response::Refused { .. }
+        ));
+        assert!(matches!(
+            sign_unit(&mut server, 5, 1000),
+            Response::Signature { .. }
+        ));
+    }
+}
@@ -200,18 +200,23 @@ where
     /// Dispatches messages to their recipients.
     pub(crate) fn dispatch_messages(&mut self, messages: Vec<(TargetedMessage<M>, Timestamp)>) {
         for (TargetedMessage { message, target }, delivery_time) in messages {
-            let recipients = match target {
-                Target::AllExcept(creator) => self
-                    .validators_ids()
-                    .filter(|id| **id != creator)
-                    .cloned()
-                    .collect(),
-                Target::SingleValidator(recipient_id) => vec![recipient_id],
-            };
+            let recipients = self.recipients(target);
             self.send_messages(recipients, message, delivery_time)
         }
     }
 
+    /// Returns the IDs of the validators a message with the given target is delivered to.
+    pub(crate) fn recipients(&self, target: Target) -> Vec<ValidatorId> {
+        match target {
+            Target::AllExcept(creator) => self
+                .validators_ids()
+                .filter(|id| **id != creator)
+                .cloned()
+                .collect(),
+            Target::SingleValidator(recipient_id) => vec![recipient_id],
+        }
+    }
+
     /// Pop a message from the queue.
     /// It's a message with the earliest delivery time.
     pub(crate) fn pop_message(&mut self) -> Option<QueueEntry<M>> {
@@ -270,6 +275,7 @@ where
     }
 }
 
+#[cfg(test)]
 mod virtual_net_tests {
     use super::{Message, Node, Target, TargetedMessage, Timestamp, ValidatorId, VirtualNet};
 
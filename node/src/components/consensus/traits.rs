@@ -60,7 +60,16 @@ pub(crate) trait ValidatorSecret: Send + DataSize {
 
     type Signature: Eq + PartialEq + Clone + Debug + Hash + Serialize + DeserializeOwned + DataSize;
 
-    fn sign(&self, hash: &Self::Hash) -> Self::Signature;
+    /// Signs the hash, or returns `None` if no signature could be created.
+    fn sign(&self, hash: &Self::Hash) -> Option<Self::Signature>;
+
+    /// Returns whether signing may block, e.g. because the key is held by a remote signer.
+    ///
+    /// If so, `sign` is not used: the signatures of new vertices are requested with
+    /// `Effect::RequestSignature` instead, so that they can be created off the reactor thread.
+    fn is_blocking(&self) -> bool {
+        false
+    }
 }
 
 /// The collection of types the user can choose for cryptography, IDs, transactions, etc.
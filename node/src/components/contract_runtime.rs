@@ -1,5 +1,6 @@
 //! Contract Runtime component.
 mod config;
+mod pruning;
 mod types;
 
 pub use config::Config;
@@ -7,46 +8,55 @@ pub use types::{EraValidatorsRequest, ValidatorWeightsByEraIdRequest};
 
 use std::{
     fmt::{self, Debug, Display, Formatter},
-    sync::Arc,
+    sync::{Arc, Mutex},
     time::Instant,
 };
 
 use datasize::DataSize;
 use derive_more::From;
 use lmdb::DatabaseFlags;
-use prometheus::{self, Histogram, HistogramOpts, Registry};
+use prometheus::{self, Histogram, HistogramOpts, IntCounter, IntGauge, Registry};
 use serde::Serialize;
 use thiserror::Error;
 use tokio::task;
-use tracing::{error, trace};
+use tracing::{debug, error, trace};
 
 use casper_execution_engine::{
     core::engine_state::{
         genesis::GenesisResult, EngineConfig, EngineState, Error, GetEraValidatorsError,
-        GetEraValidatorsRequest,
+        GetEraValidatorsRequest, StepResult, UpgradeResult,
     },
     shared::newtypes::{Blake2bHash, CorrelationId},
     storage::{
-        error::lmdb::Error as StorageLmdbError, global_state::lmdb::LmdbGlobalState,
+        error::lmdb::Error as StorageLmdbError,
+        global_state::{lmdb::LmdbGlobalState, CommitResult},
         protocol_data_store::lmdb::LmdbProtocolDataStore,
-        transaction_source::lmdb::LmdbEnvironment, trie_store::lmdb::LmdbTrieStore,
+        transaction_source::lmdb::LmdbEnvironment,
+        trie_store::lmdb::LmdbTrieStore,
     },
 };
-use casper_types::{system::auction::ValidatorWeights, ProtocolVersion};
+use casper_types::{bytesrepr::ToBytes, system::auction::ValidatorWeights, ProtocolVersion};
 
 use crate::{
     components::Component,
-    effect::{requests::ContractRuntimeRequest, EffectBuilder, EffectExt, Effects},
+    crypto::hash::Digest,
+    effect::{
+        requests::ContractRuntimeRequest, EffectBuilder, EffectExt, EffectOptionExt, Effects,
+    },
     types::Chainspec,
     utils::WithDir,
     NodeRng, StorageConfig,
 };
+use pruning::GlobalStatePruner;
 
 /// The contract runtime components.
 #[derive(DataSize)]
 pub struct ContractRuntime {
     engine_state: Arc<EngineState<LmdbGlobalState>>,
     metrics: Arc<ContractRuntimeMetrics>,
+    /// The global state pruner, if pruning is enabled.
+    #[data_size(skip)]
+    pruner: Option<Arc<Mutex<GlobalStatePruner>>>,
 }
 
 impl Debug for ContractRuntime {
@@ -61,12 +71,23 @@ pub enum Event {
     /// A request made of the contract runtime component.
     #[from]
     Request(ContractRuntimeRequest),
+    /// A block has been executed.
+    BlockExecuted {
+        /// The post state root hash of the executed block.
+        state_root_hash: Digest,
+    },
+    /// The next step of the global state pruning cycle in progress should be performed.
+    PruneStep,
 }
 
 impl Display for Event {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Event::Request(request) => write!(f, "{}", request),
+            Event::BlockExecuted { state_root_hash } => {
+                write!(f, "block executed with state root hash {}", state_root_hash)
+            }
+            Event::PruneStep => write!(f, "prune step"),
         }
     }
 }
@@ -87,6 +108,11 @@ pub struct ContractRuntimeMetrics {
     missing_trie_keys: Histogram,
     put_trie: Histogram,
     read_trie: Histogram,
+    pruning_step: Histogram,
+    pruning_in_progress: IntGauge,
+    pruning_cycles: IntCounter,
+    pruning_marked_tries: IntCounter,
+    pruning_deleted_tries: IntCounter,
 }
 
 /// Value of upper bound of histogram.
@@ -130,6 +156,18 @@ const PUT_TRIE_NAME: &str = "contract_runtime_put_trie";
 const PUT_TRIE_HELP: &str = "tracking run of engine_state.put_trie in seconds.";
 const MISSING_TRIE_KEYS_NAME: &str = "contract_runtime_missing_trie_keys";
 const MISSING_TRIE_KEYS_HELP: &str = "tracking run of engine_state.missing_trie_keys in seconds.";
+const PRUNING_STEP_NAME: &str = "contract_runtime_pruning_step";
+const PRUNING_STEP_HELP: &str = "tracking run of a single global state pruning step in seconds.";
+const PRUNING_IN_PROGRESS_NAME: &str = "contract_runtime_pruning_in_progress";
+const PRUNING_IN_PROGRESS_HELP: &str = "whether a global state pruning cycle is in progress.";
+const PRUNING_CYCLES_NAME: &str = "contract_runtime_pruning_cycles";
+const PRUNING_CYCLES_HELP: &str = "number of completed global state pruning cycles.";
+const PRUNING_MARKED_TRIES_NAME: &str = "contract_runtime_pruning_marked_tries";
+const PRUNING_MARKED_TRIES_HELP: &str =
+    "number of trie nodes marked as reachable by global state pruning.";
+const PRUNING_DELETED_TRIES_NAME: &str = "contract_runtime_pruning_deleted_tries";
+const PRUNING_DELETED_TRIES_HELP: &str =
+    "number of unreachable trie nodes deleted by global state pruning.";
 
 /// Create prometheus Histogram and register.
 fn register_histogram_metric(
@@ -148,6 +186,28 @@ fn register_histogram_metric(
     Ok(histogram)
 }
 
+/// Create prometheus IntGauge and register.
+fn register_int_gauge_metric(
+    registry: &Registry,
+    metric_name: &str,
+    metric_help: &str,
+) -> Result<IntGauge, prometheus::Error> {
+    let gauge = IntGauge::new(metric_name, metric_help)?;
+    registry.register(Box::new(gauge.clone()))?;
+    Ok(gauge)
+}
+
+/// Create prometheus IntCounter and register.
+fn register_int_counter_metric(
+    registry: &Registry,
+    metric_name: &str,
+    metric_help: &str,
+) -> Result<IntCounter, prometheus::Error> {
+    let counter = IntCounter::new(metric_name, metric_help)?;
+    registry.register(Box::new(counter.clone()))?;
+    Ok(counter)
+}
+
 impl ContractRuntimeMetrics {
     /// Constructor of metrics which creates and registers metrics objects for use.
     fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
@@ -189,6 +249,31 @@ impl ContractRuntimeMetrics {
                 MISSING_TRIE_KEYS_NAME,
                 MISSING_TRIE_KEYS_HELP,
             )?,
+            pruning_step: register_histogram_metric(
+                registry,
+                PRUNING_STEP_NAME,
+                PRUNING_STEP_HELP,
+            )?,
+            pruning_in_progress: register_int_gauge_metric(
+                registry,
+                PRUNING_IN_PROGRESS_NAME,
+                PRUNING_IN_PROGRESS_HELP,
+            )?,
+            pruning_cycles: register_int_counter_metric(
+                registry,
+                PRUNING_CYCLES_NAME,
+                PRUNING_CYCLES_HELP,
+            )?,
+            pruning_marked_tries: register_int_counter_metric(
+                registry,
+                PRUNING_MARKED_TRIES_NAME,
+                PRUNING_MARKED_TRIES_HELP,
+            )?,
+            pruning_deleted_tries: register_int_counter_metric(
+                registry,
+                PRUNING_DELETED_TRIES_NAME,
+                PRUNING_DELETED_TRIES_HELP,
+            )?,
         })
     }
 }
@@ -207,6 +292,65 @@ where
         event: Self::Event,
     ) -> Effects<Self::Event> {
         match event {
+            Event::BlockExecuted { state_root_hash } => {
+                let pruner = match &self.pruner {
+                    Some(pruner) => Arc::clone(pruner),
+                    None => return Effects::new(),
+                };
+                let metrics = Arc::clone(&self.metrics);
+                async move {
+                    task::spawn_blocking(move || {
+                        let started = pruner
+                            .lock()
+                            .expect("pruner lock poisoned")
+                            .block_executed(state_root_hash.into());
+                        if started {
+                            debug!(%state_root_hash, "starting global state pruning cycle");
+                            metrics.pruning_in_progress.set(1);
+                        }
+                        started.then(|| ())
+                    })
+                    .await
+                    .expect("should run")
+                }
+                .map_some(|()| Event::PruneStep)
+            }
+            Event::PruneStep => {
+                let pruner = match &self.pruner {
+                    Some(pruner) => Arc::clone(pruner),
+                    None => return Effects::new(),
+                };
+                let metrics = Arc::clone(&self.metrics);
+                async move {
+                    task::spawn_blocking(move || {
+                        let start = Instant::now();
+                        let result = pruner.lock().expect("pruner lock poisoned").step();
+                        metrics.pruning_step.observe(start.elapsed().as_secs_f64());
+                        match result {
+                            Ok(progress) => {
+                                metrics.pruning_marked_tries.inc_by(progress.marked as i64);
+                                metrics
+                                    .pruning_deleted_tries
+                                    .inc_by(progress.deleted as i64);
+                                if progress.finished {
+                                    metrics.pruning_cycles.inc();
+                                    metrics.pruning_in_progress.set(0);
+                                }
+                                trace!(?progress, "prune step result");
+                                (!progress.finished).then(|| ())
+                            }
+                            Err(error) => {
+                                error!(?error, "global state pruning step failed");
+                                metrics.pruning_in_progress.set(0);
+                                None
+                            }
+                        }
+                    })
+                    .await
+                    .expect("should run")
+                }
+                .map_some(|()| Event::PruneStep)
+            }
             Event::Request(ContractRuntimeRequest::GetProtocolData {
                 protocol_version,
                 responder,
@@ -256,14 +400,25 @@ where
                 trace!(?state_root_hash, ?effects, "commit");
                 let engine_state = Arc::clone(&self.engine_state);
                 let metrics = Arc::clone(&self.metrics);
+                let pruner = self.pruner.clone();
                 async move {
                     let correlation_id = CorrelationId::new();
                     let result = task::spawn_blocking(move || {
                         let start = Instant::now();
-                        let apply_result = engine_state.apply_effect(
-                            correlation_id,
-                            state_root_hash.into(),
-                            effects,
+                        let apply_result = commit_with_pruning(
+                            pruner.as_deref(),
+                            || {
+                                engine_state.apply_effect(
+                                    correlation_id,
+                                    state_root_hash.into(),
+                                    effects,
+                                )
+                            },
+                            |result, pruner| {
+                                if let Ok(CommitResult::Success { state_root }) = result {
+                                    pruner.committed(*state_root)
+                                }
+                            },
                         );
                         metrics.apply_effect.observe(start.elapsed().as_secs_f64());
                         apply_result
@@ -282,11 +437,23 @@ where
                 trace!(?upgrade_config, "upgrade");
                 let engine_state = Arc::clone(&self.engine_state);
                 let metrics = Arc::clone(&self.metrics);
+                let pruner = self.pruner.clone();
                 async move {
                     let correlation_id = CorrelationId::new();
                     let result = task::spawn_blocking(move || {
                         let start = Instant::now();
-                        let result = engine_state.commit_upgrade(correlation_id, *upgrade_config);
+                        let result = commit_with_pruning(
+                            pruner.as_deref(),
+                            || engine_state.commit_upgrade(correlation_id, *upgrade_config),
+                            |result, pruner| {
+                                if let Ok(UpgradeResult::Success {
+                                    post_state_hash, ..
+                                }) = result
+                                {
+                                    pruner.committed(*post_state_hash)
+                                }
+                            },
+                        );
                         metrics
                             .commit_upgrade
                             .observe(start.elapsed().as_secs_f64());
@@ -472,11 +639,23 @@ where
                 trace!(?step_request, "step request");
                 let engine_state = Arc::clone(&self.engine_state);
                 let metrics = Arc::clone(&self.metrics);
+                let pruner = self.pruner.clone();
                 async move {
                     let correlation_id = CorrelationId::new();
                     let result = task::spawn_blocking(move || {
                         let start = Instant::now();
-                        let result = engine_state.commit_step(correlation_id, step_request);
+                        let result = commit_with_pruning(
+                            pruner.as_deref(),
+                            || engine_state.commit_step(correlation_id, step_request),
+                            |result, pruner| {
+                                if let Ok(StepResult::Success {
+                                    post_state_hash, ..
+                                }) = result
+                                {
+                                    pruner.committed(*post_state_hash)
+                                }
+                            },
+                        );
                         metrics.commit_step.observe(start.elapsed().as_secs_f64());
                         result
                     })
@@ -520,12 +699,26 @@ where
                 trace!(?trie, "put_trie request");
                 let engine_state = Arc::clone(&self.engine_state);
                 let metrics = Arc::clone(&self.metrics);
+                let pruner = self.pruner.clone();
                 async move {
                     let correlation_id = CorrelationId::new();
                     let result = task::spawn_blocking(move || {
                         let start = Instant::now();
-                        let result = engine_state
-                            .put_trie_and_find_missing_descendant_trie_keys(correlation_id, &*trie);
+                        let trie_key = trie.to_bytes().ok().map(|bytes| Blake2bHash::new(&bytes));
+                        let result = commit_with_pruning(
+                            pruner.as_deref(),
+                            || {
+                                engine_state.put_trie_and_find_missing_descendant_trie_keys(
+                                    correlation_id,
+                                    &*trie,
+                                )
+                            },
+                            |_, pruner| {
+                                if let Some(trie_key) = trie_key {
+                                    pruner.put_trie(trie_key)
+                                }
+                            },
+                        );
                         metrics.put_trie.observe(start.elapsed().as_secs_f64());
                         result
                     })
@@ -598,6 +791,16 @@ impl ContractRuntime {
             DatabaseFlags::empty(),
         )?);
 
+        let pruner = if contract_runtime_config.prune_global_state() {
+            Some(Arc::new(Mutex::new(GlobalStatePruner::new(
+                Arc::clone(&environment),
+                Arc::clone(&trie_store),
+                contract_runtime_config,
+            ))))
+        } else {
+            None
+        };
+
         let global_state = LmdbGlobalState::empty(environment, trie_store, protocol_data_store)?;
         let engine_config = EngineConfig::new();
 
@@ -607,6 +810,7 @@ impl ContractRuntime {
         Ok(ContractRuntime {
             engine_state,
             metrics,
+            pruner,
         })
     }
 
@@ -621,11 +825,24 @@ impl ContractRuntime {
         );
         // Transforms a chainspec into a valid genesis config for execution engine.
         let ee_config = chainspec.as_ref().into();
-        self.engine_state.commit_genesis(
-            correlation_id,
-            genesis_config_hash.into(),
-            protocol_version,
-            &ee_config,
+        commit_with_pruning(
+            self.pruner.as_deref(),
+            || {
+                self.engine_state.commit_genesis(
+                    correlation_id,
+                    genesis_config_hash.into(),
+                    protocol_version,
+                    &ee_config,
+                )
+            },
+            |result, pruner| {
+                if let Ok(GenesisResult::Success {
+                    post_state_hash, ..
+                }) = result
+                {
+                    pruner.committed(*post_state_hash)
+                }
+            },
         )
     }
 
@@ -641,3 +858,18 @@ impl ContractRuntime {
         }
     }
 }
+
+/// Runs `commit` while holding the pruner lock, if pruning is enabled, so that it cannot interleave
+/// with a pruning step, then lets `record` tell the pruner about whatever `commit` wrote.
+fn commit_with_pruning<T>(
+    maybe_pruner: Option<&Mutex<GlobalStatePruner>>,
+    commit: impl FnOnce() -> T,
+    record: impl FnOnce(&T, &mut GlobalStatePruner),
+) -> T {
+    let mut maybe_guard = maybe_pruner.map(|pruner| pruner.lock().expect("pruner lock poisoned"));
+    let result = commit();
+    if let Some(pruner) = maybe_guard.as_mut() {
+        record(&result, pruner);
+    }
+    result
+}
@@ -5,6 +5,9 @@ use casper_execution_engine::shared::utils;
 
 const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_MAX_READERS: u32 = 512;
+const DEFAULT_PRUNE_GLOBAL_STATE: bool = false;
+const DEFAULT_RETAINED_STATE_ROOTS: u64 = 1_000;
+const DEFAULT_PRUNING_BATCH_SIZE: usize = 10_000;
 
 /// Contract runtime configuration.
 #[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize)]
@@ -21,6 +24,18 @@ pub struct Config {
     ///
     /// Defaults to 512.
     max_readers: Option<u32>,
+    /// Whether to remove global state which is unreachable from the most recent block state roots.
+    ///
+    /// Defaults to false.
+    prune_global_state: Option<bool>,
+    /// The number of most recent block state roots whose global state is retained when pruning.
+    ///
+    /// Defaults to 1,000.
+    retained_state_roots: Option<u64>,
+    /// The maximum number of trie nodes visited in a single pruning step.
+    ///
+    /// Defaults to 10,000.
+    pruning_batch_size: Option<usize>,
 }
 
 impl Config {
@@ -35,6 +50,22 @@ impl Config {
     pub(crate) fn max_readers(&self) -> u32 {
         self.max_readers.unwrap_or(DEFAULT_MAX_READERS)
     }
+
+    pub(crate) fn prune_global_state(&self) -> bool {
+        self.prune_global_state
+            .unwrap_or(DEFAULT_PRUNE_GLOBAL_STATE)
+    }
+
+    pub(crate) fn retained_state_roots(&self) -> u64 {
+        self.retained_state_roots
+            .unwrap_or(DEFAULT_RETAINED_STATE_ROOTS)
+            .max(1)
+    }
+
+    pub(crate) fn pruning_batch_size(&self) -> usize {
+        self.pruning_batch_size
+            .unwrap_or(DEFAULT_PRUNING_BATCH_SIZE)
+    }
 }
 
 impl Default for Config {
@@ -42,6 +73,9 @@ impl Default for Config {
         Config {
             max_global_state_size: Some(DEFAULT_MAX_GLOBAL_STATE_SIZE),
             max_readers: Some(DEFAULT_MAX_READERS),
+            prune_global_state: Some(DEFAULT_PRUNE_GLOBAL_STATE),
+            retained_state_roots: Some(DEFAULT_RETAINED_STATE_ROOTS),
+            pruning_batch_size: Some(DEFAULT_PRUNING_BATCH_SIZE),
         }
     }
 }
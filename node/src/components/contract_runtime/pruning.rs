@@ -0,0 +1,84 @@
+//! Background pruning of global state which is unreachable from recent block state roots.
+
+use std::{collections::VecDeque, sync::Arc};
+
+use casper_execution_engine::{
+    shared::newtypes::{Blake2bHash, CorrelationId},
+    storage::{
+        error::lmdb::Error as StorageLmdbError,
+        transaction_source::lmdb::LmdbEnvironment,
+        trie_store::{
+            lmdb::LmdbTrieStore,
+            pruning::{LmdbTriePruner, PruningProgress},
+        },
+    },
+};
+
+use super::Config;
+
+/// Tracks the most recent block state roots and drives pruning cycles over the trie store.
+///
+/// A new cycle is started once as many block state roots have dropped out of the retained window
+/// as the window holds, i.e. roughly once every `retained_state_roots` blocks.
+pub(super) struct GlobalStatePruner {
+    pruner: LmdbTriePruner,
+    retained_state_roots: VecDeque<Blake2bHash>,
+    max_retained_state_roots: usize,
+    evicted_since_last_cycle: usize,
+    latest_committed_root: Option<Blake2bHash>,
+}
+
+impl GlobalStatePruner {
+    pub(super) fn new(
+        environment: Arc<LmdbEnvironment>,
+        trie_store: Arc<LmdbTrieStore>,
+        config: &Config,
+    ) -> Self {
+        let max_retained_state_roots = config.retained_state_roots() as usize;
+        GlobalStatePruner {
+            pruner: LmdbTriePruner::new(environment, trie_store, config.pruning_batch_size()),
+            retained_state_roots: VecDeque::with_capacity(max_retained_state_roots),
+            max_retained_state_roots,
+            evicted_since_last_cycle: 0,
+            latest_committed_root: None,
+        }
+    }
+
+    /// Records a state root produced by a commit to global state.
+    ///
+    /// Intermediate roots produced while executing a block are not retained, but the latest one is
+    /// always kept, since the next commit builds on it.
+    pub(super) fn committed(&mut self, state_root: Blake2bHash) {
+        self.latest_committed_root = Some(state_root);
+        self.pruner.protect(state_root);
+    }
+
+    /// Records a trie written directly to the trie store, e.g. while synchronizing global state.
+    pub(super) fn put_trie(&mut self, trie_key: Blake2bHash) {
+        self.pruner.protect(trie_key);
+    }
+
+    /// Records the post state root of an executed block.
+    ///
+    /// Returns `true` if a new pruning cycle was started.
+    pub(super) fn block_executed(&mut self, state_root: Blake2bHash) -> bool {
+        self.retained_state_roots.push_back(state_root);
+        while self.retained_state_roots.len() > self.max_retained_state_roots {
+            self.retained_state_roots.pop_front();
+            self.evicted_since_last_cycle += 1;
+        }
+        if self.evicted_since_last_cycle < self.max_retained_state_roots || !self.pruner.is_idle() {
+            return false;
+        }
+        let mut retained_roots: Vec<Blake2bHash> =
+            self.retained_state_roots.iter().copied().collect();
+        retained_roots.extend(self.latest_committed_root);
+        self.evicted_since_last_cycle = 0;
+        self.pruner.start(retained_roots)
+    }
+
+    /// Performs one batch of work of the pruning cycle in progress.
+    pub(super) fn step(&mut self) -> Result<PruningProgress, StorageLmdbError> {
+        self.pruner.step(CorrelationId::new())
+    }
+}
@@ -1,5 +1,7 @@
 mod config;
 mod event;
+#[cfg(test)]
+mod tests;
 
 use std::{convert::Infallible, fmt::Debug};
 
@@ -7,13 +9,16 @@ use thiserror::Error;
 use tracing::{debug, error, info};
 
 use crate::{
-    components::Component,
+    components::{block_proposer::PendingDeployLimitError, Component},
     effect::{
         announcements::DeployAcceptorAnnouncement,
-        requests::{ContractRuntimeRequest, StorageRequest},
+        requests::{BlockProposerRequest, ContractRuntimeRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
     },
-    types::{chainspec::DeployConfig, Chainspec, Deploy, DeployValidationFailure, NodeId},
+    types::{
+        chainspec::DeployConfig, Chainspec, Deploy, DeployHash, DeployValidationFailure, NodeId,
+        Timestamp,
+    },
     utils::Source,
     NodeRng,
 };
@@ -34,6 +39,15 @@ pub enum Error {
     /// A deploy was sent from account with insufficient balance.
     #[error("insufficient balance")]
     InsufficientBalance,
+    /// The deploy cannot be added to the pending deploys without exceeding the configured limits.
+    #[error("pending deploy limit exceeded: {0}")]
+    ExceededPendingDeployLimit(PendingDeployLimitError),
+    /// The deploy depends on a deploy which is not known to this node.
+    #[error("unknown dependency: {0}")]
+    UnknownDependency(DeployHash),
+    /// The deploy depends on a deploy which has expired.
+    #[error("expired dependency: {0}")]
+    ExpiredDependency(DeployHash),
 }
 
 /// A helper trait constraining `DeployAcceptor` compatible reactor events.
@@ -42,6 +56,7 @@ pub trait ReactorEventT:
     + From<DeployAcceptorAnnouncement<NodeId>>
     + From<StorageRequest>
     + From<ContractRuntimeRequest>
+    + From<BlockProposerRequest>
     + Send
 {
 }
@@ -51,6 +66,7 @@ impl<REv> ReactorEventT for REv where
         + From<DeployAcceptorAnnouncement<NodeId>>
         + From<StorageRequest>
         + From<ContractRuntimeRequest>
+        + From<BlockProposerRequest>
         + Send
 {
 }
@@ -104,6 +120,123 @@ impl DeployAcceptor {
             return effects;
         }
 
+        // Check that the dependencies of a deploy received from a client are all known and
+        // unexpired.  Deploys received from peers are not checked, as their dependencies may
+        // simply not have been gossiped to us yet.
+        if source.from_client() && !deploy.header().dependencies().is_empty() {
+            let dependency_hashes = deploy.header().dependencies().iter().copied().collect();
+            return effect_builder
+                .get_deploys_from_storage(dependency_hashes)
+                .event(move |dependencies| Event::GetDependenciesResult {
+                    deploy,
+                    source,
+                    dependencies,
+                    maybe_responder,
+                });
+        }
+
+        self.check_pending_deploy_limits(effect_builder, deploy, source, maybe_responder)
+    }
+
+    fn dependencies<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        dependencies: Vec<Option<Deploy>>,
+        maybe_responder: Option<Responder<Result<(), Error>>>,
+    ) -> Effects<Event> {
+        let now = Timestamp::now();
+        let unresolvable = deploy
+            .header()
+            .dependencies()
+            .iter()
+            .zip(dependencies)
+            .find_map(
+                |(dependency_hash, maybe_dependency)| match maybe_dependency {
+                    None => Some(Error::UnknownDependency(*dependency_hash)),
+                    Some(dependency) if dependency.header().expired(now) => {
+                        Some(Error::ExpiredDependency(*dependency_hash))
+                    }
+                    Some(_) => None,
+                },
+            );
+
+        match unresolvable {
+            None => {
+                self.check_pending_deploy_limits(effect_builder, deploy, source, maybe_responder)
+            }
+            Some(error) => {
+                info!(deploy_hash = %deploy.id(), %error, "deploy has an unresolvable dependency");
+                let mut effects = Effects::new();
+                // Return an error message to the RPC component via the responder.
+                if let Some(responder) = maybe_responder {
+                    effects.extend(responder.respond(Err(error)).ignore());
+                }
+                effects.extend(
+                    effect_builder
+                        .announce_invalid_deploy(deploy, source)
+                        .ignore(),
+                );
+                effects
+            }
+        }
+    }
+
+    fn check_pending_deploy_limits<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        maybe_responder: Option<Responder<Result<(), Error>>>,
+    ) -> Effects<Event> {
+        // Check that a deploy received from a client fits within the limits on pending deploys.
+        if source.from_client() {
+            if let Ok(deploy_type) = deploy.deploy_type() {
+                return effect_builder
+                    .check_pending_deploy_limits(*deploy.id(), deploy_type)
+                    .event(move |result| Event::PendingDeployLimitsResult {
+                        deploy,
+                        source,
+                        result,
+                        maybe_responder,
+                    });
+            }
+        }
+
+        self.verify_account(effect_builder, deploy, source, maybe_responder)
+    }
+
+    fn pending_deploy_limits<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        result: Result<(), PendingDeployLimitError>,
+        maybe_responder: Option<Responder<Result<(), Error>>>,
+    ) -> Effects<Event> {
+        match result {
+            Ok(()) => self.verify_account(effect_builder, deploy, source, maybe_responder),
+            Err(error) => {
+                info!(deploy_hash = %deploy.id(), %error, "deploy exceeds pending deploy limits");
+                // Return an error message to the RPC component via the responder.
+                match maybe_responder {
+                    Some(responder) => responder
+                        .respond(Err(Error::ExceededPendingDeployLimit(error)))
+                        .ignore(),
+                    None => Effects::new(),
+                }
+            }
+        }
+    }
+
+    fn verify_account<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        maybe_responder: Option<Responder<Result<(), Error>>>,
+    ) -> Effects<Event> {
         let account_key = deploy.header().account().to_account_hash().into();
 
         // Verify account if deploy received from client and node is configured to do so.
@@ -229,6 +362,26 @@ impl<REv: ReactorEventT> Component<REv> for DeployAcceptor {
                 source,
                 is_new,
             } => self.handle_put_to_storage(effect_builder, deploy, source, is_new),
+            Event::GetDependenciesResult {
+                deploy,
+                source,
+                dependencies,
+                maybe_responder,
+            } => self.dependencies(
+                effect_builder,
+                deploy,
+                source,
+                dependencies,
+                maybe_responder,
+            ),
+            Event::PendingDeployLimitsResult {
+                deploy,
+                source,
+                result,
+                maybe_responder,
+            } => {
+                self.pending_deploy_limits(effect_builder, deploy, source, result, maybe_responder)
+            }
             Event::AccountVerificationResult {
                 deploy,
                 source,
@@ -4,7 +4,7 @@ use serde::Serialize;
 
 use super::Source;
 use crate::{
-    components::deploy_acceptor::Error,
+    components::{block_proposer::PendingDeployLimitError, deploy_acceptor::Error},
     effect::{announcements::RpcServerAnnouncement, Responder},
     types::{Deploy, NodeId},
 };
@@ -25,6 +25,20 @@ pub enum Event {
         source: Source<NodeId>,
         is_new: bool,
     },
+    /// The result of retrieving the `Deploy`'s dependencies from the storage component.
+    GetDependenciesResult {
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        dependencies: Vec<Option<Deploy>>,
+        maybe_responder: Option<Responder<Result<(), Error>>>,
+    },
+    /// The result of checking the `Deploy` against the block proposer's limits on pending deploys.
+    PendingDeployLimitsResult {
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        result: Result<(), PendingDeployLimitError>,
+        maybe_responder: Option<Responder<Result<(), Error>>>,
+    },
     /// The result of verifying `Account` exists and has meets minimum balance requirements.
     AccountVerificationResult {
         deploy: Box<Deploy>,
@@ -60,6 +74,21 @@ impl Display for Event {
                     write!(formatter, "had already stored {}", deploy.id())
                 }
             }
+            Event::GetDependenciesResult {
+                deploy,
+                dependencies,
+                ..
+            } => write!(
+                formatter,
+                "retrieved {} of {} dependencies of {}",
+                dependencies.iter().flatten().count(),
+                dependencies.len(),
+                deploy.id()
+            ),
+            Event::PendingDeployLimitsResult { deploy, result, .. } => match result {
+                Ok(()) => write!(formatter, "{} is within pending deploy limits", deploy.id()),
+                Err(error) => write!(formatter, "{} rejected: {}", deploy.id(), error),
+            },
             Event::AccountVerificationResult {
                 deploy,
                 account_key,
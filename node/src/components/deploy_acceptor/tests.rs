@@ -0,0 +1,102 @@
+//! Unit tests for the deploy acceptor component.
+
+use derive_more::From;
+use rand::Rng;
+
+use casper_types::SecretKey;
+
+use super::*;
+use crate::{
+    crypto::AsymmetricKeyExt,
+    effect::announcements::ControlAnnouncement,
+    reactor::ReactorEvent,
+    testing::{ComponentHarness, TestRng},
+    types::TimeDiff,
+};
+
+/// Top-level event for the test reactor, wrapping the events the deploy acceptor sends.
+#[derive(Debug, From)]
+enum TestEvent {
+    #[from]
+    DeployAcceptor(Event),
+    #[from]
+    DeployAcceptorAnnouncement(DeployAcceptorAnnouncement<NodeId>),
+    #[from]
+    Storage(StorageRequest),
+    #[from]
+    ContractRuntime(ContractRuntimeRequest),
+    #[from]
+    BlockProposer(BlockProposerRequest),
+    #[from]
+    ControlAnnouncement(ControlAnnouncement),
+}
+
+impl ReactorEvent for TestEvent {
+    fn as_control(&self) -> Option<&ControlAnnouncement> {
+        match self {
+            TestEvent::ControlAnnouncement(ctrl_ann) => Some(ctrl_ann),
+            _ => None,
+        }
+    }
+}
+
+fn new_deploy(rng: &mut TestRng, timestamp: Timestamp, dependencies: Vec<DeployHash>) -> Deploy {
+    Deploy::new(
+        timestamp,
+        TimeDiff::from(60_000),
+        1,
+        dependencies,
+        String::from("casper-example"),
+        rng.gen(),
+        rng.gen(),
+        &SecretKey::random(rng),
+    )
+}
+
+/// Passes the given dependencies of a deploy received from a client to the deploy acceptor, and
+/// returns the response to the client.
+fn accept_with_dependencies(
+    dependency_hashes: Vec<DeployHash>,
+    dependencies: Vec<Option<Deploy>>,
+) -> Result<(), Error> {
+    let mut harness = ComponentHarness::<TestEvent>::default();
+    let chainspec = Chainspec::random(&mut harness.rng);
+    let mut deploy_acceptor = DeployAcceptor::new(Config::new(false), &chainspec);
+    let deploy = new_deploy(&mut harness.rng, Timestamp::now(), dependency_hashes);
+    harness.send_request(&mut deploy_acceptor, |responder| {
+        Event::GetDependenciesResult {
+            deploy: Box::new(deploy),
+            source: Source::Client,
+            dependencies,
+            maybe_responder: Some(responder),
+        }
+    })
+}
+
+#[test]
+fn should_reject_deploy_with_unknown_dependency() {
+    let mut rng = crate::new_rng();
+    let known = new_deploy(&mut rng, Timestamp::now(), vec![]);
+    let unknown = DeployHash::random(&mut rng);
+
+    let result = accept_with_dependencies(vec![*known.id(), unknown], vec![Some(known), None]);
+    assert!(
+        matches!(result, Err(Error::UnknownDependency(hash)) if hash == unknown),
+        "unexpected result {:?}",
+        result
+    );
+}
+
+#[test]
+fn should_reject_deploy_with_expired_dependency() {
+    let mut rng = crate::new_rng();
+    let expired = new_deploy(&mut rng, Timestamp::from(0), vec![]);
+    let expired_hash = *expired.id();
+
+    let result = accept_with_dependencies(vec![expired_hash], vec![Some(expired)]);
+    assert!(
+        matches!(result, Err(Error::ExpiredDependency(hash)) if hash == expired_hash),
+        "unexpected result {:?}",
+        result
+    );
+}
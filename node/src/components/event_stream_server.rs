@@ -13,22 +13,33 @@
 //! a request of other components itself. The handled announcements are serialized to JSON and
 //! pushed to subscribers.
 //!
-//! This component uses a ring buffer for outbound events providing some robustness against
-//! unintended subscriber disconnects, if a disconnected subscriber re-subscribes before the buffer
-//! has advanced past their last received event.
+//! This component persists outbound events to a disk-backed log, retained by count and by age,
+//! providing robustness against unintended subscriber disconnects and node restarts, if a
+//! disconnected subscriber re-subscribes before the log has pruned their last received event.
+//!
+//! Subscribers may restrict the events they receive via query parameters on the endpoint, selecting
+//! event kinds and filtering `DeployProcessed` events by account or deploy hash, and
+//! `FinalitySignature` events by block hash.  Filtering happens before serialization.
+//!
+//! If enabled in the config, the same events are also served over a WebSocket endpoint, where
+//! clients manage any number of filtered subscriptions using JSON-RPC `subscribe` and `unsubscribe`
+//! requests on a single connection.  Other JSON-RPC methods are not served there.
 //!
 //! For details about the SSE model and a list of supported SSEs, see:
 //! <https://github.com/CasperLabs/ceps/blob/master/text/0009-client-api.md#rpcs>
 
 mod config;
 mod event;
+mod event_log;
 mod http_server;
 mod sse_server;
+mod ws_server;
 
-use std::{convert::Infallible, fmt::Debug};
+use std::{convert::Infallible, fmt::Debug, path::Path};
 
 use datasize::DataSize;
 use semver::Version;
+use thiserror::Error;
 use tokio::sync::mpsc::{self, UnboundedSender};
 
 use super::Component;
@@ -40,8 +51,29 @@ use crate::{
 
 pub use config::Config;
 pub(crate) use event::Event;
+use event_log::EventLog;
 pub use sse_server::SseData;
 
+/// Error returned when constructing the event stream server.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Error starting the HTTP server.
+    #[error("http server listening error: {0}")]
+    Listening(#[from] ListeningError),
+
+    /// Error opening or accessing the event log.
+    #[error("event log error: {0}")]
+    EventLog(#[from] lmdb::Error),
+
+    /// Error serializing or deserializing a logged event.
+    #[error("event log serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// A key or value in the event log is malformed.
+    #[error("corrupt event log entry: {0}")]
+    CorruptEventLog(String),
+}
+
 /// A helper trait whose bounds represent the requirements for a reactor event that `run_server` can
 /// work with.
 pub trait ReactorEventT: From<Event> + Send {}
@@ -57,13 +89,24 @@ pub(crate) struct EventStreamServer {
 }
 
 impl EventStreamServer {
-    pub(crate) fn new(config: Config, api_version: Version) -> Result<Self, ListeningError> {
+    /// Creates a new event stream server, keeping its event log in `storage_path`.
+    pub(crate) fn new(
+        config: Config,
+        storage_path: &Path,
+        api_version: Version,
+    ) -> Result<Self, Error> {
         let (sse_data_sender, sse_data_receiver) = mpsc::unbounded_channel();
+        let event_log = EventLog::new(
+            storage_path,
+            config.event_stream_buffer_length,
+            config.event_stream_buffer_max_age,
+        )?;
         let builder = utils::start_listening(&config.address)?;
         tokio::spawn(http_server::run(
             config,
             api_version,
             builder,
+            event_log,
             sse_data_receiver,
         ));
 
@@ -119,6 +162,15 @@ where
                 timestamp,
             }),
             Event::FinalitySignature(fs) => self.broadcast(SseData::FinalitySignature(fs)),
+            Event::DeploySuperseded {
+                deploy_hash,
+                superseded_by,
+                account,
+            } => self.broadcast(SseData::DeploySuperseded {
+                deploy_hash: Box::new(deploy_hash),
+                superseded_by: Box::new(superseded_by),
+                account,
+            }),
         }
     }
 }
@@ -1,17 +1,25 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use crate::types::TimeDiff;
+
 /// Default binding address for the SSE HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
 const DEFAULT_ADDRESS: &str = "0.0.0.0:0";
 
-/// Default number of SSEs to buffer.
-const DEFAULT_EVENT_STREAM_BUFFER_LENGTH: u32 = 100;
+/// Default number of SSEs to retain in the event log.
+const DEFAULT_EVENT_STREAM_BUFFER_LENGTH: u32 = 10_000;
+
+/// Default maximum age of SSEs retained in the event log.
+const DEFAULT_EVENT_STREAM_BUFFER_MAX_AGE: &str = "1day";
 
 /// Default broadcast channel size.
 const DEFAULT_BROADCAST_CHANNEL_SIZE: usize = 100;
 
+/// Default setting for whether the WebSocket endpoint is enabled.
+const DEFAULT_ENABLE_WEBSOCKET: bool = true;
+
 /// Default rate limit in qps.
 const DEFAULT_QPS_LIMIT: u64 = 100;
 
@@ -23,9 +31,12 @@ pub struct Config {
     /// Address to bind event stream SSE HTTP server to.
     pub address: String,
 
-    /// Number of SSEs to buffer.
+    /// Maximum number of SSEs to retain in the event log for replay to reconnecting clients.
     pub event_stream_buffer_length: u32,
 
+    /// Maximum age of SSEs retained in the event log.
+    pub event_stream_buffer_max_age: TimeDiff,
+
     /// The number of events to buffer in the tokio broadcast channel to help slower clients to try
     /// to avoid missing events.  See <https://docs.rs/tokio/0.2.22/tokio/sync/broadcast/index.html#lagging>
     /// for further details.
@@ -33,6 +44,9 @@ pub struct Config {
 
     /// Rate limit for queries per second.
     pub qps_limit: u64,
+
+    /// Whether to serve the event stream over the WebSocket endpoint too.
+    pub enable_websocket: bool,
 }
 
 impl Config {
@@ -41,8 +55,10 @@ impl Config {
         Config {
             address: DEFAULT_ADDRESS.to_string(),
             event_stream_buffer_length: DEFAULT_EVENT_STREAM_BUFFER_LENGTH,
+            event_stream_buffer_max_age: DEFAULT_EVENT_STREAM_BUFFER_MAX_AGE.parse().unwrap(),
             broadcast_channel_size: DEFAULT_BROADCAST_CHANNEL_SIZE,
             qps_limit: DEFAULT_QPS_LIMIT,
+            enable_websocket: DEFAULT_ENABLE_WEBSOCKET,
         }
     }
 }
@@ -25,6 +25,11 @@ pub enum Event {
         timestamp: Timestamp,
     },
     FinalitySignature(Box<FinalitySignature>),
+    DeploySuperseded {
+        deploy_hash: DeployHash,
+        superseded_by: DeployHash,
+        account: PublicKey,
+    },
 }
 
 impl Display for Event {
@@ -44,6 +49,15 @@ impl Display for Event {
                 public_key, timestamp, era_id,
             ),
             Event::FinalitySignature(fs) => write!(formatter, "finality signature {}", fs),
+            Event::DeploySuperseded {
+                deploy_hash,
+                superseded_by,
+                ..
+            } => write!(
+                formatter,
+                "deploy {} superseded by {}",
+                deploy_hash, superseded_by
+            ),
         }
     }
 }
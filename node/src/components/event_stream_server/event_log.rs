@@ -0,0 +1,387 @@
+//! A disk-backed log of the events sent on the event stream.
+//!
+//! Events are keyed by their ID in a dedicated LMDB database, so that clients can resume from any
+//! retained event.  Each value holds the time at which the event was logged followed by the
+//! JSON-encoded event data.  The ID to be assigned next is persisted alongside the events, so IDs
+//! remain stable across node restarts even if every event has since been pruned.
+//!
+//! The database is only read and written on blocking threads, so that disk I/O never stalls the
+//! server's event loop.
+
+use std::{convert::TryInto, path::Path, sync::Arc};
+
+use futures::executor;
+use lmdb::{
+    Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, RwTransaction, Transaction,
+    WriteFlags,
+};
+use tokio::{sync::mpsc, task};
+use tracing::{debug, error};
+
+use super::{
+    sse_server::{Id, ServerSentEvent},
+    Error, SseData,
+};
+use crate::types::{TimeDiff, Timestamp};
+
+/// Filename for the LMDB database created by the event log.
+const EVENT_LOG_DB_FILENAME: &str = "sse_event_log.lmdb";
+/// Maximum size of the event log database: 1 GiB.
+const EVENT_LOG_MAP_SIZE: usize = 1024 * 1024 * 1024;
+/// Length of the timestamp prefix of each logged value.
+const TIMESTAMP_LENGTH: usize = 8;
+/// Key in the metadata database under which the next event ID is stored.
+const NEXT_ID_KEY: &[u8] = b"next_id";
+
+/// The events sent on the event stream, retained by count and by age.
+pub(super) struct EventLog {
+    store: Store,
+    /// The number of events currently retained.
+    event_count: u32,
+    /// The ID to be assigned to the next event.
+    next_id: Id,
+}
+
+impl EventLog {
+    /// Opens the event log in the given directory, creating it if it does not exist.
+    pub(super) fn new(root: &Path, max_events: u32, max_age: TimeDiff) -> Result<Self, Error> {
+        let env = Environment::new()
+            .set_flags(
+                // We manage our own directory.
+                EnvironmentFlags::NO_SUB_DIR
+                // Disable thread local storage, strongly suggested for operation with tokio.
+                    | EnvironmentFlags::NO_TLS,
+            )
+            .set_max_dbs(2)
+            .set_map_size(EVENT_LOG_MAP_SIZE)
+            .open(&root.join(EVENT_LOG_DB_FILENAME))?;
+        let events_db = env.create_db(Some("events"), DatabaseFlags::empty())?;
+        let metadata_db = env.create_db(Some("metadata"), DatabaseFlags::empty())?;
+        let store = Store {
+            env: Arc::new(env),
+            events_db,
+            metadata_db,
+            max_events,
+            max_age,
+        };
+
+        let (event_count, next_id) = {
+            let txn = store.env.begin_ro_txn()?;
+            let next_id = match txn.get(metadata_db, &NEXT_ID_KEY) {
+                Ok(bytes) => decode_id(bytes)?,
+                Err(lmdb::Error::NotFound) => 0,
+                Err(error) => return Err(error.into()),
+            };
+            let mut cursor = txn.open_ro_cursor(events_db)?;
+            let event_count = cursor.iter_start().count() as u32;
+            (event_count, next_id)
+        };
+
+        let mut txn = store.env.begin_rw_txn()?;
+        let event_count = store.prune(&mut txn, event_count)?;
+        txn.commit()?;
+
+        debug!(event_count, next_id, "opened event log");
+        Ok(EventLog {
+            store,
+            event_count,
+            next_id,
+        })
+    }
+
+    /// Assigns consecutive IDs to the given data and appends the resulting events to the log in a
+    /// single transaction, pruning any events which are no longer retained.
+    ///
+    /// A failure to write to the log is logged, but the events are returned regardless so that
+    /// they can still be sent to connected clients.
+    pub(super) async fn append(&mut self, batch: Vec<SseData>) -> Vec<ServerSentEvent> {
+        let mut events = Vec::with_capacity(batch.len());
+        let mut entries = Vec::with_capacity(batch.len());
+        for data in batch {
+            let id = self.next_id;
+            self.next_id = id.wrapping_add(1);
+            match encode_value(&data) {
+                Ok(value) => entries.push((id, value)),
+                Err(error) => error!(%error, id, "failed to serialize event for event log"),
+            }
+            events.push(ServerSentEvent { id: Some(id), data });
+        }
+
+        let store = self.store.clone();
+        let event_count = self.event_count;
+        let next_id = self.next_id;
+        match task::spawn_blocking(move || store.write(event_count, next_id, &entries)).await {
+            Ok(Ok(event_count)) => self.event_count = event_count,
+            Ok(Err(error)) => error!(%error, "failed to append to event log"),
+            Err(error) => error!(%error, "failed to join event log writer"),
+        }
+        events
+    }
+
+    /// Sends all events retained at the time of calling with an ID of at least `start_from` to
+    /// `sender`, in order.
+    ///
+    /// The events are read from a cursor on a blocking thread, waiting for the client to keep up,
+    /// so at most the capacity of the channel is held in memory.  Events appended in the meantime
+    /// are not sent, as the client receives those via the broadcast channel.  Reading stops early
+    /// if the receiver is dropped.  The sender is dropped once all events have been sent, ending
+    /// the client's stream of logged events.
+    pub(super) fn send_events_from(
+        &self,
+        start_from: Id,
+        mut sender: mpsc::Sender<ServerSentEvent>,
+    ) {
+        let store = self.store.clone();
+        let end = self.next_id;
+        task::spawn_blocking(move || {
+            let result = store.for_each_event_from(start_from, |event| {
+                event.id < Some(end) && executor::block_on(sender.send(event)).is_ok()
+            });
+            if let Err(error) = result {
+                error!(%error, "failed to read event log");
+            }
+        });
+    }
+}
+
+/// The database holding the event log, shared with the blocking threads which read and write it.
+#[derive(Clone)]
+struct Store {
+    env: Arc<Environment>,
+    /// The logged events, keyed by big-endian event ID.
+    events_db: Database,
+    /// Metadata about the log, i.e. the next event ID.
+    metadata_db: Database,
+    /// The maximum number of events retained.
+    max_events: u32,
+    /// The maximum age of retained events.
+    max_age: TimeDiff,
+}
+
+impl Store {
+    /// Writes the given encoded events and the next event ID in a single transaction, given that
+    /// `event_count` events are currently retained.
+    ///
+    /// Returns the number of events remaining after pruning.
+    fn write(
+        &self,
+        mut event_count: u32,
+        next_id: Id,
+        entries: &[(Id, Vec<u8>)],
+    ) -> Result<u32, Error> {
+        let mut txn = self.env.begin_rw_txn()?;
+        for (id, value) in entries {
+            // Keys are ordered by ID, so once IDs wrap, the older events can no longer be told
+            // apart from newer ones and are discarded.
+            if *id == 0 && event_count > 0 {
+                txn.clear_db(self.events_db)?;
+                event_count = 0;
+            }
+            txn.put(
+                self.events_db,
+                &id.to_be_bytes(),
+                value,
+                WriteFlags::empty(),
+            )?;
+            event_count += 1;
+        }
+        txn.put(
+            self.metadata_db,
+            &NEXT_ID_KEY,
+            &next_id.to_be_bytes(),
+            WriteFlags::empty(),
+        )?;
+        let event_count = self.prune(&mut txn, event_count)?;
+        txn.commit()?;
+        Ok(event_count)
+    }
+
+    /// Calls `f` with each retained event with an ID of at least `start_from`, in order, until it
+    /// returns `false`.
+    fn for_each_event_from<F>(&self, start_from: Id, mut f: F) -> Result<(), Error>
+    where
+        F: FnMut(ServerSentEvent) -> bool,
+    {
+        let txn = self.env.begin_ro_txn()?;
+        let mut cursor = txn.open_ro_cursor(self.events_db)?;
+        for (key, value) in cursor.iter_from(start_from.to_be_bytes()) {
+            let event = ServerSentEvent {
+                id: Some(decode_id(key)?),
+                data: decode_data(value)?,
+            };
+            if !f(event) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes the oldest of the `event_count` logged events while more than `max_events` are
+    /// retained, or while they are at least `max_age` old.
+    ///
+    /// Returns the number of events remaining.
+    fn prune(&self, txn: &mut RwTransaction, event_count: u32) -> Result<u32, Error> {
+        let oldest_retained = Timestamp::now().saturating_sub(self.max_age);
+        let mut expired_keys = vec![];
+        {
+            let mut cursor = txn.open_ro_cursor(self.events_db)?;
+            let mut remaining = event_count;
+            for (key, value) in cursor.iter_start() {
+                if remaining <= self.max_events && decode_timestamp(value)? > oldest_retained {
+                    break;
+                }
+                expired_keys.push(key.to_vec());
+                remaining -= 1;
+            }
+        }
+        for key in &expired_keys {
+            txn.del(self.events_db, key, None)?;
+        }
+        Ok(event_count - expired_keys.len() as u32)
+    }
+}
+
+/// Encodes the value logged for the given data: the current time followed by the JSON-encoded
+/// data.
+fn encode_value(data: &SseData) -> Result<Vec<u8>, Error> {
+    let mut value = Timestamp::now().millis().to_be_bytes().to_vec();
+    serde_json::to_writer(&mut value, data)?;
+    Ok(value)
+}
+
+fn decode_id(key: &[u8]) -> Result<Id, Error> {
+    let bytes = key
+        .try_into()
+        .map_err(|_| Error::CorruptEventLog(format!("invalid event ID of {} bytes", key.len())))?;
+    Ok(Id::from_be_bytes(bytes))
+}
+
+fn decode_timestamp(value: &[u8]) -> Result<Timestamp, Error> {
+    let millis = value
+        .get(..TIMESTAMP_LENGTH)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| {
+            Error::CorruptEventLog(format!("event of {} bytes lacks a timestamp", value.len()))
+        })?;
+    Ok(Timestamp::from(u64::from_be_bytes(millis)))
+}
+
+fn decode_data(value: &[u8]) -> Result<SseData, Error> {
+    let json = value.get(TIMESTAMP_LENGTH..).ok_or_else(|| {
+        Error::CorruptEventLog(format!("event of {} bytes lacks a timestamp", value.len()))
+    })?;
+    Ok(serde_json::from_slice(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{components::consensus::EraId, crypto::AsymmetricKeyExt, testing::TestRng};
+    use casper_types::PublicKey;
+
+    fn fault(rng: &mut TestRng) -> SseData {
+        SseData::Fault {
+            era_id: EraId(1),
+            public_key: PublicKey::random(rng),
+            timestamp: Timestamp::now(),
+        }
+    }
+
+    fn ids(events: &[ServerSentEvent]) -> Vec<Id> {
+        events.iter().map(|event| event.id.unwrap()).collect()
+    }
+
+    fn events_from(event_log: &EventLog, start_from: Id) -> Vec<ServerSentEvent> {
+        let mut events = vec![];
+        event_log
+            .store
+            .for_each_event_from(start_from, |event| {
+                events.push(event);
+                true
+            })
+            .unwrap();
+        events
+    }
+
+    #[tokio::test]
+    async fn should_retain_events_across_restarts() {
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let max_age: TimeDiff = "1day".parse().unwrap();
+
+        let mut event_log = EventLog::new(tempdir.path(), 3, max_age).unwrap();
+        let mut appended = event_log
+            .append(vec![fault(&mut rng), fault(&mut rng)])
+            .await;
+        for _ in 0..3 {
+            appended.extend(event_log.append(vec![fault(&mut rng)]).await);
+        }
+        assert_eq!(ids(&appended), vec![0, 1, 2, 3, 4]);
+
+        // Only the last three events are retained.
+        assert_eq!(events_from(&event_log, 0), appended[2..].to_vec());
+        assert_eq!(ids(&events_from(&event_log, 3)), vec![3, 4]);
+        assert!(events_from(&event_log, 5).is_empty());
+        drop(event_log);
+
+        // IDs continue from where they left off after reopening.
+        let mut event_log = EventLog::new(tempdir.path(), 3, max_age).unwrap();
+        assert_eq!(events_from(&event_log, 0), appended[2..].to_vec());
+        let events = event_log.append(vec![fault(&mut rng)]).await;
+        assert_eq!(ids(&events), vec![5]);
+        assert_eq!(ids(&events_from(&event_log, 0)), vec![3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn should_prune_expired_events() {
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+
+        let mut event_log = EventLog::new(tempdir.path(), 100, "1day".parse().unwrap()).unwrap();
+        let batch = (0..3).map(|_| fault(&mut rng)).collect();
+        event_log.append(batch).await;
+        drop(event_log);
+
+        // With a zero maximum age, every logged event has expired on reopening, but IDs still
+        // continue from where they left off.
+        let mut event_log = EventLog::new(tempdir.path(), 100, TimeDiff::from_seconds(0)).unwrap();
+        assert!(events_from(&event_log, 0).is_empty());
+        drop(event_log);
+        event_log = EventLog::new(tempdir.path(), 100, "1day".parse().unwrap()).unwrap();
+        let events = event_log.append(vec![fault(&mut rng)]).await;
+        assert_eq!(ids(&events), vec![3]);
+    }
+
+    #[tokio::test]
+    async fn should_stream_events_to_subscriber() {
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+
+        let mut event_log = EventLog::new(tempdir.path(), 100, "1day".parse().unwrap()).unwrap();
+        let batch = (0..5).map(|_| fault(&mut rng)).collect();
+        let appended = event_log.append(batch).await;
+
+        // The channel holds fewer events than are replayed, so the reader must wait for the
+        // subscriber to keep up.
+        let (sender, mut receiver) = mpsc::channel(2);
+        event_log.send_events_from(1, sender);
+        let mut received = vec![];
+        while let Some(event) = receiver.recv().await {
+            received.push(event);
+        }
+        assert_eq!(received, appended[1..].to_vec());
+    }
+
+    #[test]
+    fn should_reject_corrupt_entries() {
+        assert!(matches!(decode_id(&[0; 3]), Err(Error::CorruptEventLog(_))));
+        assert!(matches!(
+            decode_timestamp(&[0; 7]),
+            Err(Error::CorruptEventLog(_))
+        ));
+        assert!(matches!(
+            decode_data(&[0; 7]),
+            Err(Error::CorruptEventLog(_))
+        ));
+    }
+}
@@ -12,26 +12,30 @@ use tokio::{
 };
 use tower::builder::ServiceBuilder;
 use tracing::{info, trace};
-use wheelbuf::WheelBuf;
 
 use super::{
+    event_log::EventLog,
     sse_server::{self, BroadcastChannelMessage, ServerSentEvent},
     Config, SseData,
 };
 
 /// Run the HTTP server.
 ///
-/// `data_receiver` will provide the server with local events which should then be sent to all
-/// subscribed clients.
+/// `data_receiver` will provide the server with local events which should then be logged to
+/// `event_log` and sent to all subscribed clients.
 pub(super) async fn run(
     config: Config,
     api_version: Version,
     builder: Builder<AddrIncoming>,
+    mut event_log: EventLog,
     mut data_receiver: mpsc::UnboundedReceiver<SseData>,
 ) {
     // Event stream channels and filter.
     let (broadcaster, mut new_subscriber_info_receiver, sse_filter) =
-        sse_server::create_channels_and_filter(config.broadcast_channel_size);
+        sse_server::create_channels_and_filter(
+            config.broadcast_channel_size,
+            config.enable_websocket,
+        );
 
     let service = warp_json_rpc::service(sse_filter);
 
@@ -54,35 +58,24 @@ pub(super) async fn run(
 
     let server_joiner = tokio::spawn(server_with_shutdown);
 
-    // Initialize the index and buffer for the SSEs.
-    let mut event_index = 0_u32;
-    let mut buffer = WheelBuf::new(vec![
-        ServerSentEvent::initial_event(api_version.clone());
-        config.event_stream_buffer_length as usize
-    ]);
-
     // Start handling received messages from the two channels; info on new client subscribers and
     // incoming events announced by node components.
     let event_stream_fut = async {
         loop {
             select! {
                 maybe_new_subscriber = new_subscriber_info_receiver.recv() => {
-                    if let Some(subscriber) = maybe_new_subscriber {
-                        // First send the client the `ApiVersion` event.  We don't care if this
-                        // errors - the client may have disconnected already.
+                    if let Some(mut subscriber) = maybe_new_subscriber {
+                        // First send the client the `ApiVersion` event.  The channel is empty, so
+                        // this can only fail if the client has disconnected already, in which case
+                        // we don't care.
                         let _ = subscriber
                             .initial_events_sender
-                            .send(ServerSentEvent::initial_event(api_version.clone()));
-                        // If the client supplied a "start_from" index, provide the buffered events.
-                        // If they requested more than is buffered, just provide the whole buffer.
+                            .try_send(ServerSentEvent::initial_event(api_version.clone()));
+                        // If the client supplied a "start_from" index, provide the logged events.
+                        // If they requested more than is retained, just provide the whole log.
                         if let Some(start_index) = subscriber.start_from {
-                            for event in buffer
-                                .iter()
-                                .skip_while(|event| event.id.unwrap() < start_index)
-                            {
-                                // As per sending `SSE_INITIAL_EVENT`, we don't care if this errors.
-                                let _ = subscriber.initial_events_sender.send(event.clone());
-                            }
+                            event_log
+                                .send_events_from(start_index, subscriber.initial_events_sender);
                         }
                     }
                 }
@@ -90,15 +83,19 @@ pub(super) async fn run(
                 maybe_data = data_receiver.recv() => {
                     match maybe_data {
                         Some(data) => {
-                            // Buffer the data and broadcast it to subscribed clients.
-                            trace!("Event stream server received {:?}", data);
-                            let event = ServerSentEvent { id: Some(event_index), data };
-                            buffer.push(event.clone());
-                            let message = BroadcastChannelMessage::ServerSentEvent(event);
-                            // This can validly fail if there are no connected clients, so don't log
-                            // the error.
-                            let _ = broadcaster.send(message);
-                            event_index = event_index.wrapping_add(1);
+                            // Log the data along with any further data already queued, then
+                            // broadcast it to subscribed clients.
+                            let mut batch = vec![data];
+                            while let Ok(data) = data_receiver.try_recv() {
+                                batch.push(data);
+                            }
+                            trace!("Event stream server received {:?}", batch);
+                            for event in event_log.append(batch).await {
+                                let message = BroadcastChannelMessage::ServerSentEvent(event);
+                                // This can validly fail if there are no connected clients, so
+                                // don't log the error.
+                                let _ = broadcaster.send(message);
+                            }
                         }
                         None => {
                             // The data sender has been dropped - exit the loop.
@@ -1,7 +1,9 @@
 //! Types and functions used by the http server to manage the event-stream.
 
+use std::fmt::Display;
+
 use datasize::DataSize;
-use futures::{Stream, StreamExt};
+use futures::{future, Stream, StreamExt};
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use tokio::sync::{
@@ -11,22 +13,39 @@ use tokio::sync::{
 use tracing::{error, info, trace};
 use warp::{
     filters::BoxedFilter,
+    http::StatusCode,
+    reply,
     sse::{self, ServerSentEvent as WarpServerSentEvent},
     Filter, Reply,
 };
 
-use casper_types::{ExecutionResult, PublicKey};
+use casper_types::{AsymmetricType, ExecutionResult, PublicKey};
 
+use super::ws_server;
 use crate::{
     components::consensus::EraId,
+    crypto::hash::Digest,
     types::{Block, BlockHash, DeployHash, FinalitySignature, TimeDiff, Timestamp},
 };
 
 /// The URL path.
 pub const SSE_API_PATH: &str = "events";
 
+/// The kinds of event which can be selected using the `events` query parameter.
+const EVENT_KINDS: [&str; 5] = [
+    "BlockAdded",
+    "DeployProcessed",
+    "Fault",
+    "FinalitySignature",
+    "DeploySuperseded",
+];
+
+/// The number of initial events buffered for a new client before the event log waits for the
+/// client's handler to consume them.
+const INITIAL_EVENTS_CHANNEL_SIZE: usize = 64;
+
 /// The "id" field of the events sent on the event stream to clients.
-type Id = u32;
+pub(super) type Id = u32;
 
 /// The "data" field of the events sent on the event stream to clients.
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, DataSize)]
@@ -59,6 +78,27 @@ pub enum SseData {
     },
     /// New finality signature received.
     FinalitySignature(Box<FinalitySignature>),
+    /// The given pending deploy has been replaced by a deploy from the same account with the same
+    /// timestamp and a higher gas price, and will not be executed.
+    DeploySuperseded {
+        deploy_hash: Box<DeployHash>,
+        superseded_by: Box<DeployHash>,
+        account: PublicKey,
+    },
+}
+
+impl SseData {
+    /// The name of this kind of event, as used in the `events` query parameter.
+    fn kind(&self) -> &'static str {
+        match self {
+            SseData::ApiVersion(_) => "ApiVersion",
+            SseData::BlockAdded { .. } => "BlockAdded",
+            SseData::DeployProcessed { .. } => "DeployProcessed",
+            SseData::Fault { .. } => "Fault",
+            SseData::FinalitySignature(_) => "FinalitySignature",
+            SseData::DeploySuperseded { .. } => "DeploySuperseded",
+        }
+    }
 }
 
 /// The components of a single SSE.
@@ -97,20 +137,184 @@ pub(super) struct NewSubscriberInfo {
     /// The event ID from which the stream should start for this client.
     pub(super) start_from: Option<Id>,
     /// A channel to send the initial events to the client's handler.  This will always send the
-    /// ApiVersion as the first event, and then any buffered events as indicated by `start_from`.
-    pub(super) initial_events_sender: mpsc::UnboundedSender<ServerSentEvent>,
+    /// ApiVersion as the first event, and then any logged events as indicated by `start_from`.
+    pub(super) initial_events_sender: mpsc::Sender<ServerSentEvent>,
 }
 
 /// The endpoint's query string, e.g. `http://localhost:22777/events?start_from=999`
-#[derive(Deserialize, Debug)]
-struct Query {
-    start_from: Option<Id>,
+///
+/// Apart from `start_from`, each parameter takes a comma-separated list of values, e.g.
+/// `http://localhost:22777/events?events=DeployProcessed&account=01ab...,0203...`
+///
+/// The same fields form the parameters of a `subscribe` request on the WebSocket endpoint.
+#[derive(Deserialize, Debug, Default)]
+pub(super) struct Query {
+    pub(super) start_from: Option<Id>,
+    /// The kinds of event to send.
+    events: Option<String>,
+    /// The hex-encoded public keys of the accounts whose `DeployProcessed` events to send.
+    account: Option<String>,
+    /// The hex-encoded hashes of the deploys whose `DeployProcessed` events to send.
+    deploy_hash: Option<String>,
+    /// The hex-encoded hashes of the blocks whose `FinalitySignature` events to send.
+    block_hash: Option<String>,
+}
+
+/// The filter applied to the events sent to a single client, built from the query string.
+///
+/// An event is only sent if it matches every filter which was given.  The initial `ApiVersion`
+/// event is always sent.
+#[derive(Clone, Default, Debug)]
+pub(super) struct EventFilter {
+    /// The kinds of event to send, or every kind if empty.
+    event_kinds: Vec<&'static str>,
+    /// The accounts whose `DeployProcessed` events to send, or every account's if empty.
+    accounts: Vec<PublicKey>,
+    /// The deploys whose `DeployProcessed` events to send, or every deploy's if empty.
+    deploy_hashes: Vec<DeployHash>,
+    /// The blocks whose `FinalitySignature` events to send, or every block's if empty.
+    block_hashes: Vec<BlockHash>,
+}
+
+impl EventFilter {
+    /// Parses the filter from the query string, returning a description of any invalid value.
+    pub(super) fn from_query(query: &Query) -> Result<Self, String> {
+        Ok(EventFilter {
+            event_kinds: parse_list(&query.events, "event kind", |kind| {
+                EVENT_KINDS
+                    .iter()
+                    .find(|known_kind| **known_kind == kind)
+                    .copied()
+                    .ok_or("unknown event kind")
+            })?,
+            accounts: parse_list(&query.account, "account", |hex| {
+                PublicKey::from_hex(hex.as_bytes())
+            })?,
+            deploy_hashes: parse_list(&query.deploy_hash, "deploy hash", |hex| {
+                Digest::from_hex(hex).map(DeployHash::new)
+            })?,
+            block_hashes: parse_list(&query.block_hash, "block hash", |hex| {
+                Digest::from_hex(hex).map(BlockHash::new)
+            })?,
+        })
+    }
+
+    /// Returns `true` if the given event should be sent to the client.
+    fn matches(&self, data: &SseData) -> bool {
+        if let SseData::ApiVersion(_) = data {
+            return true;
+        }
+        if !self.event_kinds.is_empty() && !self.event_kinds.contains(&data.kind()) {
+            return false;
+        }
+        match data {
+            SseData::DeployProcessed {
+                deploy_hash,
+                account,
+                ..
+            } => {
+                (self.accounts.is_empty() || self.accounts.contains(account))
+                    && (self.deploy_hashes.is_empty() || self.deploy_hashes.contains(deploy_hash))
+            }
+            SseData::FinalitySignature(finality_signature) => {
+                self.block_hashes.is_empty()
+                    || self.block_hashes.contains(&finality_signature.block_hash)
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Parses each item of an optional comma-separated list.
+fn parse_list<T, E, F>(maybe_list: &Option<String>, name: &str, parse: F) -> Result<Vec<T>, String>
+where
+    E: Display,
+    F: Fn(&str) -> Result<T, E>,
+{
+    maybe_list
+        .iter()
+        .flat_map(|list| list.split(','))
+        .map(|item| parse(item).map_err(|error| format!("invalid {} '{}': {}", name, item, error)))
+        .collect()
+}
+
+/// The channels used to subscribe a new client to the event stream, shared by the SSE and the
+/// WebSocket endpoints.
+#[derive(Clone)]
+pub(super) struct Subscriptions {
+    /// The sender of new events, from which each client's stream of ongoing events is created.
+    broadcaster: broadcast::Sender<BroadcastChannelMessage>,
+    /// The sender of the information required by the server to handle a new client subscription.
+    new_subscriber_info_sender: mpsc::UnboundedSender<NewSubscriberInfo>,
+}
+
+impl Subscriptions {
+    pub(super) fn new(
+        broadcaster: broadcast::Sender<BroadcastChannelMessage>,
+        new_subscriber_info_sender: mpsc::UnboundedSender<NewSubscriberInfo>,
+    ) -> Self {
+        Subscriptions {
+            broadcaster,
+            new_subscriber_info_sender,
+        }
+    }
+
+    /// Subscribes a new client, returning its stream of messages.
+    ///
+    /// The stream starts with the `ApiVersion` event, followed by any logged events from
+    /// `start_from` onwards, followed by all events created from the moment of subscribing.  Events
+    /// not matching `event_filter` are dropped.
+    pub(super) fn subscribe(
+        &self,
+        start_from: Option<Id>,
+        event_filter: EventFilter,
+    ) -> impl Stream<Item = Result<BroadcastChannelMessage, RecvError>> + 'static {
+        // Create a channel for the client's handler to receive the stream of initial events.
+        let (initial_events_sender, initial_events_receiver) =
+            mpsc::channel(INITIAL_EVENTS_CHANNEL_SIZE);
+
+        // Supply the server with the sender part of the channel along with the client's requested
+        // starting point.
+        let new_subscriber_info = NewSubscriberInfo {
+            start_from,
+            initial_events_sender,
+        };
+        if self
+            .new_subscriber_info_sender
+            .send(new_subscriber_info)
+            .is_err()
+        {
+            error!("failed to send new subscriber info");
+        }
+
+        // Create a channel for the client's handler to receive the stream of ongoing events.
+        let ongoing_events_receiver = self.broadcaster.subscribe();
+
+        // The initial events receiver is exhausted first, and contains the initial `ApiVersion`
+        // message followed by any historical events the client requested.  The ongoing events
+        // receiver is then consumed, and will remain in use until either the client disconnects
+        // or the server shuts down (indicated by sending a `Shutdown` variant via the channel).
+        initial_events_receiver
+            .map(|event| Ok(BroadcastChannelMessage::ServerSentEvent(event)))
+            .chain(ongoing_events_receiver)
+            .filter(move |result| {
+                future::ready(match result {
+                    Ok(BroadcastChannelMessage::ServerSentEvent(event)) => {
+                        event_filter.matches(&event.data)
+                    }
+                    _ => true,
+                })
+            })
+    }
 }
 
 /// Creates the message-passing channels required to run the event-stream server and the warp filter
 /// for the event-stream server.
+///
+/// If `enable_websocket` is `true`, the filter also serves the WebSocket endpoint.
 pub(super) fn create_channels_and_filter(
     broadcast_channel_size: usize,
+    enable_websocket: bool,
 ) -> (
     broadcast::Sender<BroadcastChannelMessage>,
     mpsc::UnboundedReceiver<NewSubscriberInfo>,
@@ -118,87 +322,148 @@ pub(super) fn create_channels_and_filter(
 ) {
     // Create a channel to broadcast new events to all subscribed clients' streams.
     let (broadcaster, _) = broadcast::channel(broadcast_channel_size);
-    let cloned_broadcaster = broadcaster.clone();
 
     // Create a channel for `NewSubscriberInfo`s to pass the information required to handle a new
     // client subscription.
     let (new_subscriber_info_sender, new_subscriber_info_receiver) = mpsc::unbounded_channel();
 
-    let filter = warp::get()
+    let subscriptions = Subscriptions::new(broadcaster.clone(), new_subscriber_info_sender);
+    let ws_filter = ws_server::create_filter(subscriptions.clone(), enable_websocket);
+
+    let sse_filter = warp::get()
         .and(warp::path(SSE_API_PATH))
         .and(warp::query().map(move |query: Query| {
-            // Create a channel for the client's handler to receive the stream of initial events.
-            let (initial_events_sender, initial_events_receiver) = mpsc::unbounded_channel();
-
-            // Supply the server with the sender part of the channel along with the client's
-            // requested starting point.
-            let new_subscriber_info = NewSubscriberInfo {
-                start_from: query.start_from,
-                initial_events_sender,
+            let event_filter = match EventFilter::from_query(&query) {
+                Ok(event_filter) => event_filter,
+                Err(error) => {
+                    return reply::with_status(error, StatusCode::BAD_REQUEST).into_response()
+                }
             };
-            if new_subscriber_info_sender
-                .send(new_subscriber_info)
-                .is_err()
-            {
-                error!("failed to send new subscriber info");
-            }
-
-            // Create a channel for the client's handler to receive the stream of ongoing events.
-            let ongoing_events_receiver = cloned_broadcaster.subscribe();
+            let events = subscriptions.subscribe(query.start_from, event_filter);
+            sse::reply(sse::keep_alive().stream(stream_to_client(events))).into_response()
+        }));
 
-            sse::reply(sse::keep_alive().stream(stream_to_client(
-                initial_events_receiver,
-                ongoing_events_receiver,
-            )))
-        }))
-        .boxed();
+    let filter = ws_filter.or(sse_filter).boxed();
 
     (broadcaster, new_subscriber_info_receiver, filter)
 }
 
-/// This takes the two channel receivers and turns them into a stream of SSEs to the subscribed
+/// This takes a client's stream of messages and turns it into a stream of SSEs to the subscribed
 /// client.
-///
-/// The initial events receiver (an mpsc receiver) is exhausted first, and contains an initial
-/// `ApiVersion` message, followed by any historical events the client requested using the query
-/// string.
-///
-/// The ongoing events channel (a broadcast receiver) is then consumed, and will remain in use until
-/// either the client disconnects, or the server shuts down (indicated by sending a `Shutdown`
-/// variant via the channel).  This channel will receive all SSEs created from the moment the client
-/// subscribed to the server's event stream.
 fn stream_to_client(
-    initial_events: mpsc::UnboundedReceiver<ServerSentEvent>,
-    ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
+    events: impl Stream<Item = Result<BroadcastChannelMessage, RecvError>> + 'static,
 ) -> impl Stream<Item = Result<impl WarpServerSentEvent, RecvError>> + 'static {
-    initial_events
-        .map(|event| Ok(BroadcastChannelMessage::ServerSentEvent(event)))
-        .chain(ongoing_events)
-        .map(|result| {
-            trace!(?result);
-            match result {
-                Ok(BroadcastChannelMessage::ServerSentEvent(event)) => {
-                    match (event.id, &event.data) {
-                        (None, &SseData::ApiVersion { .. }) => Ok(sse::json(event.data).boxed()),
-                        (Some(id), &SseData::BlockAdded { .. })
-                        | (Some(id), &SseData::DeployProcessed { .. })
-                        | (Some(id), &SseData::FinalitySignature(_))
-                        | (Some(id), &SseData::Fault { .. }) => {
-                            Ok((sse::id(id), sse::json(event.data)).boxed())
-                        }
-                        _ => unreachable!("only ApiVersion may have no event ID"),
-                    }
-                }
-                Ok(BroadcastChannelMessage::Shutdown) | Err(RecvError::Closed) => {
-                    Err(RecvError::Closed)
-                }
-                Err(RecvError::Lagged(amount)) => {
-                    info!(
-                        "client lagged by {} events - dropping event stream connection to client",
-                        amount
-                    );
-                    Err(RecvError::Lagged(amount))
+    events.map(|result| {
+        trace!(?result);
+        match result {
+            Ok(BroadcastChannelMessage::ServerSentEvent(event)) => match (event.id, &event.data) {
+                (None, &SseData::ApiVersion { .. }) => Ok(sse::json(event.data).boxed()),
+                (Some(id), &SseData::BlockAdded { .. })
+                | (Some(id), &SseData::DeployProcessed { .. })
+                | (Some(id), &SseData::FinalitySignature(_))
+                | (Some(id), &SseData::Fault { .. })
+                | (Some(id), &SseData::DeploySuperseded { .. }) => {
+                    Ok((sse::id(id), sse::json(event.data)).boxed())
                 }
+                _ => unreachable!("only ApiVersion may have no event ID"),
+            },
+            Ok(BroadcastChannelMessage::Shutdown) | Err(RecvError::Closed) => {
+                Err(RecvError::Closed)
             }
-        })
+            Err(RecvError::Lagged(amount)) => {
+                info!(
+                    "client lagged by {} events - dropping event stream connection to client",
+                    amount
+                );
+                Err(RecvError::Lagged(amount))
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::{crypto::AsymmetricKeyExt, testing::TestRng};
+
+    fn deploy_processed(rng: &mut TestRng, deploy_hash: DeployHash, account: PublicKey) -> SseData {
+        SseData::DeployProcessed {
+            deploy_hash: Box::new(deploy_hash),
+            account,
+            timestamp: Timestamp::now(),
+            ttl: TimeDiff::from_seconds(60),
+            dependencies: vec![],
+            block_hash: Box::new(BlockHash::random(rng)),
+            execution_result: Box::new(rng.gen()),
+        }
+    }
+
+    fn query(events: Option<&str>, account: Option<&str>, deploy_hash: Option<&str>) -> Query {
+        Query {
+            events: events.map(str::to_string),
+            account: account.map(str::to_string),
+            deploy_hash: deploy_hash.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_filter_by_event_kind() {
+        let mut rng = crate::new_rng();
+        let block = Block::random(&mut rng);
+        let block_added = SseData::BlockAdded {
+            block_hash: *block.hash(),
+            block: Box::new(block),
+        };
+        let fault = SseData::Fault {
+            era_id: EraId(1),
+            public_key: PublicKey::random(&mut rng),
+            timestamp: Timestamp::now(),
+        };
+        let api_version = SseData::ApiVersion(Version::new(1, 0, 0));
+
+        let filter = EventFilter::from_query(&Query::default()).unwrap();
+        assert!(filter.matches(&block_added));
+        assert!(filter.matches(&fault));
+
+        let filter =
+            EventFilter::from_query(&query(Some("Fault,DeployProcessed"), None, None)).unwrap();
+        assert!(!filter.matches(&block_added));
+        assert!(filter.matches(&fault));
+        assert!(filter.matches(&api_version));
+
+        assert!(EventFilter::from_query(&query(Some("ApiVersion"), None, None)).is_err());
+        assert!(EventFilter::from_query(&query(Some("Fault,"), None, None)).is_err());
+    }
+
+    #[test]
+    fn should_filter_deploy_processed_by_account_and_deploy_hash() {
+        let mut rng = crate::new_rng();
+        let account = PublicKey::random(&mut rng);
+        let deploy_hash = DeployHash::random(&mut rng);
+        let matching = deploy_processed(&mut rng, deploy_hash, account);
+        let other_account = deploy_processed(&mut rng, deploy_hash, PublicKey::random(&mut rng));
+        let other_deploy = deploy_processed(&mut rng, DeployHash::random(&mut rng), account);
+
+        let filter = EventFilter::from_query(&query(None, Some(&account.to_hex()), None)).unwrap();
+        assert!(filter.matches(&matching));
+        assert!(!filter.matches(&other_account));
+        assert!(filter.matches(&other_deploy));
+
+        let deploy_hash_hex = hex::encode(deploy_hash.inner());
+        let filter = EventFilter::from_query(&query(
+            None,
+            Some(&account.to_hex()),
+            Some(&deploy_hash_hex),
+        ))
+        .unwrap();
+        assert!(filter.matches(&matching));
+        assert!(!filter.matches(&other_account));
+        assert!(!filter.matches(&other_deploy));
+
+        assert!(EventFilter::from_query(&query(None, Some("01"), None)).is_err());
+        assert!(EventFilter::from_query(&query(None, None, Some("not hex"))).is_err());
+    }
 }
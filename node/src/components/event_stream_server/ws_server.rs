@@ -0,0 +1,452 @@
+//! The WebSocket endpoint of the event stream server.
+//!
+//! Clients connect to the endpoint and manage subscriptions to the event stream using JSON-RPC 2.0
+//! requests over the connection:
+//!
+//! * `subscribe` takes the same parameters as the query string of the SSE endpoint, i.e.
+//!   `start_from` and the event filters, all optional.  It returns a subscription ID which is
+//!   unique within the connection.
+//! * `unsubscribe` takes the `subscription` ID to cancel and returns whether it was active.
+//!
+//! Events are then delivered as `subscription` notifications carrying the subscription ID, the
+//! event ID (absent only for the initial `ApiVersion` event) and the event data, exactly as sent on
+//! the SSE endpoint.
+//!
+//! No other methods are served on the endpoint: the node's JSON-RPC API is only available on the
+//! RPC server's HTTP endpoint.
+//!
+//! As on the SSE endpoint, a client which falls too far behind the event stream is disconnected.
+
+use std::collections::HashMap;
+
+use futures::{channel::oneshot, SinkExt, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::{
+    broadcast::RecvError,
+    mpsc::{self, error::TrySendError},
+};
+use tracing::{debug, info, trace};
+use warp::{
+    filters::BoxedFilter,
+    ws::{Message, WebSocket, Ws},
+    Filter, Reply,
+};
+
+use super::sse_server::{
+    BroadcastChannelMessage, EventFilter, Id, Query, ServerSentEvent, Subscriptions,
+};
+
+/// The URL path.
+pub const WS_API_PATH: &str = "ws";
+
+/// The maximum number of active subscriptions on a single connection.
+const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 16;
+
+/// The maximum number of responses and notifications queued for writing to a single connection.
+const MAX_QUEUED_MESSAGES_PER_CONNECTION: usize = 100;
+
+/// The JSON-RPC version used on the endpoint.
+const JSON_RPC_VERSION: &str = "2.0";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// The ID of a subscription, unique within a connection.
+type SubscriptionId = u64;
+
+/// A JSON-RPC request received from a client.
+#[derive(Deserialize, Debug)]
+struct Request {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// The parameters of an `unsubscribe` request.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct UnsubscribeParams {
+    subscription: SubscriptionId,
+}
+
+/// The parameters of a `subscription` notification.
+#[derive(Serialize, Debug)]
+struct NotificationParams<'a> {
+    subscription: SubscriptionId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Id>,
+    event: &'a super::SseData,
+}
+
+/// The state of a single client connection.
+struct Connection {
+    subscriptions: Subscriptions,
+    /// Sender of the messages to be written to the client.
+    outbound_sender: mpsc::Sender<Message>,
+    /// Sender signalling that a subscription fell behind, to close the connection.
+    lag_sender: mpsc::Sender<()>,
+    /// The cancellation handles of the active subscriptions.
+    active_subscriptions: HashMap<SubscriptionId, oneshot::Sender<()>>,
+    next_subscription_id: SubscriptionId,
+}
+
+impl Connection {
+    /// Handles a single text message from the client, returning the response to send.
+    fn handle_message(&mut self, text: &str) -> Value {
+        let request: Request = match serde_json::from_str(text) {
+            Ok(request) => request,
+            Err(error) => return error_response(Value::Null, PARSE_ERROR, error.to_string()),
+        };
+        if request.jsonrpc != JSON_RPC_VERSION {
+            return error_response(
+                request.id,
+                INVALID_REQUEST,
+                format!("jsonrpc must be {}", JSON_RPC_VERSION),
+            );
+        }
+
+        match request.method.as_str() {
+            "subscribe" => {
+                let query: Query = match request.params {
+                    Value::Null => Query::default(),
+                    params => match serde_json::from_value(params) {
+                        Ok(query) => query,
+                        Err(error) => {
+                            return error_response(request.id, INVALID_PARAMS, error.to_string())
+                        }
+                    },
+                };
+                match self.subscribe(query) {
+                    Ok(subscription_id) => result_response(request.id, json!(subscription_id)),
+                    Err(error) => error_response(request.id, INVALID_PARAMS, error),
+                }
+            }
+            "unsubscribe" => match serde_json::from_value::<UnsubscribeParams>(request.params) {
+                Ok(params) => {
+                    let was_active = self
+                        .active_subscriptions
+                        .remove(&params.subscription)
+                        .is_some();
+                    result_response(request.id, json!(was_active))
+                }
+                Err(error) => error_response(request.id, INVALID_PARAMS, error.to_string()),
+            },
+            method => error_response(
+                request.id,
+                METHOD_NOT_FOUND,
+                format!(
+                    "unknown method {}: only subscribe and unsubscribe are served on this \
+                    endpoint",
+                    method
+                ),
+            ),
+        }
+    }
+
+    /// Starts a new subscription, spawning a task which forwards its events to the client until
+    /// it is cancelled.
+    fn subscribe(&mut self, query: Query) -> Result<SubscriptionId, String> {
+        let event_filter = EventFilter::from_query(&query)?;
+        // Drop the handles of subscriptions which have ended of their own accord.
+        self.active_subscriptions
+            .retain(|_, cancel_sender| !cancel_sender.is_canceled());
+        if self.active_subscriptions.len() >= MAX_SUBSCRIPTIONS_PER_CONNECTION {
+            return Err(format!(
+                "at most {} subscriptions may be active on a connection",
+                MAX_SUBSCRIPTIONS_PER_CONNECTION
+            ));
+        }
+
+        let subscription_id = self.next_subscription_id;
+        self.next_subscription_id += 1;
+
+        let (cancel_sender, cancel_receiver) = oneshot::channel();
+        let events = self
+            .subscriptions
+            .subscribe(query.start_from, event_filter)
+            .take_until(cancel_receiver);
+        tokio::spawn(forward_events(
+            subscription_id,
+            events,
+            self.outbound_sender.clone(),
+            self.lag_sender.clone(),
+        ));
+        self.active_subscriptions
+            .insert(subscription_id, cancel_sender);
+        Ok(subscription_id)
+    }
+}
+
+/// Sends the events of a subscription to the client as notifications.
+///
+/// Returns once the subscription is cancelled, the client disconnects, the client lags too far
+/// behind the event stream or the server shuts down.  If the client lags, either because the
+/// subscription missed events or because the client's outbound queue is full, the connection is
+/// closed via `lag_sender`.
+async fn forward_events(
+    subscription_id: SubscriptionId,
+    events: impl Stream<Item = Result<BroadcastChannelMessage, RecvError>>,
+    mut outbound_sender: mpsc::Sender<Message>,
+    mut lag_sender: mpsc::Sender<()>,
+) {
+    futures::pin_mut!(events);
+    while let Some(result) = events.next().await {
+        let ServerSentEvent { id, data } = match result {
+            Ok(BroadcastChannelMessage::ServerSentEvent(event)) => event,
+            Ok(BroadcastChannelMessage::Shutdown) | Err(RecvError::Closed) => break,
+            Err(RecvError::Lagged(amount)) => {
+                info!(
+                    subscription_id,
+                    "client lagged by {} events - dropping websocket connection to client", amount
+                );
+                let _ = lag_sender.try_send(());
+                break;
+            }
+        };
+        let notification = json!({
+            "jsonrpc": JSON_RPC_VERSION,
+            "method": "subscription",
+            "params": NotificationParams {
+                subscription: subscription_id,
+                id,
+                event: &data,
+            },
+        });
+        match outbound_sender.try_send(Message::text(notification.to_string())) {
+            Ok(()) => (),
+            Err(TrySendError::Full(_)) => {
+                info!(
+                    subscription_id,
+                    "client not reading fast enough - dropping websocket connection to client"
+                );
+                let _ = lag_sender.try_send(());
+                break;
+            }
+            Err(TrySendError::Closed(_)) => break,
+        }
+    }
+    trace!(subscription_id, "websocket subscription ended");
+}
+
+/// Handles a client connection until it is closed, at which point all of its subscriptions end.
+async fn handle_connection(websocket: WebSocket, subscriptions: Subscriptions) {
+    let (mut ws_sender, mut ws_receiver) = websocket.split();
+    let (outbound_sender, mut outbound_receiver) =
+        mpsc::channel(MAX_QUEUED_MESSAGES_PER_CONNECTION);
+    let (lag_sender, mut lag_receiver) = mpsc::channel(1);
+
+    // Write the responses and notifications to the client from a single task, until the client
+    // disconnects or lags behind.
+    tokio::spawn(async move {
+        loop {
+            let message = tokio::select! {
+                maybe_message = outbound_receiver.recv() => match maybe_message {
+                    Some(message) => message,
+                    None => break,
+                },
+                Some(()) = lag_receiver.recv() => break,
+            };
+            if let Err(error) = ws_sender.send(message).await {
+                debug!(%error, "failed to write to websocket");
+                break;
+            }
+        }
+        let _ = ws_sender.close().await;
+    });
+
+    let mut connection = Connection {
+        subscriptions,
+        outbound_sender,
+        lag_sender,
+        active_subscriptions: HashMap::new(),
+        next_subscription_id: 0,
+    };
+    while let Some(result) = ws_receiver.next().await {
+        let message = match result {
+            Ok(message) => message,
+            Err(error) => {
+                debug!(%error, "failed to read from websocket");
+                break;
+            }
+        };
+        if message.is_close() {
+            break;
+        }
+        // Pings are answered by the underlying library, and binary messages are not supported.
+        let text = match message.to_str() {
+            Ok(text) => text,
+            Err(()) => continue,
+        };
+        let response = connection.handle_message(text);
+        if connection
+            .outbound_sender
+            .send(Message::text(response.to_string()))
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+    // Dropping the connection cancels its subscriptions and ends the writer task.
+    trace!("websocket connection closed");
+}
+
+fn result_response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": JSON_RPC_VERSION, "id": id, "result": result })
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": JSON_RPC_VERSION,
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Creates the warp filter for the WebSocket endpoint, which rejects all requests if `enabled` is
+/// `false`.
+pub(super) fn create_filter(
+    subscriptions: Subscriptions,
+    enabled: bool,
+) -> BoxedFilter<(impl Reply,)> {
+    warp::path(WS_API_PATH)
+        .and(warp::path::end())
+        .and(warp::any().and_then(move || async move {
+            if enabled {
+                Ok(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        }))
+        .untuple_one()
+        .and(warp::ws())
+        .map(move |ws: Ws| {
+            let subscriptions = subscriptions.clone();
+            ws.on_upgrade(move |websocket| handle_connection(websocket, subscriptions))
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+
+    use super::*;
+
+    fn connection() -> (Connection, mpsc::Receiver<Message>) {
+        let (broadcaster, _) = tokio::sync::broadcast::channel(10);
+        let (new_subscriber_info_sender, _) = mpsc::unbounded_channel();
+        let (outbound_sender, outbound_receiver) =
+            mpsc::channel(MAX_QUEUED_MESSAGES_PER_CONNECTION);
+        let (lag_sender, _) = mpsc::channel(1);
+        let connection = Connection {
+            subscriptions: Subscriptions::new(broadcaster, new_subscriber_info_sender),
+            outbound_sender,
+            lag_sender,
+            active_subscriptions: HashMap::new(),
+            next_subscription_id: 0,
+        };
+        (connection, outbound_receiver)
+    }
+
+    fn error_code(response: &Value) -> Option<i64> {
+        response["error"]["code"].as_i64()
+    }
+
+    #[tokio::test]
+    async fn should_subscribe_and_unsubscribe() {
+        let (mut connection, _outbound_receiver) = connection();
+
+        let response = connection
+            .handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"subscribe","params":{"start_from":3,"events":"BlockAdded"}}"#);
+        assert_eq!(response["id"], json!(1));
+        assert_eq!(response["result"], json!(0));
+
+        let response =
+            connection.handle_message(r#"{"jsonrpc":"2.0","id":2,"method":"subscribe"}"#);
+        assert_eq!(response["result"], json!(1));
+
+        let unsubscribe =
+            r#"{"jsonrpc":"2.0","id":3,"method":"unsubscribe","params":{"subscription":0}}"#;
+        assert_eq!(
+            connection.handle_message(unsubscribe)["result"],
+            json!(true)
+        );
+        assert_eq!(
+            connection.handle_message(unsubscribe)["result"],
+            json!(false)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_requests() {
+        let (mut connection, _outbound_receiver) = connection();
+
+        assert_eq!(
+            error_code(&connection.handle_message("not json")),
+            Some(PARSE_ERROR)
+        );
+        assert_eq!(
+            error_code(
+                &connection.handle_message(r#"{"jsonrpc":"1.0","id":1,"method":"subscribe"}"#)
+            ),
+            Some(INVALID_REQUEST)
+        );
+        assert_eq!(
+            error_code(
+                &connection.handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"publish"}"#)
+            ),
+            Some(METHOD_NOT_FOUND)
+        );
+        assert_eq!(
+            error_code(&connection.handle_message(
+                r#"{"jsonrpc":"2.0","id":1,"method":"subscribe","params":{"events":"Unknown"}}"#
+            )),
+            Some(INVALID_PARAMS)
+        );
+        assert_eq!(
+            error_code(
+                &connection.handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"unsubscribe"}"#)
+            ),
+            Some(INVALID_PARAMS)
+        );
+
+        for _ in 0..MAX_SUBSCRIPTIONS_PER_CONNECTION {
+            connection.handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"subscribe"}"#);
+        }
+        assert_eq!(
+            error_code(
+                &connection.handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"subscribe"}"#)
+            ),
+            Some(INVALID_PARAMS)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_signal_lag_when_outbound_queue_is_full() {
+        let (mut outbound_sender, mut outbound_receiver) = mpsc::channel(1);
+        let (lag_sender, mut lag_receiver) = mpsc::channel(1);
+        outbound_sender.try_send(Message::text("queued")).unwrap();
+
+        let event = ServerSentEvent {
+            id: Some(0),
+            data: super::super::SseData::ApiVersion(Version::new(1, 0, 0)),
+        };
+        let events =
+            futures::stream::iter(vec![Ok(BroadcastChannelMessage::ServerSentEvent(event))]);
+        forward_events(0, events, outbound_sender, lag_sender).await;
+
+        assert_eq!(lag_receiver.recv().await, Some(()));
+        assert_eq!(
+            outbound_receiver.recv().await,
+            Some(Message::text("queued"))
+        );
+        assert_eq!(outbound_receiver.recv().await, None);
+    }
+}
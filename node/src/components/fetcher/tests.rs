@@ -15,6 +15,7 @@ use crate::{
     components::{deploy_acceptor, in_memory_network::NetworkController, storage},
     effect::{
         announcements::{DeployAcceptorAnnouncement, NetworkAnnouncement},
+        requests::BlockProposerRequest,
         Responder,
     },
     protocol::Message,
@@ -78,6 +79,13 @@ reactor!(Reactor {
         );
         deploy_acceptor = infallible DeployAcceptor(cfg.deploy_acceptor_config, &*chainspec_loader.chainspec());
         deploy_fetcher = Fetcher::<Deploy>("deploy", cfg.fetcher_config, registry);
+        block_proposer = has_effects BlockProposer(
+            registry.clone(),
+            effect_builder,
+            0,
+            chainspec_loader.chainspec().as_ref(),
+            Default::default(),
+        );
     }
 
     events: {
@@ -92,6 +100,7 @@ reactor!(Reactor {
         StorageRequest -> storage;
         StateStoreRequest -> storage;
         FetcherRequest<NodeId, Deploy> -> deploy_fetcher;
+        BlockProposerRequest -> block_proposer;
 
         // The only contract runtime request will be the commit of genesis, which we discard.
         ContractRuntimeRequest -> #;
@@ -105,6 +114,8 @@ reactor!(Reactor {
         // from a client.
         RpcServerAnnouncement -> [deploy_acceptor];
         ChainspecLoaderAnnouncement -> [!];
+        // No deploys are buffered by the block proposer in this test, so none can be superseded.
+        BlockProposerAnnouncement -> [!];
     }
 });
 
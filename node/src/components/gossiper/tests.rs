@@ -29,7 +29,7 @@ use crate::{
             ControlAnnouncement, DeployAcceptorAnnouncement, GossiperAnnouncement,
             NetworkAnnouncement, RpcServerAnnouncement,
         },
-        requests::ContractRuntimeRequest,
+        requests::{BlockProposerRequest, ContractRuntimeRequest},
         Responder,
     },
     protocol::Message as NodeMessage,
@@ -69,6 +69,8 @@ enum Event {
     DeployGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<Deploy>),
     #[from]
     ContractRuntime(#[serde(skip_serializing)] contract_runtime::Event),
+    #[from]
+    BlockProposerRequest(#[serde(skip_serializing)] BlockProposerRequest),
 }
 
 impl ReactorEvent for Event {
@@ -121,6 +123,9 @@ impl Display for Event {
             Event::ContractRuntime(event) => {
                 write!(formatter, "contract-runtime event: {}", event)
             }
+            Event::BlockProposerRequest(req) => {
+                write!(formatter, "block proposer request: {}", req)
+            }
         }
     }
 }
@@ -162,7 +167,8 @@ impl reactor::Reactor for Reactor {
 
         let (storage_config, storage_tempdir) = storage::Config::default_for_tests();
         let storage_withdir = WithDir::new(storage_tempdir.path(), storage_config);
-        let storage = Storage::new(&storage_withdir, None, Version::new(1, 0, 0)).unwrap();
+        let storage =
+            Storage::new(&storage_withdir, None, Version::new(1, 0, 0), registry).unwrap();
 
         let contract_runtime_config = contract_runtime::Config::default();
         let contract_runtime =
@@ -328,6 +334,16 @@ impl reactor::Reactor for Reactor {
             Event::DeployGossiperAnnouncement(_ann) => {
                 unreachable!("the deploy gossiper should never make an announcement")
             }
+            Event::BlockProposerRequest(BlockProposerRequest::CheckPendingDeployLimits {
+                responder,
+                ..
+            }) => {
+                // There is no block proposer in the gossiper test, so no limits apply.
+                responder.respond(Ok(())).ignore()
+            }
+            Event::BlockProposerRequest(request) => {
+                unreachable!("unexpected block proposer request: {}", request)
+            }
             Event::Network(event) => reactor::wrap_effects(
                 Event::Network,
                 self.network.handle_event(effect_builder, rng, event),
@@ -23,18 +23,20 @@ use std::{convert::Infallible, fmt::Debug};
 
 use datasize::DataSize;
 use futures::join;
+use itertools::Itertools;
 use semver::Version;
+use thiserror::Error;
 
 use casper_execution_engine::{
     core::engine_state::{
-        self, BalanceRequest, BalanceResult, GetBidsRequest, GetEraValidatorsError, QueryRequest,
-        QueryResult,
+        self, deploy_item::DeployItem, execute_request::ExecuteRequest, BalanceRequest,
+        BalanceResult, GetBidsRequest, GetEraValidatorsError, QueryRequest, QueryResult,
     },
     storage::protocol_data::ProtocolData,
 };
-use casper_types::{system::auction::EraValidators, Key, ProtocolVersion, URef};
+use casper_types::{system::auction::EraValidators, ExecutionResult, Key, ProtocolVersion, URef};
 
-use self::rpcs::chain::BlockIdentifier;
+use self::rpcs::chain::{BlockIdentifier, BlockWithContents};
 
 use super::Component;
 use crate::{
@@ -43,12 +45,15 @@ use crate::{
     effect::{
         announcements::RpcServerAnnouncement,
         requests::{
-            ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest, LinearChainRequest,
-            MetricsRequest, NetworkInfoRequest, RpcRequest, StorageRequest,
+            BlockProposerRequest, ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest,
+            LinearChainRequest, MetricsRequest, NetworkInfoRequest, RpcRequest, StorageRequest,
         },
         EffectBuilder, EffectExt, Effects, Responder,
     },
-    types::{NodeId, StatusFeed},
+    types::{
+        chainspec::DeployConfig, Chainspec, Deploy, DeployHash, DeployMetadata,
+        DeployValidationFailure, NodeId, StatusFeed, Timestamp,
+    },
     utils::{self, ListeningError},
     NodeRng,
 };
@@ -68,6 +73,7 @@ pub trait ReactorEventT:
     + From<MetricsRequest>
     + From<NetworkInfoRequest<NodeId>>
     + From<StorageRequest>
+    + From<BlockProposerRequest>
     + Send
 {
 }
@@ -83,19 +89,39 @@ impl<REv> ReactorEventT for REv where
         + From<MetricsRequest>
         + From<NetworkInfoRequest<NodeId>>
         + From<StorageRequest>
+        + From<BlockProposerRequest>
         + Send
         + 'static
 {
 }
 
+/// An error preventing a deploy from being speculatively executed.
+#[derive(Debug, Error)]
+pub enum SpeculativeExecuteError {
+    /// The deploy would be rejected by the deploy acceptor.
+    #[error("invalid deploy: {0}")]
+    InvalidDeploy(DeployValidationFailure),
+    /// The state root hash against which to execute the deploy is not known.
+    #[error("state root hash not known")]
+    RootNotFound,
+}
+
 #[derive(DataSize, Debug)]
-pub(crate) struct RpcServer {}
+pub(crate) struct RpcServer {
+    /// The maximum number of blocks which can be requested in a single block range request.
+    max_block_range: u64,
+    /// The name of the chain, which deploys to be speculatively executed must specify.
+    chain_name: String,
+    /// The limits deploys to be speculatively executed must satisfy.
+    deploy_config: DeployConfig,
+}
 
 impl RpcServer {
     pub(crate) fn new<REv>(
         config: Config,
         effect_builder: EffectBuilder<REv>,
         api_version: Version,
+        chainspec: &Chainspec,
     ) -> Result<Self, ListeningError>
     where
         REv: ReactorEventT,
@@ -106,9 +132,15 @@ impl RpcServer {
             effect_builder,
             api_version,
             config.qps_limit,
+            config.max_block_range,
+            config.max_batch_size,
         ));
 
-        Ok(RpcServer {})
+        Ok(RpcServer {
+            max_block_range: config.max_block_range,
+            chain_name: chainspec.network_config.name.clone(),
+            deploy_config: chainspec.deploy_config,
+        })
     }
 }
 
@@ -160,6 +192,72 @@ impl RpcServer {
             })
     }
 
+    fn handle_speculative_execute<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        state_root_hash: Digest,
+        mut deploy: Deploy,
+        protocol_version: ProtocolVersion,
+        responder: Responder<Result<ExecutionResult, SpeculativeExecuteError>>,
+    ) -> Effects<Event> {
+        // Don't estimate the costs of a deploy which the network would reject.
+        if let Err(error) = deploy.is_acceptable(&self.chain_name, &self.deploy_config) {
+            return responder
+                .respond(Err(SpeculativeExecuteError::InvalidDeploy(error)))
+                .ignore();
+        }
+        let deploy_hash = *deploy.id();
+        // The deploy's own account stands in for the block proposer, so no third party is paid.
+        let proposer = *deploy.header().account();
+        let execute_request = ExecuteRequest::new(
+            state_root_hash.into(),
+            Timestamp::now().millis(),
+            vec![Ok(DeployItem::from(deploy))],
+            protocol_version,
+            proposer,
+        );
+        effect_builder
+            .request_execute(execute_request)
+            .event(move |result| Event::SpeculativeExecuteResult {
+                deploy_hash,
+                result,
+                main_responder: responder,
+            })
+    }
+
+    fn handle_get_deploy<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        hash: DeployHash,
+        responder: Responder<Option<(Deploy, DeployMetadata, Vec<DeployHash>)>>,
+    ) -> Effects<Event> {
+        async move {
+            let result = match effect_builder
+                .get_deploy_and_metadata_from_storage(hash)
+                .await
+            {
+                Some((deploy, metadata)) => {
+                    let mut dependency_metadata = Vec::new();
+                    if metadata.execution_results.is_empty() {
+                        for dependency in deploy.header().dependencies() {
+                            let maybe_metadata = effect_builder
+                                .get_deploy_and_metadata_from_storage(*dependency)
+                                .await
+                                .map(|(_, dependency_metadata)| dependency_metadata);
+                            dependency_metadata.push(maybe_metadata);
+                        }
+                    }
+                    let unresolved =
+                        unresolved_dependencies(&deploy, &metadata, &dependency_metadata);
+                    Some((deploy, metadata, unresolved))
+                }
+                None => None,
+            };
+            responder.respond(result).await;
+        }
+        .ignore()
+    }
+
     fn handle_get_balance<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -177,6 +275,34 @@ impl RpcServer {
     }
 }
 
+/// Returns the dependencies of the deploy which haven't been executed yet, given the deploy's
+/// metadata and the metadata of each of its dependencies, in order, or `None` for a dependency not
+/// known to storage.
+///
+/// Only a deploy which hasn't been executed yet can be waiting on dependencies, so the metadata of
+/// the dependencies is ignored if it has been.
+pub(crate) fn unresolved_dependencies(
+    deploy: &Deploy,
+    metadata: &DeployMetadata,
+    dependency_metadata: &[Option<DeployMetadata>],
+) -> Vec<DeployHash> {
+    if !metadata.execution_results.is_empty() {
+        return Vec::new();
+    }
+    deploy
+        .header()
+        .dependencies()
+        .iter()
+        .zip(dependency_metadata)
+        .filter(|(_, maybe_metadata)| {
+            maybe_metadata
+                .as_ref()
+                .map_or(true, |metadata| metadata.execution_results.is_empty())
+        })
+        .map(|(dependency, _)| *dependency)
+        .collect()
+}
+
 impl<REv> Component<REv> for RpcServer
 where
     REv: ReactorEventT,
@@ -194,6 +320,18 @@ where
             Event::RpcRequest(RpcRequest::SubmitDeploy { deploy, responder }) => effect_builder
                 .announce_deploy_received(deploy, Some(responder))
                 .ignore(),
+            Event::RpcRequest(RpcRequest::SpeculativeExecute {
+                state_root_hash,
+                deploy,
+                protocol_version,
+                responder,
+            }) => self.handle_speculative_execute(
+                effect_builder,
+                state_root_hash,
+                *deploy,
+                protocol_version,
+                responder,
+            ),
             Event::RpcRequest(RpcRequest::GetBlock {
                 maybe_id: Some(BlockIdentifier::Hash(hash)),
                 responder,
@@ -234,6 +372,61 @@ where
                     result: Box::new(result),
                     main_responder: responder,
                 }),
+            Event::RpcRequest(RpcRequest::GetBlockRange {
+                start_height,
+                end_height,
+                include_deploys,
+                include_transfers,
+                responder,
+            }) => {
+                let max_block_range = self.max_block_range;
+                async move {
+                    if end_height.saturating_sub(start_height) >= max_block_range {
+                        responder.respond(Err(max_block_range)).await;
+                        return;
+                    }
+                    let mut blocks = vec![];
+                    for height in start_height..=end_height {
+                        let (block, signatures) = match effect_builder
+                            .get_block_at_height_with_metadata_from_storage(height)
+                            .await
+                        {
+                            Some(block_with_metadata) => block_with_metadata,
+                            None => break,
+                        };
+                        let deploys = if include_deploys {
+                            let deploy_hashes = block
+                                .deploy_hashes()
+                                .iter()
+                                .chain(block.transfer_hashes())
+                                .copied()
+                                .collect();
+                            let deploys =
+                                effect_builder.get_deploys_from_storage(deploy_hashes).await;
+                            Some(deploys.into_iter().flatten().collect())
+                        } else {
+                            None
+                        };
+                        let transfers = if include_transfers {
+                            let block_hash = *block.hash();
+                            let transfers = effect_builder
+                                .get_block_transfers_from_storage(block_hash)
+                                .await;
+                            Some(transfers.unwrap_or_default())
+                        } else {
+                            None
+                        };
+                        blocks.push(BlockWithContents {
+                            block,
+                            signatures,
+                            deploys,
+                            transfers,
+                        });
+                    }
+                    responder.respond(Ok(blocks)).await;
+                }
+                .ignore()
+            }
             Event::RpcRequest(RpcRequest::QueryProtocolData {
                 protocol_version,
                 responder,
@@ -271,19 +464,53 @@ where
                 purse_uref,
                 responder,
             }) => self.handle_get_balance(effect_builder, state_root_hash, purse_uref, responder),
-            Event::RpcRequest(RpcRequest::GetDeploy { hash, responder }) => effect_builder
-                .get_deploy_and_metadata_from_storage(hash)
-                .event(move |result| Event::GetDeployResult {
-                    hash,
-                    result: Box::new(result),
-                    main_responder: responder,
-                }),
+            Event::RpcRequest(RpcRequest::GetDeploy { hash, responder }) => {
+                self.handle_get_deploy(effect_builder, hash, responder)
+            }
+            Event::RpcRequest(RpcRequest::GetAccountDeploys {
+                account_hash,
+                offset,
+                limit,
+                responder,
+            }) => async move {
+                let account_deploys = effect_builder
+                    .get_account_deploys_from_storage(account_hash, offset, limit)
+                    .await;
+                responder.respond(account_deploys).await;
+            }
+            .ignore(),
+            Event::RpcRequest(RpcRequest::IsPruned { item, responder }) => async move {
+                let is_pruned = effect_builder.is_pruned_in_storage(item).await;
+                responder.respond(is_pruned).await;
+            }
+            .ignore(),
+            Event::RpcRequest(RpcRequest::GetPendingDeploys { account, responder }) => async move {
+                let pending_deploys = effect_builder.get_pending_deploys(account).await;
+                responder.respond(pending_deploys).await;
+            }
+            .ignore(),
             Event::RpcRequest(RpcRequest::GetPeers { responder }) => effect_builder
                 .network_peers()
                 .event(move |peers| Event::GetPeersResult {
                     peers,
                     main_responder: responder,
                 }),
+            Event::RpcRequest(RpcRequest::GetEvidence {
+                era_id,
+                public_key,
+                responder,
+            }) => async move {
+                let evidence = effect_builder
+                    .get_evidence_from_storage(era_id, public_key)
+                    .await;
+                responder.respond(evidence).await;
+            }
+            .ignore(),
+            Event::RpcRequest(RpcRequest::GetValidatorStatus { responder }) => async move {
+                let validator_status = effect_builder.consensus_validator_status().await;
+                responder.respond(validator_status).await;
+            }
+            .ignore(),
             Event::RpcRequest(RpcRequest::GetStatus { responder }) => async move {
                 let (last_added_block, peers, chainspec_info, consensus_status) = join!(
                     effect_builder.get_highest_block_from_storage(),
@@ -332,11 +559,22 @@ where
                 result,
                 main_responder,
             } => main_responder.respond(result).ignore(),
-            Event::GetDeployResult {
-                hash: _,
+            Event::SpeculativeExecuteResult {
                 result,
                 main_responder,
-            } => main_responder.respond(*result).ignore(),
+                ..
+            } => {
+                let result = result
+                    .map(|execution_results| {
+                        let ee_execution_result = execution_results
+                            .into_iter()
+                            .exactly_one()
+                            .expect("should only be one exec result");
+                        ExecutionResult::from(&ee_execution_result)
+                    })
+                    .map_err(|_| SpeculativeExecuteError::RootNotFound);
+                main_responder.respond(result).ignore()
+            }
             Event::GetPeersResult {
                 peers,
                 main_responder,
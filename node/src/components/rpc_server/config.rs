@@ -7,6 +7,10 @@ use serde::{Deserialize, Serialize};
 const DEFAULT_ADDRESS: &str = "0.0.0.0:0";
 /// Default rate limit in qps.
 const DEFAULT_QPS_LIMIT: u64 = 100;
+/// Default maximum number of blocks returned by a single `chain_get_block_range` request.
+const DEFAULT_MAX_BLOCK_RANGE: u64 = 100;
+/// Default maximum number of calls in a single JSON-RPC batch request.
+const DEFAULT_MAX_BATCH_SIZE: usize = 100;
 
 /// JSON-RPC HTTP server configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
@@ -18,6 +22,12 @@ pub struct Config {
 
     /// Max rate limit in qps.
     pub qps_limit: u64,
+
+    /// Maximum number of blocks returned by a single `chain_get_block_range` request.
+    pub max_block_range: u64,
+
+    /// Maximum number of calls in a single JSON-RPC batch request.
+    pub max_batch_size: usize,
 }
 
 impl Config {
@@ -26,6 +36,8 @@ impl Config {
         Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
+            max_block_range: DEFAULT_MAX_BLOCK_RANGE,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
         }
     }
 }
@@ -6,15 +6,18 @@ use std::{
 use derive_more::From;
 
 use casper_execution_engine::{
-    core::engine_state::{self, BalanceResult, GetBidsResult, GetEraValidatorsError, QueryResult},
+    core::engine_state::{
+        self, BalanceResult, ExecutionResults, GetBidsResult, GetEraValidatorsError, QueryResult,
+    },
     storage::protocol_data::ProtocolData,
 };
-use casper_types::{system::auction::EraValidators, Transfer};
+use casper_types::{system::auction::EraValidators, ExecutionResult, Transfer};
 
+use super::SpeculativeExecuteError;
 use crate::{
     effect::{requests::RpcRequest, Responder},
     rpcs::chain::BlockIdentifier,
-    types::{Block, BlockHash, BlockSignatures, Deploy, DeployHash, DeployMetadata, NodeId},
+    types::{Block, BlockHash, BlockSignatures, DeployHash, NodeId},
 };
 
 #[derive(Debug, From)]
@@ -47,11 +50,6 @@ pub enum Event {
         result: Result<GetBidsResult, engine_state::Error>,
         main_responder: Responder<Result<GetBidsResult, engine_state::Error>>,
     },
-    GetDeployResult {
-        hash: DeployHash,
-        result: Box<Option<(Deploy, DeployMetadata)>>,
-        main_responder: Responder<Option<(Deploy, DeployMetadata)>>,
-    },
     GetPeersResult {
         peers: BTreeMap<NodeId, String>,
         main_responder: Responder<BTreeMap<NodeId, String>>,
@@ -64,6 +62,11 @@ pub enum Event {
         result: Result<BalanceResult, engine_state::Error>,
         main_responder: Responder<Result<BalanceResult, engine_state::Error>>,
     },
+    SpeculativeExecuteResult {
+        deploy_hash: DeployHash,
+        result: Result<ExecutionResults, engine_state::RootNotFound>,
+        main_responder: Responder<Result<ExecutionResult, SpeculativeExecuteError>>,
+    },
 }
 
 impl Display for Event {
@@ -107,9 +110,15 @@ impl Display for Event {
             Event::GetBalanceResult { result, .. } => {
                 write!(formatter, "balance result: {:?}", result)
             }
-            Event::GetDeployResult { hash, result, .. } => {
-                write!(formatter, "get deploy result for {}: {:?}", hash, result)
-            }
+            Event::SpeculativeExecuteResult {
+                deploy_hash,
+                result,
+                ..
+            } => write!(
+                formatter,
+                "speculative execution result for {}: {:?}",
+                deploy_hash, result
+            ),
             Event::GetPeersResult { peers, .. } => write!(formatter, "get peers: {}", peers.len()),
             Event::GetMetricsResult { text, .. } => match text {
                 Some(txt) => write!(formatter, "get metrics ({} bytes)", txt.len()),
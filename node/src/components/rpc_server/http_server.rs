@@ -1,24 +1,39 @@
-use std::{convert::Infallible, time::Duration};
+use std::{
+    convert::Infallible,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use futures::future;
-use http::{Response, StatusCode};
+use http::{
+    header::{HeaderValue, CONTENT_LENGTH},
+    request::Parts,
+    Request, Response, StatusCode,
+};
 use hyper::{
     server::{conn::AddrIncoming, Builder},
     Body,
 };
 use semver::Version;
 use serde::Serialize;
+use serde_json::Value;
 use tokio::sync::oneshot;
-use tower::builder::ServiceBuilder;
-use tracing::{info, trace};
+use tower::{Service, ServiceExt};
+use tracing::{info, trace, warn};
 use warp::{Filter, Rejection};
 
 use super::{
-    rpcs::{self, RpcWithOptionalParamsExt, RpcWithParamsExt, RpcWithoutParamsExt, RPC_API_PATH},
+    rpcs::{
+        self, ErrorCode, RpcWithOptionalParamsExt, RpcWithParams, RpcWithParamsExt,
+        RpcWithoutParamsExt, RPC_API_PATH,
+    },
     ReactorEventT,
 };
 use crate::effect::EffectBuilder;
 
+/// The duration of each bucket of the rate limiter.
+const RATE_LIMIT_BUCKET_DURATION: Duration = Duration::from_secs(1);
+
 // This is a workaround for not being able to create a `warp_json_rpc::Response` without a
 // `warp_json_rpc::Builder`.
 fn new_error_response(error: warp_json_rpc::Error) -> Response<Body> {
@@ -43,17 +58,246 @@ fn new_error_response(error: warp_json_rpc::Error) -> Response<Body> {
         .unwrap()
 }
 
+/// Returns the empty response to a request holding only notifications.
+fn new_empty_response() -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .body(Body::empty())
+        .unwrap()
+}
+
+/// Returns whether the given JSON-RPC call is a notification, i.e. has no "id" member, in which
+/// case no response must be sent for it.
+fn is_notification(call: &Value) -> bool {
+    call.as_object()
+        .map_or(false, |call| !call.contains_key("id"))
+}
+
+/// A one-second bucket of the rate limiter, along with the number of calls charged to it.
+struct Bucket {
+    start: Instant,
+    charged: u64,
+}
+
+/// Limits the rate at which JSON-RPC calls are handled to `qps_limit` per second, across all
+/// connections.
+///
+/// Each call is charged to the earliest one-second bucket which is not yet full and delayed until
+/// that bucket starts.  A call with a cost exceeding the remaining capacity of its bucket spills
+/// over into the following ones, delaying later calls accordingly.
+struct RateLimiter {
+    qps_limit: u64,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    fn new(qps_limit: u64) -> Self {
+        RateLimiter {
+            qps_limit: qps_limit.max(1),
+            bucket: Mutex::new(Bucket {
+                start: Instant::now(),
+                charged: 0,
+            }),
+        }
+    }
+
+    /// Waits until a call with the given cost can be handled within the rate limit.
+    async fn acquire(&self, cost: u64) {
+        let delay = {
+            let mut bucket = self.bucket.lock().expect("rate limiter lock poisoned");
+            let now = Instant::now();
+            if bucket.start + RATE_LIMIT_BUCKET_DURATION <= now {
+                bucket.start = now;
+                bucket.charged = 0;
+            }
+            while bucket.charged >= self.qps_limit {
+                bucket.start += RATE_LIMIT_BUCKET_DURATION;
+                bucket.charged -= self.qps_limit;
+            }
+            bucket.charged += cost;
+            bucket.start.saturating_duration_since(now)
+        };
+        if delay > Duration::from_secs(0) {
+            tokio::time::delay_for(delay).await;
+        }
+    }
+}
+
+/// Returns the cost of the given JSON-RPC call for the purposes of rate limiting.
+///
+/// Every call costs one, except for "chain_get_block_range" which costs one per block requested,
+/// up to `max_block_range`.
+fn call_cost(call: &Value, max_block_range: u64) -> u64 {
+    if call.get("method").and_then(Value::as_str) != Some(rpcs::chain::GetBlockRange::METHOD) {
+        return 1;
+    }
+    let height = |name: &str| {
+        call.get("params")
+            .and_then(|params| params.get(name))
+            .and_then(Value::as_u64)
+    };
+    match (height("start_height"), height("end_height")) {
+        (Some(start_height), Some(end_height)) if start_height <= end_height => {
+            (end_height - start_height).min(max_block_range.saturating_sub(1)) + 1
+        }
+        _ => 1,
+    }
+}
+
+/// Creates a request for a single call of a batch, with the same method, URI and headers as the
+/// batch request.
+fn new_call_request(parts: &Parts, call: &Value) -> Request<Body> {
+    let body = serde_json::to_vec(call).expect("should serialize JSON value");
+    let mut request = Request::new(Body::empty());
+    *request.method_mut() = parts.method.clone();
+    *request.uri_mut() = parts.uri.clone();
+    *request.version_mut() = parts.version;
+    *request.headers_mut() = parts.headers.clone();
+    request
+        .headers_mut()
+        .insert(CONTENT_LENGTH, HeaderValue::from(body.len()));
+    *request.body_mut() = Body::from(body);
+    request
+}
+
+/// Handles a single call of a batch, returning its JSON-RPC response object, or `None` if the call
+/// is a notification.
+async fn handle_call<S>(service: S, request: Request<Body>, notification: bool) -> Option<Value>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = Infallible>,
+{
+    let response = match service.oneshot(request).await {
+        Ok(response) => response,
+        Err(infallible) => match infallible {},
+    };
+    if notification {
+        return None;
+    }
+    let body = match hyper::body::to_bytes(response.into_body()).await {
+        Ok(body) => body,
+        Err(error) => {
+            warn!(%error, "failed to read response to call in batch");
+            return None;
+        }
+    };
+    match serde_json::from_slice(&body) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!(%error, "response to call in batch is not JSON");
+            None
+        }
+    }
+}
+
+/// Handles an HTTP request, passing each JSON-RPC call it holds to `service` once permitted by the
+/// rate limiter.
+///
+/// A request holding a JSON array is treated as a JSON-RPC batch: each element is handled as a
+/// separate call, concurrently, and the responses are collected into a JSON array.  Batches of more
+/// than `max_batch_size` calls are rejected, and the whole batch is charged to the rate limiter
+/// before any of its calls is handled.
+///
+/// Notifications are handled, but not responded to.  If a request holds only notifications, the
+/// response is empty.
+async fn handle_request<S>(
+    request: Request<Body>,
+    service: S,
+    rate_limiter: Arc<RateLimiter>,
+    max_block_range: u64,
+    max_batch_size: usize,
+) -> Result<Response<Body>, Infallible>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = Infallible> + Clone,
+{
+    let (parts, body) = request.into_parts();
+    let body = match hyper::body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(error) => {
+            warn!(%error, "failed to read request body");
+            return Ok(new_error_response(warp_json_rpc::Error::PARSE_ERROR));
+        }
+    };
+
+    let calls = match serde_json::from_slice(&body) {
+        Ok(Value::Array(calls)) if parts.uri.path().trim_matches('/') == RPC_API_PATH => calls,
+        maybe_call => {
+            let (cost, notification) = maybe_call
+                .map(|call| (call_cost(&call, max_block_range), is_notification(&call)))
+                .unwrap_or((1, false));
+            rate_limiter.acquire(cost).await;
+            let response = service
+                .oneshot(Request::from_parts(parts, Body::from(body)))
+                .await?;
+            if notification {
+                return Ok(new_empty_response());
+            }
+            return Ok(response);
+        }
+    };
+
+    if calls.is_empty() {
+        return Ok(new_error_response(warp_json_rpc::Error::INVALID_REQUEST));
+    }
+    if calls.len() > max_batch_size {
+        let error_msg = format!(
+            "batch of {} calls exceeds the maximum of {}",
+            calls.len(),
+            max_batch_size
+        );
+        info!("{}", error_msg);
+        return Ok(new_error_response(warp_json_rpc::Error::custom(
+            ErrorCode::BatchTooLarge as i64,
+            error_msg,
+        )));
+    }
+
+    let cost = calls
+        .iter()
+        .map(|call| call_cost(call, max_block_range))
+        .fold(0, u64::saturating_add);
+    rate_limiter.acquire(cost).await;
+
+    let responses: Vec<Value> = future::join_all(calls.iter().map(|call| {
+        handle_call(
+            service.clone(),
+            new_call_request(&parts, call),
+            is_notification(call),
+        )
+    }))
+    .await
+    .into_iter()
+    .flatten()
+    .collect();
+
+    if responses.is_empty() {
+        return Ok(new_empty_response());
+    }
+
+    let body = Body::from(serde_json::to_vec(&responses).expect("should serialize JSON values"));
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .unwrap())
+}
+
 /// Run the JSON-RPC server.
 pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
     effect_builder: EffectBuilder<REv>,
     api_version: Version,
     qps_limit: u64,
+    max_block_range: u64,
+    max_batch_size: usize,
 ) {
     // RPC filters.
     let rpc_put_deploy =
         rpcs::account::PutDeploy::create_filter(effect_builder, api_version.clone());
+    let rpc_speculative_execute =
+        rpcs::account::SpeculativeExecute::create_filter(effect_builder, api_version.clone());
     let rpc_get_block = rpcs::chain::GetBlock::create_filter(effect_builder, api_version.clone());
+    let rpc_get_block_range =
+        rpcs::chain::GetBlockRange::create_filter(effect_builder, api_version.clone());
     let rpc_get_block_transfers =
         rpcs::chain::GetBlockTransfers::create_filter(effect_builder, api_version.clone());
     let rpc_get_state_root_hash =
@@ -61,9 +305,19 @@ pub(super) async fn run<REv: ReactorEventT>(
     let rpc_get_item = rpcs::state::GetItem::create_filter(effect_builder, api_version.clone());
     let rpc_get_balance =
         rpcs::state::GetBalance::create_filter(effect_builder, api_version.clone());
+    let rpc_get_dictionary_item =
+        rpcs::state::GetDictionaryItem::create_filter(effect_builder, api_version.clone());
     let rpc_get_deploy = rpcs::info::GetDeploy::create_filter(effect_builder, api_version.clone());
+    let rpc_get_account_deploys =
+        rpcs::info::GetAccountDeploys::create_filter(effect_builder, api_version.clone());
+    let rpc_get_pending_deploys =
+        rpcs::info::GetPendingDeploys::create_filter(effect_builder, api_version.clone());
     let rpc_get_peers = rpcs::info::GetPeers::create_filter(effect_builder, api_version.clone());
     let rpc_get_status = rpcs::info::GetStatus::create_filter(effect_builder, api_version.clone());
+    let rpc_get_validator_status =
+        rpcs::consensus::GetValidatorStatus::create_filter(effect_builder, api_version.clone());
+    let rpc_get_evidence =
+        rpcs::consensus::GetEvidence::create_filter(effect_builder, api_version.clone());
     let rpc_get_era_info =
         rpcs::chain::GetEraInfoBySwitchBlock::create_filter(effect_builder, api_version.clone());
     let rpc_get_auction_info =
@@ -90,14 +344,21 @@ pub(super) async fn run<REv: ReactorEventT>(
     //        update to or move away from warp_json_rpc.
     let service = warp_json_rpc::service(
         rpc_put_deploy
+            .or(rpc_speculative_execute)
             .or(rpc_get_block)
+            .or(rpc_get_block_range)
             .or(rpc_get_block_transfers)
             .or(rpc_get_state_root_hash)
             .or(rpc_get_item)
             .or(rpc_get_balance)
+            .or(rpc_get_dictionary_item)
             .or(rpc_get_deploy)
+            .or(rpc_get_account_deploys)
+            .or(rpc_get_pending_deploys)
             .or(rpc_get_peers)
             .or(rpc_get_status)
+            .or(rpc_get_validator_status)
+            .or(rpc_get_evidence)
             .or(rpc_get_era_info)
             .or(rpc_get_auction_info)
             .or(rpc_get_rpcs)
@@ -105,13 +366,23 @@ pub(super) async fn run<REv: ReactorEventT>(
             .or(parse_failure),
     );
 
-    // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
-    let make_svc =
-        hyper::service::make_service_fn(move |_| future::ok::<_, Infallible>(service.clone()));
+    // Every call, including each call of a batch, is charged against the same rate limiter.
+    let rate_limiter = Arc::new(RateLimiter::new(qps_limit));
 
-    let make_svc = ServiceBuilder::new()
-        .rate_limit(qps_limit, Duration::from_secs(1))
-        .service(make_svc);
+    // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
+    let make_svc = hyper::service::make_service_fn(move |_| {
+        let service = service.clone();
+        let rate_limiter = Arc::clone(&rate_limiter);
+        future::ok::<_, Infallible>(hyper::service::service_fn(move |request| {
+            handle_request(
+                request,
+                service.clone(),
+                Arc::clone(&rate_limiter),
+                max_block_range,
+                max_batch_size,
+            )
+        }))
+    });
 
     let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
 
@@ -131,3 +402,134 @@ pub(super) async fn run<REv: ReactorEventT>(
 
     trace!("JSON-RPC server stopped");
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// Returns a service which responds to each request with the request's body.
+    fn echo_service(
+    ) -> impl Service<Request<Body>, Response = Response<Body>, Error = Infallible> + Clone {
+        hyper::service::service_fn(|request: Request<Body>| async move {
+            Ok::<_, Infallible>(Response::new(request.into_body()))
+        })
+    }
+
+    async fn post_with_rate_limiter(body: Value, rate_limiter: Arc<RateLimiter>) -> Value {
+        let request = Request::post(format!("/{}", RPC_API_PATH))
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = handle_request(request, echo_service(), rate_limiter, 10, 3)
+            .await
+            .unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        if body.is_empty() {
+            return Value::Null;
+        }
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    async fn post(body: Value) -> Value {
+        post_with_rate_limiter(body, Arc::new(RateLimiter::new(100))).await
+    }
+
+    #[test]
+    fn should_charge_block_range_calls_per_block() {
+        let call = |method: &str, params: Value| json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let range = |start_height: u64, end_height: u64| json!({ "start_height": start_height, "end_height": end_height });
+
+        assert_eq!(call_cost(&call("chain_get_block", json!({})), 10), 1);
+        assert_eq!(call_cost(&call("info_get_status", range(0, 5)), 10), 1);
+        assert_eq!(
+            call_cost(&call("chain_get_block_range", range(3, 3)), 10),
+            1
+        );
+        assert_eq!(
+            call_cost(&call("chain_get_block_range", range(3, 7)), 10),
+            5
+        );
+        assert_eq!(
+            call_cost(&call("chain_get_block_range", range(0, 99)), 10),
+            10
+        );
+        assert_eq!(
+            call_cost(&call("chain_get_block_range", range(7, 3)), 10),
+            1
+        );
+        assert_eq!(
+            call_cost(&call("chain_get_block_range", json!([3, 7])), 10),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn should_handle_each_call_of_batch() {
+        let single = json!({"jsonrpc": "2.0", "id": 1, "method": "info_get_status"});
+        assert_eq!(post(single.clone()).await, single);
+
+        let batch = json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "info_get_status"},
+            {"jsonrpc": "2.0", "id": 2, "method": "info_get_peers"},
+        ]);
+        assert_eq!(post(batch.clone()).await, batch);
+    }
+
+    #[tokio::test]
+    async fn should_reject_empty_batch() {
+        let response = post(json!([])).await;
+        let expected_error = serde_json::to_value(warp_json_rpc::Error::INVALID_REQUEST).unwrap();
+        assert_eq!(response["error"], expected_error);
+    }
+
+    #[tokio::test]
+    async fn should_reject_oversized_batch() {
+        let call = json!({"jsonrpc": "2.0", "id": 1, "method": "info_get_status"});
+        let response = post(json!([call, call, call, call])).await;
+        assert_eq!(
+            response["error"]["code"],
+            json!(ErrorCode::BatchTooLarge as i64)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_not_respond_to_notifications() {
+        let notification = json!({"jsonrpc": "2.0", "method": "info_get_status"});
+        assert_eq!(post(notification.clone()).await, Value::Null);
+
+        let call = json!({"jsonrpc": "2.0", "id": 1, "method": "info_get_peers"});
+        assert_eq!(post(json!([notification, call])).await, json!([call]));
+        assert_eq!(post(json!([notification, notification])).await, Value::Null);
+    }
+
+    #[tokio::test]
+    async fn should_charge_whole_batch_before_handling_it() {
+        let rate_limiter = Arc::new(RateLimiter::new(2));
+        let start = Instant::now();
+        let batch = json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "info_get_status"},
+            {"jsonrpc": "2.0", "id": 2, "method": "info_get_status"},
+            {"jsonrpc": "2.0", "id": 3, "method": "info_get_status"},
+        ]);
+        post_with_rate_limiter(batch, Arc::clone(&rate_limiter)).await;
+        assert!(start.elapsed() < RATE_LIMIT_BUCKET_DURATION);
+
+        // The batch filled the current bucket, so the next call is delayed.
+        rate_limiter.acquire(1).await;
+        assert!(start.elapsed() >= RATE_LIMIT_BUCKET_DURATION);
+    }
+
+    #[tokio::test]
+    async fn should_delay_calls_exceeding_rate_limit() {
+        let rate_limiter = RateLimiter::new(2);
+        let start = Instant::now();
+        rate_limiter.acquire(1).await;
+        rate_limiter.acquire(1).await;
+        assert!(start.elapsed() < RATE_LIMIT_BUCKET_DURATION);
+
+        // The third call is delayed to the next bucket.
+        rate_limiter.acquire(1).await;
+        assert!(start.elapsed() >= RATE_LIMIT_BUCKET_DURATION);
+    }
+}
@@ -4,6 +4,7 @@
 
 pub mod account;
 pub mod chain;
+pub mod consensus;
 pub mod docs;
 pub mod info;
 pub mod state;
@@ -35,7 +36,7 @@ pub const RPC_API_PATH: &str = "rpc";
 ///
 /// See <https://www.jsonrpc.org/specification#error_object> for details.
 #[repr(i64)]
-enum ErrorCode {
+pub(super) enum ErrorCode {
     NoSuchDeploy = -32000,
     NoSuchBlock = -32001,
     ParseQueryKey = -32002,
@@ -45,6 +46,12 @@ enum ErrorCode {
     GetBalanceFailed = -32006,
     GetBalanceFailedToExecute = -32007,
     InvalidDeploy = -32008,
+    PendingDeployLimitExceeded = -32009,
+    NoSuchStateRoot = -32010,
+    ParseDictionarySeedURef = -32011,
+    InvalidBlockRange = -32012,
+    Pruned = -32013,
+    BatchTooLarge = -32014,
 }
 
 #[derive(Debug)]
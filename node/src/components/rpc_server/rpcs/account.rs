@@ -15,15 +15,21 @@ use serde::{Deserialize, Serialize};
 use tracing::info;
 use warp_json_rpc::Builder;
 
+use casper_types::{ExecutionResult, ProtocolVersion, TransformEntry, U512};
+
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ReactorEventT, RpcRequest, RpcWithParams, RpcWithParamsExt,
 };
 use crate::{
-    components::rpc_server::rpcs::ErrorCode,
+    components::{
+        deploy_acceptor,
+        rpc_server::{rpcs::ErrorCode, SpeculativeExecuteError},
+    },
+    crypto::hash::Digest,
     effect::EffectBuilder,
     reactor::QueueKind,
-    types::{Deploy, DeployHash},
+    types::{Block, Deploy, DeployHash},
 };
 
 static PUT_DEPLOY_PARAMS: Lazy<PutDeployParams> = Lazy::new(|| PutDeployParams {
@@ -33,6 +39,18 @@ static PUT_DEPLOY_RESULT: Lazy<PutDeployResult> = Lazy::new(|| PutDeployResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION.clone(),
     deploy_hash: *Deploy::doc_example().id(),
 });
+static SPECULATIVE_EXECUTE_PARAMS: Lazy<SpeculativeExecuteParams> =
+    Lazy::new(|| SpeculativeExecuteParams {
+        state_root_hash: Some(*Block::doc_example().header().state_root_hash()),
+        deploy: Deploy::doc_example().clone(),
+    });
+static SPECULATIVE_EXECUTE_RESULT: Lazy<SpeculativeExecuteResult> = Lazy::new(|| {
+    SpeculativeExecuteResult::new(
+        DOCS_EXAMPLE_PROTOCOL_VERSION.clone(),
+        *Block::doc_example().header().state_root_hash(),
+        ExecutionResult::example().clone(),
+    )
+});
 
 /// Params for "account_put_deploy" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -106,6 +124,17 @@ impl RpcWithParamsExt for PutDeploy {
                     };
                     Ok(response_builder.success(result)?)
                 }
+                Err(error @ deploy_acceptor::Error::ExceededPendingDeployLimit(_)) => {
+                    info!(
+                        %deploy_hash,
+                        %error,
+                        "the deploy submitted by the client exceeds the pending deploy limits",
+                    );
+                    Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::PendingDeployLimitExceeded as i64,
+                        error.to_string(),
+                    ))?)
+                }
                 Err(error) => {
                     info!(
                         %deploy_hash,
@@ -122,3 +151,160 @@ impl RpcWithParamsExt for PutDeploy {
         .boxed()
     }
 }
+
+/// Params for "account_speculative_execute" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecuteParams {
+    /// The state root hash against which to execute the deploy.  If not given, the state root
+    /// hash of the latest block is used.
+    pub state_root_hash: Option<Digest>,
+    /// The `Deploy`.
+    pub deploy: Deploy,
+}
+
+impl DocExample for SpeculativeExecuteParams {
+    fn doc_example() -> &'static Self {
+        &*SPECULATIVE_EXECUTE_PARAMS
+    }
+}
+
+/// Result for "account_speculative_execute" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecuteResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: Version,
+    /// The state root hash against which the deploy was executed.
+    pub state_root_hash: Digest,
+    /// The result of executing the deploy.
+    pub execution_result: ExecutionResult,
+    /// The gas consumed by executing the deploy.
+    pub gas_consumed: U512,
+    /// The transforms which executing the deploy would have applied to global state.
+    pub transforms: Vec<TransformEntry>,
+}
+
+impl SpeculativeExecuteResult {
+    fn new(
+        api_version: Version,
+        state_root_hash: Digest,
+        execution_result: ExecutionResult,
+    ) -> Self {
+        let (gas_consumed, transforms) = match &execution_result {
+            ExecutionResult::Success { effect, cost, .. }
+            | ExecutionResult::Failure { effect, cost, .. } => (*cost, effect.transforms.clone()),
+        };
+        SpeculativeExecuteResult {
+            api_version,
+            state_root_hash,
+            execution_result,
+            gas_consumed,
+            transforms,
+        }
+    }
+}
+
+impl DocExample for SpeculativeExecuteResult {
+    fn doc_example() -> &'static Self {
+        &*SPECULATIVE_EXECUTE_RESULT
+    }
+}
+
+/// "account_speculative_execute" RPC
+pub struct SpeculativeExecute {}
+
+impl RpcWithParams for SpeculativeExecute {
+    const METHOD: &'static str = "account_speculative_execute";
+    type RequestParams = SpeculativeExecuteParams;
+    type ResponseResult = SpeculativeExecuteResult;
+}
+
+impl RpcWithParamsExt for SpeculativeExecute {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: Version,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let deploy_hash = *params.deploy.id();
+
+            // Default to the state root hash of the latest block.
+            let state_root_hash = match params.state_root_hash {
+                Some(state_root_hash) => state_root_hash,
+                None => {
+                    let maybe_block = effect_builder
+                        .make_request(
+                            |responder| RpcRequest::GetBlock {
+                                maybe_id: None,
+                                responder,
+                            },
+                            QueueKind::Api,
+                        )
+                        .await;
+                    match maybe_block {
+                        Some((block, _)) => *block.header().state_root_hash(),
+                        None => {
+                            let error_msg =
+                                "speculative-execute failed to get last added block".to_string();
+                            info!("{}", error_msg);
+                            return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                                ErrorCode::NoSuchBlock as i64,
+                                error_msg,
+                            ))?);
+                        }
+                    }
+                }
+            };
+
+            let protocol_version = ProtocolVersion::from_parts(
+                api_version.major as u32,
+                api_version.minor as u32,
+                api_version.patch as u32,
+            );
+
+            let execution_result = effect_builder
+                .make_request(
+                    |responder| RpcRequest::SpeculativeExecute {
+                        state_root_hash,
+                        deploy: Box::new(params.deploy),
+                        protocol_version,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            match execution_result {
+                Ok(execution_result) => {
+                    info!(%deploy_hash, "deploy was speculatively executed");
+                    let result =
+                        Self::ResponseResult::new(api_version, state_root_hash, execution_result);
+                    Ok(response_builder.success(result)?)
+                }
+                Err(error @ SpeculativeExecuteError::InvalidDeploy(_)) => {
+                    info!(
+                        %deploy_hash,
+                        %error,
+                        "the deploy to be speculatively executed was invalid",
+                    );
+                    Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::InvalidDeploy as i64,
+                        error.to_string(),
+                    ))?)
+                }
+                Err(SpeculativeExecuteError::RootNotFound) => {
+                    let error_msg = format!("state root hash {} not known", state_root_hash);
+                    info!(%deploy_hash, "{}", error_msg);
+                    Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::NoSuchStateRoot as i64,
+                        error_msg,
+                    ))?)
+                }
+            }
+        }
+        .boxed()
+    }
+}
@@ -22,13 +22,15 @@ use casper_types::{Key, Transfer};
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithOptionalParams, RpcWithOptionalParamsExt,
+    RpcWithParams, RpcWithParamsExt,
 };
 use crate::{
+    components::storage::PrunableItem,
     crypto::hash::Digest,
     effect::EffectBuilder,
     reactor::QueueKind,
     rpcs::common::{self},
-    types::{Block, BlockHash, BlockSignatures, Item, JsonBlock},
+    types::{Block, BlockHash, BlockSignatures, Deploy, Item, JsonBlock},
 };
 pub use era_summary::EraSummary;
 use era_summary::ERA_SUMMARY;
@@ -50,6 +52,20 @@ static GET_BLOCK_TRANSFERS_RESULT: Lazy<GetBlockTransfersResult> =
         block_hash: Some(Block::doc_example().id()),
         transfers: Some(vec![Transfer::default()]),
     });
+static GET_BLOCK_RANGE_PARAMS: Lazy<GetBlockRangeParams> = Lazy::new(|| GetBlockRangeParams {
+    start_height: Block::doc_example().header().height(),
+    end_height: Block::doc_example().header().height(),
+    include_deploys: true,
+    include_transfers: true,
+});
+static GET_BLOCK_RANGE_RESULT: Lazy<GetBlockRangeResult> = Lazy::new(|| GetBlockRangeResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION.clone(),
+    blocks: vec![JsonBlockWithContents {
+        block: JsonBlock::doc_example().clone(),
+        deploys: Some(vec![Deploy::doc_example().clone()]),
+        transfers: Some(vec![Transfer::default()]),
+    }],
+});
 static GET_STATE_ROOT_HASH_PARAMS: Lazy<GetStateRootHashParams> =
     Lazy::new(|| GetStateRootHashParams {
         block_identifier: BlockIdentifier::Height(Block::doc_example().header().height()),
@@ -250,6 +266,182 @@ impl RpcWithOptionalParamsExt for GetBlockTransfers {
     }
 }
 
+/// A block read from storage along with its signatures and, if requested, its deploys and
+/// transfers.
+#[derive(Debug)]
+pub struct BlockWithContents {
+    /// The block.
+    pub block: Block,
+    /// The finality signatures of the block.
+    pub signatures: BlockSignatures,
+    /// The deploys of the block, including its native transfer deploys, if requested.
+    pub deploys: Option<Vec<Deploy>>,
+    /// The transfers performed by the block's deploys, if requested.
+    pub transfers: Option<Vec<Transfer>>,
+}
+
+/// Params for "chain_get_block_range" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlockRangeParams {
+    /// The height of the first block to retrieve.
+    pub start_height: u64,
+    /// The height of the last block to retrieve (inclusive).
+    pub end_height: u64,
+    /// Whether to include the deploys of each block, including its native transfer deploys.
+    #[serde(default)]
+    pub include_deploys: bool,
+    /// Whether to include the transfers performed by each block's deploys.
+    #[serde(default)]
+    pub include_transfers: bool,
+}
+
+impl DocExample for GetBlockRangeParams {
+    fn doc_example() -> &'static Self {
+        &*GET_BLOCK_RANGE_PARAMS
+    }
+}
+
+/// A block along with its deploys and transfers, as returned by "chain_get_block_range".
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonBlockWithContents {
+    /// The block.
+    pub block: JsonBlock,
+    /// The deploys of the block, including its native transfer deploys, if requested.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub deploys: Option<Vec<Deploy>>,
+    /// The transfers performed by the block's deploys, if requested.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub transfers: Option<Vec<Transfer>>,
+}
+
+impl From<BlockWithContents> for JsonBlockWithContents {
+    fn from(block_with_contents: BlockWithContents) -> Self {
+        JsonBlockWithContents {
+            block: JsonBlock::new(block_with_contents.block, block_with_contents.signatures),
+            deploys: block_with_contents.deploys,
+            transfers: block_with_contents.transfers,
+        }
+    }
+}
+
+/// Result for "chain_get_block_range" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlockRangeResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: Version,
+    /// The blocks in the range, in ascending order of height.
+    ///
+    /// Blocks are returned up to the first one in the range which the node does not hold, e.g. if
+    /// the range extends beyond the highest block.
+    pub blocks: Vec<JsonBlockWithContents>,
+}
+
+impl DocExample for GetBlockRangeResult {
+    fn doc_example() -> &'static Self {
+        &*GET_BLOCK_RANGE_RESULT
+    }
+}
+
+/// "chain_get_block_range" RPC.
+pub struct GetBlockRange {}
+
+impl RpcWithParams for GetBlockRange {
+    const METHOD: &'static str = "chain_get_block_range";
+    type RequestParams = GetBlockRangeParams;
+    type ResponseResult = GetBlockRangeResult;
+}
+
+impl RpcWithParamsExt for GetBlockRange {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: Version,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            if params.start_height > params.end_height {
+                let error_msg = format!(
+                    "start height {} is greater than end height {}",
+                    params.start_height, params.end_height
+                );
+                info!("{}", error_msg);
+                return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                    ErrorCode::InvalidBlockRange as i64,
+                    error_msg,
+                ))?);
+            }
+
+            let result = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetBlockRange {
+                        start_height: params.start_height,
+                        end_height: params.end_height,
+                        include_deploys: params.include_deploys,
+                        include_transfers: params.include_transfers,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let blocks = match result {
+                Ok(blocks) => blocks,
+                Err(max_block_range) => {
+                    let error_msg = format!(
+                        "requested {} blocks, but at most {} can be requested at once",
+                        params
+                            .end_height
+                            .saturating_sub(params.start_height)
+                            .saturating_add(1),
+                        max_block_range
+                    );
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::InvalidBlockRange as i64,
+                        error_msg,
+                    ))?);
+                }
+            };
+
+            if blocks.is_empty() {
+                let is_pruned = effect_builder
+                    .make_request(
+                        |responder| RpcRequest::IsPruned {
+                            item: PrunableItem::BlockAtHeight(params.start_height),
+                            responder,
+                        },
+                        QueueKind::Api,
+                    )
+                    .await;
+                if is_pruned {
+                    let error_msg =
+                        format!("block at height {} has been pruned", params.start_height);
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::Pruned as i64,
+                        error_msg,
+                    ))?);
+                }
+            }
+
+            // Return the result.
+            let result = Self::ResponseResult {
+                api_version,
+                blocks: blocks
+                    .into_iter()
+                    .map(JsonBlockWithContents::from)
+                    .collect(),
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
 /// Params for "chain_get_state_root_hash" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -455,7 +647,6 @@ async fn get_block_with_metadata<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
 ) -> Result<Option<(Block, BlockSignatures)>, warp_json_rpc::Error> {
     // Get the block from storage or the latest from the linear chain.
-    let getting_specific_block = maybe_id.is_some();
     let maybe_result = effect_builder
         .make_request(
             |responder| RpcRequest::GetBlock {
@@ -466,8 +657,24 @@ async fn get_block_with_metadata<REv: ReactorEventT>(
         )
         .await;
 
-    if maybe_result.is_none() && getting_specific_block {
-        info!("failed to get {:?} from storage", maybe_id.unwrap());
+    if let (None, Some(block_id)) = (&maybe_result, maybe_id) {
+        info!("failed to get {:?} from storage", block_id);
+        let item = match block_id {
+            BlockIdentifier::Hash(block_hash) => PrunableItem::Block(block_hash),
+            BlockIdentifier::Height(height) => PrunableItem::BlockAtHeight(height),
+        };
+        let is_pruned = effect_builder
+            .make_request(
+                |responder| RpcRequest::IsPruned { item, responder },
+                QueueKind::Api,
+            )
+            .await;
+        if is_pruned {
+            return Err(warp_json_rpc::Error::custom(
+                ErrorCode::Pruned as i64,
+                "block has been pruned",
+            ));
+        }
         return Err(warp_json_rpc::Error::custom(
             ErrorCode::NoSuchBlock as i64,
             "block not known",
@@ -0,0 +1,233 @@
+//! RPCs related to consensus.
+
+// TODO - remove once schemars stops causing warning.
+#![allow(clippy::field_reassign_with_default)]
+
+use futures::{future::BoxFuture, FutureExt};
+use http::Response;
+use hyper::Body;
+use once_cell::sync::Lazy;
+use schemars::JsonSchema;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use warp_json_rpc::Builder;
+
+use casper_types::{PublicKey, U512};
+
+use super::{
+    docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
+    Error, ReactorEventT, RpcRequest, RpcWithParams, RpcWithParamsExt, RpcWithoutParams,
+    RpcWithoutParamsExt,
+};
+use crate::{
+    components::consensus::{EquivocationStatus, EraId, ParticipationStatus, RoundSuccess},
+    effect::EffectBuilder,
+    reactor::QueueKind,
+    types::Timestamp,
+};
+
+static GET_VALIDATOR_STATUS_RESULT: Lazy<GetValidatorStatusResult> =
+    Lazy::new(|| GetValidatorStatusResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION.clone(),
+        our_public_key: *PublicKey::doc_example(),
+        eras: vec![EraStatus {
+            era_id: 42,
+            our_round_success: Some(RoundSuccess {
+                round_exponent: 16,
+                successful_rounds: 38,
+                total_rounds: 40,
+            }),
+            validators: vec![ValidatorStatus {
+                public_key: *PublicKey::doc_example(),
+                weight: U512::from(1_000_000_000_000u64),
+                last_unit_timestamp: Some(*Timestamp::doc_example()),
+                participation: ParticipationStatus::Active,
+                equivocation: EquivocationStatus::Honest,
+                round_exponent: Some(16),
+            }],
+        }],
+    });
+
+static GET_EVIDENCE_PARAMS: Lazy<GetEvidenceParams> = Lazy::new(|| GetEvidenceParams {
+    era_id: 42,
+    public_key: *PublicKey::doc_example(),
+});
+static GET_EVIDENCE_RESULT: Lazy<GetEvidenceResult> = Lazy::new(|| GetEvidenceResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION.clone(),
+    evidence: None,
+});
+
+/// The status of a validator in an era, as seen by this node.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorStatus {
+    /// The validator's public key.
+    pub public_key: PublicKey,
+    /// The validator's weight in the era.
+    pub weight: U512,
+    /// The timestamp of the validator's latest unit, unless it has none or is faulty.
+    pub last_unit_timestamp: Option<Timestamp>,
+    /// Whether the validator is taking part in the era.
+    pub participation: ParticipationStatus,
+    /// Whether the validator is known to have equivocated.
+    pub equivocation: EquivocationStatus,
+    /// The round exponent of the validator's latest unit, unless it has none or is faulty.
+    pub round_exponent: Option<u8>,
+}
+
+/// The status of the validators in a bonded era, as seen by this node.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EraStatus {
+    /// The era ID.
+    pub era_id: u64,
+    /// The success of this node's recent rounds, if it is an active validator in the era.
+    pub our_round_success: Option<RoundSuccess>,
+    /// The status of each validator.
+    pub validators: Vec<ValidatorStatus>,
+}
+
+/// Result for "consensus_get_validator_status" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetValidatorStatusResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: Version,
+    /// This node's public key.
+    pub our_public_key: PublicKey,
+    /// The status of the validators in the current and the other bonded eras, newest first.
+    pub eras: Vec<EraStatus>,
+}
+
+impl DocExample for GetValidatorStatusResult {
+    fn doc_example() -> &'static Self {
+        &*GET_VALIDATOR_STATUS_RESULT
+    }
+}
+
+/// "consensus_get_validator_status" RPC.
+pub struct GetValidatorStatus {}
+
+impl RpcWithoutParams for GetValidatorStatus {
+    const METHOD: &'static str = "consensus_get_validator_status";
+    type ResponseResult = GetValidatorStatusResult;
+}
+
+impl RpcWithoutParamsExt for GetValidatorStatus {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        api_version: Version,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let (our_public_key, era_statuses) = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetValidatorStatus { responder },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let eras = era_statuses
+                .into_iter()
+                .map(|era_status| EraStatus {
+                    era_id: era_status.era_id.0,
+                    our_round_success: era_status.our_round_success,
+                    validators: era_status
+                        .validators
+                        .into_iter()
+                        .map(|(weight, participation)| ValidatorStatus {
+                            public_key: participation.validator_id,
+                            weight,
+                            last_unit_timestamp: participation.last_unit_timestamp,
+                            participation: participation.participation,
+                            equivocation: participation.equivocation,
+                            round_exponent: participation.round_exp,
+                        })
+                        .collect(),
+                })
+                .collect();
+            let result = Self::ResponseResult {
+                api_version,
+                our_public_key,
+                eras,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "consensus_get_evidence" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEvidenceParams {
+    /// The era in which the validator equivocated.
+    pub era_id: u64,
+    /// The validator's public key.
+    pub public_key: PublicKey,
+}
+
+impl DocExample for GetEvidenceParams {
+    fn doc_example() -> &'static Self {
+        &*GET_EVIDENCE_PARAMS
+    }
+}
+
+/// Result for "consensus_get_evidence" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEvidenceResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: Version,
+    /// The hex-encoded evidence that the validator equivocated in the era, if this node holds
+    /// any.  It is encoded in the consensus protocol's `bincode` format and contains the
+    /// conflicting units or endorsements signed by the validator, so it can be verified against
+    /// the era's validator set without trusting this node.
+    pub evidence: Option<String>,
+}
+
+impl DocExample for GetEvidenceResult {
+    fn doc_example() -> &'static Self {
+        &*GET_EVIDENCE_RESULT
+    }
+}
+
+/// "consensus_get_evidence" RPC.
+pub struct GetEvidence {}
+
+impl RpcWithParams for GetEvidence {
+    const METHOD: &'static str = "consensus_get_evidence";
+    type RequestParams = GetEvidenceParams;
+    type ResponseResult = GetEvidenceResult;
+}
+
+impl RpcWithParamsExt for GetEvidence {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: Version,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let evidence = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetEvidence {
+                        era_id: EraId(params.era_id),
+                        public_key: params.public_key,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let result = Self::ResponseResult {
+                api_version,
+                evidence: evidence.map(hex::encode),
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
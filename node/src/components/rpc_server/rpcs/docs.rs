@@ -18,10 +18,11 @@ use serde_json::{json, Value};
 use warp_json_rpc::Builder;
 
 use super::{
-    account::PutDeploy,
-    chain::{GetBlock, GetBlockTransfers, GetStateRootHash},
-    info::{GetDeploy, GetPeers, GetStatus},
-    state::{GetAuctionInfo, GetBalance, GetItem},
+    account::{PutDeploy, SpeculativeExecute},
+    chain::{GetBlock, GetBlockRange, GetBlockTransfers, GetStateRootHash},
+    consensus::{GetEvidence, GetValidatorStatus},
+    info::{GetAccountDeploys, GetDeploy, GetPeers, GetPendingDeploys, GetStatus},
+    state::{GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem},
     Error, ReactorEventT, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
     RpcWithoutParamsExt,
 };
@@ -67,18 +68,39 @@ static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     };
 
     schema.push_with_params::<PutDeploy>("receives a Deploy to be executed by the network");
+    schema.push_with_params::<SpeculativeExecute>(
+        "executes a Deploy against global state without committing its effects",
+    );
     schema.push_with_params::<GetDeploy>("returns a Deploy from the network");
+    schema.push_with_params::<GetAccountDeploys>(
+        "returns the deploys sent by or transferring to or from an account, newest first",
+    );
+    schema.push_with_optional_params::<GetPendingDeploys>(
+        "returns the deploys held by the node which have not been executed yet",
+    );
     schema.push_without_params::<GetPeers>("returns a list of peers connected to the node");
     schema.push_without_params::<GetStatus>("returns the current status of the node");
+    schema.push_without_params::<GetValidatorStatus>(
+        "returns the participation of the validators in the current and recent eras",
+    );
+    schema.push_with_params::<GetEvidence>(
+        "returns the evidence that a validator equivocated in an era, if the node holds any",
+    );
     schema.push_with_optional_params::<GetBlock>("returns a Block from the network");
     schema.push_with_optional_params::<GetBlockTransfers>(
         "returns all transfers for a Block from the network",
     );
+    schema.push_with_params::<GetBlockRange>(
+        "returns the Blocks within a range of heights, optionally with their Deploys and Transfers",
+    );
     schema.push_with_optional_params::<GetStateRootHash>(
         "returns a state root hash at a given Block",
     );
     schema.push_with_params::<GetItem>("returns a stored value from the network");
     schema.push_with_params::<GetBalance>("returns a purse's balance from the network");
+    schema.push_with_params::<GetDictionaryItem>(
+        "returns an item from a Dictionary stored on the network",
+    );
     schema.push_with_optional_params::<GetEraInfoBySwitchBlock>(
         "returns an EraInfo from the network",
     );
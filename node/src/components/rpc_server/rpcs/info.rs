@@ -15,19 +15,25 @@ use serde::{Deserialize, Serialize};
 use tracing::info;
 use warp_json_rpc::Builder;
 
-use casper_types::ExecutionResult;
+use casper_types::{
+    account::AccountHash, bytesrepr::ToBytes, ExecutionResult, PublicKey, Transfer, Transform,
+};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
-    Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithParams, RpcWithParamsExt, RpcWithoutParams,
-    RpcWithoutParamsExt,
+    Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithOptionalParams, RpcWithOptionalParamsExt,
+    RpcWithParams, RpcWithParamsExt, RpcWithoutParams, RpcWithoutParamsExt,
 };
 use crate::{
+    components::storage::PrunableItem,
     effect::EffectBuilder,
     reactor::QueueKind,
-    types::{Block, BlockHash, Deploy, DeployHash, GetStatusResult, Item, PeersMap},
+    types::{Block, BlockHash, Deploy, DeployHash, DeployHeader, GetStatusResult, Item, PeersMap},
 };
 
+/// The maximum number of deploys returned by a single "info_get_account_deploys" request.
+const MAX_ACCOUNT_DEPLOYS_LIMIT: u64 = 100;
+
 static GET_DEPLOY_PARAMS: Lazy<GetDeployParams> = Lazy::new(|| GetDeployParams {
     deploy_hash: *Deploy::doc_example().id(),
 });
@@ -38,7 +44,40 @@ static GET_DEPLOY_RESULT: Lazy<GetDeployResult> = Lazy::new(|| GetDeployResult {
         block_hash: Block::doc_example().id(),
         result: ExecutionResult::example().clone(),
     }],
+    unresolved_dependencies: vec![],
 });
+static GET_ACCOUNT_DEPLOYS_PARAMS: Lazy<GetAccountDeploysParams> =
+    Lazy::new(|| GetAccountDeploysParams {
+        account_hash: PublicKey::doc_example().to_account_hash(),
+        offset: 0,
+        limit: 10,
+    });
+static GET_ACCOUNT_DEPLOYS_RESULT: Lazy<GetAccountDeploysResult> =
+    Lazy::new(|| GetAccountDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION.clone(),
+        deploys: vec![JsonAccountDeploy {
+            deploy_hash: *Deploy::doc_example().id(),
+            header: Deploy::doc_example().header().clone(),
+            execution_results: vec![JsonExecutionResult {
+                block_hash: Block::doc_example().id(),
+                result: ExecutionResult::example().clone(),
+            }],
+            transfers: vec![],
+        }],
+    });
+static GET_PENDING_DEPLOYS_PARAMS: Lazy<GetPendingDeploysParams> =
+    Lazy::new(|| GetPendingDeploysParams {
+        account: *PublicKey::doc_example(),
+    });
+static GET_PENDING_DEPLOYS_RESULT: Lazy<GetPendingDeploysResult> =
+    Lazy::new(|| GetPendingDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION.clone(),
+        pending_deploy_hashes: vec![*Deploy::doc_example().id()],
+        pending_count: 1,
+        pending_bytes: Deploy::doc_example().serialized_length() as u64,
+        finalized_unexecuted_deploy_hashes: vec![],
+        finalized_unexecuted_count: 0,
+    });
 static GET_PEERS_RESULT: Lazy<GetPeersResult> = Lazy::new(|| GetPeersResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION.clone(),
     peers: GetStatusResult::doc_example().peers.clone(),
@@ -79,6 +118,9 @@ pub struct GetDeployResult {
     pub deploy: Deploy,
     /// The map of block hash to execution result.
     pub execution_results: Vec<JsonExecutionResult>,
+    /// The dependencies of the deploy which have not been executed yet.  Always empty once the
+    /// deploy itself has been executed.
+    pub unresolved_dependencies: Vec<DeployHash>,
 }
 
 impl DocExample for GetDeployResult {
@@ -115,17 +157,36 @@ impl RpcWithParamsExt for GetDeploy {
                 )
                 .await;
 
-            let (deploy, metadata) = match maybe_deploy_and_metadata {
-                Some((deploy, metadata)) => (deploy, metadata),
+            let (deploy, metadata, unresolved_dependencies) = match maybe_deploy_and_metadata {
+                Some((deploy, metadata, unresolved_dependencies)) => {
+                    (deploy, metadata, unresolved_dependencies)
+                }
                 None => {
                     info!(
                         "failed to get {} and metadata from storage",
                         params.deploy_hash
                     );
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::NoSuchDeploy as i64,
-                        "deploy not known",
-                    ))?);
+                    let is_pruned = effect_builder
+                        .make_request(
+                            |responder| RpcRequest::IsPruned {
+                                item: PrunableItem::Deploy(params.deploy_hash),
+                                responder,
+                            },
+                            QueueKind::Api,
+                        )
+                        .await;
+                    let error = if is_pruned {
+                        warp_json_rpc::Error::custom(
+                            ErrorCode::Pruned as i64,
+                            "deploy has been pruned",
+                        )
+                    } else {
+                        warp_json_rpc::Error::custom(
+                            ErrorCode::NoSuchDeploy as i64,
+                            "deploy not known",
+                        )
+                    };
+                    return Ok(response_builder.error(error)?);
                 }
             };
 
@@ -140,6 +201,204 @@ impl RpcWithParamsExt for GetDeploy {
                 api_version,
                 deploy,
                 execution_results,
+                unresolved_dependencies,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "info_get_account_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysParams {
+    /// The account hash.
+    pub account_hash: AccountHash,
+    /// The number of the account's newest deploys to skip.
+    pub offset: u64,
+    /// The maximum number of deploys to return.  Values above 100 are treated as 100.
+    pub limit: u64,
+}
+
+impl DocExample for GetAccountDeploysParams {
+    fn doc_example() -> &'static Self {
+        &*GET_ACCOUNT_DEPLOYS_PARAMS
+    }
+}
+
+/// A deploy sent by an account or transferring to or from it.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JsonAccountDeploy {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The deploy header.
+    pub header: DeployHeader,
+    /// The map of block hash to execution result.  Empty if the deploy hasn't been executed yet.
+    pub execution_results: Vec<JsonExecutionResult>,
+    /// The transfers made by the deploy from or to the account.
+    pub transfers: Vec<Transfer>,
+}
+
+/// Result for "info_get_account_deploys" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: Version,
+    /// The deploys of the account, newest first.
+    pub deploys: Vec<JsonAccountDeploy>,
+}
+
+impl DocExample for GetAccountDeploysResult {
+    fn doc_example() -> &'static Self {
+        &*GET_ACCOUNT_DEPLOYS_RESULT
+    }
+}
+
+/// "info_get_account_deploys" RPC.
+pub struct GetAccountDeploys {}
+
+impl RpcWithParams for GetAccountDeploys {
+    const METHOD: &'static str = "info_get_account_deploys";
+    type RequestParams = GetAccountDeploysParams;
+    type ResponseResult = GetAccountDeploysResult;
+}
+
+impl RpcWithParamsExt for GetAccountDeploys {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: Version,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let account_hash = params.account_hash;
+            let account_deploys = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetAccountDeploys {
+                        account_hash,
+                        offset: params.offset as usize,
+                        limit: params.limit.min(MAX_ACCOUNT_DEPLOYS_LIMIT) as usize,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let deploys = account_deploys
+                .into_iter()
+                .map(|(deploy_hash, header, metadata)| {
+                    let mut transfers = vec![];
+                    let mut execution_results = vec![];
+                    for (block_hash, result) in metadata.execution_results {
+                        if let ExecutionResult::Success { effect, .. } = &result {
+                            transfers.extend(effect.transforms.iter().filter_map(|entry| {
+                                match &entry.transform {
+                                    Transform::WriteTransfer(transfer)
+                                        if transfer.from == account_hash
+                                            || transfer.to == Some(account_hash) =>
+                                    {
+                                        Some(*transfer)
+                                    }
+                                    _ => None,
+                                }
+                            }));
+                        }
+                        execution_results.push(JsonExecutionResult { block_hash, result });
+                    }
+                    JsonAccountDeploy {
+                        deploy_hash,
+                        header,
+                        execution_results,
+                        transfers,
+                    }
+                })
+                .collect();
+
+            let result = Self::ResponseResult {
+                api_version,
+                deploys,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Params for "info_get_pending_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetPendingDeploysParams {
+    /// Only list deploys created by this account.
+    pub account: PublicKey,
+}
+
+impl DocExample for GetPendingDeploysParams {
+    fn doc_example() -> &'static Self {
+        &*GET_PENDING_DEPLOYS_PARAMS
+    }
+}
+
+/// Result for "info_get_pending_deploys" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetPendingDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: Version,
+    /// The deploys waiting to be included in a block, in the order they would be proposed.
+    pub pending_deploy_hashes: Vec<DeployHash>,
+    /// The number of deploys waiting to be included in a block.
+    pub pending_count: u64,
+    /// The total serialized size in bytes of the deploys waiting to be included in a block.
+    pub pending_bytes: u64,
+    /// The deploys included in a finalized block, but not executed yet.
+    pub finalized_unexecuted_deploy_hashes: Vec<DeployHash>,
+    /// The number of deploys included in a finalized block, but not executed yet.
+    pub finalized_unexecuted_count: u64,
+}
+
+impl DocExample for GetPendingDeploysResult {
+    fn doc_example() -> &'static Self {
+        &*GET_PENDING_DEPLOYS_RESULT
+    }
+}
+
+/// "info_get_pending_deploys" RPC.
+pub struct GetPendingDeploys {}
+
+impl RpcWithOptionalParams for GetPendingDeploys {
+    const METHOD: &'static str = "info_get_pending_deploys";
+    type OptionalRequestParams = GetPendingDeploysParams;
+    type ResponseResult = GetPendingDeploysResult;
+}
+
+impl RpcWithOptionalParamsExt for GetPendingDeploys {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        maybe_params: Option<Self::OptionalRequestParams>,
+        api_version: Version,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let account = maybe_params.map(|params| params.account);
+            let pending_deploys = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetPendingDeploys { account, responder },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let result = Self::ResponseResult {
+                api_version,
+                pending_count: pending_deploys.pending.len() as u64,
+                pending_deploy_hashes: pending_deploys.pending,
+                pending_bytes: pending_deploys.pending_bytes,
+                finalized_unexecuted_count: pending_deploys.finalized_unexecuted.len() as u64,
+                finalized_unexecuted_deploy_hashes: pending_deploys.finalized_unexecuted,
             };
             Ok(response_builder.success(result)?)
         }
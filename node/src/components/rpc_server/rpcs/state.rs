@@ -36,6 +36,10 @@ use crate::{
     },
 };
 
+const DOCS_EXAMPLE_DICTIONARY_SEED_UREF: &str =
+    "uref-09480c3248ef76b603d386f3f4f8a5f87f597d4eaffd475433f861af187ab5db-007";
+const DOCS_EXAMPLE_DICTIONARY_ITEM_KEY: &str = "a_unique_entry_identifier";
+
 static GET_ITEM_PARAMS: Lazy<GetItemParams> = Lazy::new(|| GetItemParams {
     state_root_hash: *Block::doc_example().header().state_root_hash(),
     key: "deploy-af684263911154d26fa05be9963171802801a0b6aff8f199b7391eacb8edc9e1".to_string(),
@@ -56,6 +60,22 @@ static GET_BALANCE_RESULT: Lazy<GetBalanceResult> = Lazy::new(|| GetBalanceResul
     balance_value: U512::from(123_456),
     merkle_proof: MERKLE_PROOF.clone(),
 });
+static GET_DICTIONARY_ITEM_PARAMS: Lazy<GetDictionaryItemParams> =
+    Lazy::new(|| GetDictionaryItemParams {
+        state_root_hash: *Block::doc_example().header().state_root_hash(),
+        dictionary_seed_uref: DOCS_EXAMPLE_DICTIONARY_SEED_UREF.to_string(),
+        dictionary_item_key: DOCS_EXAMPLE_DICTIONARY_ITEM_KEY.to_string(),
+    });
+static GET_DICTIONARY_ITEM_RESULT: Lazy<GetDictionaryItemResult> = Lazy::new(|| {
+    let seed_uref = URef::from_formatted_str(DOCS_EXAMPLE_DICTIONARY_SEED_UREF).unwrap();
+    let dictionary_key = Key::dictionary(seed_uref, DOCS_EXAMPLE_DICTIONARY_ITEM_KEY.as_bytes());
+    GetDictionaryItemResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION.clone(),
+        dictionary_key: dictionary_key.to_formatted_string(),
+        stored_value: StoredValue::CLValue(CLValue::from_t(1u64).unwrap()),
+        merkle_proof: MERKLE_PROOF.clone(),
+    }
+});
 static GET_AUCTION_INFO_RESULT: Lazy<GetAuctionInfoResult> = Lazy::new(|| GetAuctionInfoResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION.clone(),
     auction_state: AuctionState::doc_example().clone(),
@@ -284,6 +304,113 @@ impl RpcWithParamsExt for GetBalance {
     }
 }
 
+/// Params for "state_get_dictionary_item" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDictionaryItemParams {
+    /// Hash of the state root.
+    pub state_root_hash: Digest,
+    /// The dictionary's seed URef, as formatted string.
+    pub dictionary_seed_uref: String,
+    /// The key under which the item is stored in the dictionary.
+    pub dictionary_item_key: String,
+}
+
+impl DocExample for GetDictionaryItemParams {
+    fn doc_example() -> &'static Self {
+        &*GET_DICTIONARY_ITEM_PARAMS
+    }
+}
+
+/// Result for "state_get_dictionary_item" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDictionaryItemResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: Version,
+    /// The global state key of the dictionary item, as formatted string.
+    pub dictionary_key: String,
+    /// The stored value.
+    pub stored_value: StoredValue,
+    /// The merkle proof.
+    pub merkle_proof: String,
+}
+
+impl DocExample for GetDictionaryItemResult {
+    fn doc_example() -> &'static Self {
+        &*GET_DICTIONARY_ITEM_RESULT
+    }
+}
+
+/// "state_get_dictionary_item" RPC.
+pub struct GetDictionaryItem {}
+
+impl RpcWithParams for GetDictionaryItem {
+    const METHOD: &'static str = "state_get_dictionary_item";
+    type RequestParams = GetDictionaryItemParams;
+    type ResponseResult = GetDictionaryItemResult;
+}
+
+impl RpcWithParamsExt for GetDictionaryItem {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: Version,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            // Try to parse the dictionary's seed URef from the params.
+            let seed_uref = match URef::from_formatted_str(&params.dictionary_seed_uref)
+                .map_err(|error| format!("failed to parse dictionary_seed_uref: {:?}", error))
+            {
+                Ok(uref) => uref,
+                Err(error_msg) => {
+                    info!("{}", error_msg);
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::ParseDictionarySeedURef as i64,
+                        error_msg,
+                    ))?);
+                }
+            };
+
+            let dictionary_key = Key::dictionary(seed_uref, params.dictionary_item_key.as_bytes());
+
+            // Run the query.
+            let query_result = effect_builder
+                .make_request(
+                    |responder| RpcRequest::QueryGlobalState {
+                        state_root_hash: params.state_root_hash,
+                        base_key: dictionary_key,
+                        path: vec![],
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let (stored_value, proof_bytes) = match common::extract_query_result(query_result) {
+                Ok(tuple) => tuple,
+                Err((error_code, error_msg)) => {
+                    info!("{}", error_msg);
+                    return Ok(response_builder
+                        .error(warp_json_rpc::Error::custom(error_code as i64, error_msg))?);
+                }
+            };
+
+            let result = Self::ResponseResult {
+                api_version,
+                dictionary_key: dictionary_key.to_formatted_string(),
+                stored_value,
+                merkle_proof: hex::encode(proof_bytes),
+            };
+
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
 /// Result for "state_get_auction_info" RPC response.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
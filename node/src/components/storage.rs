@@ -7,8 +7,10 @@
 //! * storing and loading deploys,
 //! * [temporary until refactored] holding `DeployMetadata` for each deploy,
 //! * holding a read-only copy of the chainspec,
-//! * keeping an index of blocks by height and
-//! * [unimplemented] managing disk usage by pruning blocks and deploys from storage.
+//! * keeping an index of blocks by height,
+//! * keeping an index of deploys by the accounts involved in them,
+//! * holding the evidence of validators' equivocations, by era, and
+//! * optionally managing disk usage by pruning block bodies and deploys from storage.
 //!
 //! Any I/O performed by the component is done on the event handling thread, this is on purpose as
 //! the assumption is that caching by LMDB will offset any gains from offloading it onto a separate
@@ -28,8 +30,42 @@
 //!
 //! ## Indices
 //!
-//! The current implementation keeps only in-memory indices, which are not persisted, based upon the
-//! estimate that they are reasonably quick to rebuild on start-up and do not take up much memory.
+//! The block indices are kept in-memory only and are not persisted, based upon the estimate that
+//! they are reasonably quick to rebuild on start-up and do not take up much memory.
+//!
+//! The index of deploys by account is persisted in its own database, as rebuilding it would
+//! require reading every stored deploy and execution result. It is keyed by the account hash, the
+//! deploy's timestamp and the deploy hash, with the timestamp stored such that the entries of an
+//! account are ordered newest first.
+//!
+//! ## Evidence
+//!
+//! Evidence against equivocating validators is kept in its own database, keyed by the era ID and
+//! the hash of the validator's public key. Storage treats it as opaque, already serialized bytes,
+//! and retains the first evidence stored for each validator and era. Evidence is never pruned, so
+//! that slashings remain auditable.
+//!
+//! ## Pruning
+//!
+//! If `retained_block_body_eras` is set, the bodies of blocks from eras older than that many of the
+//! most recently completed ones are pruned whenever a switch block is stored, along with their
+//! deploys, the deploys' metadata and the blocks' transfers. Block headers and finality signatures
+//! are retained for all blocks, as are switch blocks in their entirety. The retained eras must
+//! cover the maximum deploy TTL, as the block proposer reads the deploys of unexpired blocks from
+//! their bodies.
+//!
+//! The height below which blocks have been pruned is persisted, and the hashes of pruned deploys
+//! are kept in their own database, such that requests for pruned items can be told apart from
+//! requests for items which were never stored.
+//!
+//! ## Disk usage
+//!
+//! All databases share a single memory map, which is grown online in steps of
+//! `map_size_growth_step` whenever less than one step of it remains unused before a write. The sum
+//! of the configured maximum store sizes is a hard ceiling on the size of the map: once it is
+//! reached, the map is no longer grown and writes fail when it fills up. The usage of the map and
+//! of each database is exported as metrics, and a warning is logged while the map is close to its
+//! ceiling.
 //!
 //! ## Errors
 //!
@@ -37,36 +73,40 @@
 //! Corruption, temporary resource exhaustion and potential bugs.
 
 mod lmdb_ext;
+mod metrics;
 #[cfg(test)]
 mod tests;
 
 #[cfg(test)]
-use std::{collections::BTreeSet, convert::TryFrom};
+use std::collections::BTreeSet;
 use std::{
-    collections::{btree_map::Entry, BTreeMap},
+    cmp,
+    collections::{btree_map::Entry, BTreeMap, HashMap},
+    convert::TryFrom,
     fmt::{self, Display, Formatter},
     fs, io, mem,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 use datasize::DataSize;
 use derive_more::From;
 use lmdb::{
-    Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, Transaction, WriteFlags,
+    Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, RwTransaction, Transaction,
+    WriteFlags,
 };
+use prometheus::Registry;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use static_assertions::const_assert;
 #[cfg(test)]
 use tempfile::TempDir;
 use thiserror::Error;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use super::Component;
-#[cfg(test)]
-use crate::crypto::hash::Digest;
 use crate::{
     components::consensus::EraId,
+    crypto::hash::Digest,
     effect::{
         requests::{StateStoreRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
@@ -74,15 +114,19 @@ use crate::{
     fatal,
     reactor::ReactorEvent,
     types::{
-        Block, BlockBody, BlockHash, BlockHeader, BlockSignatures, Deploy, DeployHash,
-        DeployHeader, DeployMetadata, TimeDiff,
+        Block, BlockBody, BlockHash, BlockHeader, BlockSignatures, Chainspec, Deploy, DeployHash,
+        DeployHeader, DeployMetadata, TimeDiff, Timestamp,
     },
     utils::WithDir,
     NodeRng,
 };
 use casper_execution_engine::shared::newtypes::Blake2bHash;
-use casper_types::{ExecutionResult, ProtocolVersion, Transfer, Transform};
-use lmdb_ext::{LmdbExtError, TransactionExt, WriteTransactionExt};
+use casper_types::{
+    account::{AccountHash, ACCOUNT_HASH_LENGTH},
+    ExecutionResult, ProtocolVersion, PublicKey, Transfer, Transform,
+};
+use lmdb_ext::{EnvironmentExt, LmdbExtError, TransactionExt, WriteTransactionExt};
+use metrics::StorageMetrics;
 
 /// Filename for the LMDB database created by the Storage component.
 const STORAGE_DB_FILENAME: &str = "storage.lmdb";
@@ -102,8 +146,21 @@ const DEFAULT_MAX_DEPLOY_STORE_SIZE: usize = 300 * GIB;
 const DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE: usize = 300 * GIB;
 /// Default max state store size.
 const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
+/// Default step by which the memory map is grown.
+const DEFAULT_MAP_SIZE_GROWTH_STEP: usize = GIB;
+/// Percentage of the maximum map size in use above which a warning is logged.
+const MAP_USAGE_WARNING_PERCENT: usize = 90;
 /// Maximum number of allowed dbs.
-const MAX_DB_COUNT: u32 = 7;
+const MAX_DB_COUNT: u32 = 10;
+/// Length of a key in the account deploys index: account hash, inverted timestamp and deploy hash.
+const ACCOUNT_DEPLOYS_KEY_LENGTH: usize = ACCOUNT_HASH_LENGTH + 8 + Digest::LENGTH;
+/// Length of a key in the evidence database: era ID and the hash of the validator's public key.
+const EVIDENCE_KEY_LENGTH: usize = 8 + ACCOUNT_HASH_LENGTH;
+/// Key in the state store under which the height of the lowest block whose body is retained is
+/// stored.
+const LOWEST_RETAINED_HEIGHT_KEY: &[u8] = b"storage_lowest_retained_block_height";
+/// Maximum number of blocks whose bodies are pruned in a single write transaction.
+const PRUNE_BATCH_SIZE: usize = 1000;
 
 /// OS-specific lmdb flags.
 #[cfg(not(target_os = "macos"))]
@@ -128,6 +185,27 @@ pub enum Event {
     StateStoreRequest(StateStoreRequest),
 }
 
+/// An item which may have been pruned from storage.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub enum PrunableItem {
+    /// The block with the given hash, along with its transfers.
+    Block(BlockHash),
+    /// The block at the given height, along with its transfers.
+    BlockAtHeight(u64),
+    /// The deploy with the given hash, along with its metadata.
+    Deploy(DeployHash),
+}
+
+impl Display for PrunableItem {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PrunableItem::Block(block_hash) => write!(formatter, "block {}", block_hash),
+            PrunableItem::BlockAtHeight(height) => write!(formatter, "block at height {}", height),
+            PrunableItem::Deploy(deploy_hash) => write!(formatter, "deploy {}", deploy_hash),
+        }
+    }
+}
+
 /// A storage component initialization error.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -154,9 +232,36 @@ pub enum Error {
         /// Second block hash encountered at `era_id`.
         second: BlockHash,
     },
+    /// Block bodies would be pruned while the TTL of their deploys hasn't expired yet.
+    #[error(
+        "retained_block_body_eras is {retained_block_body_eras}, but must be at least {required} \
+         to cover the maximum deploy TTL"
+    )]
+    InsufficientBlockBodyRetention {
+        /// The configured number of retained eras.
+        retained_block_body_eras: u64,
+        /// The minimum number of retained eras covering the maximum deploy TTL.
+        required: u64,
+    },
+    /// The body of a block whose deploys' TTL hasn't expired yet has been pruned.
+    #[error("body of block {0} pruned while its deploys may still be unexpired")]
+    PrunedBlockWithinDeployTtl(BlockHash),
+    /// Found a database entry which cannot be decoded, is not stored under its hash or is invalid.
+    #[error("corrupt entry {key} in {database} database: {error}")]
+    CorruptEntry {
+        /// The name of the database.
+        database: &'static str,
+        /// The hex-encoded key of the entry.
+        key: String,
+        /// What is wrong with the entry.
+        error: String,
+    },
     /// LMDB error while operating.
     #[error("internal database error: {0}")]
     InternalStorage(#[from] LmdbExtError),
+    /// Failure to register the storage metrics.
+    #[error("failed to register metrics: {0}")]
+    Metrics(#[from] prometheus::Error),
 }
 
 // We wholesale wrap lmdb errors and treat them as internal errors here.
@@ -191,13 +296,35 @@ pub struct Storage {
     /// The transfer database.
     #[data_size(skip)]
     transfer_db: Database,
+    /// The index of deploys by the accounts which sent them or took part in their transfers.
+    #[data_size(skip)]
+    account_deploys_db: Database,
     /// The state storage database.
     #[data_size(skip)]
     state_store_db: Database,
+    /// The hashes of deploys which have been pruned.
+    #[data_size(skip)]
+    pruned_deploy_db: Database,
+    /// The evidence against equivocating validators, by era.
+    #[data_size(skip)]
+    evidence_db: Database,
+    /// The number of most recently completed eras whose block bodies are retained, if pruning.
+    retained_block_body_eras: Option<u64>,
+    /// The height of the lowest block whose body is retained, unless it is a switch block.
+    lowest_retained_height: u64,
     /// A map of block height to block ID.
     block_height_index: BTreeMap<u64, BlockHash>,
     /// A map of era ID to switch block ID.
     switch_block_era_id_index: BTreeMap<EraId, BlockHash>,
+    /// The step by which the memory map is grown.
+    map_size_growth_step: usize,
+    /// The size the memory map may not be grown beyond.
+    max_map_size: usize,
+    /// The percentage of the maximum map size in use at which a warning was last logged.
+    last_warned_usage_percent: usize,
+    /// Metrics of the disk usage of storage.
+    #[data_size(skip)]
+    metrics: StorageMetrics,
 }
 
 impl<REv> Component<REv> for Storage
@@ -236,6 +363,7 @@ impl Storage {
         cfg: &WithDir<Config>,
         hard_reset_to_start_of_era: Option<EraId>,
         version: Version,
+        registry: &Registry,
     ) -> Result<Self, Error> {
         let config = cfg.value();
 
@@ -247,10 +375,10 @@ impl Storage {
         }
 
         // Calculate the upper bound for the memory map that is potentially used.
-        let total_size = config
-            .max_block_store_size
-            .saturating_add(config.max_deploy_store_size)
-            .saturating_add(config.max_deploy_metadata_store_size);
+        let max_map_size = config.max_map_size();
+        // The map starts out at a single growth step, LMDB enlarges it to cover any existing data
+        // on opening and it is grown further as needed.
+        let initial_map_size = cmp::min(config.map_size_growth_step, max_map_size);
 
         // Creates the environment and databases.
         let env = Environment::new()
@@ -263,16 +391,21 @@ impl Storage {
             )
             .set_max_readers(MAX_TRANSACTIONS)
             .set_max_dbs(MAX_DB_COUNT)
-            .set_map_size(total_size)
+            .set_map_size(initial_map_size)
             .open(&root.join(STORAGE_DB_FILENAME))?;
+        // Make room for the reindexing below, which may need to write.
+        grow_map_if_needed(&env, config.map_size_growth_step, max_map_size)?;
 
         let block_header_db = env.create_db(Some("block_header"), DatabaseFlags::empty())?;
         let block_metadata_db = env.create_db(Some("block_metadata"), DatabaseFlags::empty())?;
         let deploy_db = env.create_db(Some("deploys"), DatabaseFlags::empty())?;
         let deploy_metadata_db = env.create_db(Some("deploy_metadata"), DatabaseFlags::empty())?;
         let transfer_db = env.create_db(Some("transfer"), DatabaseFlags::empty())?;
+        let account_deploys_db = env.create_db(Some("account_deploys"), DatabaseFlags::empty())?;
         let state_store_db = env.create_db(Some("state_store"), DatabaseFlags::empty())?;
         let block_body_db = env.create_db(Some("block_body"), DatabaseFlags::empty())?;
+        let pruned_deploy_db = env.create_db(Some("pruned_deploys"), DatabaseFlags::empty())?;
+        let evidence_db = env.create_db(Some("evidence"), DatabaseFlags::empty())?;
 
         // We now need to restore the block-height index. Log messages allow timing here.
         info!("reindexing block store");
@@ -321,7 +454,16 @@ impl Storage {
         // Check the integrity of the block metadata database.
         check_block_metadata_db(&env, &block_metadata_db)?;
 
-        Ok(Storage {
+        let lowest_retained_height = {
+            let txn = env.begin_ro_txn()?;
+            match txn.get(state_store_db, &LOWEST_RETAINED_HEIGHT_KEY) {
+                Ok(raw) => lmdb_ext::deserialize(raw)?,
+                Err(lmdb::Error::NotFound) => 0,
+                Err(err) => return Err(err.into()),
+            }
+        };
+
+        let mut storage = Storage {
             root,
             env,
             block_header_db,
@@ -330,10 +472,189 @@ impl Storage {
             deploy_db,
             deploy_metadata_db,
             transfer_db,
+            account_deploys_db,
             state_store_db,
+            pruned_deploy_db,
+            evidence_db,
+            retained_block_body_eras: config.retained_block_body_eras,
+            lowest_retained_height,
             block_height_index,
             switch_block_era_id_index,
-        })
+            map_size_growth_step: config.map_size_growth_step,
+            max_map_size,
+            last_warned_usage_percent: 0,
+            metrics: StorageMetrics::new(registry.clone())?,
+        };
+        storage.metrics.max_map_size.set(max_map_size as i64);
+
+        // Catch up on any pruning missed, e.g. if it has just been enabled.
+        storage.prune_block_bodies()?;
+        storage.update_usage()?;
+        Ok(storage)
+    }
+
+    /// Opens existing storage read-only, e.g. to check its integrity while the node is stopped.
+    ///
+    /// Unlike `new`, this never writes: the memory map is not grown and nothing is pruned.
+    /// Inconsistencies in the block header, block body and block metadata databases don't abort
+    /// opening, but are returned along with the storage.  Blocks with corrupt or duplicate headers
+    /// are left out of the indices.
+    pub(crate) fn open_read_only(
+        cfg: &WithDir<Config>,
+        registry: &Registry,
+    ) -> Result<(Self, Vec<Error>), Error> {
+        let config = cfg.value();
+        let root = cfg.with_dir(config.path.clone());
+        let max_map_size = config.max_map_size();
+
+        // LMDB enlarges the map to cover the existing data on opening.
+        let env = Environment::new()
+            .set_flags(
+                EnvironmentFlags::READ_ONLY
+                    | EnvironmentFlags::NO_SUB_DIR
+                    | EnvironmentFlags::NO_TLS,
+            )
+            .set_max_readers(MAX_TRANSACTIONS)
+            .set_max_dbs(MAX_DB_COUNT)
+            .open(&root.join(STORAGE_DB_FILENAME))?;
+
+        let block_header_db = env.open_db(Some("block_header"))?;
+        let block_metadata_db = env.open_db(Some("block_metadata"))?;
+        let deploy_db = env.open_db(Some("deploys"))?;
+        let deploy_metadata_db = env.open_db(Some("deploy_metadata"))?;
+        let transfer_db = env.open_db(Some("transfer"))?;
+        let account_deploys_db = env.open_db(Some("account_deploys"))?;
+        let state_store_db = env.open_db(Some("state_store"))?;
+        let block_body_db = env.open_db(Some("block_body"))?;
+        let pruned_deploy_db = env.open_db(Some("pruned_deploys"))?;
+        let evidence_db = env.open_db(Some("evidence"))?;
+
+        info!("indexing block store");
+        let mut corruptions = Vec::new();
+        let mut block_height_index = BTreeMap::new();
+        let mut switch_block_era_id_index = BTreeMap::new();
+        {
+            let txn = env.begin_ro_txn()?;
+            let mut cursor = txn.open_ro_cursor(block_header_db)?;
+            for (raw_key, raw_val) in cursor.iter() {
+                let corrupt_entry = |error: String| Error::CorruptEntry {
+                    database: "block_header",
+                    key: hex::encode(raw_key),
+                    error,
+                };
+                let block_header: BlockHeader = match lmdb_ext::deserialize(raw_val) {
+                    Ok(block_header) => block_header,
+                    Err(error) => {
+                        corruptions.push(corrupt_entry(error.to_string()));
+                        continue;
+                    }
+                };
+                if raw_key != block_header.hash().as_ref() {
+                    let error = format!("stored header has hash {}", block_header.hash());
+                    corruptions.push(corrupt_entry(error));
+                    continue;
+                }
+                if let Err(error) = insert_to_block_header_indices(
+                    &mut block_height_index,
+                    &mut switch_block_era_id_index,
+                    &block_header,
+                ) {
+                    corruptions.push(error);
+                }
+            }
+        }
+        info!("block store indexing complete");
+
+        corruptions.extend(find_corrupt_block_bodies(&env, &block_body_db)?);
+        corruptions.extend(find_corrupt_block_signatures(&env, &block_metadata_db)?);
+
+        let lowest_retained_height = {
+            let txn = env.begin_ro_txn()?;
+            match txn.get(state_store_db, &LOWEST_RETAINED_HEIGHT_KEY) {
+                Ok(raw) => lmdb_ext::deserialize(raw)?,
+                Err(lmdb::Error::NotFound) => 0,
+                Err(err) => return Err(err.into()),
+            }
+        };
+
+        let storage = Storage {
+            root,
+            env,
+            block_header_db,
+            block_body_db,
+            block_metadata_db,
+            deploy_db,
+            deploy_metadata_db,
+            transfer_db,
+            account_deploys_db,
+            state_store_db,
+            pruned_deploy_db,
+            evidence_db,
+            retained_block_body_eras: config.retained_block_body_eras,
+            lowest_retained_height,
+            block_height_index,
+            switch_block_era_id_index,
+            map_size_growth_step: config.map_size_growth_step,
+            max_map_size,
+            last_warned_usage_percent: 0,
+            metrics: StorageMetrics::new(registry.clone())?,
+        };
+        Ok((storage, corruptions))
+    }
+
+    /// Begins a write transaction, first growing the memory map if less than a growth step of it
+    /// remains unused.
+    ///
+    /// All write transactions of storage must be started through this method.
+    fn begin_rw_txn(&self) -> Result<RwTransaction<'_>, Error> {
+        if let Some(new_map_size) =
+            grow_map_if_needed(&self.env, self.map_size_growth_step, self.max_map_size)?
+        {
+            self.metrics.map_size.set(new_map_size as i64);
+        }
+        Ok(self.env.begin_rw_txn()?)
+    }
+
+    /// Updates the disk usage metrics, logging a warning if the memory map is close to its maximum
+    /// size.
+    fn update_usage(&mut self) -> Result<(), Error> {
+        let usage = self.env.map_usage()?;
+        self.metrics.map_size.set(usage.map_size as i64);
+        self.metrics.map_used.set(usage.used as i64);
+
+        let txn = self.env.begin_ro_txn()?;
+        for (db_name, db) in &[
+            ("block_header", self.block_header_db),
+            ("block_body", self.block_body_db),
+            ("block_metadata", self.block_metadata_db),
+            ("deploys", self.deploy_db),
+            ("deploy_metadata", self.deploy_metadata_db),
+            ("transfer", self.transfer_db),
+            ("account_deploys", self.account_deploys_db),
+            ("state_store", self.state_store_db),
+            ("pruned_deploys", self.pruned_deploy_db),
+            ("evidence", self.evidence_db),
+        ] {
+            let db_usage = txn.db_usage(*db)?;
+            self.metrics
+                .set_db_usage(db_name, db_usage.entries, db_usage.used);
+        }
+        txn.commit()?;
+
+        // Warn once for every further percent used above the threshold.
+        let usage_percent = usage.used.saturating_mul(100) / cmp::max(self.max_map_size, 1);
+        if usage_percent < MAP_USAGE_WARNING_PERCENT {
+            self.last_warned_usage_percent = 0;
+        } else if usage_percent > self.last_warned_usage_percent {
+            warn!(
+                used = usage.used,
+                max_map_size = self.max_map_size,
+                "storage is {}% full, consider pruning or raising the maximum store sizes",
+                usage_percent
+            );
+            self.last_warned_usage_percent = usage_percent;
+        }
+        Ok(())
     }
 
     /// Handles a state store request.
@@ -353,7 +674,7 @@ impl Storage {
                 data,
                 responder,
             } => {
-                let mut txn = self.env.begin_rw_txn()?;
+                let mut txn = self.begin_rw_txn()?;
                 txn.put(self.state_store_db, &key, &data, WriteFlags::default())?;
                 txn.commit()?;
                 Ok(responder.respond(()).ignore())
@@ -370,6 +691,11 @@ impl Storage {
         }
     }
 
+    /// Returns the directory in which the storage files are kept.
+    pub(crate) fn root_path(&self) -> &Path {
+        &self.root
+    }
+
     /// Reads from the state storage DB.
     /// If key is non-empty, returns bytes from under the key. Otherwise returns `Ok(None)`.
     /// May also fail with storage errors.
@@ -393,7 +719,7 @@ impl Storage {
     where
         K: AsRef<[u8]>,
     {
-        let mut txn = self.env.begin_rw_txn()?;
+        let mut txn = self.begin_rw_txn()?;
         let result = match txn.del(self.state_store_db, &key, None) {
             Ok(_) => Ok(true),
             Err(lmdb::Error::NotFound) => Ok(false),
@@ -403,6 +729,457 @@ impl Storage {
         Ok(result)
     }
 
+    /// Writes a block to storage and updates the block indices.
+    ///
+    /// Returns `true` if the block was stored successfully.
+    pub(crate) fn write_block(&mut self, block: &Block) -> Result<bool, Error> {
+        let mut txn = self.begin_rw_txn()?;
+        if !txn.put_value(
+            self.block_body_db,
+            block.header().body_hash(),
+            block.body(),
+            true,
+        )? {
+            error!("Could not insert block body for block: {}", block);
+            txn.abort();
+            return Ok(false);
+        }
+        if !txn.put_value(self.block_header_db, block.hash(), block.header(), true)? {
+            error!("Could not insert block header for block: {}", block);
+            txn.abort();
+            return Ok(false);
+        }
+        txn.commit()?;
+        insert_to_block_header_indices(
+            &mut self.block_height_index,
+            &mut self.switch_block_era_id_index,
+            block.header(),
+        )?;
+        if block.header().is_switch_block() {
+            self.prune_block_bodies()?;
+        }
+        self.update_usage()?;
+        Ok(true)
+    }
+
+    /// Prunes the bodies of non-switch blocks from eras which are no longer retained, along with
+    /// their deploys, deploy metadata and transfers.
+    ///
+    /// Does nothing unless `retained_block_body_eras` is set.
+    fn prune_block_bodies(&mut self) -> Result<(), Error> {
+        let retained_eras = match self.retained_block_body_eras {
+            Some(retained_eras) => retained_eras,
+            None => return Ok(()),
+        };
+        let latest_completed_era = match self.switch_block_era_id_index.keys().last() {
+            Some(era_id) => era_id.0,
+            None => return Ok(()),
+        };
+        // The bodies of the current era and of the `retained_eras` most recently completed eras
+        // are retained.
+        let first_retained_era = (latest_completed_era + 1).saturating_sub(retained_eras);
+        if first_retained_era == 0 {
+            return Ok(());
+        }
+        let previous_switch_block_hash = match self
+            .switch_block_era_id_index
+            .range(..EraId(first_retained_era))
+            .next_back()
+        {
+            Some((_, block_hash)) => *block_hash,
+            None => return Ok(()),
+        };
+
+        // Blocks are retained from the one following the last switch block before the first
+        // retained era.
+        let lowest_retained_height = match self
+            .get_block_header(&mut self.env.begin_ro_txn()?, &previous_switch_block_hash)?
+        {
+            Some(block_header) => block_header.height() + 1,
+            None => return Ok(()),
+        };
+        if lowest_retained_height <= self.lowest_retained_height {
+            return Ok(());
+        }
+
+        // Prune in batches, so that catching up on a long history doesn't hold a single write
+        // transaction over all of it. The progress is persisted with each batch.
+        let mut pruned_block_count = 0;
+        while self.lowest_retained_height < lowest_retained_height {
+            let mut heights = self
+                .block_height_index
+                .range(self.lowest_retained_height..lowest_retained_height);
+            let batch: Vec<BlockHash> = heights
+                .by_ref()
+                .take(PRUNE_BATCH_SIZE)
+                .map(|(_, block_hash)| *block_hash)
+                .collect();
+            let batch_end = heights
+                .next()
+                .map_or(lowest_retained_height, |(height, _)| *height);
+
+            let mut txn = self.begin_rw_txn()?;
+            for block_hash in &batch {
+                if self.prune_block_body(&mut txn, block_hash)? {
+                    pruned_block_count += 1;
+                }
+            }
+            txn.put_value(
+                self.state_store_db,
+                &LOWEST_RETAINED_HEIGHT_KEY,
+                &batch_end,
+                true,
+            )?;
+            txn.commit()?;
+            self.lowest_retained_height = batch_end;
+        }
+
+        info!(
+            pruned_block_count,
+            lowest_retained_height, "pruned block bodies from storage"
+        );
+        Ok(())
+    }
+
+    /// Deletes the body of the given block, unless it is a switch block, along with its deploys,
+    /// their metadata and index entries, and its transfers.
+    ///
+    /// Returns `true` if the block was pruned.
+    fn prune_block_body(
+        &self,
+        txn: &mut RwTransaction,
+        block_hash: &BlockHash,
+    ) -> Result<bool, LmdbExtError> {
+        let block_header: BlockHeader = match txn.get_value(self.block_header_db, block_hash)? {
+            Some(block_header) => block_header,
+            None => return Ok(false),
+        };
+        if block_header.is_switch_block() {
+            return Ok(false);
+        }
+        let block_body: BlockBody =
+            match txn.get_value(self.block_body_db, block_header.body_hash())? {
+                Some(block_body) => block_body,
+                None => return Ok(false),
+            };
+        for deploy_hash in block_body
+            .deploy_hashes()
+            .iter()
+            .chain(block_body.transfer_hashes())
+        {
+            self.prune_deploy(txn, deploy_hash)?;
+        }
+        // Blocks without deploys by the same proposer have identical bodies, so a body without
+        // deploys may still be needed by a retained block and is kept.
+        if !block_body.deploy_hashes().is_empty() || !block_body.transfer_hashes().is_empty() {
+            del_if_exists(txn, self.block_body_db, block_header.body_hash())?;
+        }
+        del_if_exists(txn, self.transfer_db, block_hash)?;
+        Ok(true)
+    }
+
+    /// Deletes the given deploy along with its metadata and its entries in the account deploys
+    /// index, and records it as pruned.
+    fn prune_deploy(
+        &self,
+        txn: &mut RwTransaction,
+        deploy_hash: &DeployHash,
+    ) -> Result<(), LmdbExtError> {
+        let maybe_deploy: Option<Deploy> = txn.get_value(self.deploy_db, deploy_hash)?;
+        let maybe_metadata: Option<DeployMetadata> =
+            txn.get_value(self.deploy_metadata_db, deploy_hash)?;
+        if let Some(deploy) = maybe_deploy {
+            let header = deploy.header();
+            let mut involved_accounts = vec![header.account().to_account_hash()];
+            for execution_result in maybe_metadata
+                .iter()
+                .flat_map(|metadata| metadata.execution_results.values())
+            {
+                if let ExecutionResult::Success { effect, .. } = execution_result {
+                    for transform_entry in &effect.transforms {
+                        if let Transform::WriteTransfer(transfer) = &transform_entry.transform {
+                            involved_accounts.push(transfer.from);
+                            involved_accounts.extend(transfer.to);
+                        }
+                    }
+                }
+            }
+            involved_accounts.sort();
+            involved_accounts.dedup();
+            for account_hash in &involved_accounts {
+                let key = account_deploys_key(account_hash, header.timestamp(), deploy_hash);
+                del_if_exists(txn, self.account_deploys_db, &key)?;
+            }
+            del_if_exists(txn, self.deploy_db, deploy_hash)?;
+        }
+        del_if_exists(txn, self.deploy_metadata_db, deploy_hash)?;
+        txn.put(self.pruned_deploy_db, deploy_hash, b"", WriteFlags::empty())?;
+        Ok(())
+    }
+
+    /// Returns whether the given block's body has been pruned.
+    fn is_block_pruned(&self, block_header: &BlockHeader) -> bool {
+        !block_header.is_switch_block() && block_header.height() < self.lowest_retained_height
+    }
+
+    /// Returns whether the given item has been pruned from storage.
+    fn is_pruned<Tx: Transaction>(
+        &self,
+        txn: &mut Tx,
+        item: PrunableItem,
+    ) -> Result<bool, LmdbExtError> {
+        let block_hash = match item {
+            PrunableItem::Block(block_hash) => block_hash,
+            PrunableItem::BlockAtHeight(height) => match self.block_height_index.get(&height) {
+                Some(block_hash) => *block_hash,
+                None => return Ok(false),
+            },
+            PrunableItem::Deploy(deploy_hash) => {
+                return match txn.get(self.pruned_deploy_db, &deploy_hash) {
+                    Ok(_) => Ok(true),
+                    Err(lmdb::Error::NotFound) => Ok(false),
+                    Err(err) => Err(err.into()),
+                };
+            }
+        };
+        Ok(self
+            .get_block_header(txn, &block_hash)?
+            .map_or(false, |block_header| self.is_block_pruned(&block_header)))
+    }
+
+    /// Writes a deploy to storage and adds it to the index of deploys of its account.
+    ///
+    /// Returns `true` if the deploy was stored on this attempt or `false` if it was previously
+    /// stored.
+    pub(crate) fn write_deploy(&self, deploy: &Deploy) -> Result<bool, Error> {
+        let mut txn = self.begin_rw_txn()?;
+        let outcome = txn.put_value(self.deploy_db, deploy.id(), deploy, false)?;
+        if outcome {
+            let header = deploy.header();
+            self.put_account_deploy(
+                &mut txn,
+                &header.account().to_account_hash(),
+                header.timestamp(),
+                deploy.id(),
+            )?;
+        }
+        txn.commit()?;
+        Ok(outcome)
+    }
+
+    /// Writes the execution results of deploys in the given block to storage, along with the
+    /// transfers they made.
+    pub(crate) fn write_execution_results(
+        &self,
+        block_hash: &BlockHash,
+        execution_results: HashMap<DeployHash, ExecutionResult>,
+    ) -> Result<(), Error> {
+        let mut txn = self.begin_rw_txn()?;
+
+        let mut transfers: Vec<Transfer> = vec![];
+
+        for (deploy_hash, execution_result) in execution_results {
+            let mut metadata = self
+                .get_deploy_metadata(&mut txn, &deploy_hash)?
+                .unwrap_or_default();
+
+            // If we have a previous execution result, we can continue if it is the same.
+            if let Some(prev) = metadata.execution_results.get(block_hash) {
+                if prev == &execution_result {
+                    continue;
+                } else {
+                    debug!(%deploy_hash, %block_hash, "different execution result");
+                }
+            }
+
+            let mut involved_accounts = vec![];
+            if let ExecutionResult::Success { effect, .. } = execution_result.clone() {
+                for transform_entry in effect.transforms {
+                    if let Transform::WriteTransfer(transfer) = transform_entry.transform {
+                        involved_accounts.push(transfer.from);
+                        involved_accounts.extend(transfer.to);
+                        transfers.push(transfer);
+                    }
+                }
+            }
+
+            // Deploys stored before the account index existed are indexed once executed. Results
+            // for deploys we do not hold cannot be indexed, as their timestamp is unknown.
+            if let Some(header) = self.get_deploy_header(&mut txn, &deploy_hash)? {
+                involved_accounts.push(header.account().to_account_hash());
+                involved_accounts.sort();
+                involved_accounts.dedup();
+                for account_hash in &involved_accounts {
+                    self.put_account_deploy(
+                        &mut txn,
+                        account_hash,
+                        header.timestamp(),
+                        &deploy_hash,
+                    )?;
+                }
+            }
+
+            // TODO: this is currently done like this because rpc get_deploy returns the
+            // data, but the organization of deploy, block_hash, and
+            // execution_result is incorrectly represented. it should be
+            // inverted; for a given block_hash 0n deploys and each deploy has exactly 1
+            // result (aka deploy_metadata in this context).
+
+            // Update metadata and write back to db.
+            metadata
+                .execution_results
+                .insert(*block_hash, execution_result);
+            let was_written =
+                txn.put_value(self.deploy_metadata_db, &deploy_hash, &metadata, true)?;
+            assert!(
+                was_written,
+                "failed to write deploy metadata for block_hash {} deploy_hash {}",
+                block_hash, deploy_hash
+            );
+        }
+
+        let was_written = txn.put_value(self.transfer_db, block_hash, &transfers, true)?;
+        assert!(
+            was_written,
+            "failed to write transfers for block_hash {}",
+            block_hash
+        );
+
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Writes finality signatures to storage, merging them with any already stored for the block.
+    ///
+    /// Returns `true` if the signatures were stored successfully.
+    pub(crate) fn write_block_signatures(
+        &self,
+        signatures: BlockSignatures,
+    ) -> Result<bool, Error> {
+        let mut txn = self.begin_rw_txn()?;
+        let old_data: Option<BlockSignatures> =
+            txn.get_value(self.block_metadata_db, &signatures.block_hash)?;
+        let new_data = match old_data {
+            None => signatures,
+            Some(mut data) => {
+                for (pk, sig) in signatures.proofs {
+                    data.insert_proof(pk, sig);
+                }
+                data
+            }
+        };
+        let outcome = txn.put_value(
+            self.block_metadata_db,
+            &new_data.block_hash,
+            &new_data,
+            true,
+        )?;
+        txn.commit()?;
+        Ok(outcome)
+    }
+
+    /// Reads a deploy and its metadata from storage.
+    ///
+    /// Missing metadata is filled using a default.
+    pub(crate) fn read_deploy_and_metadata(
+        &self,
+        deploy_hash: &DeployHash,
+    ) -> Result<Option<(Deploy, DeployMetadata)>, Error> {
+        let mut txn = self.env.begin_ro_txn()?;
+
+        // A missing deploy causes an early `None` return.
+        let deploy: Deploy = if let Some(deploy) = txn.get_value(self.deploy_db, deploy_hash)? {
+            deploy
+        } else {
+            return Ok(None);
+        };
+
+        let metadata = self
+            .get_deploy_metadata(&mut txn, deploy_hash)?
+            .unwrap_or_default();
+        Ok(Some((deploy, metadata)))
+    }
+
+    /// Reads the block at the given height and its finality signatures from storage.
+    pub(crate) fn read_block_and_signatures_by_height(
+        &self,
+        height: u64,
+    ) -> Result<Option<(Block, BlockSignatures)>, Error> {
+        let mut txn = self.env.begin_ro_txn()?;
+
+        let block: Block = if let Some(block) = self.get_block_by_height(&mut txn, height)? {
+            block
+        } else {
+            return Ok(None);
+        };
+
+        let hash = block.hash();
+        let signatures = match self.get_finality_signatures(&mut txn, hash)? {
+            Some(signatures) => signatures,
+            None => BlockSignatures::new(*hash, block.header().era_id()),
+        };
+        Ok(Some((block, signatures)))
+    }
+
+    /// Returns the height of the highest block in storage, if any.
+    pub(crate) fn read_highest_block_height(&self) -> Option<u64> {
+        self.block_height_index.keys().last().copied()
+    }
+
+    /// Reads the block at the given height as stored, without verifying it.
+    ///
+    /// Unlike the other read methods, a block whose header or body is not stored under its hash is
+    /// returned as is, so that integrity checks can report on it.
+    pub(crate) fn read_stored_block_by_height(
+        &self,
+        height: u64,
+    ) -> Result<Option<StoredBlock>, Error> {
+        let block_hash = match self.block_height_index.get(&height) {
+            Some(block_hash) => *block_hash,
+            None => return Ok(None),
+        };
+        let mut txn = self.env.begin_ro_txn()?;
+        let header: BlockHeader = match txn.get_value(self.block_header_db, &block_hash)? {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+        let is_pruned = self.is_block_pruned(&header);
+        let body = if is_pruned {
+            None
+        } else {
+            txn.get_value(self.block_body_db, header.body_hash())?
+        };
+        let signatures = self.get_finality_signatures(&mut txn, &block_hash)?;
+        txn.commit()?;
+        Ok(Some(StoredBlock {
+            block_hash,
+            header,
+            body,
+            is_pruned,
+            signatures,
+        }))
+    }
+
+    /// Reads the deploy stored under the given hash, without verifying it.
+    pub(crate) fn read_deploy(&self, deploy_hash: &DeployHash) -> Result<Option<Deploy>, Error> {
+        Ok(self
+            .env
+            .begin_ro_txn()?
+            .get_value(self.deploy_db, deploy_hash)?)
+    }
+
+    /// Reads the header of the switch block of the given era.
+    pub(crate) fn read_switch_block_header_by_era_id(
+        &self,
+        era_id: EraId,
+    ) -> Result<Option<BlockHeader>, Error> {
+        let block_hash = match self.switch_block_era_id_index.get(&era_id) {
+            Some(block_hash) => *block_hash,
+            None => return Ok(None),
+        };
+        Ok(self.get_block_header(&mut self.env.begin_ro_txn()?, &block_hash)?)
+    }
+
     /// Handles a storage request.
     fn handle_storage_request<REv>(&mut self, req: StorageRequest) -> Result<Effects<Event>, Error>
     where
@@ -413,29 +1190,7 @@ impl Storage {
         // average the actual execution time will be very low.
         Ok(match req {
             StorageRequest::PutBlock { block, responder } => {
-                let mut txn = self.env.begin_rw_txn()?;
-                if !txn.put_value(
-                    self.block_body_db,
-                    block.header().body_hash(),
-                    block.body(),
-                    true,
-                )? {
-                    error!("Could not insert block body for block: {}", block);
-                    txn.abort();
-                    return Ok(responder.respond(false).ignore());
-                }
-                if !txn.put_value(self.block_header_db, block.hash(), block.header(), true)? {
-                    error!("Could not insert block header for block: {}", block);
-                    txn.abort();
-                    return Ok(responder.respond(false).ignore());
-                }
-                txn.commit()?;
-                insert_to_block_header_indices(
-                    &mut self.block_height_index,
-                    &mut self.switch_block_era_id_index,
-                    block.header(),
-                )?;
-                responder.respond(true).ignore()
+                responder.respond(self.write_block(&block)?).ignore()
             }
             StorageRequest::GetBlock {
                 block_hash,
@@ -474,12 +1229,7 @@ impl Storage {
                 block_hash,
                 responder,
             } => responder
-                // TODO: Find a solution for efficiently retrieving the blocker header without the
-                // block. Deserialization that allows trailing bytes could be a possible solution.
-                .respond(
-                    self.get_single_block(&mut self.env.begin_ro_txn()?, &block_hash)?
-                        .map(|block| block.header().clone()),
-                )
+                .respond(self.get_block_header(&mut self.env.begin_ro_txn()?, &block_hash)?)
                 .ignore(),
             StorageRequest::GetBlockTransfers {
                 block_hash,
@@ -488,10 +1238,7 @@ impl Storage {
                 .respond(self.get_transfers(&mut self.env.begin_ro_txn()?, &block_hash)?)
                 .ignore(),
             StorageRequest::PutDeploy { deploy, responder } => {
-                let mut txn = self.env.begin_rw_txn()?;
-                let outcome = txn.put_value(self.deploy_db, deploy.id(), &deploy, false)?;
-                txn.commit()?;
-                responder.respond(outcome).ignore()
+                responder.respond(self.write_deploy(&deploy)?).ignore()
             }
             StorageRequest::GetDeploys {
                 deploy_hashes,
@@ -516,82 +1263,15 @@ impl Storage {
                 execution_results,
                 responder,
             } => {
-                let mut txn = self.env.begin_rw_txn()?;
-
-                let mut transfers: Vec<Transfer> = vec![];
-
-                for (deploy_hash, execution_result) in execution_results {
-                    let mut metadata = self
-                        .get_deploy_metadata(&mut txn, &deploy_hash)?
-                        .unwrap_or_default();
-
-                    // If we have a previous execution result, we can continue if it is the same.
-                    if let Some(prev) = metadata.execution_results.get(&block_hash) {
-                        if prev == &execution_result {
-                            continue;
-                        } else {
-                            debug!(%deploy_hash, %block_hash, "different execution result");
-                        }
-                    }
-
-                    if let ExecutionResult::Success { effect, .. } = execution_result.clone() {
-                        for transform_entry in effect.transforms {
-                            if let Transform::WriteTransfer(transfer) = transform_entry.transform {
-                                transfers.push(transfer);
-                            }
-                        }
-                    }
-
-                    // TODO: this is currently done like this because rpc get_deploy returns the
-                    // data, but the organization of deploy, block_hash, and
-                    // execution_result is incorrectly represented. it should be
-                    // inverted; for a given block_hash 0n deploys and each deploy has exactly 1
-                    // result (aka deploy_metadata in this context).
-
-                    // Update metadata and write back to db.
-                    metadata
-                        .execution_results
-                        .insert(*block_hash, execution_result);
-                    let was_written =
-                        txn.put_value(self.deploy_metadata_db, &deploy_hash, &metadata, true)?;
-                    assert!(
-                        was_written,
-                        "failed to write deploy metadata for block_hash {} deploy_hash {}",
-                        block_hash, deploy_hash
-                    );
-                }
-
-                let was_written =
-                    txn.put_value(self.transfer_db, &*block_hash, &transfers, true)?;
-                assert!(
-                    was_written,
-                    "failed to write transfers for block_hash {}",
-                    block_hash
-                );
-
-                txn.commit()?;
+                self.write_execution_results(&block_hash, execution_results)?;
                 responder.respond(()).ignore()
             }
             StorageRequest::GetDeployAndMetadata {
                 deploy_hash,
                 responder,
-            } => {
-                let mut txn = self.env.begin_ro_txn()?;
-
-                // A missing deploy causes an early `None` return.
-                let deploy: Deploy =
-                    if let Some(deploy) = txn.get_value(self.deploy_db, &deploy_hash)? {
-                        deploy
-                    } else {
-                        return Ok(responder.respond(None).ignore());
-                    };
-
-                // Missing metadata is filled using a default.
-                let metadata = self
-                    .get_deploy_metadata(&mut txn, &deploy_hash)?
-                    .unwrap_or_default();
-                responder.respond(Some((deploy, metadata))).ignore()
-            }
+            } => responder
+                .respond(self.read_deploy_and_metadata(&deploy_hash)?)
+                .ignore(),
             StorageRequest::GetBlockAndMetadataByHash {
                 block_hash,
                 responder,
@@ -615,23 +1295,9 @@ impl Storage {
             StorageRequest::GetBlockAndMetadataByHeight {
                 block_height,
                 responder,
-            } => {
-                let mut txn = self.env.begin_ro_txn()?;
-
-                let block: Block =
-                    if let Some(block) = self.get_block_by_height(&mut txn, block_height)? {
-                        block
-                    } else {
-                        return Ok(responder.respond(None).ignore());
-                    };
-
-                let hash = block.hash();
-                let signatures = match self.get_finality_signatures(&mut txn, hash)? {
-                    Some(signatures) => signatures,
-                    None => BlockSignatures::new(*hash, block.header().era_id()),
-                };
-                responder.respond(Some((block, signatures))).ignore()
-            }
+            } => responder
+                .respond(self.read_block_and_signatures_by_height(block_height)?)
+                .ignore(),
             StorageRequest::GetHighestBlockWithMetadata { responder } => {
                 let mut txn = self.env.begin_ro_txn()?;
                 let highest_block: Block = if let Some(block) = self
@@ -657,28 +1323,9 @@ impl Storage {
             StorageRequest::PutBlockSignatures {
                 signatures,
                 responder,
-            } => {
-                let mut txn = self.env.begin_rw_txn()?;
-                let old_data: Option<BlockSignatures> =
-                    txn.get_value(self.block_metadata_db, &signatures.block_hash)?;
-                let new_data = match old_data {
-                    None => signatures,
-                    Some(mut data) => {
-                        for (pk, sig) in signatures.proofs {
-                            data.insert_proof(pk, sig);
-                        }
-                        data
-                    }
-                };
-                let outcome = txn.put_value(
-                    self.block_metadata_db,
-                    &new_data.block_hash,
-                    &new_data,
-                    true,
-                )?;
-                txn.commit()?;
-                responder.respond(outcome).ignore()
-            }
+            } => responder
+                .respond(self.write_block_signatures(signatures)?)
+                .ignore(),
             StorageRequest::GetBlockSignatures {
                 block_hash,
                 responder,
@@ -690,6 +1337,63 @@ impl Storage {
             StorageRequest::GetFinalizedDeploys { ttl, responder } => {
                 responder.respond(self.get_finalized_deploys(ttl)?).ignore()
             }
+            StorageRequest::IsPruned { item, responder } => responder
+                .respond(self.is_pruned(&mut self.env.begin_ro_txn()?, item)?)
+                .ignore(),
+            StorageRequest::GetAccountDeploys {
+                account_hash,
+                offset,
+                limit,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                let deploy_hashes =
+                    self.get_account_deploy_hashes(&txn, &account_hash, offset, limit)?;
+                let mut account_deploys = Vec::with_capacity(deploy_hashes.len());
+                for deploy_hash in deploy_hashes {
+                    let header = match self.get_deploy_header(&mut txn, &deploy_hash)? {
+                        Some(header) => header,
+                        None => continue,
+                    };
+                    let metadata = self
+                        .get_deploy_metadata(&mut txn, &deploy_hash)?
+                        .unwrap_or_default();
+                    account_deploys.push((deploy_hash, header, metadata));
+                }
+                responder.respond(account_deploys).ignore()
+            }
+            StorageRequest::PutEvidence {
+                era_id,
+                public_key,
+                evidence,
+                responder,
+            } => {
+                let key = evidence_key(era_id, &public_key);
+                let mut txn = self.begin_rw_txn()?;
+                let outcome =
+                    match txn.put(self.evidence_db, &key, &evidence, WriteFlags::NO_OVERWRITE) {
+                        Ok(()) => true,
+                        Err(lmdb::Error::KeyExist) => false,
+                        Err(err) => return Err(err.into()),
+                    };
+                txn.commit()?;
+                responder.respond(outcome).ignore()
+            }
+            StorageRequest::GetEvidence {
+                era_id,
+                public_key,
+                responder,
+            } => {
+                let key = evidence_key(era_id, &public_key);
+                let txn = self.env.begin_ro_txn()?;
+                let evidence = match txn.get(self.evidence_db, &key) {
+                    Ok(raw) => Some(raw.to_vec()),
+                    Err(lmdb::Error::NotFound) => None,
+                    Err(err) => return Err(err.into()),
+                };
+                txn.commit()?;
+                responder.respond(evidence).ignore()
+            }
         })
     }
 
@@ -718,44 +1422,30 @@ impl Storage {
             .transpose()
     }
 
-    /// Returns vector blocks that satisfy the predicate, starting from the latest one and following
-    /// the ancestry chain.
-    fn get_blocks_while<F, Tx: Transaction>(
-        &self,
-        txn: &mut Tx,
-        predicate: F,
-    ) -> Result<Vec<Block>, LmdbExtError>
-    where
-        F: Fn(&Block) -> bool,
-    {
-        let mut next_block = self.get_highest_block(txn)?;
-        let mut blocks = Vec::new();
-        loop {
-            match next_block {
-                None => break,
-                Some(block) if !predicate(&block) => break,
-                Some(block) => {
-                    next_block = match block.parent() {
-                        None => None,
-                        Some(parent_hash) => self.get_single_block(txn, &parent_hash)?,
-                    };
-                    blocks.push(block);
-                }
-            }
-        }
-        Ok(blocks)
-    }
-
     /// Returns the vector of deploys whose TTL hasn't expired yet.
+    ///
+    /// Follows the ancestry chain from the highest block, as long as the blocks are within the TTL.
+    /// Fails if one of those blocks' body has been pruned, rather than silently omitting its
+    /// deploys.
     fn get_finalized_deploys(
         &self,
         ttl: TimeDiff,
-    ) -> Result<Vec<(DeployHash, DeployHeader)>, LmdbExtError> {
+    ) -> Result<Vec<(DeployHash, DeployHeader)>, Error> {
         let mut txn = self.env.begin_ro_txn()?;
-        // We're interested in deploys whose TTL hasn't expired yet.
-        let ttl_expired = |block: &Block| block.timestamp().elapsed() < ttl;
         let mut deploys = Vec::new();
-        for block in self.get_blocks_while(&mut txn, ttl_expired)? {
+        let mut next_block_hash = self.block_height_index.values().last().copied();
+        while let Some(block_hash) = next_block_hash {
+            let block_header = match self.get_block_header(&mut txn, &block_hash)? {
+                Some(block_header) => block_header,
+                None => break,
+            };
+            // We're interested in deploys whose TTL hasn't expired yet.
+            if block_header.timestamp().elapsed() >= ttl {
+                break;
+            }
+            let block = self
+                .get_single_block(&mut txn, &block_hash)?
+                .ok_or(Error::PrunedBlockWithinDeployTtl(block_hash))?;
             for deploy_hash in block
                 .body()
                 .deploy_hashes()
@@ -773,6 +1463,7 @@ impl Storage {
                 }
                 deploys.push((*deploy_hash, deploy_header));
             }
+            next_block_hash = block.parent().copied();
         }
         Ok(deploys)
     }
@@ -790,8 +1481,14 @@ impl Storage {
     }
 
     /// Retrieves the state root hashes from storage to check the integrity of the trie store.
-    pub fn get_state_root_hashes_for_trie_check(&self) -> Option<Vec<Blake2bHash>> {
-        let mut blake_hashes: Vec<Blake2bHash> = Vec::new();
+    ///
+    /// If `maybe_most_recent` is `Some`, only the state root hashes of that many of the highest
+    /// blocks are returned, since older global state may have been pruned.
+    pub fn get_state_root_hashes_for_trie_check(
+        &self,
+        maybe_most_recent: Option<u64>,
+    ) -> Option<Vec<Blake2bHash>> {
+        let mut headers: Vec<BlockHeader> = Vec::new();
         let txn =
             self.env.begin_ro_txn().ok().unwrap_or_else(|| {
                 panic!("could not open storage transaction for trie store check")
@@ -802,22 +1499,30 @@ impl Storage {
             .unwrap_or_else(|| panic!("could not create cursor for trie store check"));
         for (_, raw_val) in cursor.iter() {
             let header: BlockHeader = lmdb_ext::deserialize(raw_val).ok()?;
-            let blake_hash = Blake2bHash::from(*header.state_root_hash());
-            blake_hashes.push(blake_hash);
+            headers.push(header);
+        }
+
+        if let Some(most_recent) = maybe_most_recent {
+            headers.sort_by_key(|header| cmp::Reverse(header.height()));
+            headers.truncate(most_recent as usize);
         }
 
+        let mut blake_hashes: Vec<Blake2bHash> = headers
+            .iter()
+            .map(|header| Blake2bHash::from(*header.state_root_hash()))
+            .collect();
         blake_hashes.sort();
         blake_hashes.dedup();
 
         Some(blake_hashes)
     }
 
-    /// Retrieves a single block in a separate transaction from storage.
-    fn get_single_block<Tx: Transaction>(
+    /// Retrieves a single block header in a separate transaction from storage.
+    fn get_block_header<Tx: Transaction>(
         &self,
         tx: &mut Tx,
         block_hash: &BlockHash,
-    ) -> Result<Option<Block>, LmdbExtError> {
+    ) -> Result<Option<BlockHeader>, LmdbExtError> {
         let block_header: BlockHeader = match tx.get_value(self.block_header_db, &block_hash)? {
             Some(block_header) => block_header,
             None => return Ok(None),
@@ -829,6 +1534,24 @@ impl Storage {
                 found_block_header_hash,
             });
         }
+        Ok(Some(block_header))
+    }
+
+    /// Retrieves a single block in a separate transaction from storage.
+    ///
+    /// Returns `None` if the block's body has been pruned.
+    fn get_single_block<Tx: Transaction>(
+        &self,
+        tx: &mut Tx,
+        block_hash: &BlockHash,
+    ) -> Result<Option<Block>, LmdbExtError> {
+        let block_header = match self.get_block_header(tx, block_hash)? {
+            Some(block_header) => block_header,
+            None => return Ok(None),
+        };
+        if self.is_block_pruned(&block_header) {
+            return Ok(None);
+        }
         let block_body: BlockBody =
             match tx.get_value(self.block_body_db, block_header.body_hash())? {
                 Some(block_header) => block_header,
@@ -867,6 +1590,49 @@ impl Storage {
         Ok(maybe_deploy.map(|deploy| deploy.header().clone()))
     }
 
+    /// Adds a deploy to the index of deploys of the given account.
+    fn put_account_deploy(
+        &self,
+        txn: &mut RwTransaction,
+        account_hash: &AccountHash,
+        timestamp: Timestamp,
+        deploy_hash: &DeployHash,
+    ) -> Result<(), LmdbExtError> {
+        let key = account_deploys_key(account_hash, timestamp, deploy_hash);
+        txn.put(self.account_deploys_db, &key, b"", WriteFlags::empty())?;
+        Ok(())
+    }
+
+    /// Retrieves the hashes of the deploys of the given account, newest first.
+    ///
+    /// The first `offset` deploys are skipped, and at most `limit` hashes are returned.
+    fn get_account_deploy_hashes<Tx: Transaction>(
+        &self,
+        txn: &Tx,
+        account_hash: &AccountHash,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<DeployHash>, LmdbExtError> {
+        let mut cursor = txn.open_ro_cursor(self.account_deploys_db)?;
+        let mut deploy_hashes = Vec::new();
+        for (key, _) in cursor
+            .iter_from(account_hash.as_bytes())
+            .take_while(|(key, _)| key.starts_with(account_hash.as_bytes()))
+            .skip(offset)
+            .take(limit)
+        {
+            if key.len() != ACCOUNT_DEPLOYS_KEY_LENGTH {
+                return Err(LmdbExtError::DataCorrupted(
+                    format!("invalid account deploys key length {}", key.len()).into(),
+                ));
+            }
+            let digest = Digest::try_from(&key[ACCOUNT_DEPLOYS_KEY_LENGTH - Digest::LENGTH..])
+                .map_err(|err| LmdbExtError::DataCorrupted(Box::new(err)))?;
+            deploy_hashes.push(DeployHash::new(digest));
+        }
+        Ok(deploy_hashes)
+    }
+
     /// Retrieves deploy metadata associated with deploy.
     ///
     /// If no deploy metadata is stored for the specific deploy, an empty metadata instance will be
@@ -907,6 +1673,72 @@ impl Storage {
     }
 }
 
+/// Grows the memory map of the environment by `growth_step`, up to `max_map_size`, if less than a
+/// growth step of it remains unused.
+///
+/// Returns the new size of the map if it was grown.  Must not be called while a transaction is
+/// active on the environment.
+fn grow_map_if_needed(
+    env: &Environment,
+    growth_step: usize,
+    max_map_size: usize,
+) -> Result<Option<usize>, Error> {
+    let usage = env.map_usage()?;
+    let headroom = usage.map_size.saturating_sub(usage.used);
+    if headroom >= growth_step || usage.map_size >= max_map_size {
+        return Ok(None);
+    }
+    let new_map_size = cmp::min(usage.map_size.saturating_add(growth_step), max_map_size);
+    // SAFETY: Storage is the sole user of its environment and never keeps a transaction open
+    //         beyond the method that started it, so none can be active here.
+    unsafe { env.set_map_size(new_map_size)? };
+    info!(
+        old_map_size = usage.map_size,
+        new_map_size,
+        used = usage.used,
+        "grew storage memory map"
+    );
+    Ok(Some(new_map_size))
+}
+
+/// Constructs the key under which a deploy is stored in the account deploys index.
+///
+/// The timestamp is inverted and stored big-endian, such that iterating over the keys of an account
+/// yields its newest deploys first.
+fn account_deploys_key(
+    account_hash: &AccountHash,
+    timestamp: Timestamp,
+    deploy_hash: &DeployHash,
+) -> Vec<u8> {
+    let mut key = Vec::with_capacity(ACCOUNT_DEPLOYS_KEY_LENGTH);
+    key.extend_from_slice(account_hash.as_bytes());
+    key.extend_from_slice(&(u64::MAX - timestamp.millis()).to_be_bytes());
+    key.extend_from_slice(deploy_hash.as_ref());
+    key
+}
+
+/// Constructs the key under which the evidence against a validator in an era is stored.
+///
+/// The era ID is stored big-endian, such that the evidence is ordered by era.
+fn evidence_key(era_id: EraId, public_key: &PublicKey) -> Vec<u8> {
+    let mut key = Vec::with_capacity(EVIDENCE_KEY_LENGTH);
+    key.extend_from_slice(&era_id.0.to_be_bytes());
+    key.extend_from_slice(public_key.to_account_hash().as_bytes());
+    key
+}
+
+/// Deletes the value stored under the given key, if any.
+fn del_if_exists<K: AsRef<[u8]>>(
+    txn: &mut RwTransaction,
+    db: Database,
+    key: &K,
+) -> Result<(), LmdbExtError> {
+    match txn.del(db, key, None) {
+        Ok(()) | Err(lmdb::Error::NotFound) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
 /// Inserts the relevant entries to the two indices.
 ///
 /// If a duplicate entry is encountered, neither index is updated and an error is returned.
@@ -947,6 +1779,21 @@ fn insert_to_block_header_indices(
     Ok(())
 }
 
+/// A block as stored, read without verifying it.
+#[derive(Debug)]
+pub(crate) struct StoredBlock {
+    /// The hash under which the block is indexed.
+    pub(crate) block_hash: BlockHash,
+    /// The header stored under the block hash.
+    pub(crate) header: BlockHeader,
+    /// The body stored under the header's body hash, unless missing or pruned.
+    pub(crate) body: Option<BlockBody>,
+    /// Whether the block's body has been pruned.
+    pub(crate) is_pruned: bool,
+    /// The finality signatures of the block, if any.
+    pub(crate) signatures: Option<BlockSignatures>,
+}
+
 /// On-disk storage configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -971,6 +1818,18 @@ pub struct Config {
     ///
     /// The size should be a multiple of the OS page size.
     max_state_store_size: usize,
+    /// The step by which the memory map shared by all stores is grown once less than one step of
+    /// it remains unused.
+    ///
+    /// The map is never grown beyond the sum of the maximum store sizes.  The size should be a
+    /// multiple of the OS page size.
+    map_size_growth_step: usize,
+    /// The number of most recently completed eras whose block bodies, deploys and deploy metadata
+    /// are retained.
+    ///
+    /// If unset, nothing is pruned from storage.
+    #[serde(default)]
+    retained_block_body_eras: Option<u64>,
 }
 
 impl Default for Config {
@@ -982,11 +1841,55 @@ impl Default for Config {
             max_deploy_store_size: DEFAULT_MAX_DEPLOY_STORE_SIZE,
             max_deploy_metadata_store_size: DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE,
             max_state_store_size: DEFAULT_MAX_STATE_STORE_SIZE,
+            map_size_growth_step: DEFAULT_MAP_SIZE_GROWTH_STEP,
+            retained_block_body_eras: None,
         }
     }
 }
 
 impl Config {
+    /// Checks that block bodies are retained for at least as long as the deploys in them can be
+    /// unexpired, since the block proposer relies on them to not propose a deploy twice.
+    ///
+    /// An era lasts at least the era duration and at least the minimum era height times the minimum
+    /// round length, so the deploys of a block from `max_ttl / min_era_length + 1` completed eras
+    /// ago have expired. One more era is required to allow for clock drift.
+    pub(crate) fn check_block_body_retention(&self, chainspec: &Chainspec) -> Result<(), Error> {
+        let retained_block_body_eras = match self.retained_block_body_eras {
+            Some(retained_block_body_eras) => retained_block_body_eras,
+            None => return Ok(()),
+        };
+        let min_round_length = 1u64 << chainspec.highway_config.minimum_round_exponent;
+        let min_era_length = chainspec
+            .core_config
+            .era_duration
+            .millis()
+            .max(
+                chainspec
+                    .core_config
+                    .minimum_era_height
+                    .saturating_mul(min_round_length),
+            )
+            .max(1);
+        let required = chainspec.deploy_config.max_ttl.millis() / min_era_length + 2;
+        if retained_block_body_eras < required {
+            return Err(Error::InsufficientBlockBodyRetention {
+                retained_block_body_eras,
+                required,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the size the memory map may not be grown beyond, i.e. the sum of the maximum store
+    /// sizes.
+    fn max_map_size(&self) -> usize {
+        self.max_block_store_size
+            .saturating_add(self.max_deploy_store_size)
+            .saturating_add(self.max_deploy_metadata_store_size)
+            .saturating_add(self.max_state_store_size)
+    }
+
     /// Returns a default `Config` suitable for tests, along with a `TempDir` which must be kept
     /// alive for the duration of the test since its destructor removes the dir from the filesystem.
     #[cfg(test)]
@@ -1118,6 +2021,60 @@ fn check_block_body_db(env: &Environment, block_body_db: &Database) -> Result<()
     Ok(())
 }
 
+/// Returns an error for each entry of the block body database which cannot be decoded or is not
+/// stored under its hash.
+fn find_corrupt_block_bodies(
+    env: &Environment,
+    block_body_db: &Database,
+) -> Result<Vec<Error>, LmdbExtError> {
+    let txn = env.begin_ro_txn()?;
+    let mut cursor = txn.open_ro_cursor(*block_body_db)?;
+    let corruptions = cursor
+        .iter()
+        .filter_map(|(raw_key, raw_val)| {
+            let error = match lmdb_ext::deserialize::<BlockBody>(raw_val) {
+                Ok(body) if raw_key == body.hash().as_ref() => return None,
+                Ok(body) => format!("stored body has hash {}", body.hash()),
+                Err(error) => error.to_string(),
+            };
+            Some(Error::CorruptEntry {
+                database: "block_body",
+                key: hex::encode(raw_key),
+                error,
+            })
+        })
+        .collect();
+    Ok(corruptions)
+}
+
+/// Returns an error for each entry of the block metadata database which cannot be decoded, is not
+/// stored under the hash of its block or holds an invalid signature.
+fn find_corrupt_block_signatures(
+    env: &Environment,
+    block_metadata_db: &Database,
+) -> Result<Vec<Error>, LmdbExtError> {
+    let txn = env.begin_ro_txn()?;
+    let mut cursor = txn.open_ro_cursor(*block_metadata_db)?;
+    let corruptions = cursor
+        .iter()
+        .filter_map(|(raw_key, raw_val)| {
+            let error = match lmdb_ext::deserialize::<BlockSignatures>(raw_val) {
+                Ok(signatures) if raw_key != signatures.block_hash.as_ref() => {
+                    format!("stored signatures are for block {}", signatures.block_hash)
+                }
+                Ok(signatures) => signatures.verify().err()?.to_string(),
+                Err(error) => error.to_string(),
+            };
+            Some(Error::CorruptEntry {
+                database: "block_metadata",
+                key: hex::encode(raw_key),
+                error,
+            })
+        })
+        .collect();
+    Ok(corruptions)
+}
+
 /// Utility function to check the integrity of the block_metadata database at bringup.
 fn check_block_metadata_db(
     env: &Environment,
@@ -10,8 +10,10 @@
 //! Serialization errors are unified into a generic, type erased `std` error to allow for easy
 //! interchange of the serialization format if desired.
 
+use std::mem;
+
 use crate::{crypto::hash::Digest, types::BlockHash};
-use lmdb::{Database, RwTransaction, Transaction, WriteFlags};
+use lmdb::{Database, Environment, RwTransaction, Transaction, WriteFlags};
 use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
@@ -90,6 +92,37 @@ impl From<lmdb::Error> for LmdbExtError {
     }
 }
 
+/// The size of the memory map of an environment and how much of it is in use.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct MapUsage {
+    /// The size of the memory map in bytes.
+    pub(super) map_size: usize,
+    /// The number of bytes of the memory map in use, i.e. up to and including the last used page.
+    pub(super) used: usize,
+}
+
+/// The number of entries in a database and the number of bytes taken up by its pages.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct DbUsage {
+    /// The number of entries.
+    pub(super) entries: usize,
+    /// The number of bytes used by branch, leaf and overflow pages.
+    pub(super) used: usize,
+}
+
+/// Additional methods on environments.
+pub(super) trait EnvironmentExt {
+    /// Returns the size of the memory map and how much of it is in use.
+    fn map_usage(&self) -> Result<MapUsage, LmdbExtError>;
+
+    /// Resizes the memory map to `map_size` bytes, which should be a multiple of the OS page size.
+    ///
+    /// # Safety
+    ///
+    /// No transaction may be active on the environment in this process while it is resized.
+    unsafe fn set_map_size(&self, map_size: usize) -> Result<(), LmdbExtError>;
+}
+
 /// Additional methods on transaction.
 pub(super) trait TransactionExt {
     /// Helper function to load a value from a database.
@@ -98,6 +131,9 @@ pub(super) trait TransactionExt {
         db: Database,
         key: &K,
     ) -> Result<Option<V>, LmdbExtError>;
+
+    /// Returns the number of entries in a database and the space taken up by them.
+    fn db_usage(&self, db: Database) -> Result<DbUsage, LmdbExtError>;
 }
 
 /// Additional methods on write transactions.
@@ -116,6 +152,30 @@ pub(super) trait WriteTransactionExt {
     ) -> Result<bool, LmdbExtError>;
 }
 
+impl EnvironmentExt for Environment {
+    fn map_usage(&self) -> Result<MapUsage, LmdbExtError> {
+        let page_size = self.stat()?.page_size() as usize;
+        let mut info: lmdb_sys::MDB_envinfo = unsafe { mem::zeroed() };
+        match unsafe { lmdb_sys::mdb_env_info(self.env(), &mut info) } {
+            lmdb_sys::MDB_SUCCESS => Ok(MapUsage {
+                map_size: info.me_mapsize,
+                used: info
+                    .me_last_pgno
+                    .saturating_add(1)
+                    .saturating_mul(page_size),
+            }),
+            err_code => Err(lmdb::Error::from_err_code(err_code).into()),
+        }
+    }
+
+    unsafe fn set_map_size(&self, map_size: usize) -> Result<(), LmdbExtError> {
+        match lmdb_sys::mdb_env_set_mapsize(self.env(), map_size) {
+            lmdb_sys::MDB_SUCCESS => Ok(()),
+            err_code => Err(lmdb::Error::from_err_code(err_code).into()),
+        }
+    }
+}
+
 impl<T> TransactionExt for T
 where
     T: Transaction,
@@ -133,6 +193,20 @@ where
             Err(err) => Err(err.into()),
         }
     }
+
+    fn db_usage(&self, db: Database) -> Result<DbUsage, LmdbExtError> {
+        let mut stat: lmdb_sys::MDB_stat = unsafe { mem::zeroed() };
+        match unsafe { lmdb_sys::mdb_stat(self.txn(), db.dbi(), &mut stat) } {
+            lmdb_sys::MDB_SUCCESS => {
+                let pages = stat.ms_branch_pages + stat.ms_leaf_pages + stat.ms_overflow_pages;
+                Ok(DbUsage {
+                    entries: stat.ms_entries,
+                    used: pages.saturating_mul(stat.ms_psize as usize),
+                })
+            }
+            err_code => Err(lmdb::Error::from_err_code(err_code).into()),
+        }
+    }
 }
 
 impl WriteTransactionExt for RwTransaction<'_> {
@@ -0,0 +1,86 @@
+use prometheus::{IntGauge, IntGaugeVec, Opts, Registry};
+
+use crate::unregister_metric;
+
+/// Label distinguishing the databases of the storage environment.
+const DB_LABEL: &str = "db";
+
+#[derive(Debug, Clone)]
+pub(super) struct StorageMetrics {
+    /// The current size of the memory map of the storage environment.
+    pub(super) map_size: IntGauge,
+    /// The number of bytes of the memory map in use.
+    pub(super) map_used: IntGauge,
+    /// The maximum size the memory map may be grown to.
+    pub(super) max_map_size: IntGauge,
+    /// The number of entries in each database.
+    db_entries: IntGaugeVec,
+    /// The number of bytes used by each database.
+    db_used: IntGaugeVec,
+    /// registry component.
+    registry: Registry,
+}
+
+impl StorageMetrics {
+    pub(super) fn new(registry: Registry) -> Result<Self, prometheus::Error> {
+        let map_size = IntGauge::new(
+            "storage_map_size_bytes",
+            "current size of the storage memory map in bytes",
+        )?;
+        let map_used = IntGauge::new(
+            "storage_map_used_bytes",
+            "number of bytes of the storage memory map in use",
+        )?;
+        let max_map_size = IntGauge::new(
+            "storage_max_map_size_bytes",
+            "maximum size the storage memory map may be grown to in bytes",
+        )?;
+        let db_entries = IntGaugeVec::new(
+            Opts::new(
+                "storage_db_entries",
+                "number of entries in a storage database",
+            ),
+            &[DB_LABEL],
+        )?;
+        let db_used = IntGaugeVec::new(
+            Opts::new(
+                "storage_db_used_bytes",
+                "number of bytes used by the pages of a storage database",
+            ),
+            &[DB_LABEL],
+        )?;
+
+        registry.register(Box::new(map_size.clone()))?;
+        registry.register(Box::new(map_used.clone()))?;
+        registry.register(Box::new(max_map_size.clone()))?;
+        registry.register(Box::new(db_entries.clone()))?;
+        registry.register(Box::new(db_used.clone()))?;
+
+        Ok(StorageMetrics {
+            map_size,
+            map_used,
+            max_map_size,
+            db_entries,
+            db_used,
+            registry,
+        })
+    }
+
+    /// Records the number of entries in and bytes used by the named database.
+    pub(super) fn set_db_usage(&self, db_name: &str, entries: usize, used: usize) {
+        self.db_entries
+            .with_label_values(&[db_name])
+            .set(entries as i64);
+        self.db_used.with_label_values(&[db_name]).set(used as i64);
+    }
+}
+
+impl Drop for StorageMetrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.map_size);
+        unregister_metric!(self.registry, self.map_used);
+        unregister_metric!(self.registry, self.max_map_size);
+        unregister_metric!(self.registry, self.db_entries);
+        unregister_metric!(self.registry, self.db_used);
+    }
+}
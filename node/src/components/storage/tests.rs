@@ -1,23 +1,41 @@
 //! Unit tests for the storage component.
 
-use std::{borrow::Cow, collections::HashMap};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+};
 
+use lmdb::Transaction;
+use prometheus::Registry;
 use rand::{prelude::SliceRandom, Rng};
 use semver::Version;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use smallvec::smallvec;
 
-use casper_types::ExecutionResult;
+use casper_types::{
+    account::AccountHash, ExecutionEffect, ExecutionResult, ProtocolVersion, PublicKey, SecretKey,
+    Transfer, Transform, TransformEntry,
+};
 
-use super::{Config, Storage};
+use super::{
+    lmdb_ext::{EnvironmentExt, WriteTransactionExt},
+    Config, Error, PrunableItem, Storage,
+};
 use crate::{
-    components::consensus::EraId,
+    components::{
+        consensus::{EraId, EraReport},
+        rpc_server::unresolved_dependencies,
+    },
+    crypto::{hash::Digest, AsymmetricKeyExt},
     effect::{
         requests::{StateStoreRequest, StorageRequest},
         Multiple,
     },
     testing::{ComponentHarness, TestRng, UnitTestEvent},
-    types::{Block, BlockHash, Deploy, DeployHash, DeployMetadata},
+    types::{
+        Block, BlockHash, BlockHeader, Chainspec, Deploy, DeployHash, DeployHeader, DeployMetadata,
+        FinalizedBlock, ProtoBlock, TimeDiff, Timestamp,
+    },
     utils::WithDir,
 };
 
@@ -31,6 +49,8 @@ fn new_config(harness: &ComponentHarness<UnitTestEvent>) -> Config {
         max_deploy_store_size: 50 * MIB,
         max_deploy_metadata_store_size: 50 * MIB,
         max_state_store_size: 50 * MIB,
+        map_size_growth_step: MIB,
+        retained_block_body_eras: None,
     }
 }
 
@@ -47,6 +67,7 @@ fn storage_fixture(harness: &ComponentHarness<UnitTestEvent>) -> Storage {
         &WithDir::new(harness.tmp.path(), cfg),
         None,
         Version::new(1, 0, 0),
+        &Registry::new(),
     )
     .expect("could not create storage component fixture")
 }
@@ -67,6 +88,32 @@ fn storage_fixture_with_hard_reset(
         &WithDir::new(harness.tmp.path(), cfg),
         Some(reset_era_id),
         Version::new(1, 1, 0),
+        &Registry::new(),
+    )
+    .expect("could not create storage component fixture")
+}
+
+/// Storage component test fixture.
+///
+/// Creates a storage component in a temporary directory, retaining the block bodies of the given
+/// number of eras.
+///
+/// # Panics
+///
+/// Panics if setting up the storage fixture fails.
+fn storage_fixture_with_pruning(
+    harness: &ComponentHarness<UnitTestEvent>,
+    retained_block_body_eras: u64,
+) -> Storage {
+    let cfg = Config {
+        retained_block_body_eras: Some(retained_block_body_eras),
+        ..new_config(harness)
+    };
+    Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        Version::new(1, 0, 0),
+        &Registry::new(),
     )
     .expect("could not create storage component fixture")
 }
@@ -108,6 +155,36 @@ fn get_block(
     response
 }
 
+/// Loads a block header from a storage component.
+fn get_block_header(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block_hash: BlockHash,
+) -> Option<BlockHeader> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBlockHeader {
+            block_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Checks whether an item has been pruned from a storage component.
+fn is_pruned(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    item: PrunableItem,
+) -> bool {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::IsPruned { item, responder }.into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Loads a set of deploys from a storage component.
 fn get_deploys(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -142,6 +219,67 @@ fn get_deploy_and_metadata(
     response
 }
 
+/// Loads a page of the deploys of an account from a storage component.
+fn get_account_deploys(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    account_hash: AccountHash,
+    offset: usize,
+    limit: usize,
+) -> Vec<(DeployHash, DeployHeader, DeployMetadata)> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetAccountDeploys {
+            account_hash,
+            offset,
+            limit,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Stores the evidence against a validator in an era in a storage component.
+fn put_evidence(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    era_id: EraId,
+    public_key: PublicKey,
+    evidence: Vec<u8>,
+) -> bool {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::PutEvidence {
+            era_id,
+            public_key,
+            evidence,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Loads the evidence against a validator in an era from a storage component.
+fn get_evidence(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    era_id: EraId,
+    public_key: PublicKey,
+) -> Option<Vec<u8>> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetEvidence {
+            era_id,
+            public_key,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Requests the highest block from a storage component.
 fn get_highest_block(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -864,3 +1002,417 @@ fn should_hard_reset() {
     // Check the highest block is `None`.
     assert!(get_highest_block(&mut harness, &mut storage).is_none());
 }
+
+#[test]
+fn should_index_deploys_by_account() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let secret_key = SecretKey::random(&mut harness.rng);
+    let account_hash = PublicKey::from(&secret_key).to_account_hash();
+
+    // Store three deploys of the same account, oldest first.
+    let mut deploy_hashes = vec![];
+    for seconds in 1..=3u64 {
+        let deploy = Deploy::new(
+            Timestamp::from(seconds * 1_000),
+            TimeDiff::from(60_000),
+            1,
+            vec![],
+            String::from("casper-example"),
+            harness.rng.gen(),
+            harness.rng.gen(),
+            &secret_key,
+        );
+        deploy_hashes.push(*deploy.id());
+        put_deploy(&mut harness, &mut storage, Box::new(deploy));
+    }
+    deploy_hashes.reverse();
+
+    // Deploys of the account should be returned newest first.
+    let account_deploys = get_account_deploys(&mut harness, &mut storage, account_hash, 0, 10);
+    let returned_hashes: Vec<DeployHash> = account_deploys
+        .iter()
+        .map(|(deploy_hash, _, _)| *deploy_hash)
+        .collect();
+    assert_eq!(returned_hashes, deploy_hashes);
+
+    // Pagination should skip the newest deploys.
+    let account_deploys = get_account_deploys(&mut harness, &mut storage, account_hash, 1, 1);
+    assert_eq!(account_deploys.len(), 1);
+    assert_eq!(account_deploys[0].0, deploy_hashes[1]);
+
+    // An unknown account should have no deploys.
+    let other_account_hash = AccountHash::new(harness.rng.gen());
+    assert!(get_account_deploys(&mut harness, &mut storage, other_account_hash, 0, 10).is_empty());
+
+    // A deploy transferring to the other account should be indexed for it once executed.
+    let deploy_hash = deploy_hashes[0];
+    let transfer = Transfer {
+        deploy_hash: casper_types::DeployHash::new(deploy_hash.inner().to_array()),
+        from: account_hash,
+        to: Some(other_account_hash),
+        ..Default::default()
+    };
+    let execution_result = ExecutionResult::Success {
+        effect: ExecutionEffect {
+            operations: vec![],
+            transforms: vec![TransformEntry {
+                key: String::from("transfer"),
+                transform: Transform::WriteTransfer(transfer),
+            }],
+        },
+        transfers: vec![],
+        events: vec![],
+        cost: Default::default(),
+    };
+    let block_hash = BlockHash::random(&mut harness.rng);
+    let mut execution_results = HashMap::new();
+    execution_results.insert(deploy_hash, execution_result.clone());
+    put_execution_results(&mut harness, &mut storage, block_hash, execution_results);
+
+    let account_deploys =
+        get_account_deploys(&mut harness, &mut storage, other_account_hash, 0, 10);
+    assert_eq!(account_deploys.len(), 1);
+    let (returned_hash, _, metadata) = &account_deploys[0];
+    assert_eq!(*returned_hash, deploy_hash);
+    assert_eq!(
+        metadata.execution_results.get(&block_hash),
+        Some(&execution_result)
+    );
+
+    // The sender's index should not contain duplicates.
+    assert_eq!(
+        get_account_deploys(&mut harness, &mut storage, account_hash, 0, 10).len(),
+        3
+    );
+}
+
+#[test]
+fn should_report_unresolved_dependencies() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let new_deploy = |harness: &mut ComponentHarness<UnitTestEvent>, dependencies| {
+        Deploy::new(
+            Timestamp::now(),
+            TimeDiff::from(60_000),
+            1,
+            dependencies,
+            String::from("casper-example"),
+            harness.rng.gen(),
+            harness.rng.gen(),
+            &SecretKey::random(&mut harness.rng),
+        )
+    };
+    let executed = new_deploy(&mut harness, vec![]);
+    let pending = new_deploy(&mut harness, vec![]);
+    let unknown = DeployHash::random(&mut harness.rng);
+    let deploy = new_deploy(&mut harness, vec![*executed.id(), *pending.id(), unknown]);
+    for deploy in [&executed, &pending, &deploy].iter() {
+        put_deploy(&mut harness, &mut storage, Box::new((*deploy).clone()));
+    }
+    let mut execution_results = HashMap::new();
+    execution_results.insert(*executed.id(), harness.rng.gen());
+    let block_hash = BlockHash::random(&mut harness.rng);
+    put_execution_results(&mut harness, &mut storage, block_hash, execution_results);
+
+    // Looks up the deploy and its dependencies as `info_get_deploy` does.
+    let get_unresolved = |harness: &mut ComponentHarness<UnitTestEvent>, storage: &mut Storage| {
+        let (deploy, metadata) = get_deploy_and_metadata(harness, storage, *deploy.id()).unwrap();
+        let dependency_metadata: Vec<_> = deploy
+            .header()
+            .dependencies()
+            .iter()
+            .map(|dependency| {
+                get_deploy_and_metadata(harness, storage, *dependency).map(|(_, metadata)| metadata)
+            })
+            .collect();
+        unresolved_dependencies(&deploy, &metadata, &dependency_metadata)
+    };
+
+    // Dependencies which haven't been executed or aren't known are unresolved.
+    assert_eq!(
+        get_unresolved(&mut harness, &mut storage),
+        vec![*pending.id(), unknown]
+    );
+
+    // Once the deploy itself has been executed, no dependencies are unresolved.
+    let mut execution_results = HashMap::new();
+    execution_results.insert(*deploy.id(), harness.rng.gen());
+    let block_hash = BlockHash::random(&mut harness.rng);
+    put_execution_results(&mut harness, &mut storage, block_hash, execution_results);
+    assert!(get_unresolved(&mut harness, &mut storage).is_empty());
+}
+
+#[test]
+fn should_prune_block_bodies_outside_retained_eras() {
+    let blocks_count = 9_usize;
+    let blocks_per_era = 3;
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture_with_pruning(&harness, 1);
+
+    // Create and store 9 blocks, 0-2 in era 0, 3-5 in era 1 and 6-8 in era 2, each with one deploy.
+    let mut blocks = vec![];
+    let mut deploys = vec![];
+    for index in 0..blocks_count {
+        let deploy = Deploy::random(&mut harness.rng);
+        let is_switch = index % blocks_per_era == blocks_per_era - 1;
+        let era_report = if is_switch {
+            Some(EraReport {
+                equivocators: vec![],
+                rewards: BTreeMap::new(),
+                inactive_validators: vec![],
+            })
+        } else {
+            None
+        };
+        let finalized_block = FinalizedBlock::new(
+            ProtoBlock::new(vec![*deploy.id()], vec![], Timestamp::now(), false),
+            era_report,
+            EraId((index / blocks_per_era) as u64),
+            index as u64,
+            PublicKey::from(&SecretKey::random(&mut harness.rng)),
+        );
+        let block = Block::new(
+            BlockHash::random(&mut harness.rng),
+            Digest::random(&mut harness.rng),
+            Digest::random(&mut harness.rng),
+            finalized_block,
+            if is_switch {
+                Some(BTreeMap::new())
+            } else {
+                None
+            },
+            ProtocolVersion::V1_0_0,
+        );
+        put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
+        let mut execution_results = HashMap::new();
+        execution_results.insert(*deploy.id(), harness.rng.gen());
+        put_execution_results(&mut harness, &mut storage, *block.hash(), execution_results);
+        assert!(put_block(
+            &mut harness,
+            &mut storage,
+            Box::new(block.clone())
+        ));
+        blocks.push(block);
+        deploys.push(deploy);
+    }
+
+    // Once the switch block of era 2 is stored, only the bodies of era 2 and the current era are
+    // retained, so the non-switch blocks of eras 0 and 1 are pruned.
+    let assert_pruned =
+        |harness: &mut ComponentHarness<UnitTestEvent>, storage: &mut Storage, pruned: &[usize]| {
+            for (index, (block, deploy)) in blocks.iter().zip(&deploys).enumerate() {
+                let expect_pruned = pruned.contains(&index);
+                let block_hash = *block.hash();
+                let deploy_hash = *deploy.id();
+                assert_eq!(
+                    get_block(harness, storage, block_hash).is_none(),
+                    expect_pruned
+                );
+                assert_eq!(
+                    get_deploy_and_metadata(harness, storage, deploy_hash).is_none(),
+                    expect_pruned
+                );
+                assert_eq!(
+                    is_pruned(harness, storage, PrunableItem::Block(block_hash)),
+                    expect_pruned
+                );
+                assert_eq!(
+                    is_pruned(harness, storage, PrunableItem::BlockAtHeight(index as u64)),
+                    expect_pruned
+                );
+                assert_eq!(
+                    is_pruned(harness, storage, PrunableItem::Deploy(deploy_hash)),
+                    expect_pruned
+                );
+                // Headers are retained for all blocks.
+                assert_eq!(
+                    get_block_header(harness, storage, block_hash).as_ref(),
+                    Some(block.header())
+                );
+            }
+        };
+    assert_pruned(&mut harness, &mut storage, &[0, 1, 3, 4]);
+
+    // Items which were never stored are not reported as pruned.
+    let unknown_block_hash = BlockHash::random(&mut harness.rng);
+    assert!(!is_pruned(
+        &mut harness,
+        &mut storage,
+        PrunableItem::Block(unknown_block_hash)
+    ));
+    let unknown_deploy_hash = DeployHash::random(&mut harness.rng);
+    assert!(!is_pruned(
+        &mut harness,
+        &mut storage,
+        PrunableItem::Deploy(unknown_deploy_hash)
+    ));
+
+    // Pruning persists across instantiations, and retaining fewer eras prunes the non-switch
+    // blocks of era 2 too.
+    drop(storage);
+    let mut storage = storage_fixture_with_pruning(&harness, 0);
+    assert_pruned(&mut harness, &mut storage, &[0, 1, 3, 4, 6, 7]);
+}
+
+#[test]
+fn should_reject_retention_shorter_than_deploy_ttl() {
+    let harness = ComponentHarness::default();
+    let mut rng = TestRng::new();
+    let mut chainspec = Chainspec::random(&mut rng);
+    chainspec.core_config.era_duration = TimeDiff::from_seconds(3600);
+    chainspec.core_config.minimum_era_height = 1;
+    chainspec.highway_config.minimum_round_exponent = 10;
+    chainspec.deploy_config.max_ttl = TimeDiff::from_seconds(24 * 3600);
+    let config_retaining = |retained_block_body_eras| Config {
+        retained_block_body_eras,
+        ..new_config(&harness)
+    };
+
+    // A day's deploys span 24 one-hour eras, plus the partially elapsed oldest one and one more
+    // for clock drift.
+    for retained_block_body_eras in &[0, 1, 25] {
+        assert!(matches!(
+            config_retaining(Some(*retained_block_body_eras))
+                .check_block_body_retention(&chainspec),
+            Err(Error::InsufficientBlockBodyRetention { required: 26, .. })
+        ));
+    }
+    assert!(config_retaining(Some(26))
+        .check_block_body_retention(&chainspec)
+        .is_ok());
+    assert!(config_retaining(None)
+        .check_block_body_retention(&chainspec)
+        .is_ok());
+}
+
+#[test]
+fn should_grow_map_size_up_to_maximum() {
+    const MIB: usize = 1024 * 1024;
+
+    let mut harness = ComponentHarness::default();
+    let storage = storage_fixture(&harness);
+
+    // The map starts out at one growth step and is grown by another on opening, since less than a
+    // step of it remains unused.
+    let map_size = storage.env().map_usage().unwrap().map_size;
+    assert_eq!(map_size, 2 * MIB);
+    assert_eq!(storage.metrics.map_size.get(), map_size as i64);
+
+    // It is grown by a further step once more than a step's worth of data has been written.
+    let mut writes = 0;
+    while storage.env().map_usage().unwrap().map_size == map_size {
+        assert!(writes < 10_000, "map should have grown");
+        storage
+            .write_deploy(&Deploy::random(&mut harness.rng))
+            .unwrap();
+        writes += 1;
+    }
+    let usage = storage.env().map_usage().unwrap();
+    assert_eq!(usage.map_size, 3 * MIB);
+    assert!(usage.used > MIB);
+    assert_eq!(storage.metrics.map_size.get(), usage.map_size as i64);
+    drop(storage);
+
+    // With a growth step exceeding the remaining room, the map is only grown up to the sum of the
+    // maximum store sizes.
+    let cfg = Config {
+        max_block_store_size: MIB,
+        max_deploy_store_size: 2 * MIB,
+        max_deploy_metadata_store_size: MIB,
+        max_state_store_size: MIB,
+        map_size_growth_step: 4 * MIB,
+        ..new_config(&harness)
+    };
+    let storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        Version::new(1, 0, 0),
+        &Registry::new(),
+    )
+    .expect("could not create storage component fixture");
+    for _ in 0..3 {
+        storage
+            .write_deploy(&Deploy::random(&mut harness.rng))
+            .unwrap();
+        assert_eq!(storage.env().map_usage().unwrap().map_size, 5 * MIB);
+    }
+}
+
+#[test]
+fn should_keep_first_evidence_per_validator_and_era_across_instantiations() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let alice = PublicKey::from(&SecretKey::random(&mut harness.rng));
+    let bob = PublicKey::from(&SecretKey::random(&mut harness.rng));
+    let evidence: Vec<u8> = (0..100).map(|_| harness.rng.gen()).collect();
+
+    assert!(get_evidence(&mut harness, &mut storage, EraId(3), alice).is_none());
+    assert!(put_evidence(
+        &mut harness,
+        &mut storage,
+        EraId(3),
+        alice,
+        evidence.clone()
+    ));
+    // Evidence that was already stored is not replaced.
+    assert!(!put_evidence(
+        &mut harness,
+        &mut storage,
+        EraId(3),
+        alice,
+        vec![1, 2, 3]
+    ));
+
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+
+    assert_eq!(
+        get_evidence(&mut harness, &mut storage, EraId(3), alice),
+        Some(evidence)
+    );
+    assert!(get_evidence(&mut harness, &mut storage, EraId(4), alice).is_none());
+    assert!(get_evidence(&mut harness, &mut storage, EraId(3), bob).is_none());
+}
+
+#[test]
+fn should_report_corrupt_entries_when_opened_read_only() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = random_block_at_height(&mut harness.rng, 42);
+    put_block(&mut harness, &mut storage, block.clone());
+
+    // Store a copy of the block's body under the body hash of a different block.
+    let other_block = random_block_at_height(&mut harness.rng, 43);
+    let mut txn = storage.env.begin_rw_txn().unwrap();
+    txn.put_value(
+        storage.block_body_db,
+        other_block.header().body_hash(),
+        block.body(),
+        true,
+    )
+    .unwrap();
+    txn.commit().unwrap();
+    drop(storage);
+
+    let cfg = new_config(&harness);
+    let (storage, corruptions) =
+        Storage::open_read_only(&WithDir::new(harness.tmp.path(), cfg), &Registry::new())
+            .expect("should open storage read-only");
+    assert!(matches!(
+        corruptions[..],
+        [Error::CorruptEntry {
+            database: "block_body",
+            ..
+        }]
+    ));
+    assert_eq!(storage.read_highest_block_height(), Some(42));
+}
@@ -5,13 +5,10 @@
 
 use ed25519_dalek::ExpandedSecretKey;
 use k256::ecdsa::{
-    signature::{Signer, Verifier},
-    Signature as Secp256k1Signature, SigningKey as Secp256k1SecretKey,
-    VerifyingKey as Secp256k1PublicKey,
+    signature::Signer, Signature as Secp256k1Signature, SigningKey as Secp256k1SecretKey,
 };
 #[cfg(test)]
 use k256::elliptic_curve::sec1::ToEncodedPoint;
-use signature::Signature as _Signature;
 
 use casper_types::{PublicKey, SecretKey, Signature};
 
@@ -59,41 +56,7 @@ pub fn verify<T: AsRef<[u8]>>(
     signature: &Signature,
     public_key: &PublicKey,
 ) -> Result<()> {
-    match (signature, public_key) {
-        (Signature::System, _) => Err(Error::AsymmetricKey(String::from(
-            "signatures based on the system key cannot be verified",
-        ))),
-        (Signature::Ed25519(signature), PublicKey::Ed25519(public_key)) => public_key
-            .verify_strict(
-                message.as_ref(),
-                &ed25519_dalek::Signature::from_bytes(signature).map_err(|_| {
-                    Error::AsymmetricKey(format!(
-                        "failed to construct Ed25519 signature from {:?}",
-                        &signature[..]
-                    ))
-                })?,
-            )
-            .map_err(|_| Error::AsymmetricKey(String::from("failed to verify Ed25519 signature"))),
-        (Signature::Secp256k1(signature), PublicKey::Secp256k1(public_key_bytes)) => {
-            let verifier =
-                Secp256k1PublicKey::from_sec1_bytes(public_key_bytes).map_err(|error| {
-                    Error::AsymmetricKey(format!(
-                        "failed to create secp256k1 public key: {}.  Bytes: {:?}",
-                        error, public_key_bytes
-                    ))
-                })?;
-
-            verifier
-                .verify(message.as_ref(), signature)
-                .map_err(|error| {
-                    Error::AsymmetricKey(format!("failed to verify secp256k1 signature: {}", error))
-                })
-        }
-        _ => Err(Error::AsymmetricKey(format!(
-            "type mismatch between {} and {}",
-            signature, public_key
-        ))),
-    }
+    casper_types::verify(message, signature, public_key).map_err(Error::from)
 }
 
 #[cfg(test)]
@@ -0,0 +1,755 @@
+//! Offline verification of the integrity of a node's storage.
+//!
+//! Storage is opened read-only, reporting any block headers, bodies or finality signatures which
+//! are corrupt or not stored under their hashes, and any duplicate blocks at the same height or
+//! switch blocks of the same era.  Then every block in the given range of heights is read as
+//! stored and checked:
+//!
+//! * its body must be stored under the body hash in its header,
+//! * it must name the previous block as its parent,
+//! * every deploy it lists must be stored and valid, unless the block's body has been pruned,
+//! * its finality signatures must be valid, made by validators of the block's era and, if there are
+//!   any, carry more than the finality threshold of the validators' total weight, and
+//! * the global state under its state root must be fully present in the trie store, unless that
+//!   state root is no longer retained when pruning global state.
+//!
+//! Rather than stopping at the first problem, the check collects everything missing or corrupted
+//! into a [`Report`].
+//!
+//! The node must not be running while its storage is checked.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
+
+use num::rational::Ratio;
+use prometheus::Registry;
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{debug, info};
+
+use casper_execution_engine::{
+    shared::newtypes::Blake2bHash, storage::error::lmdb::Error as GlobalStateError,
+};
+use casper_types::{PublicKey, U512};
+
+use crate::{
+    chain_archive::GlobalState,
+    components::{
+        consensus::EraId,
+        storage::{Error as StorageError, Storage, StoredBlock},
+    },
+    crypto::hash::Digest,
+    reactor::validator::Config,
+    types::{chainspec, BlockHash, BlockSignatures, Chainspec, DeployHash, FinalitySignature},
+    utils::{Loadable, WithDir},
+};
+
+/// Error returned if storage could not be checked at all.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Error loading the chainspec.
+    #[error("error loading chainspec: {0}")]
+    LoadChainspec(chainspec::Error),
+
+    /// Error opening or reading the linear chain storage.
+    #[error("storage error: {0}")]
+    Storage(#[from] StorageError),
+
+    /// Error opening or reading the global state storage.
+    #[error("global state error: {0}")]
+    GlobalState(#[from] GlobalStateError),
+
+    /// There are no blocks in storage to check.
+    #[error("no blocks in storage")]
+    NoBlocks,
+
+    /// The requested range of block heights is empty.
+    #[error("invalid block height range: {from_height} to {to_height}")]
+    InvalidHeightRange {
+        /// The first height of the range.
+        from_height: u64,
+        /// The last height of the range.
+        to_height: u64,
+    },
+}
+
+/// A problem found in storage.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Problem {
+    /// An entry of a block database cannot be decoded, is not stored under its hash or is invalid.
+    CorruptEntry {
+        /// The name of the database.
+        database: &'static str,
+        /// The hex-encoded key of the entry.
+        key: String,
+        /// What is wrong with the entry.
+        error: String,
+    },
+    /// There are several blocks at the same height.
+    DuplicateBlocks {
+        /// The height of the blocks.
+        height: u64,
+        /// The hash of the block indexed at that height.
+        first: BlockHash,
+        /// The hash of the other block at that height.
+        second: BlockHash,
+    },
+    /// There are several switch blocks in the same era.
+    DuplicateSwitchBlocks {
+        /// The era ID.
+        era_id: EraId,
+        /// The hash of the switch block indexed for that era.
+        first: BlockHash,
+        /// The hash of the other switch block in that era.
+        second: BlockHash,
+    },
+    /// Storage is inconsistent in some other way.
+    InconsistentStorage {
+        /// What is wrong with storage.
+        error: String,
+    },
+    /// There are no blocks at the given heights.
+    MissingBlocks {
+        /// The lowest missing height.
+        from_height: u64,
+        /// The highest missing height.
+        to_height: u64,
+    },
+    /// The block at the given height could not be read.
+    UnreadableBlock {
+        /// The height of the block.
+        height: u64,
+        /// The error reading the block.
+        error: String,
+    },
+    /// The body of a block is missing, although it has not been pruned.
+    MissingBlockBody {
+        /// The height of the block.
+        height: u64,
+        /// The body hash in the block's header.
+        body_hash: Digest,
+    },
+    /// The body of a block is not stored under the body hash in its header.
+    BodyHashMismatch {
+        /// The height of the block.
+        height: u64,
+        /// The body hash in the block's header.
+        body_hash: Digest,
+        /// The hash of the stored body.
+        actual: Digest,
+    },
+    /// A block does not name the block at the previous height as its parent.
+    ParentMismatch {
+        /// The height of the block.
+        height: u64,
+        /// The parent hash in the block's header.
+        parent_hash: BlockHash,
+        /// The hash of the block at the previous height.
+        expected: BlockHash,
+    },
+    /// A deploy of a block is missing.
+    MissingDeploy {
+        /// The height of the block.
+        height: u64,
+        /// The hash of the deploy.
+        deploy_hash: DeployHash,
+    },
+    /// A deploy of a block could not be read, is not stored under its hash or is invalid.
+    CorruptDeploy {
+        /// The height of the block.
+        height: u64,
+        /// The hash of the deploy.
+        deploy_hash: DeployHash,
+        /// What is wrong with the deploy.
+        error: String,
+    },
+    /// The validators of an era are unknown, so the finality signatures of its blocks could only
+    /// be checked for validity.
+    UnknownEraValidators {
+        /// The era ID.
+        era_id: EraId,
+    },
+    /// A finality signature of a block is invalid.
+    InvalidFinalitySignature {
+        /// The height of the block.
+        height: u64,
+        /// The signer's public key.
+        public_key: PublicKey,
+        /// What is wrong with the signature.
+        error: String,
+    },
+    /// A finality signature of a block was made by a key which is not a validator of its era.
+    UnknownSigner {
+        /// The height of the block.
+        height: u64,
+        /// The signer's public key.
+        public_key: PublicKey,
+    },
+    /// The valid finality signatures of a block don't carry enough of the validators' weight.
+    InsufficientSignatureWeight {
+        /// The height of the block.
+        height: u64,
+        /// Why the signatures are insufficient.
+        error: String,
+    },
+    /// Tries of the global state under a block's state root are missing.
+    MissingTries {
+        /// The height of the block.
+        height: u64,
+        /// The block's state root hash.
+        state_root_hash: Digest,
+        /// The keys of the missing tries.
+        missing_tries: Vec<Digest>,
+    },
+}
+
+/// The outcome of a storage check.
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    /// The height of the first block checked.
+    pub from_height: u64,
+    /// The height of the last block checked.
+    pub to_height: u64,
+    /// The number of blocks checked.
+    pub blocks_checked: u64,
+    /// The number of checked blocks whose bodies have been pruned.
+    pub pruned_blocks: u64,
+    /// The number of checked blocks without any finality signatures.
+    pub blocks_without_signatures: u64,
+    /// The number of deploys checked.
+    pub deploys_checked: u64,
+    /// The number of distinct state roots checked.
+    pub state_roots_checked: u64,
+    /// The number of distinct tries found under the state roots checked.
+    pub tries_checked: u64,
+    /// The problems found.
+    pub problems: Vec<Problem>,
+}
+
+impl From<StorageError> for Problem {
+    fn from(error: StorageError) -> Self {
+        match error {
+            StorageError::CorruptEntry {
+                database,
+                key,
+                error,
+            } => Problem::CorruptEntry {
+                database,
+                key,
+                error,
+            },
+            StorageError::DuplicateBlockIndex {
+                height,
+                first,
+                second,
+            } => Problem::DuplicateBlocks {
+                height,
+                first,
+                second,
+            },
+            StorageError::DuplicateEraIdIndex {
+                era_id,
+                first,
+                second,
+            } => Problem::DuplicateSwitchBlocks {
+                era_id,
+                first,
+                second,
+            },
+            error => Problem::InconsistentStorage {
+                error: error.to_string(),
+            },
+        }
+    }
+}
+
+impl Report {
+    /// Returns `true` if no problems were found.
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Checks the blocks in the given range of heights, along with their deploys, finality signatures
+/// and global state, and reports anything missing or corrupted.
+///
+/// If `maybe_to_height` is `None`, all blocks up to the highest one in storage are checked.
+pub fn check_db(
+    config: WithDir<Config>,
+    from_height: u64,
+    maybe_to_height: Option<u64>,
+) -> Result<Report, Error> {
+    let (root, config) = config.into_parts();
+    let chainspec = Chainspec::from_path(&root).map_err(Error::LoadChainspec)?;
+    let (storage, corruptions) = Storage::open_read_only(
+        &WithDir::new(&root, config.storage.clone()),
+        &Registry::new(),
+    )?;
+
+    let to_height = match maybe_to_height {
+        Some(to_height) => to_height,
+        None => storage.read_highest_block_height().ok_or(Error::NoBlocks)?,
+    };
+    if from_height > to_height {
+        return Err(Error::InvalidHeightRange {
+            from_height,
+            to_height,
+        });
+    }
+
+    let genesis_validators = chainspec
+        .network_config
+        .chainspec_validator_stakes()
+        .into_iter()
+        .map(|(public_key, motes)| (public_key, motes.value()))
+        .collect();
+    let mut checker = BlockChecker::new(
+        &storage,
+        genesis_validators,
+        chainspec.highway_config.finality_threshold_fraction,
+        from_height,
+        to_height,
+    );
+    checker
+        .report
+        .problems
+        .extend(corruptions.into_iter().map(Problem::from));
+    for height in from_height..=to_height {
+        checker.check_height(height)?;
+    }
+    let (mut report, state_roots) = checker.finish();
+    info!(
+        from_height,
+        to_height,
+        blocks_checked = report.blocks_checked,
+        "checked blocks"
+    );
+
+    // Only the global state under the most recent state roots is retained when pruning.
+    let contract_runtime = &config.contract_runtime;
+    let retained_state_roots = if contract_runtime.prune_global_state() {
+        contract_runtime.retained_state_roots() as usize
+    } else {
+        state_roots.len()
+    };
+    let first_retained = state_roots.len().saturating_sub(retained_state_roots);
+    check_global_state(&root, &config, &state_roots[first_retained..], &mut report)?;
+
+    info!(problems = report.problems.len(), "storage check complete");
+    Ok(report)
+}
+
+/// Checks that the global state under each of the given state roots, paired with the height of
+/// the first block with that root, is fully present.
+fn check_global_state(
+    root: &Path,
+    config: &Config,
+    state_roots: &[(u64, Digest)],
+    report: &mut Report,
+) -> Result<(), Error> {
+    let global_state = GlobalState::open(root, config)?;
+    let mut present = HashSet::new();
+    for (height, state_root_hash) in state_roots {
+        let missing_tries =
+            global_state.find_missing_tries(Blake2bHash::from(*state_root_hash), &mut present)?;
+        report.state_roots_checked += 1;
+        if !missing_tries.is_empty() {
+            report.problems.push(Problem::MissingTries {
+                height: *height,
+                state_root_hash: *state_root_hash,
+                missing_tries: missing_tries.into_iter().map(Digest::from).collect(),
+            });
+        }
+    }
+    report.tries_checked = present.len() as u64;
+    Ok(())
+}
+
+/// Checks blocks one height at a time, in ascending order.
+struct BlockChecker<'a> {
+    storage: &'a Storage,
+    report: Report,
+    /// The validators of the genesis era.
+    genesis_validators: BTreeMap<PublicKey, U512>,
+    /// The fraction of the validators' total weight the finality signatures of a block must
+    /// exceed.
+    finality_threshold: Ratio<u64>,
+    /// The validators of each era encountered, if known.
+    era_validators: HashMap<EraId, Option<BTreeMap<PublicKey, U512>>>,
+    /// The lowest height of the current run of missing blocks, if any.
+    missing_from: Option<u64>,
+    /// The hash of the block at the previous height, if it was read.
+    previous_block_hash: Option<BlockHash>,
+    /// The distinct state roots of the blocks checked, with the height of the first block with
+    /// each root.
+    state_roots: Vec<(u64, Digest)>,
+}
+
+impl<'a> BlockChecker<'a> {
+    fn new(
+        storage: &'a Storage,
+        genesis_validators: BTreeMap<PublicKey, U512>,
+        finality_threshold: Ratio<u64>,
+        from_height: u64,
+        to_height: u64,
+    ) -> Self {
+        BlockChecker {
+            storage,
+            report: Report {
+                from_height,
+                to_height,
+                ..Report::default()
+            },
+            genesis_validators,
+            finality_threshold,
+            era_validators: HashMap::new(),
+            missing_from: None,
+            previous_block_hash: None,
+            state_roots: Vec::new(),
+        }
+    }
+
+    fn check_height(&mut self, height: u64) -> Result<(), Error> {
+        let stored_block = match self.storage.read_stored_block_by_height(height) {
+            Ok(Some(stored_block)) => stored_block,
+            Ok(None) => {
+                self.missing_from.get_or_insert(height);
+                self.previous_block_hash = None;
+                return Ok(());
+            }
+            Err(error) => {
+                self.end_missing_blocks(height);
+                self.report.problems.push(Problem::UnreadableBlock {
+                    height,
+                    error: error.to_string(),
+                });
+                self.previous_block_hash = None;
+                return Ok(());
+            }
+        };
+        self.end_missing_blocks(height);
+        self.check_block(height, &stored_block)?;
+        self.previous_block_hash = Some(stored_block.block_hash);
+        Ok(())
+    }
+
+    /// Reports the current run of missing blocks, if any, as ending below `height`.
+    fn end_missing_blocks(&mut self, height: u64) {
+        if let Some(from_height) = self.missing_from.take() {
+            self.report.problems.push(Problem::MissingBlocks {
+                from_height,
+                to_height: height - 1,
+            });
+        }
+    }
+
+    fn check_block(&mut self, height: u64, stored_block: &StoredBlock) -> Result<(), Error> {
+        let header = &stored_block.header;
+        self.report.blocks_checked += 1;
+        debug!(height, block_hash = %stored_block.block_hash, "checking block");
+
+        if let Some(expected) = self.previous_block_hash {
+            if !header.is_genesis_child() && *header.parent_hash() != expected {
+                self.report.problems.push(Problem::ParentMismatch {
+                    height,
+                    parent_hash: *header.parent_hash(),
+                    expected,
+                });
+            }
+        }
+
+        match &stored_block.body {
+            Some(body) => {
+                let actual = body.hash();
+                if actual != *header.body_hash() {
+                    self.report.problems.push(Problem::BodyHashMismatch {
+                        height,
+                        body_hash: *header.body_hash(),
+                        actual,
+                    });
+                }
+                for deploy_hash in body.deploy_hashes().iter().chain(body.transfer_hashes()) {
+                    self.check_deploy(height, deploy_hash);
+                }
+            }
+            None if stored_block.is_pruned => self.report.pruned_blocks += 1,
+            None => self.report.problems.push(Problem::MissingBlockBody {
+                height,
+                body_hash: *header.body_hash(),
+            }),
+        }
+
+        match &stored_block.signatures {
+            Some(signatures) => {
+                let mut valid_signatures =
+                    BlockSignatures::new(stored_block.block_hash, header.era_id());
+                for (public_key, signature) in &signatures.proofs {
+                    let is_valid = self.check_finality_signature(
+                        height,
+                        FinalitySignature {
+                            block_hash: stored_block.block_hash,
+                            era_id: header.era_id(),
+                            signature: *signature,
+                            public_key: *public_key,
+                        },
+                    )?;
+                    if is_valid {
+                        valid_signatures.insert_proof(*public_key, *signature);
+                    }
+                }
+                self.check_signature_weight(height, &valid_signatures)?;
+            }
+            None => self.report.blocks_without_signatures += 1,
+        }
+
+        let state_root_hash = *header.state_root_hash();
+        if self.state_roots.last().map(|(_, root)| *root) != Some(state_root_hash) {
+            self.state_roots.push((height, state_root_hash));
+        }
+        Ok(())
+    }
+
+    fn check_deploy(&mut self, height: u64, deploy_hash: &DeployHash) {
+        self.report.deploys_checked += 1;
+        let error = match self.storage.read_deploy(deploy_hash) {
+            Ok(Some(mut deploy)) => {
+                if deploy.id() != deploy_hash {
+                    format!("stored deploy has hash {}", deploy.id())
+                } else if let Err(error) = deploy.is_valid() {
+                    error.to_string()
+                } else {
+                    return;
+                }
+            }
+            Ok(None) => {
+                self.report.problems.push(Problem::MissingDeploy {
+                    height,
+                    deploy_hash: *deploy_hash,
+                });
+                return;
+            }
+            Err(error) => error.to_string(),
+        };
+        self.report.problems.push(Problem::CorruptDeploy {
+            height,
+            deploy_hash: *deploy_hash,
+            error,
+        });
+    }
+
+    /// Checks a finality signature, returning whether it is valid and made by a validator of its
+    /// era.
+    fn check_finality_signature(
+        &mut self,
+        height: u64,
+        signature: FinalitySignature,
+    ) -> Result<bool, Error> {
+        let is_valid = match signature.verify() {
+            Ok(()) => true,
+            Err(error) => {
+                self.report
+                    .problems
+                    .push(Problem::InvalidFinalitySignature {
+                        height,
+                        public_key: signature.public_key,
+                        error: error.to_string(),
+                    });
+                false
+            }
+        };
+        let is_validator = match self.validators(signature.era_id)? {
+            Some(validators) => validators.contains_key(&signature.public_key),
+            // The unknown validator set has already been reported.
+            None => true,
+        };
+        if !is_validator {
+            self.report.problems.push(Problem::UnknownSigner {
+                height,
+                public_key: signature.public_key,
+            });
+        }
+        Ok(is_valid && is_validator)
+    }
+
+    /// Checks that the given valid finality signatures of a block carry more than the finality
+    /// threshold of the validators' total weight.
+    fn check_signature_weight(
+        &mut self,
+        height: u64,
+        signatures: &BlockSignatures,
+    ) -> Result<(), Error> {
+        let finality_threshold = self.finality_threshold;
+        let result = match self.validators(signatures.era_id)? {
+            Some(validators) => signatures.verify_quorum(validators, finality_threshold),
+            // The unknown validator set has already been reported.
+            None => return Ok(()),
+        };
+        if let Err(error) = result {
+            self.report
+                .problems
+                .push(Problem::InsufficientSignatureWeight {
+                    height,
+                    error: error.to_string(),
+                });
+        }
+        Ok(())
+    }
+
+    /// Returns the validators of the given era, read from the switch block of the era before, if
+    /// known.
+    fn validators(&mut self, era_id: EraId) -> Result<Option<&BTreeMap<PublicKey, U512>>, Error> {
+        if !self.era_validators.contains_key(&era_id) {
+            let validators = if era_id.0 == 0 {
+                Some(self.genesis_validators.clone())
+            } else {
+                self.storage
+                    .read_switch_block_header_by_era_id(EraId(era_id.0 - 1))?
+                    .and_then(|header| header.next_era_validator_weights().cloned())
+            };
+            if validators.is_none() {
+                self.report
+                    .problems
+                    .push(Problem::UnknownEraValidators { era_id });
+            }
+            self.era_validators.insert(era_id, validators);
+        }
+        Ok(self.era_validators[&era_id].as_ref())
+    }
+
+    /// Reports any missing blocks at the end of the range, returning the report along with the
+    /// distinct state roots of the blocks checked.
+    fn finish(mut self) -> (Report, Vec<(u64, Digest)>) {
+        self.end_missing_blocks(self.report.to_height + 1);
+        (self.report, self.state_roots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::SecretKey;
+
+    use super::*;
+    use crate::{
+        components::storage::Config as StorageConfig, crypto::AsymmetricKeyExt, types::Block,
+    };
+
+    #[test]
+    fn should_report_missing_and_inconsistent_blocks() {
+        let mut rng = crate::new_rng();
+        let (storage_config, tempdir) = StorageConfig::default_for_tests();
+        let mut storage = Storage::new(
+            &WithDir::new(tempdir.path(), storage_config),
+            None,
+            semver::Version::new(1, 0, 0),
+            &Registry::new(),
+        )
+        .unwrap();
+
+        // Blocks at heights 1 and 2 which are not linked, and none at heights 0, 3 and 4.
+        let mut blocks = vec![];
+        for height in 1..=2 {
+            let mut block = Block::random(&mut rng);
+            block.set_height(height);
+            storage.write_block(&block).unwrap();
+            blocks.push(block);
+        }
+
+        // A signature of the second block, stored as a signature of the first one.
+        let secret_key = SecretKey::random(&mut rng);
+        let public_key = PublicKey::from(&secret_key);
+        let era_id = blocks[0].header().era_id();
+        let signature = FinalitySignature::new(*blocks[1].hash(), era_id, &secret_key, public_key);
+        let mut signatures = BlockSignatures::new(*blocks[0].hash(), era_id);
+        signatures.insert_proof(public_key, signature.signature);
+        storage.write_block_signatures(signatures).unwrap();
+
+        let mut checker = BlockChecker::new(&storage, BTreeMap::new(), Ratio::new(1, 3), 0, 4);
+        for height in 0..=4 {
+            checker.check_height(height).unwrap();
+        }
+        let (report, _) = checker.finish();
+        assert_eq!(report.blocks_checked, 2);
+        assert_eq!(report.blocks_without_signatures, 1);
+
+        let deploy_count = blocks
+            .iter()
+            .map(|block| block.deploy_hashes().len() + block.transfer_hashes().len())
+            .sum::<usize>();
+        assert_eq!(report.deploys_checked, deploy_count as u64);
+        let missing_deploys = report
+            .problems
+            .iter()
+            .filter(|problem| matches!(problem, Problem::MissingDeploy { .. }))
+            .count();
+        assert_eq!(missing_deploys, deploy_count);
+
+        assert!(matches!(
+            report.problems[0],
+            Problem::MissingBlocks {
+                from_height: 0,
+                to_height: 0
+            }
+        ));
+        assert!(report.problems.iter().any(|problem| matches!(
+            problem,
+            Problem::ParentMismatch { height: 2, expected, .. } if expected == blocks[0].hash()
+        )));
+        assert!(report
+            .problems
+            .iter()
+            .any(|problem| matches!(problem, Problem::InvalidFinalitySignature { height: 1, .. })));
+        assert!(matches!(
+            report.problems.last(),
+            Some(Problem::MissingBlocks {
+                from_height: 3,
+                to_height: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn should_report_insufficient_signature_weight() {
+        let mut rng = crate::new_rng();
+        let (storage_config, tempdir) = StorageConfig::default_for_tests();
+        let mut storage = Storage::new(
+            &WithDir::new(tempdir.path(), storage_config),
+            None,
+            semver::Version::new(1, 0, 0),
+            &Registry::new(),
+        )
+        .unwrap();
+
+        let mut block = Block::random(&mut rng);
+        block.set_height(0);
+        storage.write_block(&block).unwrap();
+        let era_id = block.header().era_id();
+
+        // Alice signs the block, but Bob, holding most of the weight, doesn't.
+        let alice_secret_key = SecretKey::random(&mut rng);
+        let alice = PublicKey::from(&alice_secret_key);
+        let bob = PublicKey::from(&SecretKey::random(&mut rng));
+        let signature = FinalitySignature::new(*block.hash(), era_id, &alice_secret_key, alice);
+        let mut signatures = BlockSignatures::new(*block.hash(), era_id);
+        signatures.insert_proof(alice, signature.signature);
+        storage.write_block_signatures(signatures).unwrap();
+
+        let validators = vec![(alice, U512::from(1)), (bob, U512::from(10))]
+            .into_iter()
+            .collect();
+        let mut checker = BlockChecker::new(&storage, BTreeMap::new(), Ratio::new(1, 3), 0, 0);
+        checker.era_validators.insert(era_id, Some(validators));
+        checker.check_height(0).unwrap();
+        let (report, _) = checker.finish();
+        assert!(report.problems.iter().any(|problem| matches!(
+            problem,
+            Problem::InsufficientSignatureWeight { height: 0, .. }
+        )));
+        assert!(!report.problems.iter().any(|problem| matches!(
+            problem,
+            Problem::InvalidFinalitySignature { .. } | Problem::UnknownSigner { .. }
+        )));
+    }
+}
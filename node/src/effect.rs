@@ -98,17 +98,20 @@ use casper_execution_engine::{
     storage::{global_state::CommitResult, protocol_data::ProtocolData, trie::Trie},
 };
 use casper_types::{
-    system::auction::EraValidators, ExecutionResult, Key, ProtocolVersion, PublicKey, Transfer,
+    account::AccountHash, system::auction::EraValidators, ExecutionResult, Key, ProtocolVersion,
+    PublicKey, Transfer,
 };
 
 use crate::{
     components::{
+        block_proposer::{DeployType, PendingDeployLimitError, PendingDeploys},
         chainspec_loader::NextUpgrade,
-        consensus::{BlockContext, EraId},
+        consensus::{BlockContext, EraId, EraValidatorStatus},
         contract_runtime::EraValidatorsRequest,
         deploy_acceptor,
         fetcher::FetchResult,
         small_network::GossipedAddress,
+        storage::PrunableItem,
     },
     crypto::hash::Digest,
     effect::requests::LinearChainRequest,
@@ -121,9 +124,9 @@ use crate::{
     utils::Source,
 };
 use announcements::{
-    BlockExecutorAnnouncement, ChainspecLoaderAnnouncement, ConsensusAnnouncement,
-    ControlAnnouncement, DeployAcceptorAnnouncement, GossiperAnnouncement, LinearChainAnnouncement,
-    NetworkAnnouncement, RpcServerAnnouncement,
+    BlockExecutorAnnouncement, BlockProposerAnnouncement, ChainspecLoaderAnnouncement,
+    ConsensusAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement, GossiperAnnouncement,
+    LinearChainAnnouncement, NetworkAnnouncement, RpcServerAnnouncement,
 };
 use requests::{
     BlockExecutorRequest, BlockProposerRequest, BlockValidationRequest, ChainspecLoaderRequest,
@@ -948,6 +951,82 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Puts the serialized evidence against an equivocating validator in an era to storage.
+    pub(crate) async fn put_evidence_to_storage(
+        self,
+        era_id: EraId,
+        public_key: PublicKey,
+        evidence: Vec<u8>,
+    ) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutEvidence {
+                era_id,
+                public_key,
+                evidence,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets the serialized evidence against a validator in an era from storage.
+    pub(crate) async fn get_evidence_from_storage(
+        self,
+        era_id: EraId,
+        public_key: PublicKey,
+    ) -> Option<Vec<u8>>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetEvidence {
+                era_id,
+                public_key,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets a page of the deploys of the given account, newest first, from storage.
+    pub(crate) async fn get_account_deploys_from_storage(
+        self,
+        account_hash: AccountHash,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<(DeployHash, DeployHeader, DeployMetadata)>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetAccountDeploys {
+                account_hash,
+                offset,
+                limit,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Checks whether the given item has been pruned from storage.
+    pub(crate) async fn is_pruned_in_storage(self, item: PrunableItem) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::IsPruned { item, responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Gets the requested block and its associated metadata.
     pub(crate) async fn get_block_at_height_with_metadata_from_storage(
         self,
@@ -1089,6 +1168,39 @@ impl<REv> EffectBuilder<REv> {
         (proto_block, block_context)
     }
 
+    /// Checks whether the block proposer would admit the given deploy to its pending deploys.
+    pub(crate) async fn check_pending_deploy_limits(
+        self,
+        hash: DeployHash,
+        deploy_type: DeployType,
+    ) -> Result<(), PendingDeployLimitError>
+    where
+        REv: From<BlockProposerRequest>,
+    {
+        self.make_request(
+            |responder| BlockProposerRequest::CheckPendingDeployLimits {
+                hash,
+                deploy_type: Box::new(deploy_type),
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Gets the deploys held by the block proposer which haven't been executed yet, optionally only
+    /// those created by the given account.
+    pub(crate) async fn get_pending_deploys(self, account: Option<PublicKey>) -> PendingDeploys
+    where
+        REv: From<BlockProposerRequest>,
+    {
+        self.make_request(
+            |responder| BlockProposerRequest::GetPendingDeploys { account, responder },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Passes a finalized proto-block to the block executor component to execute it.
     pub(crate) async fn execute_block(self, finalized_block: FinalizedBlock)
     where
@@ -1212,6 +1324,27 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// The block proposer has replaced a pending deploy with one paying a higher gas price.
+    pub(crate) async fn announce_deploy_superseded(
+        self,
+        deploy_hash: DeployHash,
+        superseded_by: DeployHash,
+        account: PublicKey,
+    ) where
+        REv: From<BlockProposerAnnouncement>,
+    {
+        self.0
+            .schedule(
+                BlockProposerAnnouncement::DeploySuperseded {
+                    deploy_hash,
+                    superseded_by,
+                    account,
+                },
+                QueueKind::Regular,
+            )
+            .await
+    }
+
     /// Runs the genesis process on the contract runtime.
     pub(crate) async fn commit_genesis(
         self,
@@ -1543,6 +1676,16 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Get our public key from consensus, and the participation of the validators in the bonded
+    /// eras.
+    pub(crate) async fn consensus_validator_status(self) -> (PublicKey, Vec<EraValidatorStatus>)
+    where
+        REv: From<ConsensusRequest>,
+    {
+        self.make_request(ConsensusRequest::ValidatorStatus, QueueKind::Regular)
+            .await
+    }
+
     /// Check if validator is bonded in the future era (`era_id`).
     /// This information is known only by the Contract Runtime since consensus component
     /// knows only about currently active eras.
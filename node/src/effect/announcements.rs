@@ -244,6 +244,33 @@ impl<T: Item> Display for GossiperAnnouncement<T> {
     }
 }
 
+/// A block proposer announcement.
+#[derive(Debug, Serialize)]
+pub enum BlockProposerAnnouncement {
+    /// A pending deploy has been replaced by a deploy from the same account with the same
+    /// timestamp and a higher gas price.
+    DeploySuperseded {
+        /// The hash of the deploy which was replaced.
+        deploy_hash: DeployHash,
+        /// The hash of the deploy which replaced it.
+        superseded_by: DeployHash,
+        /// The account which created both deploys.
+        account: PublicKey,
+    },
+}
+
+impl Display for BlockProposerAnnouncement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockProposerAnnouncement::DeploySuperseded {
+                deploy_hash,
+                superseded_by,
+                ..
+            } => write!(f, "deploy {} superseded by {}", deploy_hash, superseded_by),
+        }
+    }
+}
+
 /// A linear chain announcement.
 #[derive(Debug)]
 pub enum LinearChainAnnouncement {
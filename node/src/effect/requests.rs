@@ -35,6 +35,7 @@ use casper_execution_engine::{
     storage::{global_state::CommitResult, protocol_data::ProtocolData, trie::Trie},
 };
 use casper_types::{
+    account::AccountHash,
     system::auction::{EraValidators, ValidatorWeights},
     ExecutionResult, Key, ProtocolVersion, PublicKey, Transfer, URef,
 };
@@ -42,13 +43,16 @@ use casper_types::{
 use super::Responder;
 use crate::{
     components::{
-        consensus::EraId,
+        block_proposer::{DeployType, PendingDeployLimitError, PendingDeploys},
+        consensus::{EraId, EraValidatorStatus},
         contract_runtime::{EraValidatorsRequest, ValidatorWeightsByEraIdRequest},
         deploy_acceptor::Error,
         fetcher::FetchResult,
+        rpc_server::SpeculativeExecuteError,
+        storage::PrunableItem,
     },
     crypto::hash::Digest,
-    rpcs::chain::BlockIdentifier,
+    rpcs::chain::{BlockIdentifier, BlockWithContents},
     types::{
         Block as LinearBlock, Block, BlockHash, BlockHeader, BlockSignatures, Chainspec,
         ChainspecInfo, Deploy, DeployHash, DeployHeader, DeployMetadata, FinalitySignature,
@@ -313,6 +317,46 @@ pub enum StorageRequest {
         /// Responder to call with the results.
         responder: Responder<Option<(Deploy, DeployMetadata)>>,
     },
+    /// Retrieve the deploys sent by or transferring to or from an account, newest first.
+    GetAccountDeploys {
+        /// Hash of the account to retrieve deploys of.
+        account_hash: AccountHash,
+        /// Number of the account's newest deploys to skip.
+        offset: usize,
+        /// Maximum number of deploys to retrieve.
+        limit: usize,
+        /// Responder to call with the deploy hashes, headers and metadata.
+        responder: Responder<Vec<(DeployHash, DeployHeader, DeployMetadata)>>,
+    },
+    /// Store the serialized evidence against an equivocating validator in an era.
+    PutEvidence {
+        /// The era in which the validator equivocated.
+        era_id: EraId,
+        /// The validator's public key.
+        public_key: PublicKey,
+        /// The serialized evidence.
+        evidence: Vec<u8>,
+        /// Responder to call with the result.  Returns `true` if the evidence was stored, `false`
+        /// if there was already evidence against the validator in the era.
+        responder: Responder<bool>,
+    },
+    /// Retrieve the serialized evidence against a validator in an era.
+    GetEvidence {
+        /// The era in which the validator equivocated.
+        era_id: EraId,
+        /// The validator's public key.
+        public_key: PublicKey,
+        /// Responder to call with the serialized evidence, if any is stored.
+        responder: Responder<Option<Vec<u8>>>,
+    },
+    /// Check whether an item has been pruned from storage.
+    IsPruned {
+        /// The item to check.
+        item: PrunableItem,
+        /// Responder to call with the result.  Returns `true` if the item was held in storage, but
+        /// has since been pruned.
+        responder: Responder<bool>,
+    },
     /// Retrieve block and its metadata by its hash.
     GetBlockAndMetadataByHash {
         /// The hash of the block.
@@ -385,6 +429,31 @@ impl Display for StorageRequest {
             StorageRequest::GetDeployAndMetadata { deploy_hash, .. } => {
                 write!(formatter, "get deploy and metadata for {}", deploy_hash)
             }
+            StorageRequest::GetAccountDeploys {
+                account_hash,
+                offset,
+                limit,
+                ..
+            } => write!(
+                formatter,
+                "get {} deploys of {} from offset {}",
+                limit, account_hash, offset
+            ),
+            StorageRequest::PutEvidence {
+                era_id, public_key, ..
+            } => write!(
+                formatter,
+                "put evidence against {} in era {}",
+                public_key, era_id.0
+            ),
+            StorageRequest::GetEvidence {
+                era_id, public_key, ..
+            } => write!(
+                formatter,
+                "get evidence against {} in era {}",
+                public_key, era_id.0
+            ),
+            StorageRequest::IsPruned { item, .. } => write!(formatter, "is {} pruned", item),
             StorageRequest::GetBlockAndMetadataByHash { block_hash, .. } => {
                 write!(
                     formatter,
@@ -479,6 +548,30 @@ pub struct ProtoBlockRequest {
 pub enum BlockProposerRequest {
     /// Request a list of deploys to propose in a new block.
     RequestProtoBlock(ProtoBlockRequest),
+    /// Check whether a deploy could be added to the pending deploys without exceeding the
+    /// configured limits, possibly by evicting lower-priority deploys.
+    CheckPendingDeployLimits {
+        /// The hash of the deploy to check.
+        hash: DeployHash,
+        /// The deploy to check.
+        deploy_type: Box<DeployType>,
+        /// Responder to call with the result.
+        responder: Responder<Result<(), PendingDeployLimitError>>,
+    },
+    /// Return whether an item has been pruned from storage.
+    IsPruned {
+        /// The item to check.
+        item: PrunableItem,
+        /// Responder to call with the result.
+        responder: Responder<bool>,
+    },
+    /// Return the deploys which are held by the block proposer and haven't been executed yet.
+    GetPendingDeploys {
+        /// If `Some`, only deploys created by the given account are returned.
+        account: Option<PublicKey>,
+        /// Responder to call with the result.
+        responder: Responder<PendingDeploys>,
+    },
 }
 
 impl Display for BlockProposerRequest {
@@ -497,6 +590,16 @@ impl Display for BlockProposerRequest {
                 past_deploys.len(),
                 next_finalized
             ),
+            BlockProposerRequest::CheckPendingDeployLimits { hash, .. } => {
+                write!(formatter, "check pending deploy limits for {}", hash)
+            }
+            BlockProposerRequest::GetPendingDeploys {
+                account: Some(account),
+                ..
+            } => write!(formatter, "get pending deploys of {}", account),
+            BlockProposerRequest::GetPendingDeploys { account: None, .. } => {
+                write!(formatter, "get pending deploys")
+            }
         }
     }
 }
@@ -515,6 +618,17 @@ pub enum RpcRequest<I> {
         /// Responder to call.
         responder: Responder<Result<(), Error>>,
     },
+    /// Execute a deploy without committing its effects to global state.
+    SpeculativeExecute {
+        /// The state root hash against which to execute the deploy.
+        state_root_hash: Digest,
+        /// The deploy to execute.
+        deploy: Box<Deploy>,
+        /// The protocol version under which to execute the deploy.
+        protocol_version: ProtocolVersion,
+        /// Responder to call with the result.
+        responder: Responder<Result<ExecutionResult, SpeculativeExecuteError>>,
+    },
     /// If `maybe_identifier` is `Some`, return the specified block if it exists, else `None`.  If
     /// `maybe_identifier` is `None`, return the latest block.
     GetBlock {
@@ -523,6 +637,21 @@ pub enum RpcRequest<I> {
         /// Responder to call with the result.
         responder: Responder<Option<(LinearBlock, BlockSignatures)>>,
     },
+    /// Return the blocks with heights from `start_height` to `end_height` inclusive, stopping at
+    /// the first one not held in local storage.
+    GetBlockRange {
+        /// The height of the first block to return.
+        start_height: u64,
+        /// The height of the last block to return.
+        end_height: u64,
+        /// Whether to return the deploys of each block.
+        include_deploys: bool,
+        /// Whether to return the transfers of each block.
+        include_transfers: bool,
+        /// Responder to call with the result.  Returns `Err` with the maximum number of blocks
+        /// which can be requested if the range is larger than that.
+        responder: Responder<Result<Vec<BlockWithContents>, u64>>,
+    },
     /// Return transfers for block by hash (if any).
     GetBlockTransfers {
         /// The hash of the block to retrieve transfers for.
@@ -577,8 +706,27 @@ pub enum RpcRequest<I> {
     GetDeploy {
         /// The hash of the deploy to be retrieved.
         hash: DeployHash,
+        /// Responder to call with the deploy, its metadata and its dependencies which haven't been
+        /// executed yet.
+        responder: Responder<Option<(Deploy, DeployMetadata, Vec<DeployHash>)>>,
+    },
+    /// Return a page of the deploys of an account, newest first, with their metadata.
+    GetAccountDeploys {
+        /// The hash of the account.
+        account_hash: AccountHash,
+        /// Number of the account's newest deploys to skip.
+        offset: usize,
+        /// Maximum number of deploys to return.
+        limit: usize,
+        /// Responder to call with the deploy hashes, headers and metadata.
+        responder: Responder<Vec<(DeployHash, DeployHeader, DeployMetadata)>>,
+    },
+    /// Return the deploys which are held by the block proposer and haven't been executed yet.
+    GetPendingDeploys {
+        /// If `Some`, only deploys created by the given account are returned.
+        account: Option<PublicKey>,
         /// Responder to call with the result.
-        responder: Responder<Option<(Deploy, DeployMetadata)>>,
+        responder: Responder<PendingDeploys>,
     },
     /// Return the connected peers.
     GetPeers {
@@ -590,6 +738,20 @@ pub enum RpcRequest<I> {
         /// Responder to call with the result.
         responder: Responder<StatusFeed<I>>,
     },
+    /// Return the serialized evidence against a validator in an era, if any is stored.
+    GetEvidence {
+        /// The era in which the validator equivocated.
+        era_id: EraId,
+        /// The validator's public key.
+        public_key: PublicKey,
+        /// Responder to call with the result.
+        responder: Responder<Option<Vec<u8>>>,
+    },
+    /// Return our public key, and the participation of the validators in the bonded eras.
+    GetValidatorStatus {
+        /// Responder to call with the result.
+        responder: Responder<(PublicKey, Vec<EraValidatorStatus>)>,
+    },
     /// Return string formatted, prometheus compatible metrics or `None` if an error occurred.
     GetMetrics {
         /// Responder to call with the result.
@@ -610,6 +772,15 @@ impl<I> Display for RpcRequest<I> {
                 ..
             } => write!(formatter, "get {}", height),
             RpcRequest::GetBlock { maybe_id: None, .. } => write!(formatter, "get latest block"),
+            RpcRequest::GetBlockRange {
+                start_height,
+                end_height,
+                ..
+            } => write!(
+                formatter,
+                "get blocks from {} to {}",
+                start_height, end_height
+            ),
             RpcRequest::GetBlockTransfers { block_hash, .. } => {
                 write!(formatter, "get transfers {}", block_hash)
             }
@@ -643,9 +814,39 @@ impl<I> Display for RpcRequest<I> {
                 "balance {}, purse_uref: {}",
                 state_root_hash, purse_uref
             ),
+            RpcRequest::SpeculativeExecute {
+                state_root_hash,
+                deploy,
+                ..
+            } => write!(
+                formatter,
+                "speculatively execute {} against {}",
+                deploy.id(),
+                state_root_hash
+            ),
             RpcRequest::GetDeploy { hash, .. } => write!(formatter, "get {}", hash),
+            RpcRequest::GetAccountDeploys {
+                account_hash,
+                offset,
+                limit,
+                ..
+            } => write!(
+                formatter,
+                "get {} deploys of {} from offset {}",
+                limit, account_hash, offset
+            ),
+            RpcRequest::IsPruned { item, .. } => write!(formatter, "is {} pruned", item),
+            RpcRequest::GetPendingDeploys { .. } => write!(formatter, "get pending deploys"),
             RpcRequest::GetPeers { .. } => write!(formatter, "get peers"),
             RpcRequest::GetStatus { .. } => write!(formatter, "get status"),
+            RpcRequest::GetEvidence {
+                era_id, public_key, ..
+            } => write!(
+                formatter,
+                "get evidence against {} in era {}",
+                public_key, era_id.0
+            ),
+            RpcRequest::GetValidatorStatus { .. } => write!(formatter, "get validator status"),
             RpcRequest::GetMetrics { .. } => write!(formatter, "get metrics"),
         }
     }
@@ -997,6 +1198,8 @@ pub enum ConsensusRequest {
     IsBondedValidator(EraId, PublicKey, Responder<bool>),
     /// Request for our public key, and if we're a validator, the next round length.
     Status(Responder<(PublicKey, Option<TimeDiff>)>),
+    /// Request for our public key, and the participation of the validators in the bonded eras.
+    ValidatorStatus(Responder<(PublicKey, Vec<EraValidatorStatus>)>),
 }
 
 /// ChainspecLoader component requests.
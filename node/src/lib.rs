@@ -24,10 +24,12 @@
 
 extern crate test;
 
+mod chain_archive;
 pub mod components;
 mod config_migration;
 pub mod crypto;
 mod data_migration;
+mod db_check;
 pub mod effect;
 pub mod logging;
 pub mod protocol;
@@ -52,8 +54,12 @@ use signal_hook::{
     flag,
 };
 
+pub use chain_archive::{export_chain, import_chain, Error as ChainArchiveError};
+#[cfg(feature = "consensus-sim")]
+pub use components::consensus::{SimulationError, SimulationReport, SimulationScenario};
 pub use components::{
-    consensus::Config as ConsensusConfig,
+    block_proposer::Config as BlockProposerConfig,
+    consensus::{Config as ConsensusConfig, SignerAddress, SigningServer},
     contract_runtime::Config as ContractRuntimeConfig,
     deploy_acceptor::Config as DeployAcceptorConfig,
     event_stream_server::Config as EventStreamServerConfig,
@@ -66,6 +72,9 @@ pub use components::{
 };
 pub use config_migration::{migrate_config, Error as ConfigMigrationError};
 pub use data_migration::{migrate_data, Error as DataMigrationError};
+pub use db_check::{
+    check_db, Error as DbCheckError, Problem as DbCheckProblem, Report as DbCheckReport,
+};
 pub use types::NodeRng;
 pub use utils::OS_PAGE_SIZE;
 
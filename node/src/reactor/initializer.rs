@@ -175,16 +175,28 @@ impl Reactor {
         let hard_reset_to_start_of_era = chainspec_loader.hard_reset_to_start_of_era();
 
         let storage_config = config.map_ref(|cfg| cfg.storage.clone());
+        storage_config
+            .value()
+            .check_block_body_retention(chainspec_loader.chainspec())?;
         let storage = Storage::new(
             &storage_config,
             hard_reset_to_start_of_era,
             chainspec_loader.chainspec().protocol_config.version.clone(),
+            registry,
         )?;
 
+        let contract_runtime_config = &config.value().contract_runtime;
         let contract_runtime =
-            ContractRuntime::new(storage_config, &config.value().contract_runtime, registry)?;
+            ContractRuntime::new(storage_config, contract_runtime_config, registry)?;
 
-        if let Some(state_roots) = storage.get_state_root_hashes_for_trie_check() {
+        // If global state is pruned, only the most recent blocks' global state is guaranteed to be
+        // present.
+        let maybe_most_recent = if contract_runtime_config.prune_global_state() {
+            Some(contract_runtime_config.retained_state_roots())
+        } else {
+            None
+        };
+        if let Some(state_roots) = storage.get_state_root_hashes_for_trie_check(maybe_most_recent) {
             let missing_trie_keys = contract_runtime.trie_store_check(state_roots.clone());
             if !missing_trie_keys.is_empty() {
                 panic!(
@@ -483,8 +483,11 @@ impl reactor::Reactor for Reactor {
             protocol_version.clone(),
         )?;
 
-        let event_stream_server =
-            EventStreamServer::new(config.event_stream_server.clone(), protocol_version.clone())?;
+        let event_stream_server = EventStreamServer::new(
+            config.event_stream_server.clone(),
+            storage.root_path(),
+            protocol_version.clone(),
+        )?;
 
         let block_validator = BlockValidator::new(Arc::clone(&chainspec_loader.chainspec()));
 
@@ -776,6 +779,13 @@ impl reactor::Reactor for Reactor {
                 let mut effects = Effects::new();
                 let block_hash = *block.hash();
 
+                // let the contract runtime know the block's global state should be retained
+                let reactor_event =
+                    Event::ContractRuntime(contract_runtime::Event::BlockExecuted {
+                        state_root_hash: *block.state_root_hash(),
+                    });
+                effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+
                 // send to linear chain
                 let reactor_event = Event::LinearChain(linear_chain::Event::NewLinearChainBlock {
                     block: Box::new(block),
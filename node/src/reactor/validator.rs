@@ -47,9 +47,10 @@ use crate::{
     },
     effect::{
         announcements::{
-            BlockExecutorAnnouncement, ChainspecLoaderAnnouncement, ConsensusAnnouncement,
-            ControlAnnouncement, DeployAcceptorAnnouncement, GossiperAnnouncement,
-            LinearChainAnnouncement, NetworkAnnouncement, RpcServerAnnouncement,
+            BlockExecutorAnnouncement, BlockProposerAnnouncement, ChainspecLoaderAnnouncement,
+            ConsensusAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement,
+            GossiperAnnouncement, LinearChainAnnouncement, NetworkAnnouncement,
+            RpcServerAnnouncement,
         },
         requests::{
             BlockExecutorRequest, BlockProposerRequest, BlockValidationRequest,
@@ -191,6 +192,9 @@ pub enum Event {
     /// Chainspec loader announcement.
     #[from]
     ChainspecLoaderAnnouncement(#[serde(skip_serializing)] ChainspecLoaderAnnouncement),
+    /// Block proposer announcement.
+    #[from]
+    BlockProposerAnnouncement(#[serde(skip_serializing)] BlockProposerAnnouncement),
 }
 
 impl ReactorEvent for Event {
@@ -301,6 +305,9 @@ impl Display for Event {
             Event::ChainspecLoaderAnnouncement(ann) => {
                 write!(f, "chainspec loader announcement: {}", ann)
             }
+            Event::BlockProposerAnnouncement(ann) => {
+                write!(f, "block proposer announcement: {}", ann)
+            }
         }
     }
 }
@@ -439,6 +446,7 @@ impl reactor::Reactor for Reactor {
             config.rpc_server.clone(),
             effect_builder,
             protocol_version.clone(),
+            &*chainspec_loader.chainspec(),
         )?;
         let rest_server = RestServer::new(
             config.rest_server.clone(),
@@ -463,6 +471,7 @@ impl reactor::Reactor for Reactor {
                 .map(|block| block.height() + 1)
                 .unwrap_or(0),
             chainspec_loader.chainspec().as_ref(),
+            config.block_proposer,
         )?;
         let mut effects = reactor::wrap_effects(Event::BlockProposer, block_proposer_effects);
         let block_executor = BlockExecutor::new(
@@ -909,6 +918,19 @@ impl reactor::Reactor for Reactor {
                 let mut effects = Effects::new();
                 let block_hash = *block.hash();
 
+                // let the contract runtime know the block's global state should be retained
+                let reactor_event =
+                    Event::ContractRuntime(contract_runtime::Event::BlockExecuted {
+                        state_root_hash: *block.state_root_hash(),
+                    });
+                effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+
+                // let the block proposer know the deploys have been executed
+                let reactor_event = Event::BlockProposer(block_proposer::Event::ExecutedDeploys {
+                    deploys: execution_results.keys().copied().collect(),
+                });
+                effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+
                 // send to linear chain
                 let reactor_event = Event::LinearChain(linear_chain::Event::NewLinearChainBlock {
                     block: Box::new(block),
@@ -971,6 +993,19 @@ impl reactor::Reactor for Reactor {
                 effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
                 effects
             }
+            Event::BlockProposerAnnouncement(BlockProposerAnnouncement::DeploySuperseded {
+                deploy_hash,
+                superseded_by,
+                account,
+            }) => {
+                let reactor_event =
+                    Event::EventStreamServer(event_stream_server::Event::DeploySuperseded {
+                        deploy_hash,
+                        superseded_by,
+                        account,
+                    });
+                self.dispatch_event(effect_builder, rng, reactor_event)
+            }
         }
     }
 
@@ -19,6 +19,7 @@ use blake2::{
 use datasize::DataSize;
 use hex::FromHexError;
 use hex_fmt::{HexFmt, HexList};
+use num::rational::Ratio;
 use once_cell::sync::Lazy;
 #[cfg(test)]
 use rand::Rng;
@@ -30,6 +31,7 @@ use thiserror::Error;
 use casper_types::system::auction::BLOCK_REWARD;
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
+    light_client::{self, EraValidators, LightClientBlockHeader, LightClientError},
     ProtocolVersion, PublicKey, SecretKey, Signature, U512,
 };
 
@@ -767,6 +769,32 @@ impl BlockHeader {
     }
 }
 
+impl LightClientBlockHeader for BlockHeader {
+    fn block_hash(&self) -> [u8; Digest::LENGTH] {
+        self.hash().inner().to_array()
+    }
+
+    fn parent_hash(&self) -> [u8; Digest::LENGTH] {
+        self.parent_hash.inner().to_array()
+    }
+
+    fn height(&self) -> u64 {
+        self.height
+    }
+
+    fn era_id(&self) -> u64 {
+        self.era_id.0
+    }
+
+    fn state_root_hash(&self) -> [u8; Digest::LENGTH] {
+        self.state_root_hash.to_array()
+    }
+
+    fn next_era_validator_weights(&self) -> Option<&BTreeMap<PublicKey, U512>> {
+        BlockHeader::next_era_validator_weights(self)
+    }
+}
+
 impl Display for BlockHeader {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         write!(
@@ -964,7 +992,7 @@ impl From<bytesrepr::Error> for BlockValidationError {
 }
 
 /// A storage representation of finality signatures with the associated block hash.
-#[derive(Debug, Serialize, Deserialize, Clone, DataSize)]
+#[derive(Debug, Serialize, Deserialize, Clone, DataSize, PartialEq)]
 pub struct BlockSignatures {
     /// The block hash for a given block.
     pub(crate) block_hash: BlockHash,
@@ -1008,6 +1036,21 @@ impl BlockSignatures {
         }
         Ok(())
     }
+
+    /// Verifies the signatures against the validators of their era, returning an error unless all
+    /// signers are validators, all signatures are valid and the signers hold more than
+    /// `finality_threshold` of the validators' total weight.
+    pub(crate) fn verify_quorum(
+        &self,
+        validator_weights: &BTreeMap<PublicKey, U512>,
+        finality_threshold: Ratio<u64>,
+    ) -> Result<(), LightClientError> {
+        EraValidators::new(self.era_id.0, validator_weights.clone()).verify_finality_signatures(
+            &self.block_hash.inner().to_array(),
+            finality_threshold,
+            &self.proofs,
+        )
+    }
 }
 
 impl Display for BlockSignatures {
@@ -1022,6 +1065,36 @@ impl Display for BlockSignatures {
     }
 }
 
+impl ToBytes for BlockSignatures {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.block_hash.to_bytes()?);
+        buffer.extend(self.era_id.to_bytes()?);
+        buffer.extend(self.proofs.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.block_hash.serialized_length()
+            + self.era_id.serialized_length()
+            + self.proofs.serialized_length()
+    }
+}
+
+impl FromBytes for BlockSignatures {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (block_hash, remainder) = BlockHash::from_bytes(bytes)?;
+        let (era_id, remainder) = EraId::from_bytes(remainder)?;
+        let (proofs, remainder) = BTreeMap::<PublicKey, Signature>::from_bytes(remainder)?;
+        let block_signatures = BlockSignatures {
+            block_hash,
+            era_id,
+            proofs,
+        };
+        Ok((block_signatures, remainder))
+    }
+}
+
 /// A proto-block after execution, with the resulting post-state-hash.  This is the core component
 /// of the Casper linear blockchain.
 #[derive(DataSize, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -1614,8 +1687,8 @@ impl FinalitySignature {
         secret_key: &SecretKey,
         public_key: PublicKey,
     ) -> Self {
-        let mut bytes = block_hash.inner().to_vec();
-        bytes.extend_from_slice(&era_id.0.to_le_bytes());
+        let bytes =
+            light_client::finality_signature_message(&block_hash.inner().to_array(), era_id.0);
         let signature = crypto::sign(bytes, &secret_key, &public_key);
         FinalitySignature {
             block_hash,
@@ -1627,9 +1700,10 @@ impl FinalitySignature {
 
     /// Verifies whether the signature is correct.
     pub fn verify(&self) -> crypto::Result<()> {
-        // NOTE: This needs to be in sync with the `new` constructor.
-        let mut bytes = self.block_hash.inner().to_vec();
-        bytes.extend_from_slice(&self.era_id.0.to_le_bytes());
+        let bytes = light_client::finality_signature_message(
+            &self.block_hash.inner().to_array(),
+            self.era_id.0,
+        );
         crypto::verify(bytes, &self.signature, &self.public_key)
     }
 }
@@ -1684,6 +1758,18 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(&block_header);
     }
 
+    #[test]
+    fn block_signatures_bytesrepr_roundtrip() {
+        let mut rng = TestRng::new();
+        let block = Block::random(&mut rng);
+        let mut block_signatures = BlockSignatures::new(*block.hash(), block.header().era_id);
+        let secret_key = SecretKey::doc_example();
+        let public_key = PublicKey::from(secret_key);
+        let signature = crypto::sign(block.hash.inner(), secret_key, &public_key);
+        block_signatures.insert_proof(public_key, signature);
+        bytesrepr::test_serialization_roundtrip(&block_signatures);
+    }
+
     #[test]
     fn bytesrepr_roundtrip_era_report() {
         let mut rng = TestRng::new();
@@ -262,6 +262,11 @@ mod tests {
             remove_contract_user_group_urefs: HostFunction::new(131, [0, 1, 2, 3, 4, 5]),
             print: HostFunction::new(123, [0, 1]),
             blake2b: HostFunction::new(133, [0, 1, 2, 3]),
+            emit_event: HostFunction::new(142, [0, 1, 2, 3]),
+            verify_signature: HostFunction::new(143, [0, 1, 2, 3, 4, 5]),
+            new_dictionary: HostFunction::new(144, [0, 1]),
+            dictionary_get: HostFunction::new(145, [0, 1, 2, 3, 4]),
+            dictionary_put: HostFunction::new(146, [0, 1, 2, 3, 4, 5]),
         });
     static EXPECTED_GENESIS_WASM_COSTS: Lazy<WasmConfig> = Lazy::new(|| {
         WasmConfig::new(
@@ -333,6 +338,7 @@ mod tests {
 
         assert_eq!(spec.core_config.era_duration, TimeDiff::from(180000));
         assert_eq!(spec.core_config.minimum_era_height, 9);
+        assert!(spec.core_config.slash_equivocators);
         assert_eq!(
             spec.highway_config.finality_threshold_fraction,
             Ratio::new(2, 25)
@@ -28,6 +28,9 @@ pub struct CoreConfig {
     /// Round seigniorage rate represented as a fractional number.
     #[data_size(skip)]
     pub(crate) round_seigniorage_rate: Ratio<u64>,
+    /// Whether validators proven to have equivocated are slashed in the switch block of the era.
+    /// If not, they are only evicted.
+    pub(crate) slash_equivocators: bool,
 }
 
 #[cfg(test)]
@@ -44,6 +47,7 @@ impl CoreConfig {
             rng.gen_range(1..1_000_000_000),
             rng.gen_range(1..1_000_000_000),
         );
+        let slash_equivocators = rng.gen();
 
         CoreConfig {
             era_duration,
@@ -53,6 +57,7 @@ impl CoreConfig {
             locked_funds_period,
             unbonding_delay,
             round_seigniorage_rate,
+            slash_equivocators,
         }
     }
 }
@@ -67,6 +72,7 @@ impl ToBytes for CoreConfig {
         buffer.extend(self.locked_funds_period.to_bytes()?);
         buffer.extend(self.unbonding_delay.to_bytes()?);
         buffer.extend(self.round_seigniorage_rate.to_bytes()?);
+        buffer.extend(self.slash_equivocators.to_bytes()?);
         Ok(buffer)
     }
 
@@ -78,6 +84,7 @@ impl ToBytes for CoreConfig {
             + self.locked_funds_period.serialized_length()
             + self.unbonding_delay.serialized_length()
             + self.round_seigniorage_rate.serialized_length()
+            + self.slash_equivocators.serialized_length()
     }
 }
 
@@ -90,6 +97,7 @@ impl FromBytes for CoreConfig {
         let (locked_funds_period, remainder) = TimeDiff::from_bytes(remainder)?;
         let (unbonding_delay, remainder) = u64::from_bytes(remainder)?;
         let (round_seigniorage_rate, remainder) = Ratio::<u64>::from_bytes(remainder)?;
+        let (slash_equivocators, remainder) = bool::from_bytes(remainder)?;
         let config = CoreConfig {
             era_duration,
             minimum_era_height,
@@ -98,6 +106,7 @@ impl FromBytes for CoreConfig {
             locked_funds_period,
             unbonding_delay,
             round_seigniorage_rate,
+            slash_equivocators,
         };
         Ok((config, remainder))
     }
@@ -15,6 +15,9 @@ pub enum ExitCode {
     /// The process should exit with success.  The launcher should proceed to run the next
     /// installed version of `casper-node`.
     Success = 0,
+    /// The process should exit with `2`.  Used by the `check-db` subcommand if it found problems
+    /// in the database, as opposed to `1`, which signals that the subcommand failed to run.
+    CheckDbProblemsFound = 2,
     /// The process should exit with `101`, equivalent to panicking.  The launcher should not
     /// restart the node.
     Abort = 101,
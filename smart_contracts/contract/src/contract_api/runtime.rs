@@ -12,7 +12,8 @@ use casper_types::{
     bytesrepr::{self, FromBytes},
     contracts::{ContractVersion, NamedKeys},
     ApiError, BlockTime, CLTyped, CLValue, ContractHash, ContractPackageHash, Key, Phase,
-    RuntimeArgs, URef, BLAKE2B_DIGEST_LENGTH, BLOCKTIME_SERIALIZED_LENGTH, PHASE_SERIALIZED_LENGTH,
+    PublicKey, RuntimeArgs, Signature, URef, BLAKE2B_DIGEST_LENGTH, BLOCKTIME_SERIALIZED_LENGTH,
+    PHASE_SERIALIZED_LENGTH,
 };
 
 use crate::{contract_api, ext_ffi, unwrap_or_revert::UnwrapOrRevert};
@@ -320,6 +321,43 @@ pub fn blake2b<T: AsRef<[u8]>>(input: T) -> [u8; BLAKE2B_DIGEST_LENGTH] {
     ret
 }
 
+/// Emits an event under the given topic.
+///
+/// The event is recorded in the execution result of the currently executing deploy, provided the
+/// deploy executes successfully.  The topic must be non-empty and at most 64 bytes long, otherwise
+/// the contract reverts with [`ApiError::InvalidArgument`].
+pub fn emit_event(topic: &str, payload: CLValue) {
+    let (topic_ptr, topic_size, _topic_bytes) = contract_api::to_ptr(topic);
+    let (payload_ptr, payload_size, _payload_bytes) = contract_api::to_ptr(payload);
+    let result =
+        unsafe { ext_ffi::casper_emit_event(topic_ptr, topic_size, payload_ptr, payload_size) };
+    api_error::result_from(result).unwrap_or_revert()
+}
+
+/// Verifies the signature of the given message against the given public key.
+///
+/// Returns `true` if `signature` is a valid signature of `message` created by the secret key
+/// corresponding to `public_key`.
+pub fn verify_signature<T: AsRef<[u8]>>(
+    public_key: &PublicKey,
+    message: T,
+    signature: &Signature,
+) -> bool {
+    let (public_key_ptr, public_key_size, _public_key_bytes) = contract_api::to_ptr(*public_key);
+    let (signature_ptr, signature_size, _signature_bytes) = contract_api::to_ptr(*signature);
+    let result = unsafe {
+        ext_ffi::casper_verify_signature(
+            public_key_ptr,
+            public_key_size,
+            message.as_ref().as_ptr(),
+            message.as_ref().len(),
+            signature_ptr,
+            signature_size,
+        )
+    };
+    result != 0
+}
+
 fn read_host_buffer_into(dest: &mut [u8]) -> Result<usize, ApiError> {
     let mut bytes_written = MaybeUninit::uninit();
     let ret = unsafe {
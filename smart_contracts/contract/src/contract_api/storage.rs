@@ -86,6 +86,74 @@ pub fn new_uref<T: CLTyped + ToBytes>(init: T) -> URef {
     bytesrepr::deserialize(bytes).unwrap_or_revert()
 }
 
+/// Creates a new dictionary, returning the seed [`URef`] which identifies it.
+///
+/// The returned `URef` has `READ_ADD_WRITE` access rights.  Items can be read and written using
+/// [`dictionary_get`] and [`dictionary_put`] respectively.
+pub fn new_dictionary() -> URef {
+    let uref_non_null_ptr = contract_api::alloc_bytes(UREF_SERIALIZED_LENGTH);
+    let bytes = unsafe {
+        let ret =
+            ext_ffi::casper_new_dictionary(uref_non_null_ptr.as_ptr(), UREF_SERIALIZED_LENGTH);
+        api_error::result_from(ret).unwrap_or_revert();
+        Vec::from_raw_parts(
+            uref_non_null_ptr.as_ptr(),
+            UREF_SERIALIZED_LENGTH,
+            UREF_SERIALIZED_LENGTH,
+        )
+    };
+    bytesrepr::deserialize(bytes).unwrap_or_revert()
+}
+
+/// Reads the item stored under `dictionary_item_key` in the dictionary identified by `seed_uref`.
+pub fn dictionary_get<V: CLTyped + FromBytes>(
+    seed_uref: URef,
+    dictionary_item_key: &str,
+) -> Result<Option<V>, bytesrepr::Error> {
+    let (uref_ptr, uref_size, _bytes) = contract_api::to_ptr(seed_uref);
+
+    let value_size = {
+        let mut value_size = MaybeUninit::uninit();
+        let ret = unsafe {
+            ext_ffi::casper_dictionary_get(
+                uref_ptr,
+                uref_size,
+                dictionary_item_key.as_ptr(),
+                dictionary_item_key.len(),
+                value_size.as_mut_ptr(),
+            )
+        };
+        match api_error::result_from(ret) {
+            Ok(_) => unsafe { value_size.assume_init() },
+            Err(ApiError::ValueNotFound) => return Ok(None),
+            Err(e) => runtime::revert(e),
+        }
+    };
+
+    let value_bytes = runtime::read_host_buffer(value_size).unwrap_or_revert();
+    Ok(Some(bytesrepr::deserialize(value_bytes)?))
+}
+
+/// Writes `value` under `dictionary_item_key` in the dictionary identified by `seed_uref`.
+pub fn dictionary_put<V: CLTyped + ToBytes>(seed_uref: URef, dictionary_item_key: &str, value: V) {
+    let (uref_ptr, uref_size, _bytes1) = contract_api::to_ptr(seed_uref);
+
+    let cl_value = CLValue::from_t(value).unwrap_or_revert();
+    let (cl_value_ptr, cl_value_size, _bytes2) = contract_api::to_ptr(cl_value);
+
+    let ret = unsafe {
+        ext_ffi::casper_dictionary_put(
+            uref_ptr,
+            uref_size,
+            dictionary_item_key.as_ptr(),
+            dictionary_item_key.len(),
+            cl_value_ptr,
+            cl_value_size,
+        )
+    };
+    api_error::result_from(ret).unwrap_or_revert()
+}
+
 /// Create a new contract stored under a Key::Hash at version 1. You may upgrade this contract in
 /// the future; if you want a contract that is locked (i.e. cannot be upgraded) call
 /// `new_locked_contract` instead.
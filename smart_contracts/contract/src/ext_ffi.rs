@@ -700,6 +700,86 @@ extern "C" {
         out_ptr: *mut u8,
         out_size: usize,
     ) -> i32;
+    /// Emits an event which is recorded in the execution result of the currently executing deploy.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_ptr` - pointer to serialized topic of the event
+    /// * `topic_size` - size of serialized topic of the event
+    /// * `payload_ptr` - pointer to serialized `CLValue` payload of the event
+    /// * `payload_size` - size of serialized `CLValue` payload of the event
+    pub fn casper_emit_event(
+        topic_ptr: *const u8,
+        topic_size: usize,
+        payload_ptr: *const u8,
+        payload_size: usize,
+    ) -> i32;
+    /// Verifies the signature of the given message against the given public key.  Returns a
+    /// non-zero value if the signature is valid, or `0` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key_ptr` - pointer to serialized `PublicKey`
+    /// * `public_key_size` - size of serialized `PublicKey`
+    /// * `message_ptr` - pointer to the raw bytes of the signed message
+    /// * `message_size` - size of the signed message
+    /// * `signature_ptr` - pointer to serialized `Signature`
+    /// * `signature_size` - size of serialized `Signature`
+    pub fn casper_verify_signature(
+        public_key_ptr: *const u8,
+        public_key_size: usize,
+        message_ptr: *const u8,
+        message_size: usize,
+        signature_ptr: *const u8,
+        signature_size: usize,
+    ) -> i32;
+    /// Creates a new dictionary, writing the serialized seed `URef` which identifies it to the
+    /// indicated place in wasm memory.  It is up to the caller to ensure at least `uref_size`
+    /// bytes are allocated at `uref_ptr`, otherwise data corruption may occur.  This function
+    /// causes a `Trap` if `uref_size` is not equal to 33.
+    ///
+    /// # Arguments
+    ///
+    /// * `uref_ptr` - pointer to position in wasm memory where to write the seed `URef`
+    /// * `uref_size` - allocated size for the seed `URef`
+    pub fn casper_new_dictionary(uref_ptr: *mut u8, uref_size: usize) -> i32;
+    /// Reads the item stored under the given item key in the dictionary identified by the given
+    /// seed `URef`.  The value is serialized and buffered in the runtime, and can be obtained via
+    /// the [`casper_read_host_buffer`] function.  Returns standard error code.
+    ///
+    /// # Arguments
+    ///
+    /// * `uref_ptr` - pointer to serialized seed `URef` of the dictionary
+    /// * `uref_size` - size of serialized seed `URef` of the dictionary
+    /// * `key_bytes_ptr` - pointer to the bytes of the dictionary item key
+    /// * `key_bytes_size` - size of the bytes of the dictionary item key
+    /// * `output_size` - pointer to a value where host will write size of bytes read
+    pub fn casper_dictionary_get(
+        uref_ptr: *const u8,
+        uref_size: usize,
+        key_bytes_ptr: *const u8,
+        key_bytes_size: usize,
+        output_size: *mut usize,
+    ) -> i32;
+    /// Writes the given value under the given item key in the dictionary identified by the given
+    /// seed `URef`.  Returns standard error code.
+    ///
+    /// # Arguments
+    ///
+    /// * `uref_ptr` - pointer to serialized seed `URef` of the dictionary
+    /// * `uref_size` - size of serialized seed `URef` of the dictionary
+    /// * `key_bytes_ptr` - pointer to the bytes of the dictionary item key
+    /// * `key_bytes_size` - size of the bytes of the dictionary item key
+    /// * `value_ptr` - pointer to serialized `CLValue` to be written
+    /// * `value_size` - size of serialized `CLValue` to be written
+    pub fn casper_dictionary_put(
+        uref_ptr: *const u8,
+        uref_size: usize,
+        key_bytes_ptr: *const u8,
+        key_bytes_size: usize,
+        value_ptr: *const u8,
+        value_size: usize,
+    ) -> i32;
     /// Prints data directly to stanadard output on the host.
     ///
     /// # Arguments
@@ -0,0 +1,47 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::string::String;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::ApiError;
+
+const ARG_DICTIONARY_ITEM_KEY: &str = "dictionary_item_key";
+const ARG_VALUE: &str = "value";
+
+const DICTIONARY_SEED_UREF: &str = "dictionary_seed_uref";
+
+#[repr(u16)]
+enum Error {
+    ValueNotFound = 0,
+    ValueMismatch = 1,
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        ApiError::User(error as u16)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let dictionary_item_key: String = runtime::get_named_arg(ARG_DICTIONARY_ITEM_KEY);
+    let value: String = runtime::get_named_arg(ARG_VALUE);
+
+    let seed_uref = storage::new_dictionary();
+    storage::dictionary_put(seed_uref, &dictionary_item_key, value.clone());
+
+    let stored_value: String = storage::dictionary_get(seed_uref, &dictionary_item_key)
+        .unwrap_or_revert()
+        .unwrap_or_revert_with(Error::ValueNotFound);
+    if stored_value != value {
+        runtime::revert(Error::ValueMismatch);
+    }
+
+    runtime::put_key(DICTIONARY_SEED_UREF, seed_uref.into());
+}
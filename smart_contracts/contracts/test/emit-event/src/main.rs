@@ -0,0 +1,20 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::string::String;
+
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::CLValue;
+
+const ARG_TOPIC: &str = "topic";
+const ARG_PAYLOAD: &str = "payload";
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let topic: String = runtime::get_named_arg(ARG_TOPIC);
+    let payload: u64 = runtime::get_named_arg(ARG_PAYLOAD);
+
+    runtime::emit_event(&topic, CLValue::from_t(payload).unwrap_or_revert());
+}
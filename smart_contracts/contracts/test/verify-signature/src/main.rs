@@ -0,0 +1,30 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{bytesrepr, PublicKey, Signature};
+
+const ARG_PUBLIC_KEY: &str = "public_key";
+const ARG_MESSAGE: &str = "message";
+const ARG_SIGNATURE: &str = "signature";
+
+const VERIFICATION_RESULT: &str = "verification_result";
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let public_key: PublicKey = runtime::get_named_arg(ARG_PUBLIC_KEY);
+    let message: Vec<u8> = runtime::get_named_arg(ARG_MESSAGE);
+    let signature_bytes: Vec<u8> = runtime::get_named_arg(ARG_SIGNATURE);
+    let signature: Signature = bytesrepr::deserialize(signature_bytes).unwrap_or_revert();
+
+    let is_valid = runtime::verify_signature(&public_key, message, &signature);
+    let uref = storage::new_uref(is_valid);
+    runtime::put_key(VERIFICATION_RESULT, uref.into())
+}
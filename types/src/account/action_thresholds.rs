@@ -1,6 +1,8 @@
+use alloc::vec::Vec;
+
 use serde::{Deserialize, Serialize};
 
-use casper_types::{
+use crate::{
     account::{ActionType, SetThresholdFailure, Weight, WEIGHT_SERIALIZED_LENGTH},
     bytesrepr::{self, Error, FromBytes, ToBytes},
 };
@@ -60,10 +62,12 @@ impl ActionThresholds {
         }
     }
 
+    /// Returns the deployment action threshold.
     pub fn deployment(&self) -> &Weight {
         &self.deployment
     }
 
+    /// Returns the key management action threshold.
     pub fn key_management(&self) -> &Weight {
         &self.key_management
     }
@@ -116,17 +120,6 @@ impl FromBytes for ActionThresholds {
     }
 }
 
-#[cfg(any(feature = "gens", test))]
-pub mod gens {
-    use proptest::prelude::*;
-
-    use super::ActionThresholds;
-
-    pub fn action_thresholds_arb() -> impl Strategy<Value = ActionThresholds> {
-        Just(Default::default())
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,18 +1,23 @@
-use std::collections::{BTreeMap, BTreeSet};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
 
 use serde::{Deserialize, Serialize};
 
-use casper_types::{
+use crate::{
     account::{
         AccountHash, AddKeyFailure, RemoveKeyFailure, UpdateKeyFailure, Weight, MAX_ASSOCIATED_KEYS,
     },
     bytesrepr::{Error, FromBytes, ToBytes},
 };
 
+/// A mapping that represents the association of a [`Weight`] with an [`AccountHash`].
 #[derive(Default, PartialOrd, Ord, PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
 pub struct AssociatedKeys(BTreeMap<AccountHash, Weight>);
 
 impl AssociatedKeys {
+    /// Constructs a new AssociatedKeys.
     pub fn new(key: AccountHash, weight: Weight) -> AssociatedKeys {
         let mut bt: BTreeMap<AccountHash, Weight> = BTreeMap::new();
         bt.insert(key, weight);
@@ -55,22 +60,27 @@ impl AssociatedKeys {
         Ok(())
     }
 
+    /// Returns the weight of an account hash.
     pub fn get(&self, key: &AccountHash) -> Option<&Weight> {
         self.0.get(key)
     }
 
+    /// Returns `true` if a given key exists.
     pub fn contains_key(&self, key: &AccountHash) -> bool {
         self.0.contains_key(key)
     }
 
+    /// Returns an iterator over the account hash and the weights.
     pub fn iter(&self) -> impl Iterator<Item = (&AccountHash, &Weight)> {
         self.0.iter()
     }
 
+    /// Returns the count of the associated keys.
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    /// Returns `true` if the associated keys are empty.
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
@@ -140,29 +150,6 @@ impl FromBytes for AssociatedKeys {
     }
 }
 
-#[cfg(any(feature = "gens", test))]
-pub mod gens {
-    use proptest::prelude::*;
-
-    use casper_types::{
-        account::MAX_ASSOCIATED_KEYS,
-        gens::{account_hash_arb, weight_arb},
-    };
-
-    use super::AssociatedKeys;
-
-    pub fn associated_keys_arb() -> impl Strategy<Value = AssociatedKeys> {
-        proptest::collection::btree_map(account_hash_arb(), weight_arb(), MAX_ASSOCIATED_KEYS - 1)
-            .prop_map(|keys| {
-                let mut associated_keys = AssociatedKeys::default();
-                keys.into_iter().for_each(|(k, v)| {
-                    associated_keys.add_key(k, v).unwrap();
-                });
-                associated_keys
-            })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use std::{
@@ -170,7 +157,7 @@ mod tests {
         iter::FromIterator,
     };
 
-    use casper_types::{
+    use crate::{
         account::{AccountHash, AddKeyFailure, Weight, ACCOUNT_HASH_LENGTH, MAX_ASSOCIATED_KEYS},
         bytesrepr::{self, ToBytes},
     };
@@ -215,7 +215,7 @@ impl Distribution<Bytes> for Standard {
     }
 }
 
-struct BytesVisitor;
+pub(crate) struct BytesVisitor;
 
 impl<'de> Visitor<'de> for BytesVisitor {
     type Value = Bytes;
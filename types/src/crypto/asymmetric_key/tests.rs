@@ -1,4 +1,9 @@
-use crate::{crypto::SecretKey, PublicKey};
+use k256::ecdsa::signature::Signer;
+
+use crate::{
+    crypto::{verify, SecretKey},
+    PublicKey, Signature,
+};
 
 #[test]
 fn can_construct_ed25519_keypair_from_zeroes() {
@@ -39,3 +44,34 @@ fn can_construct_system_public_key() {
 
     assert_ne!(public_key, secret_key.into())
 }
+
+#[test]
+fn should_verify_ed25519_signature() {
+    let secret_key = ed25519_dalek::SecretKey::from_bytes(&[1; SecretKey::ED25519_LENGTH]).unwrap();
+    let public_key = ed25519_dalek::PublicKey::from(&secret_key);
+    let message = b"message";
+    let signature = ed25519_dalek::ExpandedSecretKey::from(&secret_key).sign(message, &public_key);
+    let signature = Signature::Ed25519(signature.to_bytes());
+    let public_key = PublicKey::Ed25519(public_key);
+
+    assert!(verify(message, &signature, &public_key).is_ok());
+    assert!(verify(&message[1..], &signature, &public_key).is_err());
+    assert!(verify(message, &signature, &PublicKey::System).is_err());
+}
+
+#[test]
+fn should_verify_secp256k1_signature() {
+    let secret_key = SecretKey::secp256k1([1; SecretKey::SECP256K1_LENGTH]);
+    let public_key = PublicKey::from(&secret_key);
+    let message = b"message";
+    let signature: k256::ecdsa::Signature =
+        k256::ecdsa::SigningKey::from_bytes(&[1; SecretKey::SECP256K1_LENGTH])
+            .unwrap()
+            .try_sign(message)
+            .unwrap();
+    let signature = Signature::Secp256k1(signature);
+
+    assert!(verify(message, &signature, &public_key).is_ok());
+    assert!(verify(&message[1..], &signature, &public_key).is_err());
+    assert!(verify(message, &Signature::System, &public_key).is_err());
+}
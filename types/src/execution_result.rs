@@ -38,6 +38,10 @@ use crate::{
 /// Constants to track ExecutionResult serialization.
 const EXECUTION_RESULT_FAILURE_TAG: u8 = 0;
 const EXECUTION_RESULT_SUCCESS_TAG: u8 = 1;
+/// A successful result which emitted events.  Results without events keep the layout of
+/// `EXECUTION_RESULT_SUCCESS_TAG`, which predates contract events, so that their encoding is
+/// unchanged.
+const EXECUTION_RESULT_SUCCESS_WITH_EVENTS_TAG: u8 = 2;
 
 /// Constants to track operation serialization.
 const OP_READ_TAG: u8 = 0;
@@ -99,9 +103,15 @@ static EXECUTION_RESULT: Lazy<ExecutionResult> = Lazy::new(|| {
         TransferAddr::new([130; KEY_HASH_LENGTH]),
     ];
 
+    let events = vec![ContractEvent {
+        topic: "token_minted".to_string(),
+        payload: CLValue::from_t(U512::from(1_000)).unwrap(),
+    }];
+
     ExecutionResult::Success {
         effect,
         transfers,
+        events,
         cost: U512::from(123_456),
     }
 });
@@ -128,6 +138,9 @@ pub enum ExecutionResult {
         effect: ExecutionEffect,
         /// A record of Transfers performed while executing the deploy.
         transfers: Vec<TransferAddr>,
+        /// The events emitted by contracts while executing the deploy, in order of emission.
+        #[serde(default)]
+        events: Vec<ContractEvent>,
         /// The cost of executing the deploy.
         cost: U512,
     },
@@ -184,9 +197,16 @@ impl Distribution<ExecutionResult> for Standard {
                 error_message: format!("Error message {}", rng.gen::<u64>()),
             }
         } else {
+            let event_count = rng.gen_range(0..6);
+            let mut events = vec![];
+            for _ in 0..event_count {
+                events.push(rng.gen())
+            }
+
             ExecutionResult::Success {
                 effect,
                 transfers,
+                events,
                 cost: rng.gen::<u64>().into(),
             }
         }
@@ -212,11 +232,19 @@ impl ToBytes for ExecutionResult {
             ExecutionResult::Success {
                 effect,
                 transfers,
+                events,
                 cost,
             } => {
-                buffer.push(EXECUTION_RESULT_SUCCESS_TAG);
-                buffer.extend(effect.to_bytes()?);
-                buffer.extend(transfers.to_bytes()?);
+                if events.is_empty() {
+                    buffer.push(EXECUTION_RESULT_SUCCESS_TAG);
+                    buffer.extend(effect.to_bytes()?);
+                    buffer.extend(transfers.to_bytes()?);
+                } else {
+                    buffer.push(EXECUTION_RESULT_SUCCESS_WITH_EVENTS_TAG);
+                    buffer.extend(effect.to_bytes()?);
+                    buffer.extend(transfers.to_bytes()?);
+                    buffer.extend(events.to_bytes()?);
+                }
                 buffer.extend(cost.to_bytes()?);
             }
         }
@@ -240,10 +268,17 @@ impl ToBytes for ExecutionResult {
                 ExecutionResult::Success {
                     effect,
                     transfers,
+                    events,
                     cost,
                 } => {
+                    let events_length = if events.is_empty() {
+                        0
+                    } else {
+                        events.serialized_length()
+                    };
                     effect.serialized_length()
                         + transfers.serialized_length()
+                        + events_length
                         + cost.serialized_length()
                 }
             }
@@ -274,6 +309,24 @@ impl FromBytes for ExecutionResult {
                 let execution_result = ExecutionResult::Success {
                     effect,
                     transfers,
+                    events: Vec::new(),
+                    cost,
+                };
+                Ok((execution_result, remainder))
+            }
+            EXECUTION_RESULT_SUCCESS_WITH_EVENTS_TAG => {
+                let (effect, remainder) = ExecutionEffect::from_bytes(remainder)?;
+                let (transfers, remainder) = Vec::<TransferAddr>::from_bytes(remainder)?;
+                let (events, remainder) = Vec::<ContractEvent>::from_bytes(remainder)?;
+                if events.is_empty() {
+                    // Results without events must use the original layout.
+                    return Err(bytesrepr::Error::Formatting);
+                }
+                let (cost, remainder) = U512::from_bytes(remainder)?;
+                let execution_result = ExecutionResult::Success {
+                    effect,
+                    transfers,
+                    events,
                     cost,
                 };
                 Ok((execution_result, remainder))
@@ -283,6 +336,48 @@ impl FromBytes for ExecutionResult {
     }
 }
 
+/// An event emitted by a contract while executing a deploy.
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "std", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ContractEvent {
+    /// The topic under which the event was emitted.
+    pub topic: String,
+    /// The payload of the event.
+    pub payload: CLValue,
+}
+
+impl ToBytes for ContractEvent {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.topic.to_bytes()?);
+        buffer.extend(self.payload.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.topic.serialized_length() + self.payload.serialized_length()
+    }
+}
+
+impl FromBytes for ContractEvent {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (topic, remainder) = String::from_bytes(bytes)?;
+        let (payload, remainder) = CLValue::from_bytes(remainder)?;
+        let contract_event = ContractEvent { topic, payload };
+        Ok((contract_event, remainder))
+    }
+}
+
+impl Distribution<ContractEvent> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ContractEvent {
+        ContractEvent {
+            topic: format!("topic {}", rng.gen::<u8>()),
+            payload: CLValue::from_t(rng.gen::<u64>()).unwrap(),
+        }
+    }
+}
+
 /// The effect of executing a single deploy.
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Default, Debug)]
 #[cfg_attr(feature = "std", derive(JsonSchema))]
@@ -673,10 +768,71 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(&transform);
     }
 
+    #[test]
+    fn bytesrepr_test_contract_event() {
+        let mut rng = get_rng();
+        let contract_event: ContractEvent = rng.gen();
+        bytesrepr::test_serialization_roundtrip(&contract_event);
+    }
+
     #[test]
     fn bytesrepr_test_execution_result() {
         let mut rng = get_rng();
         let execution_result: ExecutionResult = rng.gen();
         bytesrepr::test_serialization_roundtrip(&execution_result);
     }
+
+    #[test]
+    fn bytesrepr_should_decode_success_encoded_before_events() {
+        let mut rng = get_rng();
+        let effect = ExecutionEffect::default();
+        let transfers = vec![TransferAddr::new(rng.gen())];
+        let cost = U512::from(rng.gen::<u64>());
+
+        // The layout of a successful result before contract events were added.
+        let mut legacy_bytes = vec![EXECUTION_RESULT_SUCCESS_TAG];
+        legacy_bytes.extend(effect.to_bytes().unwrap());
+        legacy_bytes.extend(transfers.to_bytes().unwrap());
+        legacy_bytes.extend(cost.to_bytes().unwrap());
+
+        let expected = ExecutionResult::Success {
+            effect,
+            transfers,
+            events: Vec::new(),
+            cost,
+        };
+        let decoded: ExecutionResult = bytesrepr::deserialize(legacy_bytes.clone()).unwrap();
+        assert_eq!(decoded, expected);
+
+        // A result without events is still encoded in that layout.
+        assert_eq!(expected.to_bytes().unwrap(), legacy_bytes);
+        assert_eq!(expected.serialized_length(), legacy_bytes.len());
+    }
+
+    #[test]
+    fn bytesrepr_should_roundtrip_success_with_events() {
+        let mut rng = get_rng();
+        let execution_result = ExecutionResult::Success {
+            effect: ExecutionEffect::default(),
+            transfers: vec![],
+            events: vec![rng.gen(), rng.gen()],
+            cost: U512::from(rng.gen::<u64>()),
+        };
+        let bytes = execution_result.to_bytes().unwrap();
+        assert_eq!(bytes[0], EXECUTION_RESULT_SUCCESS_WITH_EVENTS_TAG);
+        bytesrepr::test_serialization_roundtrip(&execution_result);
+    }
+
+    #[test]
+    fn bytesrepr_should_reject_events_tag_without_events() {
+        let mut bytes = vec![EXECUTION_RESULT_SUCCESS_WITH_EVENTS_TAG];
+        bytes.extend(ExecutionEffect::default().to_bytes().unwrap());
+        bytes.extend(Vec::<TransferAddr>::new().to_bytes().unwrap());
+        bytes.extend(Vec::<ContractEvent>::new().to_bytes().unwrap());
+        bytes.extend(U512::zero().to_bytes().unwrap());
+        assert_eq!(
+            bytesrepr::deserialize::<ExecutionResult>(bytes),
+            Err(bytesrepr::Error::Formatting)
+        );
+    }
 }
@@ -13,17 +13,22 @@ use proptest::{
 };
 
 use crate::{
-    account::{AccountHash, Weight},
+    account::{
+        Account, AccountHash, ActionThresholds, AssociatedKeys, Weight, MAX_ASSOCIATED_KEYS,
+    },
     contracts::{
         ContractPackageStatus, ContractVersions, DisabledVersions, Groups, NamedKeys, Parameters,
     },
     AccessRights, CLType, CLValue, Contract, ContractHash, ContractPackage, ContractVersionKey,
     ContractWasm, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Group, Key, NamedArg,
-    Parameter, Phase, ProtocolVersion, SemVer, URef, U128, U256, U512,
+    Parameter, Phase, ProtocolVersion, SemVer, StoredValue, URef, U128, U256, U512,
 };
 
-use crate::deploy_info::gens::{deploy_hash_arb, transfer_addr_arb};
 pub use crate::{deploy_info::gens::deploy_info_arb, transfer::gens::transfer_arb};
+use crate::{
+    deploy_info::gens::{deploy_hash_arb, transfer_addr_arb},
+    system::auction::gens::era_info_arb,
+};
 
 pub fn u8_slice_32() -> impl Strategy<Value = [u8; 32]> {
     vec(any::<u8>(), 32).prop_map(|b| {
@@ -74,6 +79,7 @@ pub fn key_arb() -> impl Strategy<Value = Key> {
         uref_arb().prop_map(|uref| Key::Balance(uref.addr())),
         account_hash_arb().prop_map(Key::Bid),
         account_hash_arb().prop_map(Key::Withdraw),
+        u8_slice_32().prop_map(Key::Dictionary),
     ]
 }
 
@@ -347,3 +353,49 @@ pub fn contract_package_arb() -> impl Strategy<Value = ContractPackage> {
             )
         })
 }
+
+pub fn action_thresholds_arb() -> impl Strategy<Value = ActionThresholds> {
+    Just(Default::default())
+}
+
+pub fn associated_keys_arb() -> impl Strategy<Value = AssociatedKeys> {
+    btree_map(account_hash_arb(), weight_arb(), MAX_ASSOCIATED_KEYS - 1).prop_map(|keys| {
+        let mut associated_keys = AssociatedKeys::default();
+        keys.into_iter().for_each(|(k, v)| {
+            associated_keys.add_key(k, v).unwrap();
+        });
+        associated_keys
+    })
+}
+
+prop_compose! {
+    pub fn account_arb()(
+        account_hash in account_hash_arb(),
+        urefs in named_keys_arb(3),
+        purse in uref_arb(),
+        thresholds in action_thresholds_arb(),
+        mut associated_keys in associated_keys_arb(),
+    ) -> Account {
+            associated_keys.add_key(account_hash, Weight::new(1)).unwrap();
+            Account::new(
+                account_hash,
+                urefs,
+                purse,
+                associated_keys,
+                thresholds,
+            )
+    }
+}
+
+pub fn stored_value_arb() -> impl Strategy<Value = StoredValue> {
+    prop_oneof![
+        cl_value_arb().prop_map(StoredValue::CLValue),
+        account_arb().prop_map(StoredValue::Account),
+        contract_package_arb().prop_map(StoredValue::ContractPackage),
+        contract_arb().prop_map(StoredValue::Contract),
+        contract_wasm_arb().prop_map(StoredValue::ContractWasm),
+        era_info_arb(1..10).prop_map(StoredValue::EraInfo),
+        deploy_info_arb().prop_map(StoredValue::DeployInfo),
+        transfer_arb().prop_map(StoredValue::Transfer)
+    ]
+}
@@ -35,6 +35,7 @@ const ERA_INFO_PREFIX: &str = "era-";
 const BALANCE_PREFIX: &str = "balance-";
 const BID_PREFIX: &str = "bid-";
 const WITHDRAW_PREFIX: &str = "withdraw-";
+const DICTIONARY_PREFIX: &str = "dictionary-";
 
 /// The number of bytes in a Blake2b hash
 pub const BLAKE2B_DIGEST_LENGTH: usize = 32;
@@ -44,6 +45,10 @@ pub const KEY_HASH_LENGTH: usize = 32;
 pub const KEY_TRANSFER_LENGTH: usize = TRANSFER_ADDR_LENGTH;
 /// The number of bytes in a [`Key::DeployInfo`].
 pub const KEY_DEPLOY_INFO_LENGTH: usize = DEPLOY_HASH_LENGTH;
+/// The number of bytes in a [`Key::Dictionary`].
+pub const KEY_DICTIONARY_LENGTH: usize = 32;
+/// The maximum length in bytes of a dictionary item key.
+pub const DICTIONARY_ITEM_KEY_MAX_LENGTH: usize = 128;
 
 const KEY_ID_SERIALIZED_LENGTH: usize = 1;
 // u8 used to determine the ID
@@ -55,10 +60,14 @@ const KEY_ERA_INFO_SERIALIZED_LENGTH: usize = KEY_ID_SERIALIZED_LENGTH + U64_SER
 const KEY_BALANCE_SERIALIZED_LENGTH: usize = KEY_ID_SERIALIZED_LENGTH + UREF_ADDR_LENGTH;
 const KEY_BID_SERIALIZED_LENGTH: usize = KEY_ID_SERIALIZED_LENGTH + KEY_HASH_LENGTH;
 const KEY_WITHDRAW_SERIALIZED_LENGTH: usize = KEY_ID_SERIALIZED_LENGTH + KEY_HASH_LENGTH;
+const KEY_DICTIONARY_SERIALIZED_LENGTH: usize = KEY_ID_SERIALIZED_LENGTH + KEY_DICTIONARY_LENGTH;
 
 /// An alias for [`Key`]s hash variant.
 pub type HashAddr = [u8; KEY_HASH_LENGTH];
 
+/// An alias for [`Key`]s dictionary variant.
+pub type DictionaryAddr = [u8; KEY_DICTIONARY_LENGTH];
+
 impl From<HashAddr> for Key {
     fn from(addr: HashAddr) -> Self {
         Key::Hash(addr)
@@ -78,6 +87,7 @@ pub enum KeyTag {
     Balance = 6,
     Bid = 7,
     Withdraw = 8,
+    Dictionary = 9,
 }
 
 /// The type under which data (e.g. [`CLValue`](crate::CLValue)s, smart contracts, user accounts)
@@ -104,6 +114,8 @@ pub enum Key {
     Bid(AccountHash),
     /// A `Key` under which we store unbond information.
     Withdraw(AccountHash),
+    /// A `Key` under which we store a dictionary item.
+    Dictionary(DictionaryAddr),
 }
 
 #[derive(Debug)]
@@ -183,6 +195,7 @@ impl Key {
             Key::Balance(_) => String::from("Key::Balance"),
             Key::Bid(_) => String::from("Key::Bid"),
             Key::Withdraw(_) => String::from("Key::Unbond"),
+            Key::Dictionary(_) => String::from("Key::Dictionary"),
         }
     }
 
@@ -227,6 +240,13 @@ impl Key {
             Key::Withdraw(account_hash) => {
                 format!("{}{}", WITHDRAW_PREFIX, base16::encode_lower(&account_hash))
             }
+            Key::Dictionary(dictionary_addr) => {
+                format!(
+                    "{}{}",
+                    DICTIONARY_PREFIX,
+                    base16::encode_lower(&dictionary_addr)
+                )
+            }
         }
     }
 
@@ -260,6 +280,10 @@ impl Key {
             Ok(Key::Withdraw(AccountHash::new(AccountHashBytes::try_from(
                 base16::decode(hex)?.as_ref(),
             )?)))
+        } else if let Some(hex) = input.strip_prefix(DICTIONARY_PREFIX) {
+            Ok(Key::Dictionary(DictionaryAddr::try_from(
+                base16::decode(hex)?.as_ref(),
+            )?))
         } else {
             Err(FromStrError::InvalidPrefix)
         }
@@ -300,12 +324,32 @@ impl Key {
         }
     }
 
+    /// Returns a reference to the inner [`DictionaryAddr`] if `self` is of type
+    /// [`Key::Dictionary`], otherwise returns `None`.
+    pub fn as_dictionary(&self) -> Option<&DictionaryAddr> {
+        match self {
+            Key::Dictionary(dictionary_addr) => Some(dictionary_addr),
+            _ => None,
+        }
+    }
+
     /// Casts a [`Key::URef`] to a [`Key::Hash`]
     pub fn uref_to_hash(&self) -> Option<Key> {
         let uref = self.as_uref()?;
         let addr = uref.addr();
         Some(Key::Hash(addr))
     }
+
+    /// Creates a new [`Key::Dictionary`] variant addressing the item stored under
+    /// `dictionary_item_key` in the dictionary identified by `seed_uref`.
+    ///
+    /// The address is the Blake2b hash of the seed `URef`'s address concatenated with the item key.
+    pub fn dictionary(seed_uref: URef, dictionary_item_key: &[u8]) -> Key {
+        let mut preimage = Vec::with_capacity(UREF_ADDR_LENGTH + dictionary_item_key.len());
+        preimage.extend_from_slice(&seed_uref.addr());
+        preimage.extend_from_slice(dictionary_item_key);
+        Key::Dictionary(account::blake2b(preimage))
+    }
 }
 
 impl Display for Key {
@@ -320,6 +364,9 @@ impl Display for Key {
             Key::Balance(uref_addr) => write!(f, "Key::Balance({})", HexFmt(uref_addr)),
             Key::Bid(account_hash) => write!(f, "Key::Bid({})", account_hash),
             Key::Withdraw(account_hash) => write!(f, "Key::Withdraw({})", account_hash),
+            Key::Dictionary(dictionary_addr) => {
+                write!(f, "Key::Dictionary({})", HexFmt(dictionary_addr))
+            }
         }
     }
 }
@@ -342,6 +389,7 @@ impl Tagged<KeyTag> for Key {
             Key::Balance(_) => KeyTag::Balance,
             Key::Bid(_) => KeyTag::Bid,
             Key::Withdraw(_) => KeyTag::Withdraw,
+            Key::Dictionary(_) => KeyTag::Dictionary,
         }
     }
 }
@@ -421,6 +469,9 @@ impl ToBytes for Key {
             Key::Withdraw(account_hash) => {
                 result.append(&mut account_hash.to_bytes()?);
             }
+            Key::Dictionary(dictionary_addr) => {
+                result.append(&mut dictionary_addr.to_bytes()?);
+            }
         }
         Ok(result)
     }
@@ -438,6 +489,7 @@ impl ToBytes for Key {
             Key::Balance(_) => KEY_BALANCE_SERIALIZED_LENGTH,
             Key::Bid(_) => KEY_BID_SERIALIZED_LENGTH,
             Key::Withdraw(_) => KEY_WITHDRAW_SERIALIZED_LENGTH,
+            Key::Dictionary(_) => KEY_DICTIONARY_SERIALIZED_LENGTH,
         }
     }
 }
@@ -482,6 +534,10 @@ impl FromBytes for Key {
                 let (account_hash, rem) = AccountHash::from_bytes(remainder)?;
                 Ok((Key::Withdraw(account_hash), rem))
             }
+            tag if tag == KeyTag::Dictionary as u8 => {
+                let (dictionary_addr, rem) = FromBytes::from_bytes(remainder)?;
+                Ok((Key::Dictionary(dictionary_addr), rem))
+            }
             _ => Err(Error::Formatting),
         }
     }
@@ -489,7 +545,7 @@ impl FromBytes for Key {
 
 impl Distribution<Key> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Key {
-        match rng.gen_range(0..8) {
+        match rng.gen_range(0..10) {
             0 => Key::Account(rng.gen()),
             1 => Key::Hash(rng.gen()),
             2 => Key::URef(rng.gen()),
@@ -499,6 +555,7 @@ impl Distribution<Key> for Standard {
             6 => Key::Balance(rng.gen()),
             7 => Key::Bid(rng.gen()),
             8 => Key::Withdraw(rng.gen()),
+            9 => Key::Dictionary(rng.gen()),
             _ => unreachable!(),
         }
     }
@@ -518,6 +575,7 @@ mod serde_helpers {
         Balance(String),
         Bid(String),
         Withdraw(String),
+        Dictionary(String),
     }
 
     impl From<&Key> for HumanReadable {
@@ -533,6 +591,7 @@ mod serde_helpers {
                 Key::Balance(_) => HumanReadable::Balance(formatted_string),
                 Key::Bid(_) => HumanReadable::Bid(formatted_string),
                 Key::Withdraw(_) => HumanReadable::Withdraw(formatted_string),
+                Key::Dictionary(_) => HumanReadable::Dictionary(formatted_string),
             }
         }
     }
@@ -550,7 +609,8 @@ mod serde_helpers {
                 | HumanReadable::EraInfo(formatted_string)
                 | HumanReadable::Balance(formatted_string)
                 | HumanReadable::Bid(formatted_string)
-                | HumanReadable::Withdraw(formatted_string) => {
+                | HumanReadable::Withdraw(formatted_string)
+                | HumanReadable::Dictionary(formatted_string) => {
                     Key::from_formatted_str(&formatted_string)
                 }
             }
@@ -568,6 +628,7 @@ mod serde_helpers {
         Balance(&'a URefAddr),
         Bid(&'a AccountHash),
         Withdraw(&'a AccountHash),
+        Dictionary(&'a DictionaryAddr),
     }
 
     impl<'a> From<&'a Key> for BinarySerHelper<'a> {
@@ -582,6 +643,7 @@ mod serde_helpers {
                 Key::Balance(uref_addr) => BinarySerHelper::Balance(uref_addr),
                 Key::Bid(account_hash) => BinarySerHelper::Bid(account_hash),
                 Key::Withdraw(account_hash) => BinarySerHelper::Withdraw(account_hash),
+                Key::Dictionary(dictionary_addr) => BinarySerHelper::Dictionary(dictionary_addr),
             }
         }
     }
@@ -597,6 +659,7 @@ mod serde_helpers {
         Balance(URefAddr),
         Bid(AccountHash),
         Withdraw(AccountHash),
+        Dictionary(DictionaryAddr),
     }
 
     impl From<BinaryDeserHelper> for Key {
@@ -611,6 +674,7 @@ mod serde_helpers {
                 BinaryDeserHelper::Balance(uref_addr) => Key::Balance(uref_addr),
                 BinaryDeserHelper::Bid(account_hash) => Key::Bid(account_hash),
                 BinaryDeserHelper::Withdraw(account_hash) => Key::Withdraw(account_hash),
+                BinaryDeserHelper::Dictionary(dictionary_addr) => Key::Dictionary(dictionary_addr),
             }
         }
     }
@@ -802,6 +866,7 @@ mod tests {
         to_string_round_trip(Key::Transfer(TransferAddr::new([42; KEY_HASH_LENGTH])));
         to_string_round_trip(Key::DeployInfo(DeployHash::new([42; KEY_HASH_LENGTH])));
         to_string_round_trip(Key::EraInfo(42));
+        to_string_round_trip(Key::Dictionary([42; KEY_DICTIONARY_LENGTH]));
 
         let invalid_prefix = "a-0000000000000000000000000000000000000000000000000000000000000000";
         assert!(Key::from_formatted_str(invalid_prefix).is_err());
@@ -880,6 +945,7 @@ mod tests {
         round_trip(&Key::Balance(URef::new(array, AccessRights::READ).addr()));
         round_trip(&Key::Bid(AccountHash::new(array)));
         round_trip(&Key::Withdraw(AccountHash::new(array)));
+        round_trip(&Key::Dictionary(array));
     }
 
     #[test]
@@ -912,5 +978,18 @@ mod tests {
         round_trip(&Key::Balance(URef::new(zeros, AccessRights::READ).addr()));
         round_trip(&Key::Bid(AccountHash::new(zeros)));
         round_trip(&Key::Withdraw(AccountHash::new(zeros)));
+        round_trip(&Key::Dictionary(zeros));
+    }
+
+    #[test]
+    fn should_derive_dictionary_key_from_seed_uref_and_item_key() {
+        let seed_uref = URef::new([42; UREF_ADDR_LENGTH], AccessRights::READ_ADD_WRITE);
+        let other_seed_uref = URef::new([43; UREF_ADDR_LENGTH], AccessRights::READ_ADD_WRITE);
+
+        let key = Key::dictionary(seed_uref, b"item");
+        assert!(key.as_dictionary().is_some());
+        assert_eq!(key, Key::dictionary(seed_uref.into_read(), b"item"));
+        assert_ne!(key, Key::dictionary(seed_uref, b"other item"));
+        assert_ne!(key, Key::dictionary(other_seed_uref, b"item"));
     }
 }
@@ -0,0 +1,476 @@
+//! Verification of a chain of blocks against a trusted set of validators, for clients which follow
+//! the chain without running a node, such as wallets and bridges.
+//!
+//! Starting from the validators of an era obtained through some trustworthy means, a
+//! [`LightClient`] accepts block headers in order of height.  Each header must be the child of the
+//! previously accepted one, and must be finalized by signatures of validators holding more than
+//! the finality threshold of the era's total weight.  The validators of the next era are taken
+//! from the era's switch block, so a light client can follow the chain across eras.  The state
+//! root hash of an accepted header can then be used to verify a
+//! [`TrieMerkleProof`](crate::TrieMerkleProof) of any entry of global state.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+use num_rational::Ratio;
+
+use crate::{crypto, PublicKey, Signature, BLAKE2B_DIGEST_LENGTH, U512};
+
+/// A block header as seen by a [`LightClient`].
+pub trait LightClientBlockHeader {
+    /// Returns the hash of the block, computed from the contents of the header.
+    fn block_hash(&self) -> [u8; BLAKE2B_DIGEST_LENGTH];
+
+    /// Returns the hash of the parent block.
+    fn parent_hash(&self) -> [u8; BLAKE2B_DIGEST_LENGTH];
+
+    /// Returns the height of the block.
+    fn height(&self) -> u64;
+
+    /// Returns the ID of the era in which the block was created.
+    fn era_id(&self) -> u64;
+
+    /// Returns the root hash of the global state after executing the block.
+    fn state_root_hash(&self) -> [u8; BLAKE2B_DIGEST_LENGTH];
+
+    /// Returns the validator weights for the next era if this is a switch block, i.e. the last
+    /// block of its era.
+    fn next_era_validator_weights(&self) -> Option<&BTreeMap<PublicKey, U512>>;
+}
+
+/// Returns the message signed by a validator in a finality signature of the given block.
+pub fn finality_signature_message(
+    block_hash: &[u8; BLAKE2B_DIGEST_LENGTH],
+    era_id: u64,
+) -> Vec<u8> {
+    let mut message = block_hash.to_vec();
+    message.extend_from_slice(&era_id.to_le_bytes());
+    message
+}
+
+/// The validators of an era, along with their weights.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EraValidators {
+    era_id: u64,
+    weights: BTreeMap<PublicKey, U512>,
+}
+
+impl EraValidators {
+    /// Constructs the validators of the given era.
+    pub fn new(era_id: u64, weights: BTreeMap<PublicKey, U512>) -> Self {
+        EraValidators { era_id, weights }
+    }
+
+    /// Returns the ID of the era.
+    pub fn era_id(&self) -> u64 {
+        self.era_id
+    }
+
+    /// Returns the validators and their weights.
+    pub fn weights(&self) -> &BTreeMap<PublicKey, U512> {
+        &self.weights
+    }
+
+    /// Returns the sum of the weights of all validators.
+    pub fn total_weight(&self) -> U512 {
+        self.weights
+            .values()
+            .fold(U512::zero(), |total, weight| total + *weight)
+    }
+
+    /// Verifies the given finality signatures of a block of this era, returning an error unless
+    /// they are all valid and their signers hold more than `finality_threshold` of the total
+    /// weight.
+    pub fn verify_finality_signatures(
+        &self,
+        block_hash: &[u8; BLAKE2B_DIGEST_LENGTH],
+        finality_threshold: Ratio<u64>,
+        signatures: &BTreeMap<PublicKey, Signature>,
+    ) -> Result<(), LightClientError> {
+        let message = finality_signature_message(block_hash, self.era_id);
+        let mut signed_weight = U512::zero();
+        for (public_key, signature) in signatures {
+            let weight =
+                self.weights
+                    .get(public_key)
+                    .ok_or_else(|| LightClientError::UnknownSigner {
+                        block_hash: *block_hash,
+                        public_key: *public_key,
+                    })?;
+            crypto::verify(&message, signature, public_key).map_err(|_| {
+                LightClientError::InvalidSignature {
+                    block_hash: *block_hash,
+                    public_key: *public_key,
+                }
+            })?;
+            signed_weight += *weight;
+        }
+
+        // Compare `signed / total > numer / denom` without dividing.
+        let total_weight = self.total_weight();
+        if signed_weight * U512::from(*finality_threshold.denom())
+            <= total_weight * U512::from(*finality_threshold.numer())
+            || signed_weight.is_zero()
+        {
+            return Err(LightClientError::InsufficientSignatures {
+                block_hash: *block_hash,
+                signed_weight,
+                total_weight,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The most recent block accepted by a [`LightClient`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrustedBlock {
+    /// The hash of the block.
+    pub block_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+    /// The height of the block.
+    pub height: u64,
+    /// The root hash of the global state after executing the block.
+    pub state_root_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+}
+
+/// Follows a chain of blocks, starting from a trusted set of validators.
+#[derive(Clone, Debug)]
+pub struct LightClient {
+    /// The validators of the era of the next block.
+    validators: EraValidators,
+    /// The fraction of the total weight which must be exceeded by the signers of a block.
+    finality_threshold: Ratio<u64>,
+    /// The most recently accepted block, if any.
+    latest_block: Option<TrustedBlock>,
+}
+
+impl LightClient {
+    /// Constructs a light client trusting the given validators.
+    ///
+    /// The first block accepted must be from the era of `trusted_validators`; it can be at any
+    /// height.
+    pub fn new(trusted_validators: EraValidators, finality_threshold: Ratio<u64>) -> Self {
+        LightClient {
+            validators: trusted_validators,
+            finality_threshold,
+            latest_block: None,
+        }
+    }
+
+    /// Returns the validators of the era of the next block.
+    pub fn validators(&self) -> &EraValidators {
+        &self.validators
+    }
+
+    /// Returns the most recently accepted block, if any.
+    pub fn latest_block(&self) -> Option<&TrustedBlock> {
+        self.latest_block.as_ref()
+    }
+
+    /// Verifies the given header and its finality signatures, and accepts it as the latest block
+    /// if they are valid.
+    pub fn verify_block<H: LightClientBlockHeader>(
+        &mut self,
+        header: &H,
+        signatures: &BTreeMap<PublicKey, Signature>,
+    ) -> Result<TrustedBlock, LightClientError> {
+        let block_hash = header.block_hash();
+        if let Some(latest_block) = &self.latest_block {
+            if header.parent_hash() != latest_block.block_hash
+                || header.height() != latest_block.height + 1
+            {
+                return Err(LightClientError::NotAChild {
+                    block_hash,
+                    latest_block_hash: latest_block.block_hash,
+                });
+            }
+        }
+        if header.era_id() != self.validators.era_id {
+            return Err(LightClientError::UnexpectedEra {
+                block_hash,
+                era_id: header.era_id(),
+                expected_era_id: self.validators.era_id,
+            });
+        }
+
+        self.validators.verify_finality_signatures(
+            &block_hash,
+            self.finality_threshold,
+            signatures,
+        )?;
+
+        if let Some(next_era_validator_weights) = header.next_era_validator_weights() {
+            self.validators =
+                EraValidators::new(header.era_id() + 1, next_era_validator_weights.clone());
+        }
+        let trusted_block = TrustedBlock {
+            block_hash,
+            height: header.height(),
+            state_root_hash: header.state_root_hash(),
+        };
+        self.latest_block = Some(trusted_block);
+        Ok(trusted_block)
+    }
+
+    /// Verifies the given headers and their finality signatures in order, stopping at the first
+    /// one which is invalid.
+    pub fn verify_chain<'a, H, I>(&mut self, blocks: I) -> Result<(), LightClientError>
+    where
+        H: LightClientBlockHeader + 'a,
+        I: IntoIterator<Item = (&'a H, &'a BTreeMap<PublicKey, Signature>)>,
+    {
+        for (header, signatures) in blocks {
+            self.verify_block(header, signatures)?;
+        }
+        Ok(())
+    }
+}
+
+/// An error verifying a block with a [`LightClient`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LightClientError {
+    /// The block is not a child of the most recently accepted block.
+    NotAChild {
+        /// The hash of the block.
+        block_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+        /// The hash of the most recently accepted block.
+        latest_block_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+    },
+    /// The block is not from the era whose validators are known.
+    UnexpectedEra {
+        /// The hash of the block.
+        block_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+        /// The era of the block.
+        era_id: u64,
+        /// The era whose validators are known.
+        expected_era_id: u64,
+    },
+    /// A finality signature was made by a key which is not a validator of the block's era.
+    UnknownSigner {
+        /// The hash of the block.
+        block_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+        /// The signer.
+        public_key: PublicKey,
+    },
+    /// A finality signature is invalid.
+    InvalidSignature {
+        /// The hash of the block.
+        block_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+        /// The signer.
+        public_key: PublicKey,
+    },
+    /// The signers of the block don't hold enough weight for it to be considered finalized.
+    InsufficientSignatures {
+        /// The hash of the block.
+        block_hash: [u8; BLAKE2B_DIGEST_LENGTH],
+        /// The total weight of the signers.
+        signed_weight: U512,
+        /// The total weight of the era's validators.
+        total_weight: U512,
+    },
+}
+
+impl Display for LightClientError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            LightClientError::NotAChild {
+                block_hash,
+                latest_block_hash,
+            } => write!(
+                formatter,
+                "block {} is not a child of block {}",
+                base16::encode_lower(block_hash),
+                base16::encode_lower(latest_block_hash)
+            ),
+            LightClientError::UnexpectedEra {
+                block_hash,
+                era_id,
+                expected_era_id,
+            } => write!(
+                formatter,
+                "block {} is from era {}, but the validators of era {} are known",
+                base16::encode_lower(block_hash),
+                era_id,
+                expected_era_id
+            ),
+            LightClientError::UnknownSigner {
+                block_hash,
+                public_key,
+            } => write!(
+                formatter,
+                "block {} was signed by {}, which is not a validator",
+                base16::encode_lower(block_hash),
+                public_key
+            ),
+            LightClientError::InvalidSignature {
+                block_hash,
+                public_key,
+            } => write!(
+                formatter,
+                "invalid signature of block {} by {}",
+                base16::encode_lower(block_hash),
+                public_key
+            ),
+            LightClientError::InsufficientSignatures {
+                block_hash,
+                signed_weight,
+                total_weight,
+            } => write!(
+                formatter,
+                "block {} was signed by validators with weight {} of {}",
+                base16::encode_lower(block_hash),
+                signed_weight,
+                total_weight
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{account, crypto::SecretKey};
+
+    struct TestHeader {
+        parent_hash: [u8; 32],
+        height: u64,
+        era_id: u64,
+        next_era_validator_weights: Option<BTreeMap<PublicKey, U512>>,
+    }
+
+    impl LightClientBlockHeader for TestHeader {
+        fn block_hash(&self) -> [u8; 32] {
+            let mut bytes = self.parent_hash.to_vec();
+            bytes.extend_from_slice(&self.height.to_le_bytes());
+            bytes.extend_from_slice(&self.era_id.to_le_bytes());
+            account::blake2b(bytes)
+        }
+
+        fn parent_hash(&self) -> [u8; 32] {
+            self.parent_hash
+        }
+
+        fn height(&self) -> u64 {
+            self.height
+        }
+
+        fn era_id(&self) -> u64 {
+            self.era_id
+        }
+
+        fn state_root_hash(&self) -> [u8; 32] {
+            [self.height as u8; 32]
+        }
+
+        fn next_era_validator_weights(&self) -> Option<&BTreeMap<PublicKey, U512>> {
+            self.next_era_validator_weights.as_ref()
+        }
+    }
+
+    struct Validator {
+        secret_key: ed25519_dalek::SecretKey,
+        public_key: PublicKey,
+    }
+
+    impl Validator {
+        fn new(seed: u8) -> Self {
+            let secret_key =
+                ed25519_dalek::SecretKey::from_bytes(&[seed; SecretKey::ED25519_LENGTH]).unwrap();
+            let public_key = PublicKey::Ed25519(ed25519_dalek::PublicKey::from(&secret_key));
+            Validator {
+                secret_key,
+                public_key,
+            }
+        }
+
+        fn sign(&self, header: &TestHeader) -> Signature {
+            let message = finality_signature_message(&header.block_hash(), header.era_id);
+            let public_key = ed25519_dalek::PublicKey::from(&self.secret_key);
+            let signature = ed25519_dalek::ExpandedSecretKey::from(&self.secret_key)
+                .sign(&message, &public_key);
+            Signature::Ed25519(signature.to_bytes())
+        }
+    }
+
+    fn signatures(
+        validators: &[&Validator],
+        header: &TestHeader,
+    ) -> BTreeMap<PublicKey, Signature> {
+        validators
+            .iter()
+            .map(|validator| (validator.public_key, validator.sign(header)))
+            .collect()
+    }
+
+    fn weights(validators: &[&Validator]) -> BTreeMap<PublicKey, U512> {
+        validators
+            .iter()
+            .map(|validator| (validator.public_key, U512::from(10)))
+            .collect()
+    }
+
+    #[test]
+    fn should_follow_chain_across_eras() {
+        let alice = Validator::new(1);
+        let bob = Validator::new(2);
+        let carol = Validator::new(3);
+        let mut light_client = LightClient::new(
+            EraValidators::new(0, weights(&[&alice, &bob])),
+            Ratio::new(1, 2),
+        );
+
+        // A switch block, handing over to Bob and Carol.
+        let switch_block = TestHeader {
+            parent_hash: [0; 32],
+            height: 5,
+            era_id: 0,
+            next_era_validator_weights: Some(weights(&[&bob, &carol])),
+        };
+        // Half of the weight isn't enough.
+        assert!(matches!(
+            light_client.verify_block(&switch_block, &signatures(&[&alice], &switch_block)),
+            Err(LightClientError::InsufficientSignatures { .. })
+        ));
+        let trusted = light_client
+            .verify_block(&switch_block, &signatures(&[&alice, &bob], &switch_block))
+            .unwrap();
+        assert_eq!(trusted.height, 5);
+        assert_eq!(trusted.state_root_hash, [5; 32]);
+        assert_eq!(light_client.validators().era_id(), 1);
+
+        let block = TestHeader {
+            parent_hash: switch_block.block_hash(),
+            height: 6,
+            era_id: 1,
+            next_era_validator_weights: None,
+        };
+        // Alice is no longer a validator.
+        assert!(matches!(
+            light_client.verify_block(&block, &signatures(&[&alice, &bob, &carol], &block)),
+            Err(LightClientError::UnknownSigner { .. })
+        ));
+        // A signature of a different block is invalid.
+        let mut forged = signatures(&[&bob], &block);
+        forged.insert(carol.public_key, carol.sign(&switch_block));
+        assert!(matches!(
+            light_client.verify_block(&block, &forged),
+            Err(LightClientError::InvalidSignature { .. })
+        ));
+        light_client
+            .verify_block(&block, &signatures(&[&bob, &carol], &block))
+            .unwrap();
+
+        // A block which doesn't extend the latest one is rejected.
+        let orphan = TestHeader {
+            parent_hash: switch_block.block_hash(),
+            height: 7,
+            era_id: 1,
+            next_era_validator_weights: None,
+        };
+        assert!(matches!(
+            light_client.verify_block(&orphan, &signatures(&[&bob, &carol], &orphan)),
+            Err(LightClientError::NotAChild { .. })
+        ));
+        assert_eq!(light_client.latest_block().unwrap().height, 6);
+    }
+}
@@ -0,0 +1,623 @@
+//! Proofs of the presence of entries in the Merkle trie of global state.
+//!
+//! A [`TrieMerkleProof`] holds a key, its value and the siblings of every trie node on the path
+//! from the root down to the leaf holding the entry.  Rehashing that path yields the state root
+//! hash, so a proof can be verified by anyone who trusts a state root hash, e.g. one taken from a
+//! block header verified via the [`light_client`](crate::light_client) module.
+
+use alloc::{collections::VecDeque, string::String, vec::Vec};
+use core::{
+    convert::TryInto,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::{
+    account,
+    bytesrepr::{self, Bytes, FromBytes, ToBytes, U8_SERIALIZED_LENGTH},
+    CLValue, CLValueError, Key, StoredValue, BLAKE2B_DIGEST_LENGTH, U512,
+};
+
+/// The number of children of a node of the Merkle trie.
+pub const TRIE_RADIX: usize = 256;
+
+const TRIE_POINTER_LEAF_TAG: u8 = 0;
+const TRIE_POINTER_NODE_TAG: u8 = 1;
+
+const TRIE_LEAF_TAG: u8 = 0;
+const TRIE_NODE_TAG: u8 = 1;
+const TRIE_EXTENSION_TAG: u8 = 2;
+
+const TRIE_MERKLE_PROOF_STEP_NODE_ID: u8 = 0;
+const TRIE_MERKLE_PROOF_STEP_EXTENSION_ID: u8 = 1;
+
+/// A pointer to a child of a node of the Merkle trie, i.e. the hash of that child.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TriePointer {
+    /// A pointer to a leaf.
+    LeafPointer([u8; BLAKE2B_DIGEST_LENGTH]),
+    /// A pointer to a node or an extension.
+    NodePointer([u8; BLAKE2B_DIGEST_LENGTH]),
+}
+
+impl TriePointer {
+    /// Returns the hash of the child pointed to.
+    pub fn hash(&self) -> &[u8; BLAKE2B_DIGEST_LENGTH] {
+        match self {
+            TriePointer::LeafPointer(hash) | TriePointer::NodePointer(hash) => hash,
+        }
+    }
+
+    fn tag(&self) -> u8 {
+        match self {
+            TriePointer::LeafPointer(_) => TRIE_POINTER_LEAF_TAG,
+            TriePointer::NodePointer(_) => TRIE_POINTER_NODE_TAG,
+        }
+    }
+}
+
+impl ToBytes for TriePointer {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut ret = bytesrepr::unchecked_allocate_buffer(self);
+        ret.push(self.tag());
+        ret.extend_from_slice(self.hash());
+        Ok(ret)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH + BLAKE2B_DIGEST_LENGTH
+    }
+}
+
+impl FromBytes for TriePointer {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, rem) = u8::from_bytes(bytes)?;
+        let (hash, rem) = FromBytes::from_bytes(rem)?;
+        match tag {
+            TRIE_POINTER_LEAF_TAG => Ok((TriePointer::LeafPointer(hash), rem)),
+            TRIE_POINTER_NODE_TAG => Ok((TriePointer::NodePointer(hash), rem)),
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+/// A component of a proof that an entry exists in the Merkle trie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrieMerkleProofStep {
+    /// Corresponds to a node of the trie.
+    Node {
+        /// The index of the child on the path to the proven entry.
+        hole_index: u8,
+        /// The other children of the node.
+        indexed_pointers_with_hole: Vec<(u8, TriePointer)>,
+    },
+    /// Corresponds to an extension of the trie.
+    Extension {
+        /// The path segment skipped by the extension.
+        affix: Bytes,
+    },
+}
+
+impl TrieMerkleProofStep {
+    /// Constructor for [`TrieMerkleProofStep::Node`]
+    pub fn node(hole_index: u8, indexed_pointers_with_hole: Vec<(u8, TriePointer)>) -> Self {
+        Self::Node {
+            hole_index,
+            indexed_pointers_with_hole,
+        }
+    }
+
+    /// Constructor for [`TrieMerkleProofStep::Extension`]
+    pub fn extension(affix: Vec<u8>) -> Self {
+        Self::Extension {
+            affix: affix.into(),
+        }
+    }
+
+    /// Returns the serialized trie node or extension whose child at the proof's path is `pointer`.
+    fn to_trie_bytes(&self, pointer: TriePointer) -> Result<Vec<u8>, bytesrepr::Error> {
+        match self {
+            TrieMerkleProofStep::Node {
+                hole_index,
+                indexed_pointers_with_hole,
+            } => {
+                let mut pointer_block: [Option<TriePointer>; TRIE_RADIX] = [None; TRIE_RADIX];
+                for (index, indexed_pointer) in indexed_pointers_with_hole {
+                    pointer_block[*index as usize] = Some(*indexed_pointer);
+                }
+                pointer_block[*hole_index as usize] = Some(pointer);
+
+                let mut ret = Vec::new();
+                ret.push(TRIE_NODE_TAG);
+                for maybe_pointer in pointer_block.iter() {
+                    ret.append(&mut maybe_pointer.to_bytes()?);
+                }
+                Ok(ret)
+            }
+            TrieMerkleProofStep::Extension { affix } => {
+                let mut ret = Vec::new();
+                ret.push(TRIE_EXTENSION_TAG);
+                ret.append(&mut affix.to_bytes()?);
+                ret.append(&mut pointer.to_bytes()?);
+                Ok(ret)
+            }
+        }
+    }
+}
+
+impl ToBytes for TrieMerkleProofStep {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut ret: Vec<u8> = bytesrepr::allocate_buffer(self)?;
+        match self {
+            TrieMerkleProofStep::Node {
+                hole_index,
+                indexed_pointers_with_hole,
+            } => {
+                ret.push(TRIE_MERKLE_PROOF_STEP_NODE_ID);
+                ret.push(*hole_index);
+                ret.append(&mut indexed_pointers_with_hole.to_bytes()?)
+            }
+            TrieMerkleProofStep::Extension { affix } => {
+                ret.push(TRIE_MERKLE_PROOF_STEP_EXTENSION_ID);
+                ret.append(&mut affix.to_bytes()?)
+            }
+        };
+        Ok(ret)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH
+            + match self {
+                TrieMerkleProofStep::Node {
+                    hole_index,
+                    indexed_pointers_with_hole,
+                } => {
+                    (*hole_index).serialized_length()
+                        + (*indexed_pointers_with_hole).serialized_length()
+                }
+                TrieMerkleProofStep::Extension { affix } => affix.serialized_length(),
+            }
+    }
+}
+
+impl FromBytes for TrieMerkleProofStep {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, rem): (u8, &[u8]) = FromBytes::from_bytes(bytes)?;
+        match tag {
+            TRIE_MERKLE_PROOF_STEP_NODE_ID => {
+                let (hole_index, rem): (u8, &[u8]) = FromBytes::from_bytes(rem)?;
+                let (indexed_pointers_with_hole, rem): (Vec<(u8, TriePointer)>, &[u8]) =
+                    FromBytes::from_bytes(rem)?;
+                Ok((
+                    TrieMerkleProofStep::Node {
+                        hole_index,
+                        indexed_pointers_with_hole,
+                    },
+                    rem,
+                ))
+            }
+            TRIE_MERKLE_PROOF_STEP_EXTENSION_ID => {
+                let (affix, rem): (_, &[u8]) = FromBytes::from_bytes(rem)?;
+                Ok((TrieMerkleProofStep::Extension { affix }, rem))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+/// An error verifying a [`TrieMerkleProof`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MerkleProofError {
+    /// The proof could not be serialized in order to rehash it.
+    BytesRepr(bytesrepr::Error),
+    /// The state root hash computed from the proof differs from the expected one.
+    StateRootHashMismatch {
+        /// The state root hash computed from the proof.
+        computed: [u8; BLAKE2B_DIGEST_LENGTH],
+    },
+}
+
+impl Display for MerkleProofError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            MerkleProofError::BytesRepr(error) => {
+                write!(formatter, "failed to serialize proof: {:?}", error)
+            }
+            MerkleProofError::StateRootHashMismatch { computed } => write!(
+                formatter,
+                "proof is for a different state root hash: {}",
+                base16::encode_lower(computed)
+            ),
+        }
+    }
+}
+
+impl From<bytesrepr::Error> for MerkleProofError {
+    fn from(error: bytesrepr::Error) -> Self {
+        MerkleProofError::BytesRepr(error)
+    }
+}
+
+/// A proof that a node with a specified `key` and `value` is present in the Merkle trie.
+/// Given a state hash `x`, one can validate a proof `p` by checking `x == p.compute_state_hash()`,
+/// or by calling `p.verify(&x)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieMerkleProof<K, V> {
+    key: K,
+    value: V,
+    proof_steps: VecDeque<TrieMerkleProofStep>,
+}
+
+impl<K, V> TrieMerkleProof<K, V> {
+    /// Constructor for [`TrieMerkleProof`]
+    pub fn new(key: K, value: V, proof_steps: VecDeque<TrieMerkleProofStep>) -> Self {
+        TrieMerkleProof {
+            key,
+            value,
+            proof_steps,
+        }
+    }
+
+    /// Getter for the key in [`TrieMerkleProof`]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Getter for the value in [`TrieMerkleProof`]
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    /// Getter for the proof steps in [`TrieMerkleProof`]
+    pub fn proof_steps(&self) -> &VecDeque<TrieMerkleProofStep> {
+        &self.proof_steps
+    }
+
+    /// Transforms a [`TrieMerkleProof`] into the value it contains
+    pub fn into_value(self) -> V {
+        self.value
+    }
+}
+
+impl<K, V> TrieMerkleProof<K, V>
+where
+    K: ToBytes,
+    V: ToBytes,
+{
+    /// Recomputes a state root hash from a [`TrieMerkleProof`].
+    /// This is done in the following steps:
+    ///
+    /// 1. Using [`TrieMerkleProof::key`] and [`TrieMerkleProof::value`], construct a leaf of the
+    /// trie and compute a hash for that leaf.
+    ///
+    /// 2. We then iterate over [`TrieMerkleProof::proof_steps`] left to right, using the hash from
+    /// the previous step combined with the next step to compute a new hash.
+    ///
+    /// 3. When there are no more steps, we return the final hash we have computed.
+    ///
+    /// The steps in this function reflect the rehashing of the trie in the execution engine.
+    pub fn compute_state_hash(&self) -> Result<[u8; BLAKE2B_DIGEST_LENGTH], bytesrepr::Error> {
+        let mut hash = {
+            let mut leaf_bytes = Vec::with_capacity(
+                U8_SERIALIZED_LENGTH
+                    + self.key.serialized_length()
+                    + self.value.serialized_length(),
+            );
+            leaf_bytes.push(TRIE_LEAF_TAG);
+            leaf_bytes.append(&mut self.key.to_bytes()?);
+            leaf_bytes.append(&mut self.value.to_bytes()?);
+            account::blake2b(&leaf_bytes)
+        };
+
+        for (proof_step_index, proof_step) in self.proof_steps.iter().enumerate() {
+            let pointer = if proof_step_index == 0 {
+                TriePointer::LeafPointer(hash)
+            } else {
+                TriePointer::NodePointer(hash)
+            };
+            hash = account::blake2b(&proof_step.to_trie_bytes(pointer)?);
+        }
+        Ok(hash)
+    }
+
+    /// Verifies that the proven entry is present in the global state with the given root hash.
+    pub fn verify(
+        &self,
+        state_root_hash: &[u8; BLAKE2B_DIGEST_LENGTH],
+    ) -> Result<(), MerkleProofError> {
+        let computed = self.compute_state_hash()?;
+        if computed != *state_root_hash {
+            return Err(MerkleProofError::StateRootHashMismatch { computed });
+        }
+        Ok(())
+    }
+}
+
+impl<K, V> ToBytes for TrieMerkleProof<K, V>
+where
+    K: ToBytes,
+    V: ToBytes,
+{
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut ret: Vec<u8> = bytesrepr::allocate_buffer(self)?;
+        ret.append(&mut self.key.to_bytes()?);
+        ret.append(&mut self.value.to_bytes()?);
+        ret.append(&mut self.proof_steps.to_bytes()?);
+        Ok(ret)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.key.serialized_length()
+            + self.value.serialized_length()
+            + self.proof_steps.serialized_length()
+    }
+}
+
+impl<K, V> FromBytes for TrieMerkleProof<K, V>
+where
+    K: FromBytes,
+    V: FromBytes,
+{
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (key, rem): (K, &[u8]) = FromBytes::from_bytes(bytes)?;
+        let (value, rem): (V, &[u8]) = FromBytes::from_bytes(rem)?;
+        let (proof_steps, rem): (VecDeque<TrieMerkleProofStep>, &[u8]) =
+            FromBytes::from_bytes(rem)?;
+        Ok((
+            TrieMerkleProof {
+                key,
+                value,
+                proof_steps,
+            },
+            rem,
+        ))
+    }
+}
+
+/// An error validating the proofs of a global state query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofValidationError {
+    /// The path should not have a different length than the proof less one.
+    PathLengthDifferentThanProofLessOne,
+    /// The provided key does not match the key in the proof.
+    UnexpectedKey,
+    /// The provided value does not match the value in the proof.
+    UnexpectedValue,
+    /// The proof hash is invalid.
+    InvalidProofHash,
+    /// The path went cold.
+    PathCold,
+    /// Serialization error.
+    BytesRepr(bytesrepr::Error),
+    /// The key is not a URef.
+    KeyIsNotAURef(Key),
+    /// Failed to convert the stored value to a CLValue.
+    ValueToCLValueConversion,
+    /// Failed to convert the CLValue to the expected type.
+    CLValueError(CLValueError),
+}
+
+impl Display for ProofValidationError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            ProofValidationError::PathLengthDifferentThanProofLessOne => write!(
+                formatter,
+                "The path should not have a different length than the proof less one."
+            ),
+            ProofValidationError::UnexpectedKey => write!(
+                formatter,
+                "The provided key does not match the key in the proof."
+            ),
+            ProofValidationError::UnexpectedValue => write!(
+                formatter,
+                "The provided value does not match the value in the proof."
+            ),
+            ProofValidationError::InvalidProofHash => {
+                write!(formatter, "The proof hash is invalid.")
+            }
+            ProofValidationError::PathCold => write!(formatter, "The path went cold."),
+            ProofValidationError::BytesRepr(error) => {
+                write!(formatter, "Serialization error: {:?}", error)
+            }
+            ProofValidationError::KeyIsNotAURef(_) => write!(formatter, "Key is not a URef"),
+            ProofValidationError::ValueToCLValueConversion => {
+                write!(formatter, "Failed to convert stored value to key")
+            }
+            ProofValidationError::CLValueError(error) => write!(formatter, "{:?}", error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ProofValidationError {}
+
+impl From<CLValueError> for ProofValidationError {
+    fn from(error: CLValueError) -> Self {
+        ProofValidationError::CLValueError(error)
+    }
+}
+
+impl From<bytesrepr::Error> for ProofValidationError {
+    fn from(error: bytesrepr::Error) -> Self {
+        ProofValidationError::BytesRepr(error)
+    }
+}
+
+/// Validates the proofs returned by a query of global state under `expected_first_key`, following
+/// `path` through the named keys of accounts and contracts, against the given state root hash.
+///
+/// There must be one proof for the base key and one for each element of `path`, and the value of
+/// the last proof must be `expected_value`.
+pub fn validate_query_proof(
+    state_root_hash: &[u8; BLAKE2B_DIGEST_LENGTH],
+    proofs: &[TrieMerkleProof<Key, StoredValue>],
+    expected_first_key: &Key,
+    path: &[String],
+    expected_value: &StoredValue,
+) -> Result<(), ProofValidationError> {
+    if proofs.len() != path.len() + 1 {
+        return Err(ProofValidationError::PathLengthDifferentThanProofLessOne);
+    }
+
+    let mut proofs_iter = proofs.iter();
+
+    // length check above means we are safe to unwrap here
+    let first_proof = proofs_iter.next().unwrap();
+
+    if first_proof.key() != &expected_first_key.normalize() {
+        return Err(ProofValidationError::UnexpectedKey);
+    }
+
+    if *state_root_hash != first_proof.compute_state_hash()? {
+        return Err(ProofValidationError::InvalidProofHash);
+    }
+
+    let mut proof_value = first_proof.value();
+
+    for (proof, path_component) in proofs_iter.zip(path.iter()) {
+        let named_keys = match proof_value {
+            StoredValue::Account(account) => account.named_keys(),
+            StoredValue::Contract(contract) => contract.named_keys(),
+            _ => return Err(ProofValidationError::PathCold),
+        };
+
+        let key = match named_keys.get(path_component) {
+            Some(key) => key,
+            None => return Err(ProofValidationError::PathCold),
+        };
+
+        if proof.key() != &key.normalize() {
+            return Err(ProofValidationError::UnexpectedKey);
+        }
+
+        if *state_root_hash != proof.compute_state_hash()? {
+            return Err(ProofValidationError::InvalidProofHash);
+        }
+
+        proof_value = proof.value();
+    }
+
+    if proof_value != expected_value {
+        return Err(ProofValidationError::UnexpectedValue);
+    }
+
+    Ok(())
+}
+
+/// Validates a proof of the balance of the purse `expected_purse_key` against the given state root
+/// hash, checking that the proven balance is `expected_motes`.
+pub fn validate_balance_proof(
+    state_root_hash: &[u8; BLAKE2B_DIGEST_LENGTH],
+    balance_proof: &TrieMerkleProof<Key, StoredValue>,
+    expected_purse_key: Key,
+    expected_motes: &U512,
+) -> Result<(), ProofValidationError> {
+    let expected_balance_key = expected_purse_key
+        .into_uref()
+        .map(|uref| Key::Balance(uref.addr()))
+        .ok_or(ProofValidationError::KeyIsNotAURef(expected_purse_key))?;
+
+    if balance_proof.key() != &expected_balance_key.normalize() {
+        return Err(ProofValidationError::UnexpectedKey);
+    }
+
+    if *state_root_hash != balance_proof.compute_state_hash()? {
+        return Err(ProofValidationError::InvalidProofHash);
+    }
+
+    let balance_proof_clvalue: CLValue = balance_proof
+        .value()
+        .clone()
+        .try_into()
+        .map_err(|_| ProofValidationError::ValueToCLValueConversion)?;
+
+    let balance_motes: U512 = balance_proof_clvalue.into_t()?;
+
+    if expected_motes != &balance_motes {
+        return Err(ProofValidationError::UnexpectedValue);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AccessRights, URef};
+
+    /// Builds a proof of `value` under `key` in a trie consisting of a node with a second leaf,
+    /// beneath an extension, along with the root hash of that trie.
+    fn proof_and_root<V: ToBytes>(key: Key, value: V) -> (TrieMerkleProof<Key, V>, [u8; 32]) {
+        let mut leaf_bytes = vec![TRIE_LEAF_TAG];
+        leaf_bytes.append(&mut key.to_bytes().unwrap());
+        leaf_bytes.append(&mut value.to_bytes().unwrap());
+        let leaf_hash = account::blake2b(&leaf_bytes);
+
+        let sibling = (7, TriePointer::LeafPointer([3; 32]));
+        let node_step = TrieMerkleProofStep::node(2, vec![sibling]);
+        let node_hash = account::blake2b(
+            &node_step
+                .to_trie_bytes(TriePointer::LeafPointer(leaf_hash))
+                .unwrap(),
+        );
+        let extension_step = TrieMerkleProofStep::extension(vec![1, 2, 3]);
+        let root = account::blake2b(
+            &extension_step
+                .to_trie_bytes(TriePointer::NodePointer(node_hash))
+                .unwrap(),
+        );
+
+        let proof = TrieMerkleProof::new(key, value, vec![node_step, extension_step].into());
+        (proof, root)
+    }
+
+    #[test]
+    fn should_verify_proof_against_state_root_hash() {
+        let key = Key::Hash([1; 32]);
+        let (proof, root) = proof_and_root(key, U512::from(100));
+        assert_eq!(proof.compute_state_hash().unwrap(), root);
+        assert!(proof.verify(&root).is_ok());
+
+        // A proof of a different value under the same path doesn't match the root.
+        let forged = TrieMerkleProof::new(key, U512::from(101), proof.proof_steps().clone());
+        assert!(matches!(
+            forged.verify(&root),
+            Err(MerkleProofError::StateRootHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let (proof, _) = proof_and_root(Key::Hash([1; 32]), U512::from(100));
+        bytesrepr::test_serialization_roundtrip(&proof);
+        for step in proof.proof_steps() {
+            bytesrepr::test_serialization_roundtrip(step);
+        }
+        bytesrepr::test_serialization_roundtrip(&TriePointer::NodePointer([9; 32]));
+    }
+
+    #[test]
+    fn should_validate_balance_proof() {
+        let purse = URef::new([4; 32], AccessRights::READ_ADD_WRITE);
+        let balance = U512::from(100);
+        let stored_value = StoredValue::CLValue(CLValue::from_t(balance).unwrap());
+        let (proof, root) = proof_and_root(Key::Balance(purse.addr()), stored_value);
+
+        // The proof survives the round trip through the bytes returned by a node.
+        let proof: TrieMerkleProof<Key, StoredValue> =
+            bytesrepr::deserialize(proof.to_bytes().unwrap()).unwrap();
+
+        assert!(validate_balance_proof(&root, &proof, Key::URef(purse), &balance).is_ok());
+        assert_eq!(
+            validate_balance_proof(&root, &proof, Key::URef(purse), &U512::from(101)),
+            Err(ProofValidationError::UnexpectedValue)
+        );
+        assert_eq!(
+            validate_balance_proof(&[0; 32], &proof, Key::URef(purse), &balance),
+            Err(ProofValidationError::InvalidProofHash)
+        );
+        assert_eq!(
+            validate_balance_proof(&root, &proof, Key::Hash([1; 32]), &balance),
+            Err(ProofValidationError::KeyIsNotAURef(Key::Hash([1; 32])))
+        );
+    }
+}
@@ -1,15 +1,19 @@
-use std::fmt;
+use alloc::string::String;
+use core::fmt::{self, Display, Formatter};
 
 use serde::{Deserialize, Serialize};
 
+/// An error struct representing a type mismatch in [`StoredValue`](crate::StoredValue) operations.
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct TypeMismatch {
+    /// The name of the expected type.
     pub expected: String,
+    /// The actual type found.
     pub found: String,
 }
 
-impl fmt::Display for TypeMismatch {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+impl Display for TypeMismatch {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
         write!(
             f,
             "Type mismatch. Expected {} but found {}.",
@@ -19,6 +23,7 @@ impl fmt::Display for TypeMismatch {
 }
 
 impl TypeMismatch {
+    /// Creates a new `TypeMismatch`.
     pub fn new(expected: String, found: String) -> TypeMismatch {
         TypeMismatch { expected, found }
     }